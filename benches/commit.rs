@@ -0,0 +1,189 @@
+// Copyright 2020-2021, Cerno
+// Licensed under the MIT License
+// See the LICENSE file or <http://opensource.org/licenses/MIT>
+
+//! Commit path benchmarks
+//!
+//! These benchmarks need an actual KMS device to run against, VKMS being the obvious choice. The
+//! device path can be set through the `NUCLEID_BENCH_DEVICE` environment variable, and defaults
+//! to `/dev/dri/card0`. If the device can't be opened, the benchmarks are skipped.
+
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use nucleid::{
+    BufferType, Connector, ConnectorStatus, ConnectorUpdate, Device, FixedPoint, Format,
+    Framebuffer, Mode, Plane, PlaneType, PlaneUpdate, Point, Size,
+};
+
+struct Setup {
+    device: Device,
+    connector: Arc<Connector>,
+    plane: Arc<Plane>,
+    mode: Mode,
+    framebuffer: Framebuffer,
+}
+
+fn setup() -> Option<Setup> {
+    let path =
+        std::env::var("NUCLEID_BENCH_DEVICE").unwrap_or_else(|_| String::from("/dev/dri/card0"));
+
+    let device = match Device::new(&path) {
+        Ok(device) => device,
+        Err(err) => {
+            eprintln!("Couldn't open {}, skipping benchmarks: {}", path, err);
+            return None;
+        }
+    };
+
+    let connector = device
+        .connectors()
+        .find(|con| con.status().ok() == Some(ConnectorStatus::Connected))?;
+
+    let mode = connector.preferred_mode().ok()?;
+    let output = device.output_from_connector(&connector).ok()?;
+
+    let plane = output.planes().into_iter().find(|plane| {
+        plane.formats().any(|fmt| fmt == Format::XRGB8888)
+            && plane.plane_type() == PlaneType::Primary
+    })?;
+
+    let framebuffer = device
+        .allocate_buffer(BufferType::Dumb, mode.width(), mode.height(), 32)
+        .ok()?
+        .into_framebuffer(Format::XRGB8888)
+        .ok()?;
+
+    Some(Setup {
+        device,
+        connector,
+        plane,
+        mode,
+        framebuffer,
+    })
+}
+
+fn plane_update(setup: &Setup) -> PlaneUpdate {
+    PlaneUpdate::new(&setup.plane)
+        .set_framebuffer(&setup.framebuffer)
+        .set_source_size(Size::new(setup.mode.width(), setup.mode.height()).into())
+        .set_source_coordinates(FixedPoint::default())
+        .set_display_size(Size::new(setup.mode.width(), setup.mode.height()))
+        .set_display_coordinates(Point::default())
+}
+
+fn property_resolution(c: &mut Criterion) {
+    let setup = match setup() {
+        Some(setup) => setup,
+        None => return,
+    };
+
+    c.bench_function("plane properties", |b| {
+        b.iter(|| black_box(setup.plane.properties().unwrap()))
+    });
+
+    c.bench_function("plane property lookup", |b| {
+        b.iter(|| {
+            black_box(
+                setup
+                    .plane
+                    .properties()
+                    .unwrap()
+                    .into_iter()
+                    .find(|prop| prop.name() == "FB_ID"),
+            )
+        })
+    });
+}
+
+fn update_building(c: &mut Criterion) {
+    let setup = match setup() {
+        Some(setup) => setup,
+        None => return,
+    };
+
+    c.bench_function("update building", |b| {
+        b.iter(|| {
+            let output = setup
+                .device
+                .output_from_connector(&setup.connector)
+                .unwrap();
+
+            black_box(
+                output
+                    .start_update()
+                    .add_connector(ConnectorUpdate::new(&setup.connector))
+                    .add_plane(plane_update(&setup)),
+            )
+        })
+    });
+}
+
+fn ioctl_marshalling(c: &mut Criterion) {
+    let setup = match setup() {
+        Some(setup) => setup,
+        None => return,
+    };
+
+    // NOTE: Test-only commits go through the whole staging and ioctl argument marshalling path,
+    // without waiting for a vblank like the actual commits do.
+    let update = setup
+        .device
+        .output_from_connector(&setup.connector)
+        .unwrap()
+        .start_update()
+        .set_mode(setup.mode.clone())
+        .add_connector(ConnectorUpdate::new(&setup.connector))
+        .add_plane(plane_update(&setup));
+    c.bench_function("modeset test commit", |b| b.iter(|| update.test().unwrap()));
+
+    // NOTE: The plane can only be tested once its CRTC is enabled.
+    let update = update
+        .commit()
+        .unwrap()
+        .start_update()
+        .add_plane(plane_update(&setup));
+    c.bench_function("plane test commit", |b| b.iter(|| update.test().unwrap()));
+}
+
+fn commit(c: &mut Criterion) {
+    let setup = match setup() {
+        Some(setup) => setup,
+        None => return,
+    };
+
+    let mut output = Some(
+        setup
+            .device
+            .output_from_connector(&setup.connector)
+            .unwrap()
+            .start_update()
+            .set_mode(setup.connector.preferred_mode().unwrap())
+            .add_connector(ConnectorUpdate::new(&setup.connector))
+            .add_plane(plane_update(&setup))
+            .commit()
+            .unwrap(),
+    );
+
+    c.bench_function("page flip commit", |b| {
+        b.iter(|| {
+            let update = output
+                .take()
+                .unwrap()
+                .start_update()
+                .add_plane(plane_update(&setup));
+
+            output = Some(update.commit().unwrap());
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    property_resolution,
+    update_building,
+    ioctl_marshalling,
+    commit
+);
+criterion_main!(benches);
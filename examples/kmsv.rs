@@ -7,18 +7,13 @@ use anyhow::{Context, Result};
 use clap::{Arg, ArgAction, Command};
 
 use nucleid::{
-    BufferType, ConnectorStatus, ConnectorUpdate, Device, Format, Framebuffer, ObjectUpdate,
-    PlaneType, PlaneUpdate,
+    BufferType, ConnectorUpdate, Device, Error, FitMode, Format, Framebuffer, ObjectUpdate,
+    PlaneType, PlaneUpdate, Rect, Size,
 };
 
 struct Image {
     buffer: Framebuffer,
-    image_w: usize,
-    image_h: usize,
-    display_w: usize,
-    display_h: usize,
-    margin_w: usize,
-    margin_h: usize,
+    size: Size,
 }
 
 fn main() -> Result<()> {
@@ -36,9 +31,9 @@ fn main() -> Result<()> {
     let img_path = matches.get_many::<String>("images").unwrap();
 
     let connector = device
-        .connectors()
+        .connected_connectors()
         .into_iter()
-        .find(|con| con.status().unwrap_or(ConnectorStatus::Unknown) == ConnectorStatus::Connected)
+        .next()
         .context("No Active Connector")?;
 
     let mode = connector
@@ -50,14 +45,13 @@ fn main() -> Result<()> {
         .context("Couldn't find a valid output for that connector")?;
 
     let plane = output
-        .planes()
+        .planes_of(PlaneType::Overlay)
         .into_iter()
-        .find(|plane| {
-            plane.formats().any(|fmt| fmt == Format::XRGB8888)
-                && plane.plane_type() == PlaneType::Overlay
-        })
+        .find(|plane| plane.formats().any(|fmt| fmt == Format::XRGB8888))
         .context("Couldn't find a plane with the proper format")?;
 
+    let screen = Rect::new(0, 0, mode.width(), mode.height());
+
     let images: Vec<Image> = img_path
         .map(|path| {
             let img = image::open(path).unwrap();
@@ -75,33 +69,9 @@ fn main() -> Result<()> {
             let data = buffer.data();
             data.copy_from_slice(&rgb_data);
 
-            let scale_h = mode.height() as f32 / img_h as f32;
-            let scale_w = mode.width() as f32 / img_w as f32;
-            let scale = scale_h
-                .partial_cmp(&scale_w)
-                .map(|ord| match ord {
-                    std::cmp::Ordering::Less => scale_h,
-                    _ => scale_w,
-                })
-                .unwrap_or(1.0);
-
-            let image_h = img_h;
-            let image_w = img_w;
-
-            let display_h = ((img_h as f32) * scale).ceil() as usize;
-            let display_w = ((img_w as f32) * scale).ceil() as usize;
-
-            let margin_h = ((mode.height() - display_h) / 2) as usize;
-            let margin_w = ((mode.width() - display_w) / 2) as usize;
-
             Image {
                 buffer,
-                image_h,
-                image_w,
-                display_h,
-                display_w,
-                margin_h,
-                margin_w,
+                size: Size::new(img_w, img_h),
             }
         })
         .collect();
@@ -117,15 +87,13 @@ fn main() -> Result<()> {
                 .set_property("left margin", 0)
                 .set_property("right margin", 0),
         )
-        .add_plane(
-            PlaneUpdate::new(&plane)
-                .set_framebuffer(&first.buffer)
-                .set_source_size(first.image_w as f32, first.image_h as f32)
-                .set_source_coordinates(0.0, 0.0)
-                .set_display_size(first.display_w, first.display_h)
-                .set_display_coordinates(first.margin_w, first.margin_h),
-        )
-        .commit()?;
+        .add_plane(PlaneUpdate::new(&plane).set_framebuffer(&first.buffer).fit(
+            FitMode::Contain,
+            first.size,
+            screen,
+        ))
+        .commit()
+        .map_err(Error::from)?;
 
     let mut index = 1;
     loop {
@@ -136,15 +104,13 @@ fn main() -> Result<()> {
 
         output = output
             .start_update()
-            .add_plane(
-                PlaneUpdate::new(&plane)
-                    .set_framebuffer(&image.buffer)
-                    .set_source_size(image.image_w as f32, image.image_h as f32)
-                    .set_source_coordinates(0.0, 0.0)
-                    .set_display_size(image.display_w, image.display_h)
-                    .set_display_coordinates(image.margin_w, image.margin_h),
-            )
-            .commit()?;
+            .add_plane(PlaneUpdate::new(&plane).set_framebuffer(&image.buffer).fit(
+                FitMode::Contain,
+                image.size,
+                screen,
+            ))
+            .commit()
+            .map_err(Error::from)?;
 
         index += 1;
     }
@@ -0,0 +1,44 @@
+use std::thread;
+use std::time;
+
+use anyhow::{Context, Result};
+
+use clap::{Arg, Command};
+
+use nucleid::{Device, Recording, Replayer};
+
+fn main() -> Result<()> {
+    let matches = Command::new("KMS Commit Replay")
+        .arg(
+            Arg::new("device")
+                .short('D')
+                .help("DRM Device Path")
+                .default_value("/dev/dri/card0"),
+        )
+        .arg(
+            Arg::new("delay")
+                .short('d')
+                .help("Delay between commits, in milliseconds")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("0"),
+        )
+        .arg(Arg::new("recording").required(true))
+        .get_matches();
+    let dev_path = matches.get_one::<String>("device").unwrap();
+    let delay = *matches.get_one::<u64>("delay").unwrap();
+    let rec_path = matches.get_one::<String>("recording").unwrap();
+
+    let device = Device::new(dev_path).context("Couldn't open the DRM device")?;
+    let recording = Recording::load(rec_path).context("Couldn't load the recording")?;
+
+    let mut replayer = Replayer::new(&device);
+    for (idx, commit) in recording.commits().enumerate() {
+        replayer
+            .replay(commit)
+            .with_context(|| format!("Commit {} failed", idx))?;
+
+        thread::sleep(time::Duration::from_millis(delay));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use crate::{
+    object::Object,
+    output::{commit_properties, PropertyBlobs},
+    CommitFlags, FixedRect, Framebuffer, Plane, PlaneUpdate, Point, Rect, Result, Size, Update,
+};
+
+/// A layer to assign to a [Plane]
+///
+/// A [`LayerRequest`] describes some content to display, without any [Plane] attached yet. A
+/// list of [`LayerRequest`]s, from the bottom-most to the top-most, can be given to
+/// [`Update::assign_layers`] to find the [Plane]s able to display them.
+#[derive(Debug)]
+pub struct LayerRequest<'a> {
+    framebuffer: &'a Framebuffer,
+    source: Option<FixedRect>,
+    display: Rect,
+    alpha: Option<u16>,
+}
+
+impl<'a> LayerRequest<'a> {
+    /// Creates a new [`LayerRequest`]
+    ///
+    /// The whole [Framebuffer] will be displayed in the `display` area, scaling it if needed.
+    #[must_use]
+    pub const fn new(framebuffer: &'a Framebuffer, display: Rect) -> Self {
+        Self {
+            framebuffer,
+            source: None,
+            display,
+            alpha: None,
+        }
+    }
+
+    /// Sets the area of the [Framebuffer] to display, in pixels
+    #[must_use]
+    pub const fn set_source(mut self, source: FixedRect) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Sets the [`LayerRequest`] global alpha, from 0 (transparent) to 0xffff (opaque)
+    ///
+    /// Only the [Plane]s with an `alpha` property will be considered for this [`LayerRequest`].
+    #[must_use]
+    pub const fn set_alpha(mut self, alpha: u16) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    fn fits(&self, plane: &Plane) -> bool {
+        if !plane.formats().any(|fmt| fmt == self.framebuffer.format()) {
+            return false;
+        }
+
+        if self.alpha.is_some() && plane.property_id("alpha").is_none() {
+            return false;
+        }
+
+        true
+    }
+
+    fn plane_update(&self, plane: &Arc<Plane>, zpos: Option<u64>) -> PlaneUpdate {
+        let source = self.source.unwrap_or_else(|| {
+            Rect::from_parts(
+                Point::default(),
+                Size::new(self.framebuffer.width(), self.framebuffer.height()),
+            )
+            .into()
+        });
+
+        let update = PlaneUpdate::new(plane)
+            .set_framebuffer(self.framebuffer)
+            .set_source_coordinates(source.origin())
+            .set_source_size(source.size())
+            .set_display_coordinates(self.display.origin())
+            .set_display_size(self.display.size());
+
+        let update = match zpos {
+            Some(zpos) => update.set_property("zpos", zpos),
+            None => update,
+        };
+
+        match self.alpha {
+            Some(alpha) => update.set_property("alpha", u64::from(alpha)),
+            None => update,
+        }
+    }
+}
+
+/// The result of [`Update::assign_layers`]
+#[derive(Debug)]
+pub struct LayerAssignment {
+    update: Update,
+    planes: Vec<Option<Arc<Plane>>>,
+}
+
+impl LayerAssignment {
+    /// Returns the [Plane] a [`LayerRequest`] has been assigned to, if any
+    #[must_use]
+    pub fn plane(&self, idx: usize) -> Option<&Arc<Plane>> {
+        self.planes.get(idx).and_then(Option::as_ref)
+    }
+
+    /// Returns an iterator over the indices of the [`LayerRequest`]s that couldn't be assigned to
+    /// any [Plane]
+    ///
+    /// Those layers need to be displayed some other way, for example by compositing them in
+    /// software.
+    pub fn fallback(&self) -> impl Iterator<Item = usize> + '_ {
+        self.planes
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, plane)| if plane.is_none() { Some(idx) } else { None })
+    }
+
+    /// Returns the [Update] with a [`PlaneUpdate`] for each assigned [`LayerRequest`]
+    #[must_use]
+    pub fn into_update(self) -> Update {
+        self.update
+    }
+}
+
+pub fn assign_layers(
+    update: Update,
+    layers: &[LayerRequest<'_>],
+    flags: CommitFlags,
+) -> Result<LayerAssignment> {
+    let device = update.device()?;
+    let crtc_id = update.output().crtc_id();
+
+    let planes: Vec<_> = update
+        .output()
+        .planes()
+        .sorted_by_zpos()
+        .into_iter()
+        .filter(|plane| !update.contains_plane(plane))
+        .collect();
+
+    // NOTE: The planes still scanning out from this CRTC would take part in the test commits,
+    // and in the final one, so the ones that aren't assigned anything are disabled.
+    let active: Vec<_> = planes
+        .iter()
+        .filter(|plane| {
+            plane
+                .properties()
+                .is_ok_and(|props| props.get_u64("CRTC_ID") == Some(u64::from(crtc_id)))
+        })
+        .cloned()
+        .collect();
+
+    // NOTE: The update properties, and thus its blobs, are the same for every candidate, so we
+    // only create them once. Test-only commits don't take the blobs over, so they only need to
+    // outlive the last test commit.
+    let mut blobs = PropertyBlobs::new();
+    let base = update.properties(&device, &mut blobs)?;
+
+    let mut assigned = vec![None; layers.len()];
+    let mut zpos = vec![None; layers.len()];
+    let mut accepted = Vec::new();
+    let mut next_plane = 0;
+    let mut top = None;
+
+    // NOTE: We go through the layers from the bottom to the top, and only consider the planes
+    // above the last assigned one to keep the layers order.
+    for (layer_idx, layer) in layers.iter().enumerate() {
+        for (plane_idx, plane) in planes.iter().enumerate().skip(next_plane) {
+            if !layer.fits(plane) {
+                continue;
+            }
+
+            // NOTE: Planes sharing the same zpos don't have a defined order, so we stack the ones
+            // we can move explicitly on top of the last assigned one.
+            let plane_zpos = match plane.zpos_range() {
+                Some((min, max)) => {
+                    let plane_zpos = top.map_or(min, |top: u64| top.saturating_add(1).max(min));
+                    if plane_zpos > max {
+                        continue;
+                    }
+
+                    Some(plane_zpos)
+                }
+                None => None,
+            };
+
+            let mut candidate_blobs = PropertyBlobs::new();
+            let candidate = layer
+                .plane_update(plane, plane_zpos)
+                .properties(crtc_id, &mut candidate_blobs)?;
+
+            let mut properties = base.clone();
+            properties.extend_from_slice(&accepted);
+            properties.extend_from_slice(&candidate);
+
+            for active_plane in &active {
+                let used = Arc::ptr_eq(active_plane, plane)
+                    || assigned
+                        .iter()
+                        .flatten()
+                        .any(|assigned_plane| Arc::ptr_eq(assigned_plane, active_plane));
+
+                if !used {
+                    properties.extend(
+                        PlaneUpdate::disabled(active_plane).properties(crtc_id, &mut blobs)?,
+                    );
+                }
+            }
+
+            if commit_properties(
+                &device,
+                flags.to_test_only().bits(),
+                properties,
+                PropertyBlobs::new(),
+            )
+            .is_ok()
+            {
+                accepted.extend(candidate);
+                blobs.extend(candidate_blobs);
+                assigned[layer_idx] = Some(Arc::clone(plane));
+                zpos[layer_idx] = plane_zpos;
+                next_plane = plane_idx + 1;
+                top = Some(plane_zpos.unwrap_or_else(|| plane.zpos()));
+                break;
+            }
+        }
+    }
+    drop(blobs);
+
+    let mut update = update;
+    for ((layer, plane), zpos) in layers.iter().zip(&assigned).zip(zpos) {
+        if let Some(plane) = plane {
+            update = update.add_plane(layer.plane_update(plane, zpos));
+        }
+    }
+
+    for plane in &active {
+        if !assigned
+            .iter()
+            .flatten()
+            .any(|assigned_plane| Arc::ptr_eq(assigned_plane, plane))
+        {
+            update = update.add_plane(PlaneUpdate::disabled(plane));
+        }
+    }
+
+    Ok(LayerAssignment {
+        update,
+        planes: assigned,
+    })
+}
@@ -0,0 +1,235 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use futures_core::Stream;
+use tokio::io::unix::AsyncFd;
+
+use crate::{CommitHandle, Device, Error, Event, Output, Result, Update, Vblank};
+
+impl Device {
+    /// Registers the [Device] with the Tokio reactor
+    ///
+    /// See [`AsyncDevice::new`].
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if called outside of a Tokio runtime, or if the [Device] file
+    /// descriptor can't be registered.
+    pub fn into_async(self) -> Result<AsyncDevice> {
+        AsyncDevice::new(self)
+    }
+}
+
+/// A [Device] Driven by the Tokio Reactor
+///
+/// The [Device] file descriptor is registered with the reactor, so that the events can be
+/// awaited for instead of blocking a thread, or needing a dedicated polling thread.
+///
+/// The [Device] can still be used through [`AsyncDevice::device`] for everything else.
+#[derive(Debug)]
+pub struct AsyncDevice {
+    fd: AsyncFd<Device>,
+}
+
+impl AsyncDevice {
+    /// Creates a new [`AsyncDevice`] from a [Device]
+    ///
+    /// A file descriptor can only be registered once with the reactor, so only one
+    /// [`AsyncDevice`] can be created at a time for a [Device] and its clones.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if called outside of a Tokio runtime, or if the [Device] file
+    /// descriptor can't be registered.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{AsyncDevice, Device};
+    ///
+    /// # async fn example() -> nucleid::Result<()> {
+    /// let device = AsyncDevice::new(Device::new("/dev/dri/card0")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(device: Device) -> Result<Self> {
+        Ok(Self {
+            fd: AsyncFd::new(device)?,
+        })
+    }
+
+    /// Returns the underlying [Device]
+    #[must_use]
+    pub fn device(&self) -> &Device {
+        self.fd.get_ref()
+    }
+
+    /// Unregisters the [Device] from the reactor, and returns it
+    #[must_use]
+    pub fn into_device(self) -> Device {
+        self.fd.into_inner()
+    }
+
+    /// Commits an [Update] without blocking, and waits for it to be presented
+    ///
+    /// Returns the [Output] and the [Vblank] at which the [Update] has been presented.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the commit fails, or if the [Device] can't be accessed. Unlike
+    /// [`Update::commit`], the [Update] is lost if the commit fails. To retry it,
+    /// [`Update::commit_nonblocking`] and [`AsyncDevice::wait_for_flip`] can be used instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType, PlaneUpdate};
+    ///
+    /// # async fn example() -> nucleid::Result<()> {
+    /// let device = Device::new("/dev/dri/card0")?.into_async()?;
+    /// let mut output = device.device().first_connected_output()?;
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// loop {
+    ///     let update = output.start_update().add_plane(PlaneUpdate::new(&plane));
+    ///     let (next, vblank) = device.commit(update).await?;
+    ///     output = next;
+    ///
+    ///     println!("Presented at {:?}", vblank.time());
+    /// }
+    /// # }
+    /// ```
+    pub async fn commit(&self, update: Update) -> Result<(Output, Vblank)> {
+        let (output, handle) = update.commit_nonblocking()?;
+        let vblank = self.wait_for_flip(handle).await?;
+
+        Ok((output, vblank))
+    }
+
+    /// Waits for the non-blocking commit identified by `handle` to be presented
+    ///
+    /// Returns the [Vblank] at which the commit has been presented.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed.
+    pub async fn wait_for_flip(&self, handle: CommitHandle) -> Result<Vblank> {
+        let event = self
+            .wait_for_event(|event| matches!(event, Event::Flip(flip) if flip.handle() == handle))
+            .await?;
+
+        match event {
+            Event::Flip(flip) => Ok(flip.vblank()),
+            _ => Err(Error::Empty),
+        }
+    }
+
+    /// Waits for the events sent by the [Device], and reads them
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed.
+    pub async fn read_events(&self) -> Result<Vec<Event>> {
+        loop {
+            if self.device().poll_events(Duration::ZERO)? {
+                return self.device().read_events();
+            }
+
+            self.fd.readable().await?.clear_ready();
+        }
+    }
+
+    /// Returns a [Stream] of the events sent by the [Device]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures_core::Stream;
+    /// use nucleid::{Device, Event};
+    ///
+    /// # async fn example() -> nucleid::Result<()> {
+    /// let device = Device::new("/dev/dri/card0")?.into_async()?;
+    /// let mut events = device.events();
+    ///
+    /// while let Some(event) = std::future::poll_fn(|cx| {
+    ///     std::pin::Pin::new(&mut events).poll_next(cx)
+    /// })
+    /// .await
+    /// {
+    ///     if let Event::Flip(flip) = event? {
+    ///         println!("CRTC {} presented at {:?}", flip.crtc_id(), flip.vblank().time());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn events(&self) -> EventStream<'_> {
+        EventStream {
+            device: self,
+            pending: VecDeque::new(),
+        }
+    }
+
+    async fn wait_for_event<F>(&self, matches: F) -> Result<Event>
+    where
+        F: Fn(&Event) -> bool,
+    {
+        loop {
+            // NOTE: A deadline already expired only looks at the events already available, and
+            // never blocks.
+            match self.device().wait_for_event(&matches, Some(Instant::now())) {
+                Err(Error::Timeout) => {}
+                ret => return ret,
+            }
+
+            self.fd.readable().await?.clear_ready();
+        }
+    }
+}
+
+/// A [Stream] of the Events Sent by a [Device]
+///
+/// It's created by [`AsyncDevice::events`], and never ends.
+#[derive(Debug)]
+pub struct EventStream<'a> {
+    device: &'a AsyncDevice,
+    pending: VecDeque<Event>,
+}
+
+impl Stream for EventStream<'_> {
+    type Item = Result<Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(event) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            let device = this.device.device();
+            match device.poll_events(Duration::ZERO) {
+                Ok(true) => match device.read_events() {
+                    Ok(events) => this.pending.extend(events),
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                },
+                Ok(false) => match this.device.fd.poll_read_ready(cx) {
+                    Poll::Ready(Ok(mut guard)) => guard.clear_ready(),
+                    Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                    Poll::Pending => return Poll::Pending,
+                },
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    output::{commit_properties, PropertyBlobs},
+    CommitFlags, Error, Output, Result, Update,
+};
+
+/// Error returned when a [`DeviceUpdate`] commit fails
+///
+/// Like [`CommitError`](crate::CommitError), it gives the [`DeviceUpdate`] back so that the
+/// commit can be retried, or its [Output]s retrieved.
+///
+/// It converts into an [Error], so the `?` operator can be used in functions returning one.
+#[derive(Debug)]
+pub struct DeviceCommitError {
+    error: Error,
+    update: DeviceUpdate,
+}
+
+impl DeviceCommitError {
+    /// Returns the [Error] the commit failed with
+    #[must_use]
+    pub const fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Consumes the [`DeviceCommitError`] to retrieve the [`DeviceUpdate`] that failed to be
+    /// committed
+    #[must_use]
+    pub fn into_update(self) -> DeviceUpdate {
+        self.update
+    }
+
+    /// Consumes the [`DeviceCommitError`] to retrieve the [Output]s of the failed
+    /// [`DeviceUpdate`]
+    ///
+    /// The [Output]s are left in the state they had before the commit.
+    #[must_use]
+    pub fn into_outputs(self) -> Vec<Output> {
+        self.update.into_outputs()
+    }
+
+    /// Consumes the [`DeviceCommitError`] to retrieve both the [Error] and the [`DeviceUpdate`]
+    #[must_use]
+    pub fn into_parts(self) -> (Error, DeviceUpdate) {
+        (self.error, self.update)
+    }
+}
+
+impl std::fmt::Display for DeviceCommitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for DeviceCommitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<DeviceCommitError> for Error {
+    fn from(err: DeviceCommitError) -> Self {
+        err.error
+    }
+}
+
+/// Updates Several [Output]s at Once
+///
+/// A [`DeviceUpdate`] gathers the [Update]s of several [Output]s of the same
+/// [Device](crate::Device) to commit them in a single atomic commit. They are thus either all
+/// applied, or none of them is, and the new frames are presented at the same time.
+#[derive(Debug, Default)]
+pub struct DeviceUpdate {
+    updates: Vec<Update>,
+}
+
+impl DeviceUpdate {
+    /// Creates an empty [`DeviceUpdate`]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            updates: Vec::new(),
+        }
+    }
+
+    /// Adds the [Update] of an [Output] to the [`DeviceUpdate`]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorUpdate, Device, DeviceUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let mut update = DeviceUpdate::new();
+    /// for connector in device.connected_connectors() {
+    ///     let output = device.output_from_connector(&connector).unwrap();
+    ///     let mode = connector.preferred_mode().unwrap();
+    ///
+    ///     update = update.add_update(
+    ///         output
+    ///             .start_update()
+    ///             .set_mode(mode)
+    ///             .add_connector(ConnectorUpdate::new(&connector)),
+    ///     );
+    /// }
+    ///
+    /// let outputs = update.commit().unwrap();
+    /// ```
+    #[must_use]
+    pub fn add_update(mut self, update: Update) -> Self {
+        self.updates.push(update);
+        self
+    }
+
+    /// Checks whether the [`DeviceUpdate`] would be accepted by the hardware
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [`DeviceUpdate`] is empty, if the [Device](crate::Device) can't
+    /// be accessed or doesn't support atomic commits, or if the [`DeviceUpdate`] is rejected by
+    /// the hardware.
+    pub fn test(&self) -> Result<()> {
+        self.try_commit(CommitFlags::TEST_ONLY | CommitFlags::ALLOW_MODESET)
+    }
+
+    /// Commits all the [Update]s in a single atomic commit
+    ///
+    /// This function will block until the [Update]s are presented, and returns the [Output]s in
+    /// the order their [Update] has been added.
+    ///
+    /// # Errors
+    ///
+    /// Will return a [`DeviceCommitError`] if the [`DeviceUpdate`] is empty, if the
+    /// [Device](crate::Device) can't be accessed or doesn't support atomic commits, or if the
+    /// ioctl fails. The [`DeviceUpdate`] can be retrieved from it to retry the commit.
+    pub fn commit(self) -> std::result::Result<Vec<Output>, DeviceCommitError> {
+        match self.try_commit(CommitFlags::ALLOW_MODESET) {
+            Ok(()) => Ok(self.into_outputs()),
+            Err(error) => Err(DeviceCommitError {
+                error,
+                update: self,
+            }),
+        }
+    }
+
+    fn try_commit(&self, flags: CommitFlags) -> Result<()> {
+        let device = self.updates.first().ok_or(Error::Empty)?.device()?;
+
+        #[cfg(feature = "legacy")]
+        if device.is_legacy() {
+            return Err(Error::Unsupported("Atomic commits"));
+        }
+
+        let mut blobs = PropertyBlobs::new();
+        let properties = self
+            .updates
+            .iter()
+            .try_fold(Vec::new(), |mut properties, update| {
+                properties.extend(update.properties(&device, &mut blobs)?);
+                Ok::<_, Error>(properties)
+            })?;
+
+        if flags.contains(CommitFlags::TEST_ONLY) {
+            return commit_properties(&device, flags.bits(), properties, blobs);
+        }
+
+        let submitted: Vec<_> = self.updates.iter().map(Update::submitted_at).collect();
+
+        commit_properties(&device, flags.bits(), properties, blobs)?;
+
+        for (update, submitted) in self.updates.iter().zip(submitted) {
+            update.record_presented(&device, submitted);
+        }
+
+        Ok(())
+    }
+
+    /// Consumes the [`DeviceUpdate`] to retrieve its [Output]s, without committing it
+    #[must_use]
+    pub fn into_outputs(self) -> Vec<Output> {
+        self.updates.into_iter().map(Update::into_output).collect()
+    }
+}
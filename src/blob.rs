@@ -0,0 +1,120 @@
+use std::{
+    mem::size_of_val,
+    slice,
+    sync::{Arc, Weak},
+};
+
+use crate::{
+    device::Inner,
+    raw::{
+        drm_color_ctm, drm_color_lut, drm_mode_create_property_blob,
+        drm_mode_destroy_property_blob, drm_mode_get_property_blob, drm_mode_modeinfo,
+        drm_mode_rect,
+    },
+    Device, Error, Result,
+};
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for crate::raw::drm_color_ctm {}
+    impl Sealed for crate::raw::drm_color_lut {}
+    impl Sealed for crate::raw::drm_mode_modeinfo {}
+    impl Sealed for crate::raw::drm_mode_rect {}
+}
+
+/// A type a [Blob] can be created from
+///
+/// This is only implemented for bytes and the KMS structures without any padding, such as
+/// [`drm_color_ctm`](crate::sys::drm_color_ctm), so that their memory can be passed to the kernel
+/// as is.
+pub trait BlobData: private::Sealed + Copy + 'static {}
+
+impl BlobData for u8 {}
+impl BlobData for drm_color_ctm {}
+impl BlobData for drm_color_lut {}
+impl BlobData for drm_mode_modeinfo {}
+impl BlobData for drm_mode_rect {}
+
+pub const fn as_bytes<T: BlobData>(data: &[T]) -> &[u8] {
+    // SAFETY: BlobData is only implemented for types without padding bytes, so all of their
+    // memory is initialized, and u8 has no alignment requirement.
+    unsafe { slice::from_raw_parts(data.as_ptr().cast(), size_of_val(data)) }
+}
+
+/// A KMS Property Blob
+///
+/// A [Blob] holds the data of the properties too large to fit in their value, such as a mode, a
+/// gamma LUT, or some HDR metadata. Its ID is then used as the property value.
+///
+/// The [Blob] is destroyed when dropped. The kernel keeps its own reference to the blobs that
+/// are part of the committed state though, so it's safe to drop a [Blob] once it has been
+/// committed, as long as the state isn't re-applied with [`Device::reapply`].
+#[derive(Debug)]
+pub struct Blob {
+    dev: Weak<Inner>,
+    id: u32,
+}
+
+impl Blob {
+    pub(crate) fn new<T: BlobData>(device: &Device, data: &[T]) -> Result<Self> {
+        let id = drm_mode_create_property_blob(device, as_bytes(data))?;
+
+        Ok(Self {
+            dev: Arc::downgrade(&device.inner),
+            id,
+        })
+    }
+
+    /// Returns the [Blob] ID, to be used as a property value
+    #[must_use]
+    pub const fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the content of the [Blob]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, or if the ioctl fails.
+    pub fn data(&self) -> Result<Vec<u8>> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        drm_mode_get_property_blob(&device, self.id)
+    }
+}
+
+impl Drop for Blob {
+    fn drop(&mut self) {
+        // NOTE: The kernel destroys the blobs along with the file descriptor, so there's nothing
+        // left to do if the Device is gone.
+        if let Some(inner) = self.dev.upgrade() {
+            let _res = drm_mode_destroy_property_blob(&Device::from(inner), self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::as_bytes;
+    use crate::raw::{drm_color_lut, drm_mode_modeinfo, drm_mode_rect};
+
+    #[test]
+    fn test_as_bytes() {
+        let rects = [drm_mode_rect {
+            x1: 1,
+            y1: 2,
+            x2: 3,
+            y2: 4,
+        }; 2];
+
+        let bytes = as_bytes(&rects);
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(&bytes[20..24], &2_i32.to_ne_bytes());
+
+        assert_eq!(as_bytes(&[drm_color_lut::default(); 3]).len(), 24);
+        assert_eq!(as_bytes(&[drm_mode_modeinfo::default()]).len(), 68);
+        assert_eq!(as_bytes(&[1_u8, 2, 3]), &[1, 2, 3]);
+    }
+}
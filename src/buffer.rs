@@ -1,20 +1,44 @@
 use std::{
-    cell::RefCell,
-    convert::TryInto,
-    rc::{Rc, Weak},
+    convert::{TryFrom, TryInto},
+    mem::size_of,
+    os::unix::io::{AsRawFd, BorrowedFd, OwnedFd},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
 };
 
 use memmap::{MmapMut, MmapOptions};
 
 use crate::{
+    damage::clip_rects,
     device::Inner,
+    gem::GemHandle,
+    pixel::{cast_mut, fill_row},
     raw::{
-        drm_mode_add_framebuffer, drm_mode_create_dumb_buffer, drm_mode_destroy_dumb_buffer,
-        drm_mode_map_dumb_buffer, drm_mode_remove_framebuffer,
+        dma_buf_size, dma_buf_sync, drm_mode_add_framebuffer, drm_mode_close_framebuffer,
+        drm_mode_create_dumb_buffer, drm_mode_dirty_framebuffer, drm_mode_fb_cmd2,
+        drm_mode_get_framebuffer2, drm_mode_map_dumb_buffer, drm_mode_remove_framebuffer,
+        drm_prime_handle_to_fd, DMA_BUF_SYNC_END, DMA_BUF_SYNC_READ, DMA_BUF_SYNC_RW,
+        DMA_BUF_SYNC_START, DRM_MODE_FB_MODIFIERS,
     },
-    Device, Error, Format, Result,
+    Damage, Device, DmaBuf, DmaBufPlane, Error, Format, Modifier, Pixel, PrimeFlags, Result,
 };
 
+/// Duplicates a dma-buf file descriptor, after checking it holds at least `size` bytes
+///
+/// Mapping past the end of a dma-buf would only fault on the first access.
+fn dmabuf_for_size(fd: &impl AsRawFd, size: usize) -> Result<OwnedFd> {
+    if dma_buf_size(fd)? < size {
+        return Err(Error::DmaBufTooSmall);
+    }
+
+    // SAFETY: The file descriptor is borrowed for the duration of the call.
+    let fd = unsafe { BorrowedFd::borrow_raw(fd.as_raw_fd()) };
+
+    Ok(fd.try_clone_to_owned()?)
+}
+
 /// A DRM Buffer Type
 ///
 /// This Buffer Type contains either the generic DRM Buffer Types or the vendor specific ones.
@@ -22,6 +46,83 @@ use crate::{
 pub enum Type {
     /// A DRM Dumb Buffer, only accessible by the scanout
     Dumb,
+
+    /// A dma-buf allocated from a [`DmaHeap`](crate::DmaHeap), that can be shared with other
+    /// devices
+    #[cfg(target_os = "linux")]
+    DmaHeap(crate::DmaHeap),
+
+    /// A buffer allocated through libgbm, laid out with a [Modifier] every non-cursor
+    /// [Plane](crate::Plane) of the [Device] supports for its [Format]
+    ///
+    /// GBM buffers are allocated by the GPU driver and can be rendered into by the GPU, unlike
+    /// dumb buffers. libgbm is loaded at runtime, and [`Error::Unsupported`] is returned if it's
+    /// missing.
+    ///
+    /// Since libgbm needs a [Format], [`Device::allocate_buffer`] and its variants only support
+    /// 24 and 32 bits per pixel, allocated as [`Format::RGB888`] and [`Format::XRGB8888`]. See
+    /// [`Device::allocate_buffer_with_format`] for the other formats.
+    ///
+    /// Only the buffers laid out with [`Modifier::LINEAR`] are mapped and cleared by the CPU.
+    /// The tiled or compressed ones, and the ones whose layout is implicit, are left untouched
+    /// and have to be drawn into by the GPU, or mapped explicitly with [`Buffer::map`].
+    #[cfg(all(feature = "gbm", target_os = "linux"))]
+    Gbm,
+}
+
+/// The location of a plane in a [Buffer]
+#[derive(Clone, Copy, Debug)]
+struct PlaneLayout {
+    offset: usize,
+    pitch: usize,
+    width: usize,
+    height: usize,
+}
+
+/// A plane of a [Buffer]
+///
+/// The formats like [`Format::NV12`] store their components in several planes, all laid out in
+/// the same [Buffer]. A [`BufferPlane`] gives access to one of them.
+#[derive(Debug)]
+pub struct BufferPlane<'a> {
+    data: &'a mut [u8],
+    layout: PlaneLayout,
+}
+
+impl BufferPlane<'_> {
+    /// Extracts a mutable slice of the plane
+    ///
+    /// The slice starts at the first line of the plane, and ends at its last one.
+    #[must_use]
+    pub const fn data(&mut self) -> &mut [u8] {
+        self.data
+    }
+
+    /// Returns the width, in samples
+    ///
+    /// For the subsampled planes, a sample covers several pixels.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.layout.width
+    }
+
+    /// Returns the height, in lines
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.layout.height
+    }
+
+    /// Returns the pitch, in bytes
+    #[must_use]
+    pub const fn pitch(&self) -> usize {
+        self.layout.pitch
+    }
+
+    /// Returns the offset of the plane in the [Buffer], in bytes
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.layout.offset
+    }
 }
 
 /// A DRM Buffer
@@ -29,18 +130,31 @@ pub enum Type {
 /// A buffer to be used with the rest of the nucleid API. This needs to be turned into a
 /// [Framebuffer] before being sent to the Device
 pub struct Buffer {
-    dev: Weak<RefCell<Inner>>,
+    dev: Weak<Inner>,
     width: usize,
     height: usize,
+    bpp: usize,
     pitch: usize,
     size: usize,
-    handle: u32,
-    mapping: MmapMut,
+    planes: Vec<PlaneLayout>,
+    modifier: Option<Modifier>,
+    handle: GemHandle,
+
+    // NOTE: The buffers laid out with a non-linear modifier aren't mapped, since their CPU
+    // view would be tiled or compressed, and some can't be mapped at all.
+    mapping: Option<MmapMut>,
+
+    // NOTE: The CPU accesses to an imported dma-buf need to be bracketed by DMA_BUF_IOCTL_SYNC
+    // calls for the caches to be coherent. An access starts with the first use of the mapping,
+    // and ends once the Buffer is handed back to the device.
+    dmabuf: Option<OwnedFd>,
+    cpu_access: AtomicBool,
 }
 
 impl Buffer {
     pub(crate) fn new(device: &Device, width: usize, height: usize, bpp: usize) -> Result<Self> {
         let dumb = drm_mode_create_dumb_buffer(device, width, height, bpp)?;
+        let handle = GemHandle::new(device, dumb.handle);
         let map = drm_mode_map_dumb_buffer(device, dumb.handle)?;
 
         // NOTE: dumb.size is a u64, and usize will be a u32 on 32-bits platforms. However, a size
@@ -52,24 +166,269 @@ impl Buffer {
             MmapOptions::new()
                 .len(size)
                 .offset(map.offset)
-                .map_mut(&device.inner.borrow().file)
+                .map_mut(&device.inner.file)
         }?;
 
         Ok(Self {
-            dev: Rc::downgrade(&device.inner),
+            dev: Arc::downgrade(&device.inner),
 
             width: dumb.width as usize,
             height: dumb.height as usize,
+            bpp,
             pitch: dumb.pitch as usize,
             size,
+            planes: vec![PlaneLayout {
+                offset: 0,
+                pitch: dumb.pitch as usize,
+                width: dumb.width as usize,
+                height: dumb.height as usize,
+            }],
+            modifier: None,
+
+            handle,
+            mapping: Some(map),
+            dmabuf: None,
+            cpu_access: AtomicBool::new(false),
+        })
+    }
+
+    pub(crate) fn import(
+        device: &Device,
+        fd: &impl AsRawFd,
+        width: usize,
+        height: usize,
+        bpp: usize,
+        pitch: usize,
+    ) -> Result<Self> {
+        let size = pitch.checked_mul(height).ok_or(Error::DmaBufTooSmall)?;
+        let dmabuf = dmabuf_for_size(fd, size)?;
+
+        let map = unsafe { MmapOptions::new().len(size).map_mut(fd.as_raw_fd()) }?;
+        let handle = device.import_prime_fd(fd)?;
+
+        Ok(Self {
+            dev: Arc::downgrade(&device.inner),
+
+            width,
+            height,
+            bpp,
+            pitch,
+            size,
+            planes: vec![PlaneLayout {
+                offset: 0,
+                pitch,
+                width,
+                height,
+            }],
+            modifier: None,
+
+            handle,
+            mapping: Some(map),
+            dmabuf: Some(dmabuf),
+            cpu_access: AtomicBool::new(false),
+        })
+    }
+
+    /// Imports a dma-buf holding all the planes described by `layout`
+    ///
+    /// The dma-buf is only mapped if `layout` is linear. See [`Buffer::map`].
+    #[cfg(all(feature = "gbm", target_os = "linux"))]
+    pub(crate) fn import_layout(
+        device: &Device,
+        fd: &impl AsRawFd,
+        layout: FramebufferLayout,
+    ) -> Result<Self> {
+        let info = layout.format.info();
+        let (hsub, vsub) = info.subsampling();
+        let planes: Vec<_> = (0..layout.planes)
+            .map(|idx| PlaneLayout {
+                offset: layout.offsets[idx],
+                pitch: layout.pitches[idx],
+                width: if idx == 0 {
+                    layout.width
+                } else {
+                    layout.width.div_ceil(hsub)
+                },
+                height: if idx == 0 {
+                    layout.height
+                } else {
+                    layout.height.div_ceil(vsub)
+                },
+            })
+            .collect();
+
+        let size = planes
+            .iter()
+            .map(|plane| {
+                plane
+                    .pitch
+                    .checked_mul(plane.height)
+                    .and_then(|len| len.checked_add(plane.offset))
+                    .ok_or(Error::DmaBufTooSmall)
+            })
+            .try_fold(0, |size, end| Ok::<_, Error>(end?.max(size)))?;
+        let dmabuf = dmabuf_for_size(fd, size)?;
+
+        let map = if layout.modifier == Some(Modifier::LINEAR) {
+            Some(unsafe { MmapOptions::new().len(size).map_mut(fd.as_raw_fd()) }?)
+        } else {
+            None
+        };
+        let handle = device.import_prime_fd(fd)?;
+
+        Ok(Self {
+            dev: Arc::downgrade(&device.inner),
+
+            width: layout.width,
+            height: layout.height,
+            bpp: info.bpp(),
+            pitch: layout.pitches[0],
+            size,
+            planes,
+            modifier: layout.modifier,
 
-            handle: dumb.handle,
+            handle,
             mapping: map,
+            dmabuf: Some(dmabuf),
+            cpu_access: AtomicBool::new(false),
         })
     }
 
+    /// Splits the [Buffer] into the planes of a multi-planar [Format]
+    ///
+    /// The [Buffer] must have been allocated with the bits per pixel of the first plane, and
+    /// enough lines to hold all the planes. See [`FormatInfo::lines`](crate::FormatInfo).
+    pub(crate) fn with_format(mut self, width: usize, height: usize, fmt: Format) -> Result<Self> {
+        let info = fmt.info();
+        let (hsub, vsub) = info.subsampling();
+        let mut offset = 0;
+
+        self.planes = (0..info.planes())
+            .filter_map(|idx| Some((idx, info.plane_bpp(idx)?)))
+            .map(|(idx, bpp)| {
+                let layout = if idx == 0 {
+                    PlaneLayout {
+                        offset,
+                        pitch: self.pitch,
+                        width,
+                        height,
+                    }
+                } else {
+                    PlaneLayout {
+                        offset,
+                        pitch: (self.pitch * bpp).div_ceil(hsub * info.bpp()),
+                        width: width.div_ceil(hsub),
+                        height: height.div_ceil(vsub),
+                    }
+                };
+
+                offset += layout.pitch * layout.height;
+                layout
+            })
+            .collect();
+
+        if offset > self.size {
+            return Err(Error::PixelLayout);
+        }
+
+        self.width = width;
+        self.height = height;
+
+        Ok(self)
+    }
+
+    pub(crate) const fn handle(&self) -> u32 {
+        self.handle.handle()
+    }
+
+    fn begin_cpu_access(&self) {
+        let Some(dmabuf) = &self.dmabuf else {
+            return;
+        };
+
+        // NOTE: The sync only fails if the exporter can't flush its caches, and there's nothing
+        // we could do about it.
+        if !self.cpu_access.swap(true, Ordering::AcqRel) {
+            let _res = dma_buf_sync(dmabuf, DMA_BUF_SYNC_START | DMA_BUF_SYNC_RW);
+        }
+    }
+
+    /// Ends the current CPU access to the [Buffer], before the device accesses it
+    pub(crate) fn end_cpu_access(&self) {
+        let Some(dmabuf) = &self.dmabuf else {
+            return;
+        };
+
+        if self.cpu_access.swap(false, Ordering::AcqRel) {
+            let _res = dma_buf_sync(dmabuf, DMA_BUF_SYNC_END | DMA_BUF_SYNC_RW);
+        }
+    }
+
+    fn mapping_mut(&mut self) -> &mut [u8] {
+        self.begin_cpu_access();
+        self.mapping.as_deref_mut().unwrap_or_default()
+    }
+
+    /// Returns whether the [Buffer] is mapped, and thus accessible by the CPU
+    ///
+    /// The [Buffer]s are mapped when allocated, unless they are laid out with a non-linear
+    /// [Modifier]. See [`Buffer::map`].
+    #[must_use]
+    pub const fn is_mapped(&self) -> bool {
+        self.mapping.is_some()
+    }
+
+    /// Maps a [Buffer] that wasn't mapped when allocated
+    ///
+    /// The [Buffer]s laid out with a non-linear [Modifier], like the GBM ones can be, aren't
+    /// mapped by default. The mapping then gives access to the raw, tiled or compressed, memory
+    /// of the [Buffer], and the pixel accessors can't be used to interpret it. Does nothing if
+    /// the [Buffer] is already mapped.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::Unsupported`] if the [Buffer] wasn't imported from a dma-buf, and
+    /// [Error] if the dma-buf can't be mapped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Buffer;
+    ///
+    /// # fn allocate_tiled_buffer() -> Buffer { unimplemented!() }
+    /// let mut buffer = allocate_tiled_buffer();
+    ///
+    /// if !buffer.is_mapped() {
+    ///     buffer.map().unwrap();
+    /// }
+    /// ```
+    pub fn map(&mut self) -> Result<()> {
+        if self.mapping.is_some() {
+            return Ok(());
+        }
+
+        let dmabuf = self
+            .dmabuf
+            .as_ref()
+            .ok_or(Error::Unsupported("Buffer Mapping"))?;
+        let map = unsafe {
+            MmapOptions::new()
+                .len(self.size)
+                .map_mut(dmabuf.as_raw_fd())
+        }?;
+
+        self.mapping = Some(map);
+        Ok(())
+    }
+
+    pub(crate) fn device(&self) -> Result<Device> {
+        Ok(self.dev.upgrade().ok_or(Error::DeviceGone)?.into())
+    }
+
     /// Extracts a mutable slice of the entire [Buffer] if it is mapped
     ///
+    /// The slice is empty if the [Buffer] isn't mapped. See [`Buffer::map`].
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -86,7 +445,268 @@ impl Buffer {
     /// ```
     #[must_use]
     pub fn data(&mut self) -> &mut [u8] {
-        &mut self.mapping
+        self.mapping_mut()
+    }
+
+    /// Returns the number of planes of the [Buffer]
+    ///
+    /// Only the [Buffer]s allocated for a multi-planar [Format] have more than one plane.
+    #[must_use]
+    pub const fn planes(&self) -> usize {
+        self.planes.len()
+    }
+
+    /// Returns one of the planes of the [Buffer]
+    ///
+    /// Returns `None` if the [Buffer] doesn't have that many planes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, Format};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let mut buffer = device
+    ///     .allocate_buffer_with_format(BufferType::Dumb, 640, 480, Format::NV12)
+    ///     .unwrap();
+    ///
+    /// // Black, in limited range
+    /// buffer.plane(0).unwrap().data().fill(16);
+    /// buffer.plane(1).unwrap().data().fill(128);
+    /// ```
+    #[must_use]
+    pub fn plane(&mut self, idx: usize) -> Option<BufferPlane<'_>> {
+        let layout = *self.planes.get(idx)?;
+        let data = self.mapping_mut();
+        let end = (layout.offset + layout.pitch * layout.height).min(data.len());
+
+        Some(BufferPlane {
+            data: data.get_mut(layout.offset..end).unwrap_or_default(),
+            layout,
+        })
+    }
+
+    /// Returns an Iterator over the lines of the [Buffer]
+    ///
+    /// Each line is exactly `width` pixels long, and thus never covers the line padding.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let mut buffer = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    ///
+    /// for (y, row) in buffer.rows_mut().enumerate() {
+    ///     row.fill(if y % 2 == 0 { 0xff } else { 0x00 });
+    /// }
+    /// ```
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u8]> + '_ {
+        let len = (self.width * self.bpp).div_ceil(8);
+        let pitch = self.pitch;
+        let end = pitch * self.height;
+
+        self.mapping_mut()
+            .get_mut(..end)
+            .unwrap_or_default()
+            .chunks_exact_mut(pitch)
+            .map(move |row| &mut row[..len])
+    }
+
+    /// Fills the [Buffer] with a single color
+    ///
+    /// `color` is the raw pixel value in the [Buffer] [Format], and is truncated to its size for
+    /// the formats with less than 32 bits per pixel. The line padding is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::PixelLayout`] if the [Buffer] pixels aren't a whole number of bytes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let mut buffer = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    ///
+    /// buffer.fill(0x00ff_0000).unwrap();
+    /// ```
+    pub fn fill(&mut self, color: u32) -> Result<()> {
+        let bpp = self.bpp;
+
+        self.rows_mut()
+            .try_for_each(|row| fill_row(row, bpp, color))
+    }
+
+    /// Returns an Iterator over the [Pixel]s of each line of the [Buffer]
+    ///
+    /// Each line is exactly `width` [Pixel]s long, and thus never covers the line padding.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::PixelLayout`] if the [Buffer] lines can't hold `width` [Pixel]s, if
+    /// the pitch isn't a multiple of the [Pixel] size, or if the [Buffer] isn't mapped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let mut buffer = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    ///
+    /// for row in buffer.pixel_rows_mut::<u32>().unwrap() {
+    ///     for (x, pixel) in row.iter_mut().enumerate() {
+    ///         *pixel = if x < 320 { 0x00ff_0000 } else { 0x0000_00ff };
+    ///     }
+    /// }
+    /// ```
+    pub fn pixel_rows_mut<T: Pixel>(&mut self) -> Result<impl Iterator<Item = &mut [T]> + '_> {
+        let width = self.width;
+        if width * size_of::<T>() > self.pitch || !self.pitch.is_multiple_of(size_of::<T>()) {
+            return Err(Error::PixelLayout);
+        }
+
+        let stride = self.pitch / size_of::<T>();
+        let len = self.pitch * self.height;
+        let pixels = cast_mut::<T>(
+            self.mapping_mut()
+                .get_mut(..len)
+                .ok_or(Error::PixelLayout)?,
+        )?;
+
+        Ok(pixels
+            .chunks_exact_mut(stride)
+            .map(move |row| &mut row[..width]))
+    }
+
+    /// Extracts a mutable slice of [Pixel]s of the entire [Buffer]
+    ///
+    /// For [Buffer]s whose lines are padded, the slice would also cover the padding, so
+    /// [`Buffer::pixel_row_mut`] has to be used instead.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::PixelLayout`] if the [Buffer] lines are padded, don't hold a whole
+    /// number of [Pixel]s, or if the [Buffer] isn't mapped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let mut buffer = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    ///
+    /// let pixels = buffer.as_pixels_mut::<u32>().unwrap();
+    /// pixels.fill(0x00ff_0000);
+    /// ```
+    pub fn as_pixels_mut<T: Pixel>(&mut self) -> Result<&mut [T]> {
+        if self.pitch != self.width * size_of::<T>() {
+            return Err(Error::PixelLayout);
+        }
+
+        let len = self.pitch * self.height;
+        cast_mut(
+            self.mapping_mut()
+                .get_mut(..len)
+                .ok_or(Error::PixelLayout)?,
+        )
+    }
+
+    /// Extracts a mutable slice of the [Pixel]s of a line of the [Buffer]
+    ///
+    /// The slice is exactly `width` [Pixel]s long, and thus never covers the line padding.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::Empty`] if the line is past the [Buffer] height, and
+    /// [`Error::PixelLayout`] if the [Buffer] lines can't hold `width` [Pixel]s, or if the
+    /// [Buffer] isn't mapped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let mut buffer = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    ///
+    /// let line = buffer.pixel_row_mut::<u32>(10).unwrap();
+    /// line.fill(0x00ff_0000);
+    /// ```
+    pub fn pixel_row_mut<T: Pixel>(&mut self, y: usize) -> Result<&mut [T]> {
+        if y >= self.height {
+            return Err(Error::Empty);
+        }
+
+        let len = self.width * size_of::<T>();
+        if len > self.pitch {
+            return Err(Error::PixelLayout);
+        }
+
+        let start = y * self.pitch;
+        cast_mut(
+            self.mapping_mut()
+                .get_mut(start..start + len)
+                .ok_or(Error::PixelLayout)?,
+        )
+    }
+
+    /// Exports the [Buffer] as a dma-buf
+    ///
+    /// The dma-buf can then be handed to another device, like a video encoder or a GPU, to
+    /// access the [Buffer] without any copy. Each call creates a new file descriptor. See
+    /// [`Framebuffer::export`] to also get the layout of a [Framebuffer].
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails, for example
+    /// if the driver doesn't support PRIME exports.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, PrimeFlags};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let buffer = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    ///
+    /// let fd = buffer.export_dmabuf(PrimeFlags::CLOEXEC)
+    ///     .unwrap();
+    /// ```
+    pub fn export_dmabuf(&self, flags: PrimeFlags) -> Result<OwnedFd> {
+        let device = self.device()?;
+        self.end_cpu_access();
+
+        drm_prime_handle_to_fd(&device, self.handle(), flags.bits())
+    }
+
+    pub(crate) fn pixels(&self) -> &[u8] {
+        self.begin_cpu_access();
+        self.mapping.as_deref().unwrap_or_default()
     }
 
     /// Returns the height, in lines
@@ -208,30 +828,58 @@ impl Buffer {
     ///     .unwrap();
     /// ```
     pub fn into_framebuffer(self, fmt: Format) -> Result<Framebuffer> {
-        let device: Device = self.dev.upgrade().ok_or(Error::Empty)?.into();
-
-        let id = drm_mode_add_framebuffer(
-            &device,
-            self.handle,
-            self.width.try_into()?,
-            self.pitch.try_into()?,
-            self.height.try_into()?,
-            fmt as u32,
-        )?;
+        let mut layout = self.planes.iter().skip(1).fold(
+            FramebufferLayout::new(self.width, self.height, fmt, self.pitch),
+            |layout, plane| layout.add_plane(plane.pitch, plane.offset),
+        );
 
-        Ok(Framebuffer {
-            dev: Rc::downgrade(&device.inner),
-            buffer: self,
-            id,
-        })
+        if let Some(modifier) = self.modifier {
+            layout = layout.set_modifier(modifier);
+        }
+
+        self.into_framebuffer_with_layout(layout)
     }
-}
 
-impl Drop for Buffer {
-    fn drop(&mut self) {
-        let device: Device = self.dev.upgrade().ok_or(Error::Empty).unwrap().into();
+    /// Request the creation of a [Framebuffer] with a given [`FramebufferLayout`]
+    ///
+    /// This allows to create [Framebuffer]s for the formats with multiple planes, all stored in
+    /// the [Buffer] at different offsets, or for [Buffer]s that have been laid out according to
+    /// a [Modifier].
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails, typically
+    /// because the layout doesn't fit in the [Buffer].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, Format, FramebufferLayout, Modifier};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let buffer = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    ///
+    /// let layout = FramebufferLayout::new(640, 480, Format::XRGB8888, buffer.pitch())
+    ///     .set_modifier(Modifier::LINEAR);
+    ///
+    /// let fb = buffer.into_framebuffer_with_layout(layout)
+    ///     .unwrap();
+    /// ```
+    pub fn into_framebuffer_with_layout(self, layout: FramebufferLayout) -> Result<Framebuffer> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        let id = drm_mode_add_framebuffer(&device, layout.to_raw(&[self.handle()])?)?;
+        self.end_cpu_access();
 
-        let _res = drm_mode_destroy_dumb_buffer(&device, self.handle);
+        Ok(Framebuffer {
+            dev: Arc::downgrade(&device.inner),
+            buffer: self,
+            layout,
+            id,
+        })
     }
 }
 
@@ -242,7 +890,8 @@ impl std::fmt::Debug for Buffer {
             .field("height", &self.height)
             .field("pitch", &self.pitch)
             .field("size", &self.size)
-            .finish()
+            .field("planes", &self.planes)
+            .finish_non_exhaustive()
     }
 }
 
@@ -253,8 +902,9 @@ impl std::fmt::Debug for Buffer {
 /// [`PlaneUpdate`](crate::PlaneUpdate).
 #[derive(Debug)]
 pub struct Framebuffer {
-    dev: Weak<RefCell<Inner>>,
+    dev: Weak<Inner>,
     buffer: Buffer,
+    layout: FramebufferLayout,
     id: u32,
 }
 
@@ -262,6 +912,202 @@ impl Framebuffer {
     pub(crate) const fn id(&self) -> u32 {
         self.id
     }
+
+    /// Returns the [Framebuffer] [Format]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, Format};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let fb = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(fb.format(), Format::XRGB8888);
+    /// ```
+    #[must_use]
+    pub const fn format(&self) -> Format {
+        self.layout.format
+    }
+
+    /// Returns the [Framebuffer] [`FramebufferLayout`]
+    #[must_use]
+    pub const fn layout(&self) -> FramebufferLayout {
+        self.layout
+    }
+
+    /// Exports the [Framebuffer] as a dma-buf
+    ///
+    /// The returned [`DmaBuf`] describes the buffer layout the way EGL and Vulkan expect it, so
+    /// that a GPU can render into the [Framebuffer]. Each call creates new file descriptors.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, Format};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let fb = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// let dmabuf = fb.export().unwrap();
+    /// assert_eq!(dmabuf.planes().len(), 1);
+    /// ```
+    pub fn export(&self) -> Result<DmaBuf> {
+        let fd = self.export_dmabuf(PrimeFlags::default())?;
+
+        let layout = self.layout;
+        let planes = (0..layout.planes)
+            .map(|idx| {
+                // NOTE: Every plane needs its own file descriptor, even when sharing the buffer.
+                Ok(DmaBufPlane::new(
+                    fd.try_clone()?,
+                    layout.offsets[idx].try_into()?,
+                    layout.pitches[idx].try_into()?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DmaBuf::new(
+            layout.width.try_into()?,
+            layout.height.try_into()?,
+            layout.format,
+            layout.modifier.unwrap_or(Modifier::LINEAR).into(),
+            self.size().try_into()?,
+            planes,
+        ))
+    }
+
+    /// Notifies the kernel of the [Framebuffer] areas damaged during the current frame
+    ///
+    /// Some display controllers, like USB or SPI displays, or virtual ones, don't scan out the
+    /// [Framebuffer] continuously and need to be told which areas to update. This is only needed
+    /// when the [Framebuffer] content is changed without a new commit, the damage being passed
+    /// through [`PlaneUpdate::set_damage`](crate::PlaneUpdate::set_damage) otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails. The latter
+    /// will happen if the driver doesn't need to be notified.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Damage, Device, Format, Rect};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let mut fb = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// let mut damage = Damage::new(640, 480);
+    ///
+    /// fb.data()[..640 * 4].fill(0xff);
+    /// damage.add(Rect::new(0, 0, 640, 1));
+    ///
+    /// fb.flush(&damage).unwrap();
+    /// damage.end_frame();
+    /// ```
+    pub fn flush(&self, damage: &Damage) -> Result<()> {
+        let rects = damage.rects();
+        if rects.is_empty() {
+            return Ok(());
+        }
+
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+        self.end_cpu_access();
+
+        drm_mode_dirty_framebuffer(&device, self.id, &clip_rects(rects)?)
+    }
+
+    /// Releases the [Framebuffer] while keeping it on screen
+    ///
+    /// Dropping a [Framebuffer] removes it, and disables any [Plane](crate::Plane) it was
+    /// attached to. Closing it instead will leave the [Planes](crate::Plane) untouched, and the
+    /// kernel will free the [Framebuffer] once it isn't used anymore. This is useful to hand over
+    /// the display to another application, like a boot splash would, without any black frame.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails. The latter
+    /// will happen on kernels older than 6.8.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, Format};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let fb = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// fb.close().unwrap();
+    /// ```
+    pub fn close(mut self) -> Result<()> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        drm_mode_close_framebuffer(&device, self.id)?;
+
+        // NOTE: The framebuffer ID is now invalid for us, let Drop know it shouldn't remove it.
+        self.id = 0;
+
+        Ok(())
+    }
+
+    /// Creates another Frame Buffer over the same memory, with a different layout
+    ///
+    /// This allows, for example, to scan out only a part of the [Framebuffer] through an offset,
+    /// or to interpret its content in another [Format]. The memory is shared, and is only
+    /// released once both the [Framebuffer] and the [`ForeignFramebuffer`] are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails, typically
+    /// because the layout doesn't fit in the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, Format, FramebufferLayout};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let fb = device.allocate_buffer(BufferType::Dumb, 640, 960, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// let layout = FramebufferLayout::new(640, 480, Format::XRGB8888, fb.pitch())
+    ///     .set_offset(fb.pitch() * 480);
+    /// let bottom = fb.view(layout)
+    ///     .unwrap();
+    /// ```
+    pub fn view(&self, layout: FramebufferLayout) -> Result<ForeignFramebuffer> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        ForeignFramebuffer::with_handles(&device, vec![self.buffer.handle.clone()], layout)
+    }
 }
 
 impl std::ops::Deref for Framebuffer {
@@ -280,8 +1126,467 @@ impl std::ops::DerefMut for Framebuffer {
 
 impl Drop for Framebuffer {
     fn drop(&mut self) {
-        let device: Device = self.dev.upgrade().ok_or(Error::Empty).unwrap().into();
+        if self.id == 0 {
+            return;
+        }
+
+        // NOTE: The kernel removes the framebuffers along with the file descriptor, so there's
+        // nothing left to do if the Device is gone.
+        if let Some(inner) = self.dev.upgrade() {
+            let _res = drm_mode_remove_framebuffer(&Device::from(inner), self.id);
+        }
+    }
+}
+
+/// The memory layout of a [Framebuffer] or [`ForeignFramebuffer`]
+///
+/// A layout describes up to four planes, each with its own pitch and offset, for the formats
+/// storing their components in separate planes. It can also carry a [Modifier] for the buffers
+/// that aren't laid out linearly.
+#[derive(Clone, Copy, Debug)]
+pub struct FramebufferLayout {
+    width: usize,
+    height: usize,
+    format: Format,
+    pitches: [usize; 4],
+    offsets: [usize; 4],
+    planes: usize,
+    modifier: Option<Modifier>,
+}
+
+impl FramebufferLayout {
+    /// Creates a new [`FramebufferLayout`] with a single plane
+    ///
+    /// The pixels are assumed to start at the beginning of the buffer.
+    #[must_use]
+    pub const fn new(width: usize, height: usize, format: Format, pitch: usize) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            pitches: [pitch, 0, 0, 0],
+            offsets: [0; 4],
+            planes: 1,
+            modifier: None,
+        }
+    }
+
+    /// Sets the offset of the first pixel of the first plane in the buffer, in bytes
+    #[must_use]
+    pub const fn set_offset(mut self, offset: usize) -> Self {
+        self.offsets[0] = offset;
+        self
+    }
+
+    /// Adds a plane, with its pitch and the offset of its first pixel, in bytes
+    ///
+    /// # Panics
+    ///
+    /// If the layout already has four planes.
+    #[must_use]
+    pub const fn add_plane(mut self, pitch: usize, offset: usize) -> Self {
+        assert!(
+            self.planes < 4,
+            "A framebuffer can't have more than 4 planes"
+        );
+
+        self.pitches[self.planes] = pitch;
+        self.offsets[self.planes] = offset;
+        self.planes += 1;
+        self
+    }
+
+    /// Sets the format [Modifier] of every plane
+    ///
+    /// Without a [Modifier], the driver will pick the layout it expects for the buffer, which
+    /// is usually linear.
+    #[must_use]
+    pub const fn set_modifier(mut self, modifier: Modifier) -> Self {
+        self.modifier = Some(modifier);
+        self
+    }
+
+    /// Returns the number of planes
+    #[must_use]
+    pub const fn planes(&self) -> usize {
+        self.planes
+    }
+
+    /// Returns the format [Modifier], if any
+    #[must_use]
+    pub const fn modifier(&self) -> Option<Modifier> {
+        self.modifier
+    }
+
+    /// Creates the raw framebuffer description for this layout
+    ///
+    /// If there's less handles than planes, the last handle is used for the remaining planes,
+    /// so that all the planes can share a single buffer.
+    fn to_raw(self, handles: &[u32]) -> Result<drm_mode_fb_cmd2> {
+        let mut fb = drm_mode_fb_cmd2 {
+            width: self.width.try_into()?,
+            height: self.height.try_into()?,
+            pixel_format: self.format as u32,
+            ..drm_mode_fb_cmd2::default()
+        };
+
+        let last = *handles.last().ok_or(Error::Empty)?;
+        for idx in 0..self.planes {
+            fb.handles[idx] = handles.get(idx).copied().unwrap_or(last);
+            fb.pitches[idx] = self.pitches[idx].try_into()?;
+            fb.offsets[idx] = self.offsets[idx].try_into()?;
+
+            if let Some(modifier) = self.modifier {
+                fb.modifier[idx] = modifier.into();
+            }
+        }
+
+        if self.modifier.is_some() {
+            fb.flags |= DRM_MODE_FB_MODIFIERS;
+        }
+
+        Ok(fb)
+    }
+}
+
+/// A Frame Buffer wrapping a buffer allocated outside of nucleid
+///
+/// A [`ForeignFramebuffer`] is created by [`Device::framebuffer_from_handle`] from a GEM handle
+/// obtained by other means on the same [Device] file descriptor, for example through a vendor
+/// specific allocation ioctl or a library sharing the file descriptor.
+///
+/// The GEM handle remains owned by the caller: nucleid will never close it. The kernel keeps the
+/// underlying buffer alive as long as the [`ForeignFramebuffer`] exists, so the handle can be
+/// closed by its owner right after the [`ForeignFramebuffer`] creation.
+///
+/// A [`ForeignFramebuffer`] can also be created by [`Framebuffer::view`] over the memory of a
+/// [Framebuffer], in which case nucleid shares the GEM handle between the two, or by
+/// [`Device::import_dmabuf`] from dma-bufs, in which case nucleid owns the imported GEM handles.
+///
+/// Unlike a [Framebuffer], its content can't be accessed through nucleid.
+#[derive(Debug)]
+pub struct ForeignFramebuffer {
+    dev: Weak<Inner>,
+    id: u32,
+    layout: FramebufferLayout,
+
+    // NOTE: The handles are only owned by nucleid when created from one of its Framebuffers, or
+    // imported from dma-bufs.
+    _handles: Vec<GemHandle>,
+}
+
+impl ForeignFramebuffer {
+    pub(crate) fn new(device: &Device, handles: &[u32], layout: FramebufferLayout) -> Result<Self> {
+        Self::create(device, handles, Vec::new(), layout)
+    }
+
+    pub(crate) fn with_handles(
+        device: &Device,
+        handles: Vec<GemHandle>,
+        layout: FramebufferLayout,
+    ) -> Result<Self> {
+        let raw: Vec<_> = handles.iter().map(GemHandle::handle).collect();
+
+        Self::create(device, &raw, handles, layout)
+    }
+
+    fn create(
+        device: &Device,
+        handles: &[u32],
+        owned: Vec<GemHandle>,
+        layout: FramebufferLayout,
+    ) -> Result<Self> {
+        let id = drm_mode_add_framebuffer(device, layout.to_raw(handles)?)?;
+
+        Ok(Self {
+            dev: Arc::downgrade(&device.inner),
+            id,
+            layout,
+            _handles: owned,
+        })
+    }
+
+    pub(crate) const fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the width, in pixels
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.layout.width
+    }
+
+    /// Returns the height, in lines
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.layout.height
+    }
+
+    /// Returns the [Format]
+    #[must_use]
+    pub const fn format(&self) -> Format {
+        self.layout.format
+    }
+}
+
+impl Drop for ForeignFramebuffer {
+    fn drop(&mut self) {
+        // NOTE: The kernel removes the framebuffers along with the file descriptor, so there's
+        // nothing left to do if the Device is gone.
+        if let Some(inner) = self.dev.upgrade() {
+            let _res = drm_mode_remove_framebuffer(&Device::from(inner), self.id);
+        }
+    }
+}
+
+/// A Frame Buffer created by someone else
+///
+/// An [`AdoptedFramebuffer`] is a [Framebuffer] that is currently scanned out, but that has been
+/// created by another application or by the kernel, typically a boot splash or the firmware
+/// console. It's retrieved through [`Plane::adopt_framebuffer`](crate::Plane::adopt_framebuffer).
+///
+/// Since nucleid doesn't own it, it can't remove it: it can only be kept on screen until the
+/// application commits its own first frame, after which the [`AdoptedFramebuffer`] can be dropped.
+/// The kernel will free it once it isn't used anymore.
+///
+/// Any framebuffer can also be looked up with [`Device::framebuffer_info`], for example to take a
+/// screenshot of what's currently displayed.
+#[derive(Debug)]
+pub struct AdoptedFramebuffer {
+    dev: Weak<Inner>,
+    id: u32,
+    width: u32,
+    height: u32,
+    format: u32,
+    modifier: u64,
+    handles: [u32; 4],
+    _handles: Vec<GemHandle>,
+    pitches: [u32; 4],
+    offsets: [u32; 4],
+}
+
+impl AdoptedFramebuffer {
+    pub(crate) fn new(device: &Device, id: u32) -> Result<Self> {
+        let fb = drm_mode_get_framebuffer2(device, id)?;
+
+        // NOTE: The kernel only fills the handles if we're allowed to access the buffers, and
+        // will return the same handle for planes sharing the same buffer.
+        let mut handles: Vec<_> = fb.handles.iter().copied().filter(|h| *h != 0).collect();
+        handles.sort_unstable();
+        handles.dedup();
+
+        Ok(Self {
+            dev: Arc::downgrade(&device.inner),
+            id,
+            width: fb.width,
+            height: fb.height,
+            format: fb.pixel_format,
+            modifier: fb.modifier[0],
+            handles: fb.handles,
+            _handles: handles
+                .into_iter()
+                .map(|handle| GemHandle::new(device, handle))
+                .collect(),
+            pitches: fb.pitches,
+            offsets: fb.offsets,
+        })
+    }
+
+    fn device(&self) -> Result<Device> {
+        Ok(self.dev.upgrade().ok_or(Error::DeviceGone)?.into())
+    }
+
+    /// Returns the GEM handles of each plane
+    ///
+    /// The kernel only gives access to the buffers to the DRM master and to the privileged
+    /// clients, the handles are 0 otherwise.
+    #[must_use]
+    pub const fn handles(&self) -> [u32; 4] {
+        self.handles
+    }
+
+    fn plane_count(&self) -> usize {
+        self.pitches.iter().take_while(|pitch| **pitch != 0).count()
+    }
+
+    /// Exports the framebuffer as a dma-buf
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::UnsupportedFormat`] if nucleid doesn't support the [Format],
+    /// [`Error::BufferNotAccessible`] if we're not allowed to access the buffers, or [Error] if
+    /// the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.plane_type() == PlaneType::Primary)
+    ///     .unwrap();
+    ///
+    /// if let Some(fb) = plane.adopt_framebuffer().unwrap() {
+    ///     let dmabuf = fb.export().unwrap();
+    /// }
+    /// ```
+    pub fn export(&self) -> Result<DmaBuf> {
+        let device = self.device()?;
+        let format = self.format().ok_or(Error::UnsupportedFormat(self.format))?;
+
+        let planes = (0..self.plane_count())
+            .map(|idx| {
+                let handle = self.handles[idx];
+                if handle == 0 {
+                    return Err(Error::BufferNotAccessible);
+                }
+
+                Ok(DmaBufPlane::new(
+                    drm_prime_handle_to_fd(&device, handle, PrimeFlags::default().bits())?,
+                    self.offsets[idx],
+                    self.pitches[idx],
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let size = dma_buf_size(planes.first().ok_or(Error::Empty)?.fd())?;
+
+        Ok(DmaBuf::new(
+            self.width,
+            self.height,
+            format,
+            self.modifier,
+            u64::try_from(size)?,
+            planes,
+        ))
+    }
+
+    /// Reads the content of the first plane of the framebuffer
+    ///
+    /// This returns `pitch * height` bytes, starting at the offset of the first plane. The
+    /// content is only meaningful to the CPU if the framebuffer is laid out linearly.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the framebuffer can't be exported, or if it can't be mapped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.plane_type() == PlaneType::Primary)
+    ///     .unwrap();
+    ///
+    /// if let Some(fb) = plane.adopt_framebuffer().unwrap() {
+    ///     let pixels = fb.read_pixels().unwrap();
+    ///     std::fs::write("screenshot.raw", pixels).unwrap();
+    /// }
+    /// ```
+    pub fn read_pixels(&self) -> Result<Vec<u8>> {
+        let dmabuf = self.export()?;
+        let plane = dmabuf.planes().first().ok_or(Error::Empty)?;
+
+        let offset = usize::try_from(plane.offset())?;
+        let len = usize::try_from(plane.stride())? * usize::try_from(self.height)?;
+
+        let map = unsafe {
+            MmapOptions::new()
+                .len(offset + len)
+                .map(plane.fd().as_raw_fd())
+        }?;
+
+        // NOTE: The read needs to be bracketed by syncs for the caches to be coherent, like the
+        // Buffer accesses. A failed sync would still leave us with a mostly accurate picture.
+        let _res = dma_buf_sync(plane.fd(), DMA_BUF_SYNC_START | DMA_BUF_SYNC_READ);
+        let pixels = map[offset..offset + len].to_vec();
+        let _res = dma_buf_sync(plane.fd(), DMA_BUF_SYNC_END | DMA_BUF_SYNC_READ);
+
+        Ok(pixels)
+    }
+
+    pub(crate) const fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the width, in pixels
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height, in lines
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the [Format], if supported by nucleid
+    #[must_use]
+    pub fn format(&self) -> Option<Format> {
+        Format::try_from(self.format).ok()
+    }
+
+    /// Returns the raw fourcc code of the [Format]
+    #[must_use]
+    pub const fn fourcc(&self) -> u32 {
+        self.format
+    }
+
+    /// Returns the format modifier
+    #[must_use]
+    pub const fn modifier(&self) -> u64 {
+        self.modifier
+    }
+
+    /// Returns the pitches of each plane, in bytes
+    #[must_use]
+    pub const fn pitches(&self) -> [u32; 4] {
+        self.pitches
+    }
+
+    /// Returns the offsets of each plane, in bytes
+    #[must_use]
+    pub const fn offsets(&self) -> [u32; 4] {
+        self.offsets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FramebufferLayout;
+    use crate::{raw::DRM_MODE_FB_MODIFIERS, Format, Modifier};
+
+    #[test]
+    fn test_layout_to_raw() {
+        let fb = FramebufferLayout::new(640, 480, Format::XRGB8888, 2560)
+            .to_raw(&[42])
+            .unwrap();
+        assert_eq!(fb.handles, [42, 0, 0, 0]);
+        assert_eq!(fb.pitches, [2560, 0, 0, 0]);
+        assert_eq!(fb.flags, 0);
+
+        let fb = FramebufferLayout::new(640, 480, Format::XRGB8888, 2560)
+            .set_offset(128)
+            .add_plane(640, 2560 * 480)
+            .set_modifier(Modifier::new(1 << 56))
+            .to_raw(&[42])
+            .unwrap();
+        assert_eq!(fb.handles, [42, 42, 0, 0]);
+        assert_eq!(fb.pitches, [2560, 640, 0, 0]);
+        assert_eq!(fb.offsets, [128, 2560 * 480, 0, 0]);
+        assert_eq!(fb.modifier, [1 << 56, 1 << 56, 0, 0]);
+        assert_eq!(fb.flags, DRM_MODE_FB_MODIFIERS);
 
-        let _res = drm_mode_remove_framebuffer(&device, self.id);
+        assert!(FramebufferLayout::new(640, 480, Format::XRGB8888, 2560)
+            .to_raw(&[])
+            .is_err());
     }
 }
@@ -0,0 +1,273 @@
+use crate::raw::{drm_color_ctm, drm_color_lut};
+
+const NEUTRAL_TEMPERATURE: u32 = 6500;
+
+// NOTE: 1.0 in the S31.32 fixed point format
+const S31_32_ONE: f64 = 4_294_967_296.0;
+
+// NOTE: This is Tanner Helland's fit of the blackbody radiation color, which is accurate enough
+// between 1000K and 40000K for our purpose.
+fn blackbody(kelvin: u32) -> [f64; 3] {
+    let temp = f64::from(kelvin.clamp(1000, 40000)) / 100.0;
+
+    let red = if temp <= 66.0 {
+        255.0
+    } else {
+        329.698727446 * (temp - 60.0).powf(-0.1332047592)
+    };
+
+    let green = if temp <= 66.0 {
+        99.4708025861f64.mul_add(temp.ln(), -161.1195681661)
+    } else {
+        288.1221695283 * (temp - 60.0).powf(-0.0755148492)
+    };
+
+    let blue = if temp >= 66.0 {
+        255.0
+    } else if temp <= 19.0 {
+        0.0
+    } else {
+        138.5177312231f64.mul_add((temp - 10.0).ln(), -305.0447927307)
+    };
+
+    [red, green, blue].map(|val| (val / 255.0).clamp(0.0, 1.0))
+}
+
+/// Returns the factor to apply to each channel to shift the white point to a given temperature
+///
+/// The factors are normalized so that a temperature of 6500K, the white point of sRGB, is neutral.
+pub fn white_point(kelvin: u32) -> [f64; 3] {
+    let neutral = blackbody(NEUTRAL_TEMPERATURE);
+    let target = blackbody(kelvin);
+
+    [0, 1, 2].map(|idx| (target[idx] / neutral[idx]).clamp(0.0, 1.0))
+}
+
+fn ramp_value(idx: usize, size: usize, factor: f64) -> u16 {
+    // NOTE: LUTs are at most a few thousand entries large, so they can be represented exactly
+    #[allow(clippy::cast_precision_loss)]
+    let pos = idx as f64 / (size.max(2) - 1) as f64;
+
+    // NOTE: Both pos and factor are within [0.0; 1.0], so the result fits in a u16
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let val = (pos * factor * f64::from(u16::MAX)).round() as u16;
+
+    val
+}
+
+/// Computes a linear gamma ramp of `size` entries for each channel, scaled by `factors`
+pub fn gamma_ramp(size: usize, factors: [f64; 3]) -> [Vec<u16>; 3] {
+    factors.map(|factor| (0..size).map(|idx| ramp_value(idx, size, factor)).collect())
+}
+
+/// Computes a linear gamma LUT of `size` entries, scaled by `factors`
+pub fn gamma_lut(size: usize, factors: [f64; 3]) -> Vec<drm_color_lut> {
+    (0..size)
+        .map(|idx| drm_color_lut {
+            red: ramp_value(idx, size, factors[0]),
+            green: ramp_value(idx, size, factors[1]),
+            blue: ramp_value(idx, size, factors[2]),
+            reserved: 0,
+        })
+        .collect()
+}
+
+/// An entry of a gamma or degamma LUT
+///
+/// Each channel goes from 0, black, to [`u16::MAX`], full intensity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LutEntry {
+    /// The red channel intensity
+    pub red: u16,
+
+    /// The green channel intensity
+    pub green: u16,
+
+    /// The blue channel intensity
+    pub blue: u16,
+}
+
+impl LutEntry {
+    /// Creates a new [`LutEntry`]
+    #[must_use]
+    pub const fn new(red: u16, green: u16, blue: u16) -> Self {
+        Self { red, green, blue }
+    }
+}
+
+fn lerp_u16(a: u16, b: u16, t: f64) -> u16 {
+    let val = (f64::from(b) - f64::from(a)).mul_add(t, f64::from(a));
+
+    // NOTE: The value is between a and b, so it fits in a u16
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let val = val.round() as u16;
+
+    val
+}
+
+/// Converts a LUT to the kernel layout, with `size` entries
+///
+/// The LUT is linearly interpolated if its size doesn't match the one supported by the hardware.
+/// An empty LUT is converted to a linear ramp, leaving the colors untouched.
+pub fn resample_lut(entries: &[LutEntry], size: usize) -> Vec<drm_color_lut> {
+    let to_raw = |entry: LutEntry| drm_color_lut {
+        red: entry.red,
+        green: entry.green,
+        blue: entry.blue,
+        reserved: 0,
+    };
+
+    if entries.is_empty() {
+        return gamma_lut(size, [1.0; 3]);
+    }
+
+    if entries.len() == size {
+        return entries.iter().copied().map(to_raw).collect();
+    }
+
+    let last = entries.len().saturating_sub(1);
+    (0..size)
+        .map(|idx| {
+            // NOTE: LUTs are at most a few thousand entries large, so they can be represented
+            // exactly
+            #[allow(clippy::cast_precision_loss)]
+            let pos = idx as f64 * last as f64 / (size.max(2) - 1) as f64;
+
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let low = (pos.floor() as usize).min(last.saturating_sub(1));
+            let high = (low + 1).min(last);
+
+            #[allow(clippy::cast_precision_loss)]
+            let frac = pos - low as f64;
+
+            let (a, b) = (entries[low], entries[high]);
+            to_raw(LutEntry {
+                red: lerp_u16(a.red, b.red, frac),
+                green: lerp_u16(a.green, b.green, frac),
+                blue: lerp_u16(a.blue, b.blue, frac),
+            })
+        })
+        .collect()
+}
+
+/// A 3x3 Color Transformation Matrix
+///
+/// The matrix is applied to the `(R, G, B)` column vector of each pixel, so the first row gives
+/// the output red channel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix3x3(pub [[f64; 3]; 3]);
+
+impl Matrix3x3 {
+    /// The identity matrix, leaving the colors untouched
+    pub const IDENTITY: Self = Self([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+
+    // NOTE: The kernel expects the coefficients in the S31.32 sign-magnitude format, which
+    // isn't the two's complement.
+    fn coefficient(val: f64) -> u64 {
+        // NOTE: The float to integer casts saturate, and a CTM coefficient is at most a few
+        // units, so the magnitude fits in 63 bits.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let magnitude = ((val.abs() * S31_32_ONE).round() as u64) & !(1 << 63);
+
+        if val.is_sign_negative() && magnitude != 0 {
+            magnitude | (1 << 63)
+        } else {
+            magnitude
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> drm_color_ctm {
+        let mut matrix = [0; 9];
+
+        for (row, coefficients) in self.0.iter().enumerate() {
+            for (col, &val) in coefficients.iter().enumerate() {
+                matrix[row * 3 + col] = Self::coefficient(val);
+            }
+        }
+
+        drm_color_ctm { matrix }
+    }
+}
+
+impl Default for Matrix3x3 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gamma_lut, gamma_ramp, resample_lut, white_point, LutEntry, Matrix3x3};
+
+    #[test]
+    fn test_white_point() {
+        assert!(white_point(6500)
+            .iter()
+            .all(|val| (val - 1.0).abs() < f64::EPSILON));
+
+        let [red, green, blue] = white_point(3000);
+        assert!((red - 1.0).abs() < f64::EPSILON);
+        assert!(green < red);
+        assert!(blue < green);
+
+        let [red, green, blue] = white_point(10000);
+        assert!(red < 1.0);
+        assert!(green < 1.0);
+        assert!((blue - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_gamma_ramp() {
+        let [red, green, blue] = gamma_ramp(256, [1.0, 0.5, 0.0]);
+
+        assert_eq!(red.len(), 256);
+        assert_eq!(red[0], 0);
+        assert_eq!(red[255], u16::MAX);
+        assert_eq!(green[255], 32768);
+        assert!(blue.iter().all(|&val| val == 0));
+
+        let lut = gamma_lut(256, [1.0, 0.5, 0.0]);
+        assert_eq!(lut.len(), 256);
+        assert_eq!(lut[255].red, u16::MAX);
+        assert_eq!(lut[255].green, 32768);
+        assert_eq!(lut[255].blue, 0);
+    }
+
+    #[test]
+    fn test_resample_lut() {
+        let lut = [LutEntry::new(0, 0, 0), LutEntry::new(u16::MAX, 1000, 0)];
+
+        let raw = resample_lut(&lut, 2);
+        assert_eq!(raw.len(), 2);
+        assert_eq!(raw[1].red, u16::MAX);
+
+        let raw = resample_lut(&lut, 5);
+        assert_eq!(raw.len(), 5);
+        assert_eq!(raw[0].red, 0);
+        assert_eq!(raw[2].red, 32768);
+        assert_eq!(raw[2].green, 500);
+        assert_eq!(raw[4].red, u16::MAX);
+        assert_eq!(raw[4].green, 1000);
+    }
+
+    #[test]
+    fn test_resample_empty_lut() {
+        let raw = resample_lut(&[], 3);
+        assert_eq!(raw.len(), 3);
+        assert_eq!(raw[0].red, 0);
+        assert_eq!(raw[1].green, 32768);
+        assert_eq!(raw[2].blue, u16::MAX);
+
+        assert!(resample_lut(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn test_ctm() {
+        let raw = Matrix3x3::IDENTITY.to_raw();
+        assert_eq!(raw.matrix, [1 << 32, 0, 0, 0, 1 << 32, 0, 0, 0, 1 << 32]);
+
+        let raw = Matrix3x3([[-0.5, 0.0, 0.0], [0.0; 3], [0.0; 3]]).to_raw();
+        assert_eq!(raw.matrix[0], (1 << 63) | (1 << 31));
+        assert_eq!(raw.matrix[1], 0);
+    }
+}
@@ -0,0 +1,374 @@
+//! Software Composition Fallback
+//!
+//! The number of hardware [Plane]s varies wildly from one display controller to another, from a
+//! single primary [Plane] to a dozen overlays. This module provides a [Compositor] that takes a
+//! stack of [Layer]s and puts as many of them as possible on hardware [Plane]s, blending the
+//! remaining ones on the CPU into the primary [Plane] [Framebuffer]. This allows multi-layer
+//! applications to run on any hardware, at the cost of some CPU time.
+
+use std::{convert::TryFrom, sync::Arc};
+
+use crate::{
+    pixel::read_pixel, BufferType, Device, Error, FixedPoint, Format, Framebuffer, Output, Plane,
+    PlaneType, PlaneUpdate, Point, Rect, Result, Size, Update,
+};
+
+/// A layer of content to compose
+///
+/// The [Layer]s are given to [`Compositor::compose`] from the bottom-most to the top-most.
+///
+/// The [Compositor] needs to know which parts of a [Layer] changed since the previous
+/// composition to only blend those again. A [Layer] without any damage is considered unchanged.
+#[derive(Debug)]
+pub struct Layer<'a> {
+    framebuffer: &'a Framebuffer,
+    x: usize,
+    y: usize,
+    damage: Vec<Rect>,
+}
+
+impl<'a> Layer<'a> {
+    /// Creates a new [Layer] from a [Framebuffer]
+    ///
+    /// The [Layer] will be positioned in the top-left corner, and without any damage.
+    #[must_use]
+    pub const fn new(framebuffer: &'a Framebuffer) -> Self {
+        Self {
+            framebuffer,
+            x: 0,
+            y: 0,
+            damage: Vec::new(),
+        }
+    }
+
+    /// Sets the position of the [Layer] on the display
+    #[must_use]
+    pub const fn set_position(mut self, x: usize, y: usize) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Adds a damaged area of the [Layer], in the [Layer] coordinates
+    #[must_use]
+    pub fn add_damage(mut self, rect: Rect) -> Self {
+        self.damage.push(rect);
+        self
+    }
+
+    /// Marks the whole [Layer] as damaged
+    #[must_use]
+    pub fn damage_all(self) -> Self {
+        let rect = self.size();
+
+        self.add_damage(rect)
+    }
+
+    fn size(&self) -> Rect {
+        Rect::new(0, 0, self.framebuffer.width(), self.framebuffer.height())
+    }
+
+    fn geometry(&self) -> (u32, Rect) {
+        (self.framebuffer.id(), self.size().translate(self.x, self.y))
+    }
+}
+
+fn blend_pixel(dst: &mut [u8], src: &[u8], format: Format) {
+    let pixel = read_pixel(src, format);
+
+    // NOTE: The KMS default blending mode is with a pre-multiplied alpha, and the opaque
+    // formats are read with a maximum alpha, so they simply replace the destination.
+    let inv_alpha = u16::from(u8::MAX - pixel[3]);
+
+    for idx in 0..3 {
+        let val = u16::from(pixel[idx]) + (u16::from(dst[idx]) * inv_alpha + 127) / 255;
+
+        dst[idx] = u8::try_from(val).unwrap_or(u8::MAX);
+    }
+}
+
+fn plane_update(plane: &Arc<Plane>, fb: &Framebuffer, x: usize, y: usize) -> PlaneUpdate {
+    let size = Size::new(fb.width(), fb.height());
+
+    PlaneUpdate::new(plane)
+        .set_framebuffer(fb)
+        .set_source_coordinates(FixedPoint::default())
+        .set_source_size(size.into())
+        .set_display_coordinates(Point::new(x, y))
+        .set_display_size(size)
+}
+
+/// A Software Compositor
+///
+/// The [Compositor] owns the primary [Plane] [Framebuffer]s, and will put the top-most [Layer]s
+/// on the overlay [Plane]s of the [Output] as long as they support the [Layer] [Format]. The
+/// remaining [Layer]s are blended, in their damaged areas only, into the primary [Framebuffer].
+///
+/// The primary [Plane] is double-buffered: each composition renders into the [Framebuffer] that
+/// isn't being scanned out, so the previous [Update] needs to be committed, and its page flip
+/// completed, before composing the next one.
+///
+/// The overlay [Plane]s are assumed to be stacked in the order the kernel reports them.
+#[derive(Debug)]
+pub struct Compositor {
+    primary: Arc<Plane>,
+    overlays: Vec<Arc<Plane>>,
+    targets: [Framebuffer; 2],
+    back: usize,
+    width: usize,
+    height: usize,
+    composed: [Option<Vec<(u32, Rect)>>; 2],
+
+    // NOTE: The areas changed by the previous composition, that the back buffer is missing.
+    last_damage: Vec<Rect>,
+
+    // NOTE: The number of overlay planes used by the previous composition.
+    hw_count: usize,
+}
+
+impl Compositor {
+    /// Creates a new [Compositor] for an [Output]
+    ///
+    /// `width` and `height` are the size of the display, and thus of the [Framebuffer]s the
+    /// [Layer]s will be blended into.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Output] doesn't have a primary [Plane], or if the [Framebuffer]
+    /// allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{compositor::Compositor, ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_connector(&connector).unwrap();
+    /// let mode = connector.preferred_mode().unwrap();
+    ///
+    /// let compositor = Compositor::new(&device, &output, mode.width(), mode.height()).unwrap();
+    /// ```
+    pub fn new(device: &Device, output: &Output, width: usize, height: usize) -> Result<Self> {
+        let primary = output
+            .planes_of(PlaneType::Primary)
+            .into_iter()
+            .next()
+            .ok_or(Error::Empty)?;
+
+        let overlays = output.planes_of(PlaneType::Overlay).into_iter().collect();
+
+        let allocate = || -> Result<Framebuffer> {
+            device
+                .allocate_buffer_with_format(BufferType::Dumb, width, height, Format::XRGB8888)?
+                .into_framebuffer(Format::XRGB8888)
+        };
+
+        Ok(Self {
+            primary,
+            overlays,
+            targets: [allocate()?, allocate()?],
+            back: 0,
+            width,
+            height,
+            composed: [None, None],
+            last_damage: Vec::new(),
+            hw_count: 0,
+        })
+    }
+
+    const fn screen(&self) -> Rect {
+        Rect::new(0, 0, self.width, self.height)
+    }
+
+    fn blend(&mut self, area: &Rect, layers: &[Layer<'_>]) {
+        let target = &mut self.targets[self.back];
+        let pitch = target.pitch();
+        let data = target.data();
+
+        for line in area.y()..area.bottom() {
+            let start = line * pitch + area.x() * 4;
+
+            data[start..start + area.width() * 4].fill(0);
+        }
+
+        for layer in layers {
+            let layer_rect = layer.size().translate(layer.x, layer.y);
+            let Some(rect) = layer_rect.intersect(area) else {
+                continue;
+            };
+
+            let fb = layer.framebuffer;
+            let format = fb.format();
+            let cpp = format.bpp() / 8;
+            let src_pitch = fb.pitch();
+            let src = fb.pixels();
+
+            // NOTE: The unmapped Framebuffers are laid out with a non-linear modifier, and we
+            // can't blend them.
+            if src.is_empty() {
+                continue;
+            }
+
+            for line in rect.y()..rect.bottom() {
+                let src_line = (line - layer.y) * src_pitch;
+
+                for col in rect.x()..rect.right() {
+                    let src_offset = src_line + (col - layer.x) * cpp;
+                    let dst_offset = line * pitch + col * 4;
+
+                    blend_pixel(
+                        &mut data[dst_offset..dst_offset + 4],
+                        &src[src_offset..src_offset + cpp],
+                        format,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Composes a stack of [Layer]s and adds the resulting [`PlaneUpdate`]s to an [Update]
+    ///
+    /// The [Layer]s are ordered from the bottom-most to the top-most. The [Layer]s that are
+    /// blended in software are only blended again in their damaged areas, unless the set of
+    /// software [Layer]s, their [Framebuffer]s or positions changed since the previous call. The
+    /// overlay [Plane]s that were used by the previous call but aren't needed anymore are
+    /// disabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{
+    ///     compositor::{Compositor, Layer},
+    ///     BufferType, ConnectorStatus, ConnectorUpdate, Device, Format,
+    /// };
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_connector(&connector).unwrap();
+    /// let mode = connector.preferred_mode().unwrap();
+    ///
+    /// let mut compositor = Compositor::new(&device, &output, mode.width(), mode.height())
+    ///     .unwrap();
+    ///
+    /// let background = device.allocate_buffer(BufferType::Dumb, mode.width(), mode.height(), 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// let window = device.allocate_buffer(BufferType::Dumb, 320, 240, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::ARGB8888)
+    ///     .unwrap();
+    ///
+    /// let layers = [
+    ///     Layer::new(&background).damage_all(),
+    ///     Layer::new(&window).set_position(100, 100).damage_all(),
+    /// ];
+    ///
+    /// let update = output
+    ///     .start_update()
+    ///     .set_mode(mode)
+    ///     .add_connector(ConnectorUpdate::new(&connector));
+    ///
+    /// let output = compositor
+    ///     .compose(update, &layers)
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn compose(&mut self, mut update: Update, layers: &[Layer<'_>]) -> Update {
+        let mut hw_count = 0;
+        for (layer, plane) in layers.iter().skip(1).rev().zip(self.overlays.iter().rev()) {
+            if !plane.formats().any(|fmt| fmt == layer.framebuffer.format()) {
+                break;
+            }
+
+            hw_count += 1;
+        }
+
+        let (sw_layers, hw_layers) = layers.split_at(layers.len() - hw_count);
+        let screen = self.screen();
+
+        let geometry: Vec<_> = sw_layers.iter().map(Layer::geometry).collect();
+        let incremental = self.composed[self.back].as_ref() == Some(&geometry);
+        let damage: Vec<_> = if incremental {
+            sw_layers
+                .iter()
+                .flat_map(|layer| {
+                    layer
+                        .damage
+                        .iter()
+                        .filter_map(move |rect| layer.size().intersect(rect))
+                        .map(move |rect| rect.translate(layer.x, layer.y))
+                })
+                .filter_map(|rect| rect.intersect(&screen))
+                .collect()
+        } else {
+            vec![screen]
+        };
+
+        // NOTE: The back buffer was composed two calls ago, so it also misses the areas changed
+        // by the previous call.
+        let mut areas = damage.clone();
+        if incremental {
+            areas.extend_from_slice(&self.last_damage);
+        }
+
+        for area in &areas {
+            self.blend(area, sw_layers);
+        }
+        self.composed[self.back] = Some(geometry);
+        self.last_damage = damage;
+
+        let target = &self.targets[self.back];
+        update = update.add_plane(plane_update(&self.primary, target, 0, 0));
+        self.back = 1 - self.back;
+
+        let len = self.overlays.len();
+        for plane in &self.overlays[len - self.hw_count.max(hw_count)..len - hw_count] {
+            update = update.add_plane(PlaneUpdate::disabled(plane));
+        }
+        self.hw_count = hw_count;
+
+        let overlays = &self.overlays[len - hw_count..];
+        for (layer, plane) in hw_layers.iter().zip(overlays) {
+            update = update.add_plane(plane_update(plane, layer.framebuffer, layer.x, layer.y));
+        }
+
+        update
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::blend_pixel;
+    use crate::Format;
+
+    #[test]
+    fn test_blend_pixel() {
+        let mut dst = [0x10, 0x20, 0x30, 0x00];
+
+        blend_pixel(&mut dst, &[0xff, 0x00, 0x80, 0x00], Format::XRGB8888);
+        assert_eq!(dst, [0xff, 0x00, 0x80, 0x00]);
+
+        blend_pixel(&mut dst, &[0x00, 0x00, 0x00, 0x00], Format::ARGB8888);
+        assert_eq!(dst, [0xff, 0x00, 0x80, 0x00]);
+
+        blend_pixel(&mut dst, &[0x40, 0x40, 0x40, 0x80], Format::ARGB8888);
+        assert_eq!(dst, [0xbf, 0x40, 0x80, 0x00]);
+
+        blend_pixel(&mut dst, &[0x01, 0x02, 0x03, 0xff], Format::ARGB8888);
+        assert_eq!(dst, [0x01, 0x02, 0x03, 0x00]);
+    }
+}
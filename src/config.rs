@@ -0,0 +1,534 @@
+//! Persistent Output Configuration
+//!
+//! Deployments driving several displays usually need the same layout every time they start, no
+//! matter the order in which the kernel enumerated the connectors. This module provides a
+//! [Profile] describing the desired mode, position, rotation and scale of each output, that can
+//! be stored as TOML and applied to a [Device] later on.
+//!
+//! The outputs display the areas of a single layout [Framebuffer] covering all of them. Each
+//! output shows the area at its position, of the size of its mode divided by its scale, rotated
+//! by its primary [Plane].
+//!
+//! The [Profile] types implement serde's `Serialize` and `Deserialize`, so any other serde format
+//! can be used as well.
+//!
+//! ```toml
+//! [[output]]
+//! connector = "HDMI-A-1"
+//! width = 1920
+//! height = 1080
+//! refresh = 60
+//!
+//! [[output]]
+//! connector = "DP-1"
+//! monitor = "DEL-A0C4-3142534C"
+//! width = 1080
+//! height = 1920
+//! x = 1920
+//! rotation = "rotate-90"
+//! ```
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Connector, ConnectorStatus, ConnectorUpdate, Device, Edid, Error, FixedRect, Framebuffer, Mode,
+    Plane, PlaneType, PlaneUpdate, Point, Rect, Reflection, Result, Rotation, Size, Stereo3dLayout,
+    Update,
+};
+
+const fn default_scale() -> f32 {
+    1.0
+}
+
+#[allow(clippy::float_cmp, clippy::trivially_copy_pass_by_ref)]
+fn is_default_scale(scale: &f32) -> bool {
+    *scale == default_scale()
+}
+
+/// Returns the identifier of the monitor described by an EDID
+///
+/// The identifier is made of the PNP manufacturer ID, the product code and the serial number
+/// found in the EDID header, for example `DEL-A0C4-3142534C`.
+fn monitor_id(edid: &[u8]) -> Option<String> {
+    let edid = Edid::parse(edid).ok()?;
+
+    Some(format!(
+        "{}-{:04X}-{:08X}",
+        edid.manufacturer(),
+        edid.product_code(),
+        edid.serial_number()
+    ))
+}
+
+/// The desired configuration of a single output
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OutputConfig {
+    connector: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    monitor: Option<String>,
+
+    width: usize,
+    height: usize,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    refresh: Option<usize>,
+
+    #[serde(default)]
+    x: usize,
+
+    #[serde(default)]
+    y: usize,
+
+    #[serde(default)]
+    rotation: Rotation,
+
+    #[serde(default = "default_scale", skip_serializing_if = "is_default_scale")]
+    scale: f32,
+}
+
+impl OutputConfig {
+    /// Creates a new [`OutputConfig`] for the [Connector] named `connector`
+    ///
+    /// The output will use the first mode of the given size, at the top-left corner of the
+    /// layout, without any rotation or scaling.
+    #[must_use]
+    pub fn new(connector: &str, size: Size) -> Self {
+        Self {
+            connector: connector.to_string(),
+            monitor: None,
+            width: size.width(),
+            height: size.height(),
+            refresh: None,
+            x: 0,
+            y: 0,
+            rotation: Rotation::Rotate0,
+            scale: default_scale(),
+        }
+    }
+
+    /// Restricts the [`OutputConfig`] to a given monitor
+    ///
+    /// The monitor is identified by the manufacturer, product code and serial number found in
+    /// its EDID, for example `DEL-A0C4-3142534C`. When set, the monitor is looked for on every
+    /// [Connector], so the configuration follows it if it's plugged elsewhere.
+    #[must_use]
+    pub fn set_monitor(mut self, monitor: &str) -> Self {
+        self.monitor = Some(monitor.to_string());
+        self
+    }
+
+    /// Sets the refresh rate of the mode to use, in Hz
+    #[must_use]
+    pub const fn set_refresh(mut self, refresh: usize) -> Self {
+        self.refresh = Some(refresh);
+        self
+    }
+
+    /// Sets the position of the output in the layout
+    ///
+    /// This is the position, in the layout [Framebuffer], of the top-left corner of the area
+    /// displayed by the output.
+    #[must_use]
+    pub const fn set_position(mut self, position: Point) -> Self {
+        self.x = position.x();
+        self.y = position.y();
+        self
+    }
+
+    /// Sets the rotation of the output
+    #[must_use]
+    pub const fn set_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Sets the scale factor of the output
+    ///
+    /// The output displays an area of the layout of the size of its mode divided by `scale`,
+    /// scaled up or down by its primary [Plane].
+    #[must_use]
+    pub const fn set_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Returns the name of the [Connector] this configuration applies to
+    #[must_use]
+    pub fn connector(&self) -> &str {
+        &self.connector
+    }
+
+    /// Returns the identifier of the monitor this configuration applies to, if any
+    #[must_use]
+    pub fn monitor(&self) -> Option<&str> {
+        self.monitor.as_deref()
+    }
+
+    /// Returns the size of the mode to use
+    #[must_use]
+    pub const fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    /// Returns the refresh rate of the mode to use, if any
+    #[must_use]
+    pub const fn refresh(&self) -> Option<usize> {
+        self.refresh
+    }
+
+    /// Returns the position of the output in the layout
+    #[must_use]
+    pub const fn position(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    /// Returns the rotation of the output
+    #[must_use]
+    pub const fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Returns the scale factor of the output
+    #[must_use]
+    pub const fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Returns the area of the layout displayed by the output
+    fn source_rect(&self) -> FixedRect {
+        let (width, height) = match self.rotation {
+            Rotation::Rotate0 | Rotation::Rotate180 => (self.width, self.height),
+            Rotation::Rotate90 | Rotation::Rotate270 => (self.height, self.width),
+        };
+
+        // NOTE: The source coordinates are 16.16 fixed point values, so there's no point in
+        // being more precise than an f32.
+        #[allow(clippy::cast_precision_loss)]
+        FixedRect::new(
+            self.x as f32,
+            self.y as f32,
+            width as f32 / self.scale,
+            height as f32 / self.scale,
+        )
+    }
+
+    fn matches(&self, connector: &Connector) -> Result<bool> {
+        match &self.monitor {
+            Some(monitor) => {
+                Ok(connector.edid()?.as_deref().and_then(monitor_id).as_ref() == Some(monitor))
+            }
+            None => Ok(connector.name() == self.connector),
+        }
+    }
+
+    fn mode(&self, connector: &Connector) -> Result<Mode> {
+        connector
+            .modes()?
+            .into_iter()
+            .find(|mode| {
+                mode.width() == self.width
+                    && mode.height() == self.height
+                    && mode.stereo_layout() == Stereo3dLayout::None
+                    && self.refresh.is_none_or(|refresh| mode.refresh() == refresh)
+            })
+            .ok_or_else(|| {
+                Error::InvalidConfig(format!(
+                    "No {}x{} mode on {}",
+                    self.width,
+                    self.height,
+                    connector.name()
+                ))
+            })
+    }
+}
+
+/// A set of [`OutputConfig`]s making up a layout
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Profile {
+    #[serde(default, rename = "output")]
+    outputs: Vec<OutputConfig>,
+}
+
+impl Profile {
+    /// Creates an empty [Profile]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Parses a [Profile] from its TOML representation
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the TOML is malformed.
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|err| Error::InvalidConfig(err.to_string()))
+    }
+
+    /// Serializes the [Profile] to TOML
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Profile] can't be represented in TOML.
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).map_err(|err| Error::InvalidConfig(err.to_string()))
+    }
+
+    /// Adds an [`OutputConfig`] to the [Profile]
+    #[must_use]
+    pub fn add_output(mut self, output: OutputConfig) -> Self {
+        self.outputs.push(output);
+        self
+    }
+
+    /// Returns the [`OutputConfig`]s of the [Profile]
+    #[must_use]
+    pub fn outputs(&self) -> &[OutputConfig] {
+        &self.outputs
+    }
+
+    /// Applies the [Profile] to a [Device]
+    ///
+    /// Each [`OutputConfig`] is matched against the connected [Connector]s, by monitor if set or
+    /// by [Connector] name otherwise, and an [Update] setting the configured mode is created for
+    /// it. The [`OutputConfig`]s that don't match any connected [Connector] are ignored.
+    ///
+    /// The layout [Framebuffer] then needs to be set with [`ConfiguredOutput::set_framebuffer`]
+    /// before committing the [Update]s.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if a matching [Connector] doesn't
+    /// have the configured mode, if there's not enough [CRTC](crate::Crtc)s to drive all the
+    /// outputs, if the scale isn't strictly positive, or if the primary [Plane] of an output
+    /// doesn't support the configured rotation.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{config::Profile, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let profile = Profile::from_toml(&std::fs::read_to_string("layout.toml").unwrap())
+    ///     .unwrap();
+    ///
+    /// # let layout: nucleid::Framebuffer = unimplemented!();
+    /// for output in profile.apply(&device).unwrap() {
+    ///     let output = output
+    ///         .set_framebuffer(&layout)
+    ///         .into_update()
+    ///         .commit()
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn apply(&self, device: &Device) -> Result<Vec<ConfiguredOutput>> {
+        let connectors: Vec<Arc<Connector>> = device
+            .connectors()
+            .filter(|con| matches!(con.status(), Ok(ConnectorStatus::Connected)))
+            .collect();
+
+        let mut used_connectors = Vec::new();
+        let mut used_crtcs = Vec::new();
+        let mut configured = Vec::new();
+
+        for config in &self.outputs {
+            let mut found = None;
+            for connector in &connectors {
+                if used_connectors.contains(&connector.name()) {
+                    continue;
+                }
+
+                if config.matches(connector)? {
+                    found = Some(connector);
+                    break;
+                }
+            }
+
+            let Some(connector) = found else {
+                continue;
+            };
+
+            if !(config.scale.is_finite() && config.scale > 0.0) {
+                return Err(Error::InvalidConfig(format!(
+                    "Invalid scale {} for {}",
+                    config.scale,
+                    connector.name()
+                )));
+            }
+
+            let mode = config.mode(connector)?;
+            let output = device
+                .output_from_connector_excluding(connector, &used_crtcs)
+                .map_err(|_| {
+                    Error::InvalidConfig(format!("No CRTC available for {}", connector.name()))
+                })?;
+
+            let plane = output
+                .planes_of(PlaneType::Primary)
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    Error::InvalidConfig(format!("No primary plane for {}", connector.name()))
+                })?;
+
+            if !plane.supports_rotation(config.rotation, Reflection::empty()) {
+                return Err(Error::InvalidConfig(format!(
+                    "Rotation {:?} not supported on {}",
+                    config.rotation,
+                    connector.name()
+                )));
+            }
+
+            used_connectors.push(connector.name());
+            used_crtcs.push(output.crtc_id());
+
+            let update = output
+                .start_update()
+                .set_mode(mode)
+                .add_connector(ConnectorUpdate::new(connector));
+
+            configured.push(ConfiguredOutput {
+                config: config.clone(),
+                connector: Arc::clone(connector),
+                plane,
+                update,
+            });
+        }
+
+        Ok(configured)
+    }
+}
+
+/// An [`OutputConfig`] matched to a [Connector] by [`Profile::apply`]
+#[derive(Debug)]
+pub struct ConfiguredOutput {
+    config: OutputConfig,
+    connector: Arc<Connector>,
+    plane: Arc<Plane>,
+    update: Update,
+}
+
+impl ConfiguredOutput {
+    /// Returns the [`OutputConfig`] that has been applied
+    #[must_use]
+    pub const fn config(&self) -> &OutputConfig {
+        &self.config
+    }
+
+    /// Returns the [Connector] the [`OutputConfig`] has been matched to
+    #[must_use]
+    pub const fn connector(&self) -> &Arc<Connector> {
+        &self.connector
+    }
+
+    /// Returns the primary [Plane] displaying the layout on the [Connector]
+    #[must_use]
+    pub const fn plane(&self) -> &Arc<Plane> {
+        &self.plane
+    }
+
+    /// Displays the layout [Framebuffer] on the output
+    ///
+    /// The primary [Plane] scans out the area of the [Framebuffer] at the configured position,
+    /// of the mode size divided by the configured scale, with the configured rotation.
+    #[must_use]
+    pub fn set_framebuffer(mut self, fb: &Framebuffer) -> Self {
+        let size = self.config.size();
+
+        self.update = self.update.add_plane(
+            PlaneUpdate::new(&self.plane)
+                .set_framebuffer(fb)
+                .set_source_rect(self.config.source_rect())
+                .set_display_rect(Rect::new(0, 0, size.width(), size.height()))
+                .set_rotation(self.config.rotation),
+        );
+        self
+    }
+
+    /// Returns the [Update] setting the configured mode on the [Connector]
+    #[must_use]
+    pub fn into_update(self) -> Update {
+        self.update
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FixedRect, Point, Rotation, Size};
+
+    use super::{monitor_id, OutputConfig, Profile};
+
+    #[test]
+    fn test_monitor_id() {
+        let mut edid = vec![0; 128];
+        edid[..8].copy_from_slice(&[0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00]);
+        edid[8..16].copy_from_slice(&[0x10, 0xac, 0xc4, 0xa0, 0x4c, 0x53, 0x42, 0x31]);
+        let sum = edid.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+        edid[127] = sum.wrapping_neg();
+
+        assert_eq!(monitor_id(&edid).as_deref(), Some("DEL-A0C4-3142534C"));
+        assert_eq!(monitor_id(&edid[..12]), None);
+        assert_eq!(monitor_id(&[0; 128]), None);
+    }
+
+    #[test]
+    fn test_source_rect() {
+        let output = OutputConfig::new("DP-1", Size::new(1920, 1080));
+        assert_eq!(
+            output.source_rect(),
+            FixedRect::new(0.0, 0.0, 1920.0, 1080.0)
+        );
+
+        let output = output
+            .set_position(Point::new(1920, 0))
+            .set_rotation(Rotation::Rotate90)
+            .set_scale(2.0);
+        assert_eq!(
+            output.source_rect(),
+            FixedRect::new(1920.0, 0.0, 540.0, 960.0)
+        );
+    }
+
+    #[test]
+    fn test_profile_roundtrip() {
+        let profile = Profile::new()
+            .add_output(OutputConfig::new("HDMI-A-1", Size::new(1920, 1080)).set_refresh(60))
+            .add_output(
+                OutputConfig::new("DP-1", Size::new(1080, 1920))
+                    .set_monitor("DEL-A0C4-3142534C")
+                    .set_position(Point::new(1920, 0))
+                    .set_rotation(Rotation::Rotate90)
+                    .set_scale(1.5),
+            );
+
+        let toml = profile.to_toml().unwrap();
+        assert_eq!(Profile::from_toml(&toml).unwrap(), profile);
+    }
+
+    #[test]
+    fn test_profile_defaults() {
+        let profile = Profile::from_toml(
+            r#"
+            [[output]]
+            connector = "HDMI-A-1"
+            width = 1920
+            height = 1080
+            "#,
+        )
+        .unwrap();
+
+        let output = &profile.outputs()[0];
+        assert_eq!(output.position(), Point::new(0, 0));
+        assert_eq!(output.rotation(), Rotation::Rotate0);
+        assert_eq!(output.refresh(), None);
+        assert!((output.scale() - 1.0).abs() < f32::EPSILON);
+
+        assert!(Profile::from_toml("[[output]]\nwidth = 1").is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use std::{
-    cell::RefCell,
     convert::TryFrom,
-    rc::{Rc, Weak},
+    sync::{Arc, Weak},
+    time::{Duration, Instant},
 };
 
 use num_enum::TryFromPrimitive;
@@ -9,12 +9,15 @@ use num_enum::TryFromPrimitive;
 use crate::{
     device::Inner,
     encoder::Encoder,
-    mode::Type as ModeType,
-    object::{Object, Type as ObjectType},
-    raw::drm_mode_get_connector,
-    Device, Error, Mode, Result,
+    mode::{best_mode, sort_modes, Type as ModeType},
+    object::{impl_object_identity, Object, Type as ObjectType},
+    raw::{drm_mode_get_connector, drm_mode_get_connector_current},
+    Device, Error, Mode, Properties, Property, PropertyValue, Result, Stereo3dLayout,
 };
 
+#[cfg(feature = "edid")]
+use crate::Edid;
+
 /// [Connector] Status
 #[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
 #[repr(u32)]
@@ -31,6 +34,78 @@ pub enum Status {
     Unknown,
 }
 
+/// The [Connector] DPMS power state
+///
+/// Atomic drivers only distinguish between [`DpmsMode::On`] and the other modes, which all turn
+/// the display off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u64)]
+pub enum DpmsMode {
+    /// The display is on
+    On,
+
+    /// The display is in standby, and can be woken up quickly
+    Standby,
+
+    /// The display is suspended
+    Suspend,
+
+    /// The display is off
+    Off,
+}
+
+/// The State of the Content Protection (HDCP) of a [Connector]
+///
+/// Userspace requests the protection by setting [`ContentProtection::Desired`], and the kernel
+/// moves to [`ContentProtection::Enabled`] once the link is protected. If the protection is
+/// lost, the kernel moves back to [`ContentProtection::Desired`] and retries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u64)]
+pub enum ContentProtection {
+    /// The content protection isn't needed
+    Undesired,
+
+    /// The content protection is requested, but not established yet
+    Desired,
+
+    /// The content protection is established. Only the kernel can set this state.
+    Enabled,
+}
+
+/// The HDCP Content Type to Protect the Link for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u64)]
+pub enum HdcpContentType {
+    /// The content can be sent over any HDCP version
+    Type0,
+
+    /// The content requires HDCP 2.2 or later
+    Type1,
+}
+
+/// The Subpixel Layout of the Sink attached to a [Connector]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum Subpixel {
+    /// The layout couldn't be determined
+    Unknown = 1,
+
+    /// Red, green and blue subpixels, horizontally from left to right
+    HorizontalRgb,
+
+    /// Blue, green and red subpixels, horizontally from left to right
+    HorizontalBgr,
+
+    /// Red, green and blue subpixels, vertically from top to bottom
+    VerticalRgb,
+
+    /// Blue, green and red subpixels, vertically from top to bottom
+    VerticalBgr,
+
+    /// The sink doesn't have subpixels, like a projector
+    None,
+}
+
 /// The [Connector] Type
 #[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
 #[repr(u32)]
@@ -65,7 +140,7 @@ pub enum Type {
     /// A mini-Din-9 [Connector]
     MiniDin9,
 
-    /// A DisplayPort [Connector]
+    /// A `DisplayPort` [Connector]
     DisplayPort,
 
     /// An HDMI-A [Connector]
@@ -77,7 +152,7 @@ pub enum Type {
     /// A TV [Connector]
     TV,
 
-    /// An embedded DisplayPort [Connector]
+    /// An embedded `DisplayPort` [Connector]
     EDP,
 
     /// A Virtual [Connector]
@@ -127,18 +202,20 @@ impl std::fmt::Display for Type {
 ///
 /// A connector is the abstraction for any display sinks, including some that might not have a
 /// physical connector, such as fixed panels.
+///
+/// Two [Connector]s are equal if they represent the same connector of the same
+/// [Device], so they can be used as keys in a [`HashMap`](std::collections::HashMap).
 #[derive(Debug)]
-#[allow(dead_code)]
 pub struct Connector {
-    dev: Weak<RefCell<Inner>>,
+    dev: Weak<Inner>,
     id: u32,
     type_: Type,
     type_id: u32,
-    mm_height: usize,
-    mm_width: usize,
     encoder_ids: Vec<u32>,
 }
 
+impl_object_identity!(Connector);
+
 #[derive(Debug)]
 pub struct Modes(Vec<Mode>);
 
@@ -158,12 +235,10 @@ impl Connector {
         let con_type = Type::try_from(connector.connector_type).unwrap();
 
         Ok(Self {
-            dev: Rc::downgrade(&device.inner),
+            dev: Arc::downgrade(&device.inner),
             id,
             type_: con_type,
             type_id: connector.connector_type_id,
-            mm_height: connector.mm_height as usize,
-            mm_width: connector.mm_width as usize,
             encoder_ids,
         })
     }
@@ -192,7 +267,7 @@ impl Connector {
     /// let modes = connector.modes().unwrap();
     /// ```
     pub fn modes(&self) -> Result<Modes> {
-        let device: Device = self.dev.upgrade().ok_or(Error::Empty)?.into();
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
 
         let mut raw_modes = Vec::new();
         let _ = drm_mode_get_connector(&device, self.id, Some(&mut raw_modes), None)?;
@@ -228,19 +303,87 @@ impl Connector {
     pub fn preferred_mode(&self) -> Result<Mode> {
         self.modes()?
             .into_iter()
-            .find(|mode| mode.has_type(ModeType::Preferred))
+            .find(|mode| {
+                mode.has_type(ModeType::Preferred) && mode.stereo_layout() == Stereo3dLayout::None
+            })
             .ok_or(Error::Empty)
     }
 
-    /// Returns the [Connector] current status
+    /// Returns the [Mode]s supported by the [Connector], by order of preference
+    ///
+    /// The preferred [Mode] comes first, followed by the others from the largest to the
+    /// smallest, and then from the highest refresh rate to the lowest.
     ///
     /// # Errors
     ///
     /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
     ///
-    /// # Panics
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// for mode in connector.modes_sorted().unwrap() {
+    ///     println!("{}x{}@{}", mode.width(), mode.height(), mode.refresh());
+    /// }
+    /// ```
+    pub fn modes_sorted(&self) -> Result<Modes> {
+        let Modes(mut modes) = self.modes()?;
+
+        sort_modes(&mut modes);
+        Ok(Modes(modes))
+    }
+
+    /// Returns the [Mode] the closest to a given size and refresh rate
+    ///
+    /// The largest [Mode] fitting in `width` x `height` is picked, with the refresh rate the
+    /// closest to `refresh`, in Hertz. Progressive [Mode]s are preferred over interlaced ones.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails or if no
+    /// [Mode] fits in the given size.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let mode = connector.best_mode_for(1920, 1080, 60).unwrap();
+    /// ```
+    pub fn best_mode_for(&self, width: usize, height: usize, refresh: usize) -> Result<Mode> {
+        let Modes(modes) = self.modes()?;
+
+        best_mode(&modes, width, height, refresh)
+            .cloned()
+            .ok_or(Error::Empty)
+    }
+
+    /// Returns the [Connector] current status
+    ///
+    /// This forces the driver to probe the sink, which gives an accurate status but can take
+    /// hundreds of milliseconds, for example on HDMI where the EDID has to be read. Only the DRM
+    /// master can force a probe, the other clients get the [cached
+    /// status](Connector::status_cached).
     ///
-    /// If the connection status cannot be decoded
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails, or if the
+    /// kernel reports an unknown status.
     ///
     /// # Example
     ///
@@ -254,11 +397,155 @@ impl Connector {
     ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected);
     /// ```
     pub fn status(&self) -> Result<Status> {
-        let device: Device = self.dev.upgrade().ok_or(Error::Empty)?.into();
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
 
         let connector = drm_mode_get_connector(&device, self.id, None, None)?;
 
-        Ok(Status::try_from(connector.connection).unwrap())
+        Status::try_from(connector.connection).map_err(|_| Error::Unsupported("Connector Status"))
+    }
+
+    /// Returns whether the [Connector] has been removed from its [Device]
+    ///
+    /// Some [Connector]s, like the `DisplayPort` MST ones, can disappear once the [Device] has
+    /// been [rescanned](Device::rescan). The handles to them can still be used, but will report
+    /// errors. A [Connector] whose [Device] has been dropped is stale as well.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors().next().unwrap();
+    /// let _changes = device.rescan().unwrap();
+    ///
+    /// if connector.is_stale() {
+    ///     println!("{} is gone", connector.name());
+    /// }
+    /// ```
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        !self
+            .dev
+            .upgrade()
+            .is_some_and(|inner| Device::from(inner).has_connector(self))
+    }
+
+    /// Returns the [Connector] status found during the last probe
+    ///
+    /// Unlike [`Connector::status`], this doesn't probe the sink and returns immediately, but the
+    /// status might be outdated if the driver doesn't poll the [Connector] or report hotplug
+    /// events.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails, or if the
+    /// kernel reports an unknown status.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status_cached().unwrap() == ConnectorStatus::Connected);
+    /// ```
+    pub fn status_cached(&self) -> Result<Status> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        let connector = drm_mode_get_connector_current(&device, self.id)?;
+
+        Status::try_from(connector.connection).map_err(|_| Error::Unsupported("Connector Status"))
+    }
+
+    /// Returns the physical size of the sink, in millimeters, as `(width, height)`
+    ///
+    /// The size is usually retrieved from the EDID, and is only known once the [Connector] has
+    /// been probed with a sink attached. Returns `None` if it's unknown.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let mode = connector.preferred_mode().unwrap();
+    /// if let Some((width, _)) = connector.physical_size().unwrap() {
+    ///     let dpi = mode.width() as f64 * 25.4 / width as f64;
+    ///     println!("{dpi:.0} DPI");
+    /// }
+    /// ```
+    pub fn physical_size(&self) -> Result<Option<(usize, usize)>> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        let connector = drm_mode_get_connector_current(&device, self.id)?;
+        if connector.mm_width == 0 || connector.mm_height == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some((
+            usize::try_from(connector.mm_width)?,
+            usize::try_from(connector.mm_height)?,
+        )))
+    }
+
+    /// Returns the [Subpixel] layout of the sink
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Subpixel};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors().next().unwrap();
+    /// let rgb = connector.subpixel().unwrap() == Subpixel::HorizontalRgb;
+    /// ```
+    pub fn subpixel(&self) -> Result<Subpixel> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        let connector = drm_mode_get_connector_current(&device, self.id)?;
+
+        Ok(Subpixel::try_from(connector.subpixel).unwrap_or(Subpixel::Unknown))
+    }
+
+    /// Returns the number of [Mode]s found during the last probe
+    ///
+    /// This doesn't probe the sink, unlike [`Connector::modes`].
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    pub fn modes_count(&self) -> Result<usize> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        let connector = drm_mode_get_connector_current(&device, self.id)?;
+
+        Ok(usize::try_from(connector.count_modes)?)
+    }
+
+    /// Returns the number of encoders that can feed the [Connector]
+    #[must_use]
+    pub const fn encoders_count(&self) -> usize {
+        self.encoder_ids.len()
     }
 
     /// Returns the [Connector] type
@@ -303,8 +590,300 @@ impl Connector {
         self.type_id
     }
 
-    pub(crate) fn encoders(self: &Rc<Self>) -> Result<Encoders> {
-        let device: Device = self.dev.upgrade().ok_or(Error::Empty)?.into();
+    /// Returns the [Connector] name
+    ///
+    /// The name is built from the [Connector] [Type] and type index, following the kernel
+    /// convention, for example `HDMI-A-1`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.name() == "HDMI-A-1")
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn name(&self) -> String {
+        format!("{}-{}", self.type_, self.type_id)
+    }
+
+    /// Returns a list of the [Properties](crate::Property) available
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// for connector in device.connectors() {
+    ///     for property in connector.properties().unwrap() {
+    ///         println!("{}: {}", property.name(), property.value());
+    ///     }
+    /// }
+    /// ```
+    pub fn properties(&self) -> Result<Properties> {
+        Object::properties(self)
+    }
+
+    /// Returns the [Connector] current [`DpmsMode`]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails or if the
+    /// [Connector] doesn't expose a valid `DPMS` property.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, DpmsMode};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let blanked = device.connectors()
+    ///     .into_iter()
+    ///     .filter(|con| con.dpms().unwrap() != DpmsMode::On);
+    /// ```
+    pub fn dpms(&self) -> Result<DpmsMode> {
+        self.properties()?
+            .get_enum("DPMS")
+            .ok_or_else(|| Error::PropertyNotFound {
+                object: self.id,
+                name: String::from("DPMS"),
+            })
+    }
+
+    /// Returns the lowest and highest maximum number of bits per color component the [Connector]
+    /// accepts
+    ///
+    /// Returns `None` if the [Connector] doesn't have the `max bpc` property.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.max_bpc_range().is_some_and(|(_, max)| max >= 10))
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn max_bpc_range(&self) -> Option<(u8, u8)> {
+        let properties = self.properties().ok()?;
+
+        match *properties.get("max bpc")?.typed_value() {
+            PropertyValue::Range { min, max } => {
+                Some((u8::try_from(min).ok()?, u8::try_from(max).ok()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the maximum number of bits per color component currently set on the [Connector]
+    ///
+    /// The driver is free to use a lower depth, depending on the sink and the link bandwidth.
+    /// Returns `None` if the [Connector] doesn't have the `max bpc` property.
+    #[must_use]
+    pub fn max_bpc(&self) -> Option<u8> {
+        let value = self.properties().ok()?.get_u64("max bpc")?;
+
+        u8::try_from(value).ok()
+    }
+
+    /// Returns the current [`ContentProtection`] state of the [Connector]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails or if the
+    /// [Connector] doesn't expose a valid `Content Protection` property.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ContentProtection, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let protected = device.connectors()
+    ///     .into_iter()
+    ///     .filter(|con| {
+    ///         con.content_protection().is_ok_and(|cp| cp == ContentProtection::Enabled)
+    ///     });
+    /// ```
+    pub fn content_protection(&self) -> Result<ContentProtection> {
+        self.properties()?
+            .get_enum("Content Protection")
+            .ok_or_else(|| Error::PropertyNotFound {
+                object: self.id,
+                name: String::from("Content Protection"),
+            })
+    }
+
+    /// Returns the [`HdcpContentType`] the [Connector] link is protected for
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails or if the
+    /// [Connector] doesn't expose a valid `HDCP Content Type` property.
+    pub fn hdcp_content_type(&self) -> Result<HdcpContentType> {
+        self.properties()?
+            .get_enum("HDCP Content Type")
+            .ok_or_else(|| Error::PropertyNotFound {
+                object: self.id,
+                name: String::from("HDCP Content Type"),
+            })
+    }
+
+    /// Blocks until the kernel reports the [`ContentProtection`] as enabled
+    ///
+    /// This is meant to be called after committing a [`ContentProtection::Desired`] state
+    /// through a [`ConnectorUpdate`](crate::ConnectorUpdate), since establishing the protection
+    /// can take a few seconds. The state is polled, with an increasing delay between two
+    /// attempts.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::Timeout`] if the protection isn't enabled within `timeout`, or
+    /// [Error] if the [Device] can't be accessed, if the ioctl fails or if the [Connector]
+    /// doesn't support content protection.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use nucleid::{ConnectorUpdate, ContentProtection, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.content_protection().is_ok())
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_connector(
+    ///         ConnectorUpdate::new(&connector)
+    ///             .set_content_protection(ContentProtection::Desired)
+    ///     )
+    ///     .commit()
+    ///     .unwrap();
+    ///
+    /// connector
+    ///     .wait_for_content_protection(Duration::from_secs(5))
+    ///     .unwrap();
+    /// ```
+    pub fn wait_for_content_protection(&self, timeout: Duration) -> Result<()> {
+        const MIN_DELAY: Duration = Duration::from_millis(50);
+        const MAX_DELAY: Duration = Duration::from_millis(500);
+
+        let deadline = Instant::now() + timeout;
+        let mut delay = MIN_DELAY;
+
+        loop {
+            if self.content_protection()? == ContentProtection::Enabled {
+                return Ok(());
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(Error::Timeout);
+            }
+
+            std::thread::sleep(delay.min(remaining));
+            delay = (delay * 2).min(MAX_DELAY);
+        }
+    }
+
+    /// Returns the raw EDID of the sink attached to the [Connector], if any
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let edid = connector.edid().unwrap();
+    /// ```
+    pub fn edid(&self) -> Result<Option<Vec<u8>>> {
+        self.properties()?
+            .get("EDID")
+            .map_or(Ok(None), Property::blob_data)
+    }
+
+    /// Returns the parsed EDID of the sink attached to the [Connector], if any
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails or if the EDID
+    /// is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// if let Some(edid) = connector.parsed_edid().unwrap() {
+    ///     println!("{} {:?}", edid.manufacturer(), edid.name());
+    /// }
+    /// ```
+    #[cfg(feature = "edid")]
+    pub fn parsed_edid(&self) -> Result<Option<Edid>> {
+        self.edid()?.as_deref().map(Edid::parse).transpose()
+    }
+
+    /// Returns the [Encoder]s that can feed the [Connector]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] has been dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// for connector in device.connectors() {
+    ///     for encoder in connector.encoders().unwrap() {
+    ///         println!("{} can use encoder {}", connector.name(), encoder.id());
+    ///     }
+    /// }
+    /// ```
+    pub fn encoders(self: &Arc<Self>) -> Result<Encoders> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
 
         let encoders = device
             .encoders()
@@ -317,7 +896,7 @@ impl Connector {
 
 impl Object for Connector {
     fn device(&self) -> Result<Device> {
-        Ok(self.dev.upgrade().ok_or(Error::Empty)?.into())
+        Ok(self.dev.upgrade().ok_or(Error::DeviceGone)?.into())
     }
 
     fn object_id(&self) -> u32 {
@@ -330,10 +909,10 @@ impl Object for Connector {
 }
 
 #[derive(Debug)]
-pub struct Encoders(Vec<Rc<Encoder>>);
+pub struct Encoders(Vec<Arc<Encoder>>);
 
 impl IntoIterator for Encoders {
-    type Item = Rc<Encoder>;
+    type Item = Arc<Encoder>;
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -1,13 +1,13 @@
 use std::{
-    cell::RefCell,
-    rc::{Rc, Weak},
+    convert::TryFrom,
+    sync::{Arc, Weak},
 };
 
 use crate::{
     device::Inner,
-    object::{Object, Type as ObjectType},
-    raw::drm_mode_get_crtc,
-    Device, Error, Result,
+    object::{impl_object_identity, Object, Type as ObjectType},
+    raw::{drm_mode_crtc, drm_mode_get_crtc},
+    Device, Error, Mode, Point, Properties, Result,
 };
 
 /// A KMS CRTC
@@ -15,19 +15,24 @@ use crate::{
 /// A CRTC is the central part of the display pipeline. It receives the [`Planes`](crate::Plane)
 /// content and blends them together. It will then generate the timings through the attached
 /// [`Mode`](crate::Mode) and will feed the output to a [`Connector`](crate::Connector).
+///
+/// Two [Crtc]s are equal if they represent the same CRTC of the same [Device], so they can be
+/// used as keys in a [`HashMap`](std::collections::HashMap).
 #[derive(Debug)]
 pub struct Crtc {
-    dev: Weak<RefCell<Inner>>,
+    dev: Weak<Inner>,
     id: u32,
     idx: usize,
 }
 
+impl_object_identity!(Crtc);
+
 impl Crtc {
     pub(crate) fn new(device: &Device, id: u32, idx: usize) -> Result<Self> {
         let _ = drm_mode_get_crtc(device, id)?;
 
         Ok(Self {
-            dev: Rc::downgrade(&device.inner),
+            dev: Arc::downgrade(&device.inner),
             id,
             idx,
         })
@@ -36,11 +41,148 @@ impl Crtc {
     pub(crate) const fn index(&self) -> usize {
         self.idx
     }
+
+    fn raw(&self) -> Result<drm_mode_crtc> {
+        drm_mode_get_crtc(&self.device()?, self.id)
+    }
+
+    /// Returns the [Mode] currently programmed on the [Crtc], if any
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// for crtc in device.crtcs() {
+    ///     if let Some(mode) = crtc.current_mode().unwrap() {
+    ///         println!("{}x{}@{}", mode.width(), mode.height(), mode.refresh());
+    ///     }
+    /// }
+    /// ```
+    pub fn current_mode(&self) -> Result<Option<Mode>> {
+        let crtc = self.raw()?;
+
+        Ok((crtc.mode_valid != 0).then(|| Mode::new(crtc.mode)))
+    }
+
+    /// Returns the position, in the framebuffer, of the area scanned out by the [Crtc]
+    ///
+    /// This is only relevant to the legacy modesetting API, and is always at the origin for
+    /// the atomic one.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    pub fn position(&self) -> Result<Point> {
+        let crtc = self.raw()?;
+
+        Ok(Point::new(
+            usize::try_from(crtc.x)?,
+            usize::try_from(crtc.y)?,
+        ))
+    }
+
+    /// Returns the ID of the framebuffer attached to the primary plane of the [Crtc], if any
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// for crtc in device.crtcs() {
+    ///     if let Some(id) = crtc.framebuffer_id().unwrap() {
+    ///         println!("Scanning out framebuffer {}", id);
+    ///     }
+    /// }
+    /// ```
+    pub fn framebuffer_id(&self) -> Result<Option<u32>> {
+        let fb_id = self.raw()?.fb_id;
+
+        Ok((fb_id != 0).then_some(fb_id))
+    }
+
+    /// Returns the number of entries of the legacy gamma table of the [Crtc]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    pub fn gamma_size(&self) -> Result<usize> {
+        Ok(usize::try_from(self.raw()?.gamma_size)?)
+    }
+
+    /// Returns whether the [Crtc] is currently active
+    ///
+    /// A [Crtc] can have a [Mode] programmed while being disabled, for example when the sink
+    /// has been put in DPMS off.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let active = device.crtcs()
+    ///     .into_iter()
+    ///     .filter(|crtc| crtc.is_active().unwrap())
+    ///     .count();
+    /// ```
+    pub fn is_active(&self) -> Result<bool> {
+        // NOTE: The ACTIVE property is only exposed to atomic clients, so we need to fall back
+        // to the mode for the legacy ones.
+        match self.properties()?.get_u64("ACTIVE") {
+            Some(active) => Ok(active != 0),
+            None => Ok(self.raw()?.mode_valid != 0),
+        }
+    }
+
+    /// Returns a list of the [Properties](crate::Property) available
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// for crtc in device.crtcs() {
+    ///     let mode = crtc
+    ///         .properties()
+    ///         .unwrap()
+    ///         .get("MODE_ID")
+    ///         .unwrap()
+    ///         .blob_data()
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn properties(&self) -> Result<Properties> {
+        Object::properties(self)
+    }
 }
 
 impl Object for Crtc {
     fn device(&self) -> Result<Device> {
-        Ok(self.dev.upgrade().ok_or(Error::Empty)?.into())
+        Ok(self.dev.upgrade().ok_or(Error::DeviceGone)?.into())
     }
 
     fn object_id(&self) -> u32 {
@@ -0,0 +1,248 @@
+use std::convert::TryFrom;
+
+use crate::{Error, Mode, Result, SyncPolarity};
+
+const H_GRANULARITY: u32 = 8;
+const MIN_V_PORCH: u32 = 3;
+const MIN_V_BPORCH: u32 = 6;
+const CLOCK_STEP: u32 = 250;
+
+// NOTE: The standard blanking parameters. The blanking duty cycle is derived from the C' and M'
+// parameters of the GTF formula.
+const MIN_VSYNC_BP: f64 = 550.0;
+const HSYNC_PERCENTAGE: u32 = 8;
+const C_PRIME: f64 = 30.0;
+const M_PRIME: f64 = 300.0;
+
+// NOTE: The reduced blanking parameters. The minimum vertical blanking duration is shared by
+// both versions.
+const RB_MIN_VBLANK: f64 = 460.0;
+
+const RB_H_BLANK: u32 = 160;
+const RB_H_SYNC: u32 = 32;
+const RB_V_FPORCH: u32 = 3;
+
+const RB2_H_BLANK: u32 = 80;
+const RB2_H_SYNC: u32 = 32;
+const RB2_H_FPORCH: u32 = 8;
+const RB2_V_SYNC: u32 = 8;
+const RB2_MIN_V_FPORCH: u32 = 1;
+
+/// The Blanking of a CVT [Mode]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReducedBlanking {
+    /// The standard blanking, needed by CRT monitors
+    None,
+
+    /// The first version of the reduced blanking, for digital sinks
+    V1,
+
+    /// The second version of the reduced blanking, with an even shorter horizontal blanking
+    V2,
+}
+
+/// Returns the length of the vertical sync pulse, which encodes the aspect ratio
+fn vsync_lines(width: u32, height: u32) -> u32 {
+    let is_ratio = |w: u32, h: u32| height.is_multiple_of(h) && height * w / h == width;
+
+    if is_ratio(4, 3) {
+        4
+    } else if is_ratio(16, 9) {
+        5
+    } else if is_ratio(16, 10) {
+        6
+    } else if is_ratio(5, 4) || is_ratio(15, 9) {
+        7
+    } else {
+        10
+    }
+}
+
+// NOTE: The CVT formulas only ever truncate positive values, and the timings of any sensible
+// mode fit in a u32.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+const fn truncate(val: f64) -> u32 {
+    val as u32
+}
+
+struct Timings {
+    clock: u32,
+    horizontal: [u32; 4],
+    vertical: [u32; 4],
+    polarity: (SyncPolarity, SyncPolarity),
+}
+
+fn standard(width: u32, height: u32, refresh: f64) -> Timings {
+    let vsync = vsync_lines(width, height);
+
+    // NOTE: The horizontal period is in microseconds.
+    let hperiod = (1_000_000.0 / refresh - MIN_VSYNC_BP) / f64::from(height + MIN_V_PORCH);
+    let vsync_bp = (truncate(MIN_VSYNC_BP / hperiod) + 1).max(vsync + MIN_V_PORCH);
+    let vtotal = height + vsync_bp + MIN_V_PORCH;
+
+    let duty = (C_PRIME - M_PRIME * hperiod / 1000.0).max(20.0);
+    let hblank = truncate(f64::from(width) * duty / (100.0 - duty));
+    let hblank = hblank - hblank % (2 * H_GRANULARITY);
+    let htotal = width + hblank;
+
+    let hsync_end = width + hblank / 2;
+    let hsync_start = hsync_end - htotal * HSYNC_PERCENTAGE / 100;
+    let hsync_start = hsync_start + H_GRANULARITY - hsync_start % H_GRANULARITY;
+
+    let clock = truncate(f64::from(htotal) * 1000.0 / hperiod);
+
+    Timings {
+        clock: clock - clock % CLOCK_STEP,
+        horizontal: [width, hsync_start, hsync_end, htotal],
+        vertical: [
+            height,
+            height + MIN_V_PORCH,
+            height + MIN_V_PORCH + vsync,
+            vtotal,
+        ],
+        polarity: (SyncPolarity::Negative, SyncPolarity::Positive),
+    }
+}
+
+fn reduced_v1(width: u32, height: u32, refresh: f64) -> Timings {
+    let vsync = vsync_lines(width, height);
+
+    let hperiod = (1_000_000.0 / refresh - RB_MIN_VBLANK) / f64::from(height);
+    let vblank = (truncate(RB_MIN_VBLANK / hperiod) + 1).max(RB_V_FPORCH + vsync + MIN_V_BPORCH);
+
+    let htotal = width + RB_H_BLANK;
+    let hsync_end = width + RB_H_BLANK / 2;
+    let clock = truncate(f64::from(htotal) * 1000.0 / hperiod);
+
+    Timings {
+        clock: clock - clock % CLOCK_STEP,
+        horizontal: [width, hsync_end - RB_H_SYNC, hsync_end, htotal],
+        vertical: [
+            height,
+            height + RB_V_FPORCH,
+            height + RB_V_FPORCH + vsync,
+            height + vblank,
+        ],
+        polarity: (SyncPolarity::Positive, SyncPolarity::Negative),
+    }
+}
+
+fn reduced_v2(width: u32, height: u32, refresh: f64) -> Timings {
+    let hperiod = (1_000_000.0 / refresh - RB_MIN_VBLANK) / f64::from(height);
+    let vblank =
+        (truncate(RB_MIN_VBLANK / hperiod) + 1).max(RB2_MIN_V_FPORCH + RB2_V_SYNC + MIN_V_BPORCH);
+    let vtotal = height + vblank;
+    let vsync_end = vtotal - MIN_V_BPORCH;
+
+    let htotal = width + RB2_H_BLANK;
+    let hsync_start = width + RB2_H_FPORCH;
+
+    // NOTE: The second version uses a 1kHz clock step, so that the refresh rate is exact.
+    let clock = truncate(refresh * f64::from(htotal) * f64::from(vtotal) / 1000.0);
+
+    Timings {
+        clock,
+        horizontal: [width, hsync_start, hsync_start + RB2_H_SYNC, htotal],
+        vertical: [height, vsync_end - RB2_V_SYNC, vsync_end, vtotal],
+        polarity: (SyncPolarity::Positive, SyncPolarity::Negative),
+    }
+}
+
+/// Computes a [Mode] following the VESA Coordinated Video Timings 1.2
+pub fn cvt(width: usize, height: usize, refresh: f64, blanking: ReducedBlanking) -> Result<Mode> {
+    let width = u32::from(u16::try_from(width)?);
+    let height = u32::from(u16::try_from(height)?);
+
+    let min_vblank = match blanking {
+        ReducedBlanking::None => MIN_VSYNC_BP,
+        ReducedBlanking::V1 | ReducedBlanking::V2 => RB_MIN_VBLANK,
+    };
+
+    if !refresh.is_finite() || refresh <= 0.0 || 1_000_000.0 / refresh <= min_vblank {
+        return Err(Error::InvalidMode("Invalid Refresh Rate"));
+    }
+
+    if width < H_GRANULARITY || height == 0 {
+        return Err(Error::InvalidMode("Invalid Size"));
+    }
+
+    let timings = match blanking {
+        ReducedBlanking::None => standard(width - width % H_GRANULARITY, height, refresh),
+        ReducedBlanking::V1 => reduced_v1(width - width % H_GRANULARITY, height, refresh),
+        ReducedBlanking::V2 => reduced_v2(width, height, refresh),
+    };
+
+    let [hdisplay, hsync_start, hsync_end, htotal] = timings.horizontal;
+    let [vdisplay, vsync_start, vsync_end, vtotal] = timings.vertical;
+    let (hsync, vsync) = timings.polarity;
+
+    Mode::builder()
+        .set_clock(timings.clock)
+        .set_horizontal(
+            u16::try_from(hdisplay)?,
+            u16::try_from(hsync_start)?,
+            u16::try_from(hsync_end)?,
+            u16::try_from(htotal)?,
+        )
+        .set_vertical(
+            u16::try_from(vdisplay)?,
+            u16::try_from(vsync_start)?,
+            u16::try_from(vsync_end)?,
+            u16::try_from(vtotal)?,
+        )
+        .set_sync_polarity(hsync, vsync)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cvt, ReducedBlanking};
+    use crate::Mode;
+
+    fn timings(mode: &Mode) -> (u32, [u16; 4], [u16; 4]) {
+        let info = mode.inner();
+
+        (
+            info.clock,
+            [info.hdisplay, info.hsync_start, info.hsync_end, info.htotal],
+            [info.vdisplay, info.vsync_start, info.vsync_end, info.vtotal],
+        )
+    }
+
+    #[test]
+    fn test_cvt() {
+        let mode = cvt(1920, 1080, 60.0, ReducedBlanking::None).unwrap();
+        assert_eq!(
+            timings(&mode),
+            (173_000, [1920, 2048, 2248, 2576], [1080, 1083, 1088, 1120])
+        );
+        assert_eq!(mode.refresh(), 60);
+
+        let mode = cvt(1366, 768, 60.0, ReducedBlanking::None).unwrap();
+        assert_eq!(mode.width(), 1360);
+    }
+
+    #[test]
+    fn test_cvt_reduced() {
+        let mode = cvt(1920, 1080, 60.0, ReducedBlanking::V1).unwrap();
+        assert_eq!(
+            timings(&mode),
+            (138_500, [1920, 1968, 2000, 2080], [1080, 1083, 1088, 1111])
+        );
+
+        let mode = cvt(2560, 1440, 60.0, ReducedBlanking::V2).unwrap();
+        assert_eq!(
+            timings(&mode),
+            (234_590, [2560, 2568, 2600, 2640], [1440, 1467, 1475, 1481])
+        );
+        assert_eq!(mode.refresh(), 60);
+    }
+
+    #[test]
+    fn test_cvt_invalid() {
+        assert!(cvt(1920, 1080, 0.0, ReducedBlanking::None).is_err());
+        assert!(cvt(1920, 1080, 5000.0, ReducedBlanking::V1).is_err());
+        assert!(cvt(0, 1080, 60.0, ReducedBlanking::V2).is_err());
+        assert!(cvt(100_000, 1080, 60.0, ReducedBlanking::V2).is_err());
+    }
+}
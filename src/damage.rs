@@ -0,0 +1,249 @@
+use std::{collections::VecDeque, convert::TryFrom};
+
+use crate::{
+    raw::{drm_clip_rect, drm_mode_rect},
+    Rect, Result,
+};
+
+// NOTE: Past that number of rectangles, submitting them all is likely to cost more than what
+// we'd save by not updating the areas between them.
+const MAX_RECTS: usize = 32;
+
+// NOTE: Buffering schemes rarely go further than triple buffering, so we don't need to remember
+// more frames than that.
+const MAX_AGE: usize = 4;
+
+fn bounding_box(rects: &[Rect]) -> Option<Rect> {
+    let x = rects.iter().map(Rect::x).min()?;
+    let y = rects.iter().map(Rect::y).min()?;
+    let right = rects.iter().map(Rect::right).max()?;
+    let bottom = rects.iter().map(Rect::bottom).max()?;
+
+    Some(Rect::new(x, y, right - x, bottom - y))
+}
+
+/// Converts [Rect]s to the layout of the `FB_DAMAGE_CLIPS` property
+pub fn mode_rects(rects: &[Rect]) -> Result<Vec<drm_mode_rect>> {
+    rects
+        .iter()
+        .map(|rect| {
+            Ok(drm_mode_rect {
+                x1: i32::try_from(rect.x())?,
+                y1: i32::try_from(rect.y())?,
+                x2: i32::try_from(rect.right())?,
+                y2: i32::try_from(rect.bottom())?,
+            })
+        })
+        .collect()
+}
+
+/// Converts [Rect]s to the layout of the DIRTYFB ioctl
+pub fn clip_rects(rects: &[Rect]) -> Result<Vec<drm_clip_rect>> {
+    rects
+        .iter()
+        .map(|rect| {
+            Ok(drm_clip_rect {
+                x1: u16::try_from(rect.x())?,
+                y1: u16::try_from(rect.y())?,
+                x2: u16::try_from(rect.right())?,
+                y2: u16::try_from(rect.bottom())?,
+            })
+        })
+        .collect()
+}
+
+/// A Damage Accumulator
+///
+/// Keeps track of the areas of a [Framebuffer](crate::Framebuffer) that changed during a frame,
+/// so that only those areas need to be updated by the hardware. The current frame damage can
+/// be given to a [`PlaneUpdate`](crate::PlaneUpdate) through
+/// [`PlaneUpdate::set_damage`](crate::PlaneUpdate::set_damage), or to
+/// [`Framebuffer::flush`](crate::Framebuffer::flush).
+///
+/// The damage of the previous frames is also remembered, so that when rendering into a buffer
+/// that was last used a few frames ago, the areas to repaint can be retrieved with
+/// [`Damage::since`].
+#[derive(Clone, Debug)]
+pub struct Damage {
+    bounds: Rect,
+    current: Vec<Rect>,
+    history: VecDeque<Vec<Rect>>,
+}
+
+impl Damage {
+    /// Creates a new [Damage] accumulator for a [Framebuffer](crate::Framebuffer) of the given
+    /// size
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            bounds: Rect::new(0, 0, width, height),
+            current: Vec::new(),
+            history: VecDeque::with_capacity(MAX_AGE),
+        }
+    }
+
+    /// Adds a damaged area to the current frame
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nucleid::{Damage, Rect};
+    ///
+    /// let mut damage = Damage::new(640, 480);
+    /// damage.add(Rect::new(10, 10, 100, 100));
+    ///
+    /// assert_eq!(damage.rects(), &[Rect::new(10, 10, 100, 100)]);
+    /// ```
+    pub fn add(&mut self, rect: Rect) {
+        let Some(rect) = rect.intersect(&self.bounds) else {
+            return;
+        };
+
+        self.current.push(rect);
+
+        if self.current.len() > MAX_RECTS {
+            self.current = bounding_box(&self.current).into_iter().collect();
+        }
+    }
+
+    /// Marks the whole [Framebuffer](crate::Framebuffer) as damaged for the current frame
+    pub fn add_all(&mut self) {
+        self.current = vec![self.bounds];
+    }
+
+    /// Returns the areas damaged during the current frame
+    #[must_use]
+    pub fn rects(&self) -> &[Rect] {
+        &self.current
+    }
+
+    /// Ends the current frame, and starts a new one without any damage
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nucleid::{Damage, Rect};
+    ///
+    /// let mut damage = Damage::new(640, 480);
+    ///
+    /// damage.add(Rect::new(10, 10, 100, 100));
+    /// damage.end_frame();
+    ///
+    /// assert!(damage.rects().is_empty());
+    /// ```
+    pub fn end_frame(&mut self) {
+        if self.history.len() == MAX_AGE {
+            let _ = self.history.pop_back();
+        }
+
+        self.history.push_front(std::mem::take(&mut self.current));
+    }
+
+    /// Returns the areas to repaint in a buffer rendered `age` frames ago
+    ///
+    /// An `age` of 1 means the buffer holds the content of the previous frame, and thus only the
+    /// current frame damage needs to be repainted. An `age` of 0, following the EGL convention,
+    /// means the buffer content is unknown and the whole buffer needs to be repainted, which is
+    /// also the case if the buffer is older than the [Damage] history.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nucleid::{Damage, Rect};
+    ///
+    /// let mut damage = Damage::new(640, 480);
+    ///
+    /// damage.add(Rect::new(0, 0, 10, 10));
+    /// damage.end_frame();
+    ///
+    /// damage.add(Rect::new(20, 20, 10, 10));
+    ///
+    /// assert_eq!(damage.since(1), vec![Rect::new(20, 20, 10, 10)]);
+    /// assert_eq!(
+    ///     damage.since(2),
+    ///     vec![Rect::new(20, 20, 10, 10), Rect::new(0, 0, 10, 10)]
+    /// );
+    /// assert_eq!(damage.since(0), vec![Rect::new(0, 0, 640, 480)]);
+    /// ```
+    #[must_use]
+    pub fn since(&self, age: usize) -> Vec<Rect> {
+        if age == 0 || age > self.history.len() + 1 {
+            return vec![self.bounds];
+        }
+
+        let mut rects = self.current.clone();
+        for frame in self.history.iter().take(age - 1) {
+            rects.extend_from_slice(frame);
+        }
+
+        if rects.len() > MAX_RECTS {
+            return bounding_box(&rects).into_iter().collect();
+        }
+
+        rects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clip_rects, mode_rects, Damage, MAX_AGE, MAX_RECTS};
+    use crate::Rect;
+
+    #[test]
+    fn test_raw_rects() {
+        let rects = [Rect::new(10, 20, 30, 40)];
+
+        let mode = mode_rects(&rects).unwrap();
+        assert_eq!(
+            (mode[0].x1, mode[0].y1, mode[0].x2, mode[0].y2),
+            (10, 20, 40, 60)
+        );
+
+        let clip = clip_rects(&rects).unwrap();
+        assert_eq!(
+            (clip[0].x1, clip[0].y1, clip[0].x2, clip[0].y2),
+            (10, 20, 40, 60)
+        );
+
+        assert!(clip_rects(&[Rect::new(0, 0, 70000, 1)]).is_err());
+    }
+
+    #[test]
+    fn test_damage_clipping() {
+        let mut damage = Damage::new(100, 100);
+
+        damage.add(Rect::new(90, 90, 20, 20));
+        damage.add(Rect::new(200, 200, 20, 20));
+
+        assert_eq!(damage.rects(), &[Rect::new(90, 90, 10, 10)]);
+    }
+
+    #[test]
+    fn test_damage_merge() {
+        let mut damage = Damage::new(1000, 1000);
+
+        for idx in 0..=MAX_RECTS {
+            damage.add(Rect::new(idx * 10, idx * 5, 10, 10));
+        }
+
+        assert_eq!(
+            damage.rects(),
+            &[Rect::new(0, 0, MAX_RECTS * 10 + 10, MAX_RECTS * 5 + 10)]
+        );
+    }
+
+    #[test]
+    fn test_damage_history() {
+        let mut damage = Damage::new(100, 100);
+
+        for idx in 0..=MAX_AGE {
+            damage.add(Rect::new(idx, idx, 1, 1));
+            damage.end_frame();
+        }
+
+        assert!(damage.since(1).is_empty());
+        assert_eq!(damage.since(2), vec![Rect::new(MAX_AGE, MAX_AGE, 1, 1)]);
+        assert_eq!(damage.since(MAX_AGE + 1).len(), MAX_AGE);
+        assert_eq!(damage.since(MAX_AGE + 2), vec![Rect::new(0, 0, 100, 100)]);
+    }
+}
@@ -0,0 +1,480 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    os::unix::fs::MetadataExt,
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    object::Object,
+    raw::{i2c_set_target_address, DDC_CI_ADDRESS},
+    Connector, ConnectorType, Error, Result,
+};
+
+const DDC_CI_HOST_ADDRESS: u8 = 0x51;
+const DDC_CI_REPLY_ADDRESS: u8 = 0x6e;
+
+const DDC_CI_VCP_REQUEST: u8 = 0x01;
+const DDC_CI_VCP_REPLY: u8 = 0x02;
+const DDC_CI_VCP_SET: u8 = 0x03;
+
+const VCP_BRIGHTNESS: u8 = 0x10;
+const VCP_CONTRAST: u8 = 0x12;
+const VCP_INPUT_SOURCE: u8 = 0x60;
+
+// NOTE: The DDC/CI specification requires the host to wait for 40ms after a request before
+// reading the reply, and for 50ms after a set before sending any other command.
+const DDC_CI_REPLY_DELAY: Duration = Duration::from_millis(40);
+const DDC_CI_SET_DELAY: Duration = Duration::from_millis(50);
+
+fn checksum(initial: u8, data: &[u8]) -> u8 {
+    data.iter().fold(initial, |acc, byte| acc ^ byte)
+}
+
+fn packet(data: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(data.len() + 3);
+
+    packet.push(DDC_CI_HOST_ADDRESS);
+
+    // NOTE: DDC/CI messages are at most 32 bytes long, so the length always fits
+    #[allow(clippy::cast_possible_truncation)]
+    packet.push(0x80 | data.len() as u8);
+    packet.extend_from_slice(data);
+    packet.push(checksum(DDC_CI_REPLY_ADDRESS, &packet));
+
+    packet
+}
+
+fn parse_vcp_reply(code: u8, reply: &[u8; 11]) -> Result<VcpValue> {
+    // NOTE: The reply checksum is computed using the virtual host address
+    if reply[0] != DDC_CI_REPLY_ADDRESS || checksum(0x50, &reply[..10]) != reply[10] {
+        return Err(Error::InvalidDdcReply);
+    }
+
+    if reply[1] != 0x88 || reply[2] != DDC_CI_VCP_REPLY || reply[4] != code {
+        return Err(Error::InvalidDdcReply);
+    }
+
+    if reply[3] != 0 {
+        return Err(Error::UnsupportedVcpFeature(code));
+    }
+
+    Ok(VcpValue {
+        maximum: u16::from_be_bytes([reply[6], reply[7]]),
+        current: u16::from_be_bytes([reply[8], reply[9]]),
+    })
+}
+
+// NOTE: The kernel uses a different naming for some connector types than our Display
+// implementation.
+const fn kernel_connector_name(con_type: ConnectorType) -> &'static str {
+    match con_type {
+        ConnectorType::Unknown => "Unknown",
+        ConnectorType::VGA => "VGA",
+        ConnectorType::DVII => "DVI-I",
+        ConnectorType::DVID => "DVI-D",
+        ConnectorType::DVIA => "DVI-A",
+        ConnectorType::Composite => "Composite",
+        ConnectorType::SVIDEO => "SVIDEO",
+        ConnectorType::LVDS => "LVDS",
+        ConnectorType::Component => "Component",
+        ConnectorType::MiniDin9 => "DIN",
+        ConnectorType::DisplayPort => "DP",
+        ConnectorType::HDMIA => "HDMI-A",
+        ConnectorType::HDMIB => "HDMI-B",
+        ConnectorType::TV => "TV",
+        ConnectorType::EDP => "eDP",
+        ConnectorType::Virtual => "Virtual",
+        ConnectorType::DSI => "DSI",
+        ConnectorType::DPI => "DPI",
+        ConnectorType::Writeback => "Writeback",
+        ConnectorType::SPI => "SPI",
+    }
+}
+
+/// The value of a VCP feature
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VcpValue {
+    current: u16,
+    maximum: u16,
+}
+
+impl VcpValue {
+    /// Returns the current value of the feature
+    #[must_use]
+    pub const fn current(&self) -> u16 {
+        self.current
+    }
+
+    /// Returns the maximum value of the feature
+    #[must_use]
+    pub const fn maximum(&self) -> u16 {
+        self.maximum
+    }
+}
+
+/// A DDC/CI Monitor Control Channel
+///
+/// Most external monitors can be controlled through the DDC/CI protocol, over the i2c bus also
+/// used to retrieve their EDID. This allows to change settings such as the brightness, contrast or
+/// input source, that are otherwise only accessible through the monitor On-Screen Display.
+///
+/// The monitor settings are exposed as VCP features, identified by a code defined in the VESA
+/// Monitor Control Command Set (MCCS) specification. The most common ones have dedicated
+/// accessors, and the others can be accessed through [`Ddc::vcp_feature`] and
+/// [`Ddc::set_vcp_feature`].
+///
+/// This relies on the i2c-dev kernel driver, and the user needs to have access to the i2c
+/// device.
+#[derive(Debug)]
+pub struct Ddc {
+    file: File,
+}
+
+impl Ddc {
+    /// Opens the DDC/CI channel of a [Connector]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device](crate::Device) can't be accessed, if the [Connector]
+    /// doesn't have a DDC bus, or if the i2c device can't be opened.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Ddc, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let ddc = Ddc::new(&connector).unwrap();
+    /// ```
+    pub fn new(connector: &Connector) -> Result<Self> {
+        let device = connector.device()?;
+        let rdev = device.inner.file.metadata()?.rdev();
+
+        let dev_path = PathBuf::from(format!(
+            "/sys/dev/char/{}:{}",
+            libc::major(rdev),
+            libc::minor(rdev)
+        ));
+
+        let card_path = dev_path.canonicalize()?;
+        let card = card_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or(Error::Empty)?;
+
+        let ddc_path = card_path
+            .join(format!(
+                "{}-{}-{}",
+                card,
+                kernel_connector_name(connector.connector_type()),
+                connector.connector_type_id()
+            ))
+            .join("ddc")
+            .canonicalize()?;
+
+        let bus = ddc_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or(Error::Empty)?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(format!("/dev/{bus}"))?;
+
+        i2c_set_target_address(&file, DDC_CI_ADDRESS)?;
+
+        Ok(Self { file })
+    }
+
+    /// Returns the value of a VCP feature
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the i2c transfer fails, if the reply is invalid, or if the monitor
+    /// doesn't support the feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Ddc, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let ddc = Ddc::new(&connector).unwrap();
+    ///
+    /// // Image Sharpness
+    /// let sharpness = ddc.vcp_feature(0x87).unwrap();
+    /// ```
+    pub fn vcp_feature(&self, code: u8) -> Result<VcpValue> {
+        (&self.file).write_all(&packet(&[DDC_CI_VCP_REQUEST, code]))?;
+
+        thread::sleep(DDC_CI_REPLY_DELAY);
+
+        let mut reply = [0; 11];
+        (&self.file).read_exact(&mut reply)?;
+
+        parse_vcp_reply(code, &reply)
+    }
+
+    /// Sets the value of a VCP feature
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the i2c transfer fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Ddc, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let ddc = Ddc::new(&connector).unwrap();
+    ///
+    /// // Image Sharpness
+    /// ddc.set_vcp_feature(0x87, 50).unwrap();
+    /// ```
+    pub fn set_vcp_feature(&self, code: u8, value: u16) -> Result<()> {
+        let [high, low] = value.to_be_bytes();
+
+        (&self.file).write_all(&packet(&[DDC_CI_VCP_SET, code, high, low]))?;
+
+        thread::sleep(DDC_CI_SET_DELAY);
+
+        Ok(())
+    }
+
+    /// Returns the monitor brightness
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the i2c transfer fails, if the reply is invalid, or if the monitor
+    /// doesn't support it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Ddc, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let ddc = Ddc::new(&connector).unwrap();
+    /// let brightness = ddc.brightness().unwrap();
+    ///
+    /// println!("Brightness {}/{}", brightness.current(), brightness.maximum());
+    /// ```
+    pub fn brightness(&self) -> Result<VcpValue> {
+        self.vcp_feature(VCP_BRIGHTNESS)
+    }
+
+    /// Sets the monitor brightness
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the i2c transfer fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Ddc, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let ddc = Ddc::new(&connector).unwrap();
+    /// let brightness = ddc.brightness().unwrap();
+    ///
+    /// ddc.set_brightness(brightness.maximum() / 2).unwrap();
+    /// ```
+    pub fn set_brightness(&self, value: u16) -> Result<()> {
+        self.set_vcp_feature(VCP_BRIGHTNESS, value)
+    }
+
+    /// Returns the monitor contrast
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the i2c transfer fails, if the reply is invalid, or if the monitor
+    /// doesn't support it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Ddc, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let ddc = Ddc::new(&connector).unwrap();
+    /// let contrast = ddc.contrast().unwrap();
+    /// ```
+    pub fn contrast(&self) -> Result<VcpValue> {
+        self.vcp_feature(VCP_CONTRAST)
+    }
+
+    /// Sets the monitor contrast
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the i2c transfer fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Ddc, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let ddc = Ddc::new(&connector).unwrap();
+    /// ddc.set_contrast(50).unwrap();
+    /// ```
+    pub fn set_contrast(&self, value: u16) -> Result<()> {
+        self.set_vcp_feature(VCP_CONTRAST, value)
+    }
+
+    /// Returns the monitor input source
+    ///
+    /// The values are defined by the MCCS specification, for example 0x0f for the first
+    /// `DisplayPort` input, or 0x11 for the first HDMI input. Some monitors use different values
+    /// though.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the i2c transfer fails, if the reply is invalid, or if the monitor
+    /// doesn't support it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Ddc, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let ddc = Ddc::new(&connector).unwrap();
+    /// let input = ddc.input_source().unwrap();
+    /// ```
+    pub fn input_source(&self) -> Result<u16> {
+        Ok(self.vcp_feature(VCP_INPUT_SOURCE)?.current())
+    }
+
+    /// Sets the monitor input source
+    ///
+    /// See [`Ddc::input_source`] for the values.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the i2c transfer fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Ddc, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let ddc = Ddc::new(&connector).unwrap();
+    ///
+    /// // Switch to HDMI 1
+    /// ddc.set_input_source(0x11).unwrap();
+    /// ```
+    pub fn set_input_source(&self, value: u16) -> Result<()> {
+        self.set_vcp_feature(VCP_INPUT_SOURCE, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{packet, parse_vcp_reply, VcpValue};
+    use crate::Error;
+
+    #[test]
+    fn test_packet() {
+        assert_eq!(packet(&[0x01, 0x10]), [0x51, 0x82, 0x01, 0x10, 0xac]);
+        assert_eq!(
+            packet(&[0x03, 0x10, 0x00, 0x32]),
+            [0x51, 0x84, 0x03, 0x10, 0x00, 0x32, 0x9a]
+        );
+    }
+
+    #[test]
+    fn test_vcp_reply() {
+        let mut reply = [
+            0x6e, 0x88, 0x02, 0x00, 0x10, 0x00, 0x00, 0x64, 0x00, 0x32, 0x00,
+        ];
+        reply[10] = reply[..10].iter().fold(0x50, |acc, byte| acc ^ byte);
+
+        assert_eq!(
+            parse_vcp_reply(0x10, &reply).unwrap(),
+            VcpValue {
+                current: 50,
+                maximum: 100,
+            }
+        );
+
+        assert!(matches!(
+            parse_vcp_reply(0x12, &reply),
+            Err(Error::InvalidDdcReply)
+        ));
+
+        reply[3] = 0x01;
+        reply[10] ^= 0x01;
+        assert!(matches!(
+            parse_vcp_reply(0x10, &reply),
+            Err(Error::UnsupportedVcpFeature(0x10))
+        ));
+
+        reply[10] ^= 0xff;
+        assert!(matches!(
+            parse_vcp_reply(0x10, &reply),
+            Err(Error::InvalidDdcReply)
+        ));
+    }
+}
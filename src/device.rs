@@ -1,15 +1,39 @@
 use std::{
-    cell::{Ref, RefCell},
+    collections::HashMap,
     fs::{File, OpenOptions},
-    rc::Rc,
+    os::unix::io::{AsRawFd, BorrowedFd},
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
 };
 
+use nix::errno::Errno;
+
 use crate::{
     encoder::Encoder,
-    raw::{drm_mode_get_planes, drm_mode_get_resources, drm_set_client_capability},
-    Buffer, BufferType, Connector, Crtc, Error, Output, Plane, Result,
+    event::parse_events,
+    gem::GemHandle,
+    node::{self, DeviceInfo, NodeType, DRI_PATH},
+    object::Object,
+    output::{commit_properties, PropertyBlobs},
+    raw::{
+        drm_auth_magic, drm_drop_master, drm_gem_close, drm_get_capability, drm_mode_get_connector,
+        drm_mode_get_encoder, drm_mode_get_planes, drm_mode_get_properties, drm_mode_get_property,
+        drm_mode_get_resources, drm_poll_events, drm_prime_fd_to_handle, drm_read_events,
+        drm_set_client_capability, drm_set_master, DRM_MODE_ATOMIC_ALLOW_MODESET,
+    },
+    record::Recorder,
+    snapshot::{self, Snapshot},
+    stats::FrameStats,
+    trace::{self, presented},
+    AdoptedFramebuffer, Blob, Buffer, BufferType, Capability, CommitHandle, Connector,
+    ConnectorStatus, Crtc, DriverInfo, Error, Event, ForeignFramebuffer, Format, FramebufferLayout,
+    Output, Plane, Result, Vblank,
 };
 
+// NOTE: The maximum number of events queued for Device::read_events. The oldest ones are dropped
+// past this limit.
+const MAX_QUEUED_EVENTS: usize = 256;
+
 #[allow(dead_code)]
 #[derive(Debug)]
 #[repr(u64)]
@@ -24,77 +48,118 @@ enum ClientCapability {
 #[derive(Debug)]
 pub struct Inner {
     pub(crate) file: File,
-    crtcs: Vec<Rc<Crtc>>,
-    encoders: Vec<Rc<Encoder>>,
-    connectors: Vec<Rc<Connector>>,
-    planes: Vec<Rc<Plane>>,
+    state: Mutex<State>,
+
+    // NOTE: Signalled, along with the state lock, whenever a thread is done reading the events.
+    events_read: Condvar,
+
+    // NOTE: Importing the same dma-buf several times returns the same GEM handle, and a handle
+    // can back several framebuffers, so we need to count the users of each handle to only close
+    // it once nobody uses it anymore. The lock is held across the import and close ioctls, or
+    // another thread releasing the last reference to a handle could close it right after the
+    // kernel handed it out again.
+    gem_handles: Mutex<HashMap<u32, usize>>,
+
+    // NOTE: Recording a commit needs to look up the property names, which takes the state lock,
+    // so the recorder can't live in the state. It has its own lock instead, held while recording
+    // so that it can't be started or stopped concurrently.
+    recorder: Mutex<Option<Recorder>>,
+
+    // NOTE: Loading libgbm and its driver is slow, so we only do it once, on the first GBM
+    // allocation.
+    #[cfg(all(feature = "gbm", target_os = "linux"))]
+    pub(crate) gbm: Mutex<Option<crate::gbm::Gbm>>,
+
+    #[cfg(feature = "legacy")]
+    legacy: bool,
 }
 
-#[derive(Debug)]
-pub struct Connectors<'a> {
-    inner: Ref<'a, Inner>,
-    count: usize,
+impl Inner {
+    fn new(file: File, #[cfg(feature = "legacy")] legacy: bool) -> Self {
+        Self {
+            file,
+            state: Mutex::new(State::default()),
+            events_read: Condvar::new(),
+            gem_handles: Mutex::new(HashMap::new()),
+            recorder: Mutex::new(None),
+            #[cfg(all(feature = "gbm", target_os = "linux"))]
+            gbm: Mutex::new(None),
+            #[cfg(feature = "legacy")]
+            legacy,
+        }
+    }
 }
 
-impl<'a> Iterator for Connectors<'a> {
-    type Item = Rc<Connector>;
+#[derive(Debug, Default)]
+pub struct State {
+    crtcs: Vec<Arc<Crtc>>,
+    encoders: Vec<Arc<Encoder>>,
+    connectors: Vec<Arc<Connector>>,
+    planes: Vec<Arc<Plane>>,
+    pub(crate) committed: HashMap<(u32, u32), u64>,
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let child = self.inner.connectors.get(self.count);
-        self.count += 1;
+    // NOTE: The blobs we create internally, like the mode ones, are owned by the pending commit
+    // and then by the committed state, until their property is committed with another value.
+    pub(crate) blobs: HashMap<(u32, u32), Blob>,
 
-        child.map(Rc::clone)
-    }
+    // NOTE: Waiting for an event might read other events, so we store them until someone
+    // waits for them or reads them. Only one thread reads the events at a time, and the others
+    // wait for it to be done since it might read the ones they're waiting for.
+    events: Vec<Event>,
+    reading: bool,
+    next_commit: u64,
+
+    // NOTE: The pending non-blocking commits, along with their CRTC and the vblank sequence
+    // they've been submitted at, to detect the skipped frames.
+    pending_flips: HashMap<u64, (u32, Option<u64>)>,
+    frame_stats: HashMap<u32, FrameStats>,
+
+    // NOTE: The properties are attached to a KMS object before it's registered, so the mapping
+    // between their names and IDs never changes and can be looked up only once per object.
+    pub(crate) property_ids: HashMap<u32, HashMap<String, u32>>,
 }
 
 #[derive(Debug)]
-pub struct Crtcs<'a> {
-    inner: Ref<'a, Inner>,
-    count: usize,
-}
+pub struct Connectors(std::vec::IntoIter<Arc<Connector>>);
 
-impl<'a> Iterator for Crtcs<'a> {
-    type Item = Rc<Crtc>;
+impl Iterator for Connectors {
+    type Item = Arc<Connector>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let child = self.inner.crtcs.get(self.count);
-        self.count += 1;
-
-        child.map(Rc::clone)
+        self.0.next()
     }
 }
 
 #[derive(Debug)]
-pub struct Encoders<'a> {
-    inner: Ref<'a, Inner>,
-    count: usize,
-}
+pub struct Crtcs(std::vec::IntoIter<Arc<Crtc>>);
 
-impl<'a> Iterator for Encoders<'a> {
-    type Item = Rc<Encoder>;
+impl Iterator for Crtcs {
+    type Item = Arc<Crtc>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let child = self.inner.encoders.get(self.count);
-        self.count += 1;
-
-        child.map(Rc::clone)
+        self.0.next()
     }
 }
 
 #[derive(Debug)]
-pub struct Planes<'a> {
-    inner: Ref<'a, Inner>,
-    count: usize,
-}
+pub struct Encoders(std::vec::IntoIter<Arc<Encoder>>);
 
-impl<'a> Iterator for Planes<'a> {
-    type Item = Rc<Plane>;
+impl Iterator for Encoders {
+    type Item = Arc<Encoder>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let child = self.inner.planes.get(self.count);
-        self.count += 1;
+        self.0.next()
+    }
+}
+
+#[derive(Debug)]
+pub struct Planes(std::vec::IntoIter<Arc<Plane>>);
+
+impl Iterator for Planes {
+    type Item = Arc<Plane>;
 
-        child.map(Rc::clone)
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
     }
 }
 
@@ -102,14 +167,27 @@ impl<'a> Iterator for Planes<'a> {
 ///
 /// A Device abstracts a collection of hardware components that glued and used together will provide
 /// the display capabilities and a number of [Plane]s, [Crtc]s and [Connector]s
-#[derive(Debug)]
+///
+/// Cloning a [Device] is cheap: all the clones share the same file descriptor and state.
+///
+/// A [Device] and the objects it hands out can be shared with, or moved to, other threads. The
+/// state of the [Device] is protected by a lock, while the ioctls are issued without holding it.
+/// The GEM handle imports and releases, and the GBM allocations, are serialized by dedicated
+/// locks instead.
+#[derive(Clone, Debug)]
 pub struct Device {
-    pub(crate) inner: Rc<RefCell<Inner>>,
+    pub(crate) inner: Arc<Inner>,
 }
 
 impl Device {
     /// Creates a new [Device] from a path
     ///
+    /// Devices without modesetting support, such as vgem, can be opened as well. They will not
+    /// expose any KMS object, but [Buffer]s can still be allocated on them.
+    ///
+    /// With the `legacy` feature enabled, devices without atomic support will fall back to the
+    /// legacy KMS API when committing an [Update](crate::Update).
+    ///
     /// # Errors
     ///
     /// Will return [Error] if `path` doesn't exist, the user doesn't have permission to access it
@@ -125,55 +203,196 @@ impl Device {
     pub fn new(path: &str) -> Result<Self> {
         let file = OpenOptions::new().read(true).write(true).open(path)?;
 
+        // NOTE: Drivers without modesetting support, like vgem, reject any KMS ioctl with
+        // EOPNOTSUPP. Buffers can still be allocated on those though, so let's just skip the KMS
+        // objects discovery.
+        match drm_mode_get_resources(&file, None, None, None) {
+            Err(Error::Ioctl(Errno::EOPNOTSUPP)) => {
+                return Ok(Self {
+                    inner: Arc::new(Inner::new(
+                        file,
+                        #[cfg(feature = "legacy")]
+                        false,
+                    )),
+                });
+            }
+            ret => {
+                let _res = ret?;
+            }
+        }
+
+        // NOTE: Drivers without atomic support reject the capability with EOPNOTSUPP, and kernels
+        // predating it with EINVAL.
+        #[cfg(feature = "legacy")]
+        let legacy = match drm_set_client_capability(&file, ClientCapability::Atomic as u64) {
+            Err(Error::Ioctl(Errno::EOPNOTSUPP | Errno::EINVAL)) => true,
+            ret => {
+                ret?;
+                false
+            }
+        };
+
+        #[cfg(not(feature = "legacy"))]
         drm_set_client_capability(&file, ClientCapability::Atomic as u64)?;
         drm_set_client_capability(&file, ClientCapability::UniversalPlanes as u64)?;
 
+        // NOTE: Those capabilities only expose the stereo and aspect ratio modes and flags, so
+        // older kernels rejecting them don't prevent us from using the device.
+        let _res = drm_set_client_capability(&file, ClientCapability::Stereo3d as u64);
+        let _res = drm_set_client_capability(&file, ClientCapability::AspectRatio as u64);
+
+        let device = Self {
+            inner: Arc::new(Inner::new(
+                file,
+                #[cfg(feature = "legacy")]
+                legacy,
+            )),
+        };
+
+        let _changes = device.rescan()?;
+
+        Ok(device)
+    }
+
+    /// Refreshes the lists of KMS objects of the [Device]
+    ///
+    /// The objects are discovered when the [Device] is created, but some can come and go
+    /// afterwards, like the `DisplayPort` MST [Connector]s when a hub is plugged. The objects
+    /// that are still there are kept as is, so the handles to them remain valid. The handles to
+    /// the removed [Connector]s are reported as [stale](Connector::is_stale).
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let changes = device.rescan().unwrap();
+    /// for connector in changes.added() {
+    ///     println!("{} added", connector.name());
+    /// }
+    /// ```
+    pub fn rescan(&self) -> Result<ConnectorChanges> {
         let mut crtc_ids = Vec::new();
         let mut encoder_ids = Vec::new();
         let mut connector_ids = Vec::new();
-        let _res = drm_mode_get_resources(
-            &file,
+
+        match drm_mode_get_resources(
+            self,
             Some(&mut crtc_ids),
             Some(&mut encoder_ids),
             Some(&mut connector_ids),
-        )?;
-
-        let device = Self {
-            inner: Rc::new(RefCell::new(Inner {
-                file,
-                crtcs: Vec::new(),
-                encoders: Vec::new(),
-                connectors: Vec::new(),
-                planes: Vec::new(),
-            })),
-        };
+        ) {
+            Err(Error::Ioctl(Errno::EOPNOTSUPP)) => return Ok(ConnectorChanges::default()),
+            ret => {
+                let _res = ret?;
+            }
+        }
 
-        for (idx, id) in crtc_ids.into_iter().enumerate() {
-            let crtc = Rc::new(Crtc::new(&device, id, idx)?);
+        let plane_ids = drm_mode_get_planes(self)?;
 
-            device.inner.borrow_mut().crtcs.push(crtc);
-        }
+        // NOTE: The objects are created without holding the lock, since it issues ioctls, and
+        // the lists are swapped afterwards.
+        let (crtcs, encoders, connectors, planes) = {
+            let state = self.state();
 
-        for id in encoder_ids {
-            let encoder = Rc::new(Encoder::new(&device, id)?);
+            (
+                state.crtcs.clone(),
+                state.encoders.clone(),
+                state.connectors.clone(),
+                state.planes.clone(),
+            )
+        };
 
-            device.inner.borrow_mut().encoders.push(encoder);
-        }
+        let crtcs = reconcile(&crtcs, &crtc_ids, Crtc::object_id, |idx, id| {
+            Crtc::new(self, id, idx)
+        })?;
+        let encoders = reconcile(&encoders, &encoder_ids, Encoder::id, |_, id| {
+            Encoder::new(self, id)
+        })?;
+        let connectors = reconcile(
+            &connectors,
+            &connector_ids,
+            Connector::object_id,
+            |_, id| Connector::new(self, id),
+        )?;
+        let planes = reconcile(&planes, &plane_ids, Plane::object_id, |_, id| {
+            Plane::new(self, id)
+        })?;
 
-        for id in connector_ids {
-            let connector = Rc::new(Connector::new(&device, id)?);
+        // NOTE: A removed object ID might be reused later on for a different object, with
+        // different properties.
+        let ids: Vec<u32> = crtc_ids
+            .iter()
+            .chain(&connector_ids)
+            .chain(&plane_ids)
+            .copied()
+            .collect();
 
-            device.inner.borrow_mut().connectors.push(connector);
-        }
+        let mut state = self.state();
+        let changes = ConnectorChanges::new(&state.connectors, &connectors);
+        state.crtcs = crtcs;
+        state.encoders = encoders;
+        state.connectors = connectors;
+        state.planes = planes;
+        state.property_ids.retain(|id, _| ids.contains(id));
+        drop(state);
 
-        let plane_ids = drm_mode_get_planes(&device)?;
-        for id in plane_ids {
-            let plane = Rc::new(Plane::new(&device, id)?);
+        Ok(changes)
+    }
 
-            device.inner.borrow_mut().planes.push(plane);
-        }
+    /// Lists the DRM nodes available on the system
+    ///
+    /// Both the primary and render nodes found in `/dev/dri` are returned, primary nodes first.
+    /// The nodes that can't be opened, usually for lack of permission, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if `/dev/dri` can't be read.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, NodeType};
+    ///
+    /// for info in Device::enumerate().unwrap() {
+    ///     if info.node_type() == NodeType::Primary {
+    ///         println!("{}: {}", info.path(), info.driver());
+    ///     }
+    /// }
+    /// ```
+    pub fn enumerate() -> Result<Vec<DeviceInfo>> {
+        node::enumerate(DRI_PATH)
+    }
 
-        Ok(device)
+    /// Opens the first primary node with at least one connected [Connector]
+    ///
+    /// The [Connector]s reported by [`Device::connected_connectors`] are considered connected.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if `/dev/dri` can't be read, or [`Error::Empty`] if no primary node
+    /// can be opened with a connected [Connector].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::open_first_with_connected_connector().unwrap();
+    /// ```
+    pub fn open_first_with_connected_connector() -> Result<Self> {
+        Self::enumerate()?
+            .iter()
+            .filter(|info| info.node_type() == NodeType::Primary)
+            .filter_map(|info| Self::new(info.path()).ok())
+            .find(|device| !device.connected_connectors().is_empty())
+            .ok_or(Error::Empty)
     }
 
     /// Returns an Iterator over the [Connector]s
@@ -190,10 +409,117 @@ impl Device {
     ///     .collect();
     /// ```
     #[must_use]
-    pub fn connectors(&self) -> Connectors<'_> {
-        let inner = self.inner.borrow();
+    pub fn connectors(&self) -> Connectors {
+        // NOTE: The objects are cloned so that the state isn't locked while iterating.
+        Connectors(self.state().connectors.clone().into_iter())
+    }
+
+    /// Returns the [Connector]s that have a sink attached
+    ///
+    /// The [Connector]s reported as [Connected](ConnectorStatus::Connected) come first. Since
+    /// some drivers can't reliably detect a sink, the ones with an
+    /// [Unknown](ConnectorStatus::Unknown) status follow, as they are likely to be usable too.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connected_connectors()
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn connected_connectors(&self) -> Vec<Arc<Connector>> {
+        let connectors: Vec<_> = self
+            .connectors()
+            .filter_map(|con| con.status().ok().map(|status| (con, status)))
+            .collect();
+
+        let (connected, unknown): (Vec<_>, Vec<_>) = connectors
+            .into_iter()
+            .filter(|(_, status)| *status != ConnectorStatus::Disconnected)
+            .partition(|(_, status)| *status == ConnectorStatus::Connected);
+
+        connected
+            .into_iter()
+            .chain(unknown)
+            .map(|(con, _)| con)
+            .collect()
+    }
+
+    /// Builds an [Output] for the first connected [Connector]
+    ///
+    /// The [Connector]s are considered in the order returned by
+    /// [`Device::connected_connectors`], and the first one an [Output] can be built for is used.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if no [Connector] is connected, or if no suitable [Crtc] could be
+    /// found for any of them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    /// ```
+    pub fn first_connected_output(&self) -> Result<Output> {
+        self.connected_connectors()
+            .iter()
+            .find_map(|con| self.output_from_connector(con).ok())
+            .ok_or(Error::Empty)
+    }
+
+    /// Blocks until at least one [Connector] is connected
+    ///
+    /// The [Connector]s status is polled, with an increasing delay between two attempts, and the
+    /// first connected [Connector] is returned.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::Timeout`] if no [Connector] got connected within `timeout`, or
+    /// [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.wait_for_connection(Duration::from_secs(60)).unwrap();
+    /// let output = device.output_from_connector(&connector).unwrap();
+    /// ```
+    pub fn wait_for_connection(&self, timeout: Duration) -> Result<Arc<Connector>> {
+        const MIN_DELAY: Duration = Duration::from_millis(50);
+        const MAX_DELAY: Duration = Duration::from_secs(1);
+
+        let deadline = Instant::now() + timeout;
+        let mut delay = MIN_DELAY;
 
-        Connectors { inner, count: 0 }
+        loop {
+            for connector in self.connectors() {
+                if connector.status()? == ConnectorStatus::Connected {
+                    return Ok(connector);
+                }
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(Error::Timeout);
+            }
+
+            std::thread::sleep(delay.min(remaining));
+            delay = (delay * 2).min(MAX_DELAY);
+        }
     }
 
     /// Returns an Iterator over the [Crtc]s
@@ -210,16 +536,28 @@ impl Device {
     ///     .collect();
     /// ```
     #[must_use]
-    pub fn crtcs(&self) -> Crtcs<'_> {
-        let inner = self.inner.borrow();
-
-        Crtcs { inner, count: 0 }
+    pub fn crtcs(&self) -> Crtcs {
+        // NOTE: The objects are cloned so that the state isn't locked while iterating.
+        Crtcs(self.state().crtcs.clone().into_iter())
     }
 
-    pub(crate) fn encoders(&self) -> Encoders<'_> {
-        let inner = self.inner.borrow();
-
-        Encoders { inner, count: 0 }
+    /// Returns an Iterator over the [Encoder]s
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let encoders: Vec<_> = device.encoders()
+    ///     .collect();
+    /// ```
+    #[must_use]
+    pub fn encoders(&self) -> Encoders {
+        // NOTE: The objects are cloned so that the state isn't locked while iterating.
+        Encoders(self.state().encoders.clone().into_iter())
     }
 
     /// Returns an Iterator over the [Plane]s
@@ -236,13 +574,22 @@ impl Device {
     ///     .collect();
     /// ```
     #[must_use]
-    pub fn planes(&self) -> Planes<'_> {
-        let inner = self.inner.borrow();
-
-        Planes { inner, count: 0 }
+    pub fn planes(&self) -> Planes {
+        // NOTE: The objects are cloned so that the state isn't locked while iterating.
+        Planes(self.state().planes.clone().into_iter())
     }
 
-    /// Allocates a DRM [Buffer]
+    /// Allocates a zeroed DRM [Buffer]
+    ///
+    /// Dumb buffers are allocated by the [Device] itself, while dma-heap buffers are allocated
+    /// from a [`DmaHeap`](crate::DmaHeap) and then imported into the [Device].
+    ///
+    /// The [Buffer] is cleared before being returned, so that its first frame is always defined,
+    /// black or transparent in the usual formats. See [`Device::allocate_buffer_uninit`] to skip
+    /// the clear, and [`Device::allocate_buffer_cleared`] to clear to another color.
+    ///
+    /// The [Buffer] has a single plane of `bpp` bits per pixel. See
+    /// [`Device::allocate_buffer_with_format`] to derive the allocation from a [Format] instead.
     ///
     /// # Errors
     ///
@@ -266,59 +613,1403 @@ impl Device {
         width: usize,
         height: usize,
         bpp: usize,
+    ) -> Result<Buffer> {
+        let mut buffer = self.allocate_buffer_uninit(buftype, width, height, bpp)?;
+        buffer.data().fill(0);
+
+        Ok(buffer)
+    }
+
+    /// Allocates a DRM [Buffer] cleared to a single color
+    ///
+    /// `color` is the raw pixel value, and is truncated to the pixel size for the formats with
+    /// less than 32 bits per pixel. See [`Buffer::fill`].
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the buffer allocation fails, or if `bpp` isn't a whole number of
+    /// bytes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let buffer = device.allocate_buffer_cleared(BufferType::Dumb, 640, 480, 32, 0x0000_00ff)
+    ///     .unwrap();
+    /// ```
+    pub fn allocate_buffer_cleared(
+        &self,
+        buftype: BufferType,
+        width: usize,
+        height: usize,
+        bpp: usize,
+        color: u32,
+    ) -> Result<Buffer> {
+        let mut buffer = self.allocate_buffer_uninit(buftype, width, height, bpp)?;
+        buffer.fill(color)?;
+
+        Ok(buffer)
+    }
+
+    /// Allocates a DRM [Buffer] without clearing it
+    ///
+    /// This skips the CPU clear done by [`Device::allocate_buffer`], which is worth it for large
+    /// [Buffer]s that will be entirely drawn into before being displayed.
+    ///
+    /// The content of the [Buffer] is then whatever the kernel handed out. Buffers backed by
+    /// system memory, which covers most dumb buffers and the dma-heap ones, are always zeroed
+    /// since the kernel never exposes stale pages to userspace. However, drivers allocating
+    /// dumb buffers in dedicated video memory don't necessarily clear it, and the [Buffer] may
+    /// then hold leftovers of previous frames.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the buffer allocation fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let mut buffer = device.allocate_buffer_uninit(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    ///
+    /// buffer.data().fill(0xff);
+    /// ```
+    pub fn allocate_buffer_uninit(
+        &self,
+        buftype: BufferType,
+        width: usize,
+        height: usize,
+        bpp: usize,
     ) -> Result<Buffer> {
         let raw = match buftype {
             BufferType::Dumb => Buffer::new(self, width, height, bpp)?,
+
+            #[cfg(target_os = "linux")]
+            BufferType::DmaHeap(heap) => {
+                // NOTE: Unlike dumb buffers, the driver doesn't pick the pitch for us. Most
+                // display engines are fine with lines aligned on 64 bytes.
+                let pitch = (width * bpp).div_ceil(8).next_multiple_of(64);
+                let dmabuf = heap.allocate(pitch * height)?;
+
+                Buffer::import(self, &dmabuf, width, height, bpp, pitch)?
+            }
+
+            #[cfg(all(feature = "gbm", target_os = "linux"))]
+            BufferType::Gbm => {
+                let format = [Format::XRGB8888, Format::RGB888]
+                    .iter()
+                    .copied()
+                    .find(|fmt| fmt.info().bpp() == bpp)
+                    .ok_or(Error::Unsupported("GBM Format"))?;
+
+                crate::gbm::allocate(self, width, height, format)?
+            }
         };
 
         Ok(raw)
     }
 
-    /// Builds an [Output] from a [Connector]
+    /// Allocates a DRM [Buffer] for a given [Format]
     ///
-    /// Finds a suitable [Crtc] for a given [Connector] and creates an [Output] from
-    /// that.
+    /// Unlike [`Device::allocate_buffer`], this supports the multi-planar formats like
+    /// [`Format::NV12`]. All the planes are stored in a single [Buffer], one after the other,
+    /// and can be accessed through [`Buffer::plane`]. [`Buffer::into_framebuffer`] will then set
+    /// up the [Framebuffer](crate::Framebuffer) planes accordingly.
+    ///
+    /// The [Buffer] is cleared to zero. See [`Device::allocate_buffer_with_format_uninit`] to
+    /// skip the clear.
     ///
     /// # Errors
     ///
-    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails, or if it could
-    /// not find a suitable [Crtc] for the [Connector]
+    /// Will return [Error] if the buffer allocation fails.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, Device};
-    ///
-    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// use nucleid::{BufferType, Device, Format};
     ///
-    /// let connector = device.connectors()
-    ///     .into_iter()
-    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    /// let device = Device::new("/dev/dri/card0")
     ///     .unwrap();
     ///
-    /// let output = device.output_from_connector(&connector).unwrap();
+    /// let fb = device
+    ///     .allocate_buffer_with_format(BufferType::Dumb, 1920, 1080, Format::NV12)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::NV12)
+    ///     .unwrap();
     /// ```
-    pub fn output_from_connector(&self, connector: &Rc<Connector>) -> Result<Output> {
-        let encoder = connector
-            .encoders()?
-            .into_iter()
-            .next()
-            .ok_or(Error::Empty)?;
-
-        let crtc = encoder.crtcs()?.into_iter().next().ok_or(Error::Empty)?;
+    pub fn allocate_buffer_with_format(
+        &self,
+        buftype: BufferType,
+        width: usize,
+        height: usize,
+        format: Format,
+    ) -> Result<Buffer> {
+        let mut buffer = self.allocate_buffer_with_format_uninit(buftype, width, height, format)?;
+        buffer.data().fill(0);
 
-        Ok(Output::new(self, &crtc, &encoder, connector))
+        Ok(buffer)
     }
-}
 
-impl std::os::unix::io::AsRawFd for Device {
-    fn as_raw_fd(&self) -> std::os::unix::io::RawFd {
-        self.inner.borrow().file.as_raw_fd()
-    }
-}
+    /// Allocates a DRM [Buffer] for a given [Format] without clearing it
+    ///
+    /// This is the [`Device::allocate_buffer_with_format`] counterpart of
+    /// [`Device::allocate_buffer_uninit`], and the [Buffer] content is just as undefined.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the buffer allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, Format};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let mut buffer = device
+    ///     .allocate_buffer_with_format_uninit(BufferType::Dumb, 1920, 1080, Format::NV12)
+    ///     .unwrap();
+    ///
+    /// buffer.plane(0).unwrap().data().fill(16);
+    /// buffer.plane(1).unwrap().data().fill(128);
+    /// ```
+    pub fn allocate_buffer_with_format_uninit(
+        &self,
+        buftype: BufferType,
+        width: usize,
+        height: usize,
+        format: Format,
+    ) -> Result<Buffer> {
+        #[cfg(all(feature = "gbm", target_os = "linux"))]
+        if matches!(buftype, BufferType::Gbm) {
+            return crate::gbm::allocate(self, width, height, format);
+        }
+
+        let info = format.info();
+        let buffer = self.allocate_buffer_uninit(buftype, width, info.lines(height), info.bpp())?;
+
+        buffer.with_format(width, height, format)
+    }
+
+    /// Imports a dma-buf as a DRM [Buffer]
+    ///
+    /// The dma-buf, typically exported by another device or allocated by another process, is
+    /// mapped so that the [Buffer] can be accessed from the CPU like any other one. The file
+    /// descriptor can be closed once the [Buffer] is imported.
+    ///
+    /// The same dma-buf can be imported several times, and will only be released once all the
+    /// [Buffer]s importing it are dropped.
+    ///
+    /// The CPU accesses to the [Buffer] are synchronized with the other users of the dma-buf: an
+    /// access starts when the [Buffer] content is first accessed, and ends when it's turned into
+    /// a [Framebuffer](crate::Framebuffer), attached to a [Plane], flushed or exported.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the import or the mapping fails, and [`Error::DmaBufTooSmall`] if
+    /// the dma-buf is smaller than `pitch` times `height`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    ///
+    /// use nucleid::{Device, Format};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// # fn receive_dmabuf() -> File { unimplemented!() }
+    /// let dmabuf = receive_dmabuf();
+    /// let buffer = device.import_buffer(&dmabuf, 640, 480, 32, 640 * 4)
+    ///     .unwrap();
+    ///
+    /// let fb = buffer.into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    /// ```
+    pub fn import_buffer(
+        &self,
+        fd: &impl AsRawFd,
+        width: usize,
+        height: usize,
+        bpp: usize,
+        pitch: usize,
+    ) -> Result<Buffer> {
+        Buffer::import(self, fd, width, height, bpp, pitch)
+    }
+
+    /// Creates a [`ForeignFramebuffer`] from a GEM handle allocated outside of nucleid
+    ///
+    /// The handle must have been created on the same file descriptor as the [Device], for
+    /// example by a vendor-specific allocation ioctl. It remains owned by the caller, and won't
+    /// be closed by nucleid. See [`ForeignFramebuffer`] for more details.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails, typically
+    /// because the handle is invalid or the layout doesn't fit in the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Format, FramebufferLayout};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// # fn vendor_allocate() -> u32 { unimplemented!() }
+    /// let handle = vendor_allocate();
+    /// let layout = FramebufferLayout::new(640, 480, Format::XRGB8888, 640 * 4);
+    ///
+    /// let fb = device.framebuffer_from_handle(handle, layout)
+    ///     .unwrap();
+    /// ```
+    pub fn framebuffer_from_handle(
+        &self,
+        handle: u32,
+        layout: FramebufferLayout,
+    ) -> Result<ForeignFramebuffer> {
+        ForeignFramebuffer::new(self, &[handle], layout)
+    }
+
+    /// Creates a [`ForeignFramebuffer`] from one GEM handle per plane, allocated outside of
+    /// nucleid
+    ///
+    /// This is the multi-planar variant of [`Device::framebuffer_from_handle`], for the formats
+    /// whose planes are stored in separate buffers. If there's less handles than planes in the
+    /// [`FramebufferLayout`], the last handle is used for the remaining planes.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails, typically
+    /// because a handle is invalid or the layout doesn't fit in the buffers.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Format, FramebufferLayout, Modifier};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// # fn vendor_allocate_tiled() -> (u32, u64) { unimplemented!() }
+    /// let (handle, modifier) = vendor_allocate_tiled();
+    /// let layout = FramebufferLayout::new(640, 480, Format::XRGB8888, 640 * 4)
+    ///     .set_modifier(Modifier::new(modifier));
+    ///
+    /// let fb = device.framebuffer_from_handles(&[handle], layout)
+    ///     .unwrap();
+    /// ```
+    pub fn framebuffer_from_handles(
+        &self,
+        handles: &[u32],
+        layout: FramebufferLayout,
+    ) -> Result<ForeignFramebuffer> {
+        ForeignFramebuffer::new(self, handles, layout)
+    }
+
+    /// Creates a [`ForeignFramebuffer`] from GEM handles handed over to nucleid
+    ///
+    /// Unlike [`Device::framebuffer_from_handles`], the [`ForeignFramebuffer`] keeps the
+    /// [`GemHandle`]s, and they are closed once it's dropped, unless they are still used
+    /// elsewhere. If there's less handles than planes in the [`FramebufferLayout`], the last
+    /// handle is used for the remaining planes.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails, typically
+    /// because a handle is invalid or the layout doesn't fit in the buffers.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Format, FramebufferLayout, GemHandle};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// # fn vendor_allocate() -> u32 { unimplemented!() }
+    /// let handle = GemHandle::new(&device, vendor_allocate());
+    /// let layout = FramebufferLayout::new(640, 480, Format::XRGB8888, 640 * 4);
+    ///
+    /// let fb = device.framebuffer_from_gem_handles(vec![handle], layout)
+    ///     .unwrap();
+    ///
+    /// drop(fb);
+    /// assert_eq!(device.gem_handles(), 0);
+    /// ```
+    pub fn framebuffer_from_gem_handles(
+        &self,
+        handles: Vec<GemHandle>,
+        layout: FramebufferLayout,
+    ) -> Result<ForeignFramebuffer> {
+        ForeignFramebuffer::with_handles(self, handles, layout)
+    }
+
+    /// Creates a [`ForeignFramebuffer`] from dma-bufs exported by another device or process
+    ///
+    /// This allows to scan out buffers rendered by a GPU or captured by a camera without any
+    /// copy. The [`FramebufferLayout`] describes the planes, and the [Modifier](crate::Modifier)
+    /// the buffers have been allocated with. There's usually one dma-buf per plane, but if
+    /// there's less dma-bufs than planes, the last one is used for the remaining planes.
+    ///
+    /// The dma-bufs file descriptors can be closed once the [`ForeignFramebuffer`] is created.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctls fail, typically
+    /// because the driver can't import the dma-bufs or the layout isn't supported.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::{fs::File, os::unix::io::AsFd};
+    ///
+    /// use nucleid::{Device, Format, FramebufferLayout, Modifier};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// # fn receive_dmabuf() -> (File, u64) { unimplemented!() }
+    /// let (dmabuf, modifier) = receive_dmabuf();
+    /// let layout = FramebufferLayout::new(1920, 1080, Format::NV12, 1920)
+    ///     .add_plane(1920, 1920 * 1080)
+    ///     .set_modifier(Modifier::new(modifier));
+    ///
+    /// let fb = device.import_dmabuf(&[dmabuf.as_fd()], layout)
+    ///     .unwrap();
+    /// ```
+    pub fn import_dmabuf(
+        &self,
+        fds: &[BorrowedFd<'_>],
+        layout: FramebufferLayout,
+    ) -> Result<ForeignFramebuffer> {
+        let handles = fds
+            .iter()
+            .map(|fd| self.import_prime_fd(fd))
+            .collect::<Result<Vec<_>>>()?;
+
+        ForeignFramebuffer::with_handles(self, handles, layout)
+    }
+
+    /// Returns the number of GEM handles held by nucleid
+    ///
+    /// The GEM handles backing the [Buffer]s and [Framebuffer](crate::Framebuffer)s are closed
+    /// as soon as nothing uses them anymore, so this can be used by long-running processes to
+    /// check that they don't leak any buffer. The handles given to
+    /// [`Device::framebuffer_from_handle`] aren't accounted for, since they belong to the caller,
+    /// unlike the ones wrapped in a [`GemHandle`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// let buffer = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    /// assert_eq!(device.gem_handles(), 1);
+    ///
+    /// drop(buffer);
+    /// assert_eq!(device.gem_handles(), 0);
+    /// ```
+    #[must_use]
+    pub fn gem_handles(&self) -> usize {
+        self.gem_handles_lock().len()
+    }
+
+    /// Becomes the DRM master of the [Device]
+    ///
+    /// Only the DRM master can change the display configuration. A [Device] opened while no
+    /// other process holds the master role becomes master automatically, so this is meant to
+    /// take it back after a [`Device::drop_master`], for example when switching back to our
+    /// virtual terminal.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the ioctl fails, for example if another process is already master
+    /// or if we lack the privileges to become master.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// device.drop_master().unwrap();
+    /// // Let the display manager take over
+    /// device.set_master().unwrap();
+    /// ```
+    pub fn set_master(&self) -> Result<()> {
+        drm_set_master(self)
+    }
+
+    /// Releases the DRM master role of the [Device]
+    ///
+    /// This allows another process, such as a display manager, to take over the display until
+    /// we call [`Device::set_master`] again. No commit can be done in the meantime.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the ioctl fails, for example if the [Device] isn't master.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// device.drop_master().unwrap();
+    /// ```
+    pub fn drop_master(&self) -> Result<()> {
+        drm_drop_master(self)
+    }
+
+    /// Returns whether the [Device] is the DRM master
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// if !device.is_master().unwrap() {
+    ///     device.set_master().unwrap();
+    /// }
+    /// ```
+    pub fn is_master(&self) -> Result<bool> {
+        // NOTE: There's no ioctl to query the master status, but only the master can
+        // authenticate clients. The magic 0 is never valid, so the master gets EINVAL while
+        // any other client gets EACCES.
+        match drm_auth_magic(self, 0) {
+            Err(Error::Ioctl(Errno::EACCES)) => Ok(false),
+            Ok(()) | Err(Error::Ioctl(Errno::EINVAL)) => Ok(true),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Creates a new property [Blob] holding `data`
+    ///
+    /// The [Blob] is destroyed when dropped.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorUpdate, Device, ObjectUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let connector = device.connectors().next().unwrap();
+    /// let output = device.output_from_connector(&connector).unwrap();
+    ///
+    /// let metadata = [0u8; 28];
+    /// let blob = device.create_blob(&metadata).unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_connector(
+    ///         ConnectorUpdate::new(&connector).set_blob_property("HDR_OUTPUT_METADATA", &blob),
+    ///     )
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    pub fn create_blob(&self, data: &[u8]) -> Result<Blob> {
+        Blob::new(self, data)
+    }
+
+    /// Returns the name, date, description and version of the driver behind the [Device]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let info = device.driver_info().unwrap();
+    /// println!("{}: {}", info.name(), info.description());
+    /// ```
+    pub fn driver_info(&self) -> Result<DriverInfo> {
+        DriverInfo::new(self)
+    }
+
+    /// Returns the value of a [Capability] of the [Device]
+    ///
+    /// Boolean capabilities are reported as 0 or 1.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails, for example if
+    /// the kernel doesn't know about the [Capability].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Capability, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let width = device.capability(Capability::CursorWidth).unwrap();
+    /// let height = device.capability(Capability::CursorHeight).unwrap();
+    ///
+    /// let cursor = device
+    ///     .allocate_buffer(BufferType::Dumb, width as usize, height as usize, 32)
+    ///     .unwrap();
+    /// ```
+    pub fn capability(&self, cap: Capability) -> Result<u64> {
+        drm_get_capability(self, cap as u64)
+    }
+
+    /// Looks up an existing framebuffer
+    ///
+    /// The framebuffer can have been created by any application, or by the kernel. Its layout
+    /// is always reported, but the GEM handles of its buffers are only given to the DRM master
+    /// and to the privileged clients. The framebuffer scanned out by a [Crtc] can be found
+    /// with [`Crtc::framebuffer_id`].
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if there's no such framebuffer, or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// for crtc in device.crtcs() {
+    ///     if let Some(id) = crtc.framebuffer_id().unwrap() {
+    ///         let fb = device.framebuffer_info(id).unwrap();
+    ///         println!("{}x{} {:#010x}", fb.width(), fb.height(), fb.fourcc());
+    ///     }
+    /// }
+    /// ```
+    pub fn framebuffer_info(&self, id: u32) -> Result<AdoptedFramebuffer> {
+        AdoptedFramebuffer::new(self, id)
+    }
+
+    /// Builds an [Output] from a [Connector]
+    ///
+    /// Finds a suitable [Crtc] for a given [Connector] and creates an [Output] from
+    /// that.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails, or if it could
+    /// not find a suitable [Crtc] for the [Connector]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_connector(&connector).unwrap();
+    /// ```
+    pub fn output_from_connector(&self, connector: &Arc<Connector>) -> Result<Output> {
+        self.output_from_connector_excluding(connector, &[])
+    }
+
+    /// Builds an [Output] from a [Connector], without using any of the `used_crtcs`
+    pub(crate) fn output_from_connector_excluding(
+        &self,
+        connector: &Arc<Connector>,
+        used_crtcs: &[u32],
+    ) -> Result<Output> {
+        for encoder in connector.encoders()? {
+            let crtc = encoder
+                .crtcs()?
+                .into_iter()
+                .find(|crtc| !used_crtcs.contains(&crtc.object_id()));
+
+            if let Some(crtc) = crtc {
+                return Ok(Output::new(self, &crtc, &encoder, connector));
+            }
+        }
+
+        Err(Error::Empty)
+    }
+
+    /// Builds an [Output] from the current configuration of a [Connector]
+    ///
+    /// Unlike [`Device::output_from_connector`], this will reuse the [Crtc] currently driving the
+    /// [Connector], as set up by the bootloader, a boot splash or a previous application. As long
+    /// as no new [Mode](crate::Mode) is set, committing an [Update](crate::Update) to that
+    /// [Output] will not trigger a modeset, allowing a flicker-free transition.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails, or if the
+    /// [Connector] isn't currently enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    /// let mode = output.current_mode().unwrap().unwrap();
+    /// ```
+    pub fn output_from_current_state(&self, connector: &Arc<Connector>) -> Result<Output> {
+        let encoder_id =
+            drm_mode_get_connector(self, connector.object_id(), None, None)?.encoder_id;
+        let encoder = self
+            .encoders()
+            .find(|enc| enc.id() == encoder_id)
+            .ok_or(Error::Empty)?;
+
+        let crtc_id = drm_mode_get_encoder(self, encoder_id)?.crtc_id;
+        let crtc = self
+            .crtcs()
+            .find(|crtc| crtc.object_id() == crtc_id)
+            .ok_or(Error::Empty)?;
+
+        Ok(Output::new(self, &crtc, &encoder, connector))
+    }
+
+    /// Starts recording every committed property set to a file
+    ///
+    /// Every successful commit will be logged to the file at `path`, which will be created or
+    /// truncated. The object IDs, property names, values and commit flags are recorded so that
+    /// the sequence can be re-applied later on using a [Replayer](crate::Replayer). Test-only
+    /// commits aren't recorded, and a failure to record a commit doesn't fail the commit itself.
+    ///
+    /// The `IN_FENCE_FD` and `OUT_FENCE_PTR` properties hold a file descriptor and an address
+    /// that are only meaningful to the process that committed them, so they aren't recorded.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the file can't be created.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// device.start_recording("commits.log").unwrap();
+    /// ```
+    pub fn start_recording(&self, path: &str) -> Result<()> {
+        let recorder = Recorder::new(path)?;
+
+        *self.recorder_lock() = Some(recorder);
+
+        Ok(())
+    }
+
+    /// Stops recording the committed property sets
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// device.start_recording("commits.log").unwrap();
+    /// device.stop_recording();
+    /// ```
+    pub fn stop_recording(&self) {
+        *self.recorder_lock() = None;
+    }
+
+    /// Re-applies the last committed state
+    ///
+    /// Every property set by a successful commit is tracked by the [Device]. When the DRM master
+    /// is lost, for example during a VT switch or when logind pauses the session, another client
+    /// is free to reconfigure the display pipeline. Once master is regained, this will commit the
+    /// whole state again, triggering a modeset if needed, so that every [Output] is restored as it
+    /// was.
+    ///
+    /// The [Framebuffer](crate::Framebuffer)s that were part of the last committed state must
+    /// still be alive for the commit to succeed.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] isn't the DRM master, or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// // Session has been resumed
+    /// device.reapply().unwrap();
+    /// ```
+    pub fn reapply(&self) -> Result<()> {
+        let properties: Vec<_> = self
+            .state()
+            .committed
+            .iter()
+            .map(|(&(object_id, prop_id), &value)| (object_id, prop_id, value))
+            .collect();
+
+        if properties.is_empty() {
+            return Ok(());
+        }
+
+        commit_properties(
+            self,
+            DRM_MODE_ATOMIC_ALLOW_MODESET,
+            properties,
+            PropertyBlobs::new(),
+        )
+    }
+
+    /// Saves the current state of the display pipeline
+    ///
+    /// Every property that can be set on the [Crtc]s, [Connector]s and [Plane]s is saved, so
+    /// that the state can be restored later on with [`Device::restore_state`], for example once
+    /// a test application is done probing configurations.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let snapshot = device.save_state().unwrap();
+    /// std::fs::write("display.state", snapshot.to_string()).unwrap();
+    /// ```
+    pub fn save_state(&self) -> Result<Snapshot> {
+        snapshot::save_state(self)
+    }
+
+    /// Restores a state saved by [`Device::save_state`]
+    ///
+    /// The whole state is restored in a single commit, triggering a modeset if needed. The
+    /// framebuffers that were scanned out when the [Snapshot] was taken must still exist.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] isn't the DRM master, if one of the objects or
+    /// properties can't be found, or if the commit is rejected.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Snapshot};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let snapshot: Snapshot = std::fs::read_to_string("display.state")
+    ///     .unwrap()
+    ///     .parse()
+    ///     .unwrap();
+    /// device.restore_state(&snapshot).unwrap();
+    /// ```
+    pub fn restore_state(&self, snapshot: &Snapshot) -> Result<()> {
+        snapshot::restore_state(self, snapshot)
+    }
+
+    /// Blocks until the non-blocking commit identified by `handle` is presented
+    ///
+    /// Returns the [Vblank] at which the commit has been presented.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::Timeout`] if the commit hasn't been presented within `timeout`, or
+    /// [Error] if the [Device] can't be accessed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use nucleid::{ConnectorStatus, Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let (output, handle) = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .commit_nonblocking()
+    ///     .unwrap();
+    ///
+    /// let vblank = device.wait_for_flip(handle, Duration::from_millis(100)).unwrap();
+    /// println!("Presented at {:?}", vblank.time());
+    /// ```
+    pub fn wait_for_flip(&self, handle: CommitHandle, timeout: Duration) -> Result<Vblank> {
+        let event = self.wait_for_event(
+            |event| matches!(event, Event::Flip(flip) if flip.handle() == handle),
+            Some(Instant::now() + timeout),
+        )?;
+
+        match event {
+            Event::Flip(flip) => Ok(flip.vblank()),
+            _ => Err(Error::Empty),
+        }
+    }
+
+    /// Waits for events to be available, for at most `timeout`
+    ///
+    /// Returns `true` if [`Device::read_events`] will return without blocking.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// if device.poll_events(Duration::from_millis(20)).unwrap() {
+    ///     let events = device.read_events().unwrap();
+    /// }
+    /// ```
+    pub fn poll_events(&self, timeout: Duration) -> Result<bool> {
+        if !self.state().events.is_empty() {
+            return Ok(true);
+        }
+
+        drm_poll_events(self, timeout)
+    }
+
+    /// Reads the events sent by the [Device]
+    ///
+    /// This function will block until at least one event has been received, unless some events
+    /// have already been read while waiting for another one. [`Device::poll_events`], or polling
+    /// the [Device] file descriptor, can be used beforehand to integrate it in an event loop.
+    ///
+    /// Only the 256 most recent events read while waiting for another one are kept, the older
+    /// ones are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Event, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let mut output = device.first_connected_output().unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// loop {
+    ///     let (next, _) = output
+    ///         .start_update()
+    ///         .add_plane(PlaneUpdate::new(&plane))
+    ///         .commit_nonblocking()
+    ///         .unwrap();
+    ///     output = next;
+    ///
+    ///     for event in device.read_events().unwrap() {
+    ///         if let Event::Flip(flip) = event {
+    ///             println!(
+    ///                 "CRTC {} presented frame {} at {:?}",
+    ///                 flip.crtc_id(),
+    ///                 flip.vblank().sequence(),
+    ///                 flip.vblank().time()
+    ///             );
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn read_events(&self) -> Result<Vec<Event>> {
+        let mut state = self.state();
+
+        while state.events.is_empty() {
+            state = self.fill_events(state, None)?;
+        }
+
+        Ok(std::mem::take(&mut state.events))
+    }
+
+    /// Blocks until an event `matches`, or until the `deadline`
+    ///
+    /// The other events read in the meantime are kept for [`Device::read_events`]. A `deadline`
+    /// already expired still reads the events available without blocking.
+    pub(crate) fn wait_for_event<F>(&self, matches: F, deadline: Option<Instant>) -> Result<Event>
+    where
+        F: Fn(&Event) -> bool,
+    {
+        let mut state = self.state();
+        let mut attempted = false;
+
+        let event = loop {
+            if let Some(idx) = state.events.iter().position(&matches) {
+                break state.events.remove(idx);
+            }
+
+            if attempted && matches!(deadline, Some(deadline) if Instant::now() >= deadline) {
+                return Err(Error::Timeout);
+            }
+
+            state = self.fill_events(state, deadline)?;
+            attempted = true;
+        };
+        drop(state);
+
+        Ok(event)
+    }
+
+    /// Reads the pending events into the queue, unless another thread is already reading them
+    ///
+    /// Returns once this thread or another one has read the events, or once the `deadline` is
+    /// reached.
+    fn fill_events<'a>(
+        &'a self,
+        mut state: MutexGuard<'a, State>,
+        deadline: Option<Instant>,
+    ) -> Result<MutexGuard<'a, State>> {
+        let events_read = &self.inner.events_read;
+
+        if state.reading {
+            let state = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+
+                    events_read
+                        .wait_timeout(state, remaining)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => events_read
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner),
+            };
+
+            return Ok(state);
+        }
+
+        state.reading = true;
+        drop(state);
+
+        let ret = self.read_pending_events(deadline);
+
+        let mut state = self.state();
+        state.reading = false;
+        events_read.notify_all();
+
+        Self::dispatch_events(&mut state, ret?);
+        Ok(state)
+    }
+
+    fn read_pending_events(&self, deadline: Option<Instant>) -> Result<Vec<Event>> {
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if !drm_poll_events(self, remaining)? {
+                return Ok(Vec::new());
+            }
+        }
+
+        Ok(parse_events(&drm_read_events(self)?))
+    }
+
+    /// Queues the events read, and accounts for the flips they report
+    fn dispatch_events(state: &mut State, events: Vec<Event>) {
+        for event in &events {
+            let Event::Flip(flip) = event else {
+                continue;
+            };
+
+            let user_data = flip.handle().user_data();
+            if let Some((crtc_id, submitted)) = state.pending_flips.remove(&user_data) {
+                Self::record(state, crtc_id, submitted, flip.vblank().sequence());
+                presented(user_data, &flip.vblank());
+            }
+        }
+
+        state.events.extend(events);
+
+        // NOTE: Nobody might ever read the events, so we only keep the most recent ones.
+        let excess = state.events.len().saturating_sub(MAX_QUEUED_EVENTS);
+        let _dropped = state.events.drain(..excess);
+    }
+
+    fn record(state: &mut State, crtc_id: u32, submitted: Option<u64>, presented: u64) {
+        let skipped = state
+            .frame_stats
+            .entry(crtc_id)
+            .or_default()
+            .record(submitted, presented);
+
+        if skipped > 0 {
+            trace::skipped(crtc_id, skipped);
+        }
+    }
+
+    /// Accounts for a frame submitted after the vblank `submitted`, and presented at the vblank
+    /// `presented` on the CRTC `crtc_id`
+    pub(crate) fn record_frame(&self, crtc_id: u32, submitted: Option<u64>, presented: u64) {
+        Self::record(&mut self.state(), crtc_id, submitted, presented);
+    }
+
+    /// Registers a pending flip, before its commit is issued
+    ///
+    /// The flip event can be read by another thread as soon as the commit is issued, so it has
+    /// to be tracked beforehand, and forgotten if the commit fails.
+    pub(crate) fn track_flip(&self, handle: CommitHandle, crtc_id: u32, submitted: Option<u64>) {
+        let _old = self
+            .state()
+            .pending_flips
+            .insert(handle.user_data(), (crtc_id, submitted));
+    }
+
+    pub(crate) fn untrack_flip(&self, handle: CommitHandle) {
+        let _old = self.state().pending_flips.remove(&handle.user_data());
+    }
+
+    pub(crate) fn frame_stats(&self, crtc_id: u32) -> FrameStats {
+        self.state()
+            .frame_stats
+            .get(&crtc_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn reset_frame_stats(&self, crtc_id: u32) {
+        let _old = self.state().frame_stats.remove(&crtc_id);
+    }
+
+    pub(crate) fn next_commit_sequence(&self) -> u64 {
+        let mut state = self.state();
+
+        // NOTE: The sequence number of the non-blocking commits is used as the user data of
+        // their flip event, and the user data of the blocking commits is 0, so we start at 1.
+        state.next_commit += 1;
+        state.next_commit
+    }
+
+    #[cfg(feature = "legacy")]
+    pub(crate) fn is_legacy(&self) -> bool {
+        self.inner.legacy
+    }
+
+    /// Locks the shared state of the [Device]
+    ///
+    /// The state is never left inconsistent by a panic, so a poisoned lock is simply recovered.
+    pub(crate) fn state(&self) -> MutexGuard<'_, State> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn recorder_lock(&self) -> MutexGuard<'_, Option<Recorder>> {
+        self.inner
+            .recorder
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records a committed property set, if a recording has been started
+    ///
+    /// The state lock must not be held, since the property names are looked up through it.
+    pub(crate) fn record_commit(&self, flags: u32, properties: &[(u32, u32, u64)]) -> Result<()> {
+        self.recorder_lock()
+            .as_mut()
+            .map_or(Ok(()), |recorder| recorder.record(self, flags, properties))
+    }
+
+    fn gem_handles_lock(&self) -> MutexGuard<'_, HashMap<u32, usize>> {
+        self.inner
+            .gem_handles
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn import_prime_fd(&self, fd: &impl AsRawFd) -> Result<GemHandle> {
+        // NOTE: The kernel hands out the same handle for a dma-buf already imported, so we need
+        // to hold the lock until the handle is accounted for, or another thread releasing the
+        // last reference to it could close it in the meantime.
+        let mut handles = self.gem_handles_lock();
+        let handle = drm_prime_fd_to_handle(self, fd)?;
+        *handles.entry(handle).or_insert(0) += 1;
+        drop(handles);
+
+        Ok(GemHandle::from_acquired(self, handle))
+    }
+
+    pub(crate) fn acquire_gem_handle(&self, handle: u32) {
+        *self.gem_handles_lock().entry(handle).or_insert(0) += 1;
+    }
+
+    pub(crate) fn release_gem_handle(&self, handle: u32) -> Result<()> {
+        let mut handles = self.gem_handles_lock();
+
+        let Some(count) = handles.get_mut(&handle) else {
+            return Ok(());
+        };
+
+        *count -= 1;
+        if *count > 0 {
+            return Ok(());
+        }
+
+        let _old = handles.remove(&handle);
+        let ret = drm_gem_close(self, handle);
+        drop(handles);
+
+        ret
+    }
+
+    /// Returns whether `connector` is one of the [Connector]s currently known to the [Device]
+    pub(crate) fn has_connector(&self, connector: &Connector) -> bool {
+        self.state()
+            .connectors
+            .iter()
+            .any(|con| con.as_ref() == connector)
+    }
+
+    /// Returns the ID of the property `name` of the KMS object `object_id`
+    ///
+    /// The properties of each object are only retrieved from the kernel the first time one of
+    /// them is looked up.
+    pub(crate) fn property_id(&self, object_id: u32, name: &str) -> Result<Option<u32>> {
+        if let Some(ids) = self.state().property_ids.get(&object_id) {
+            return Ok(ids.get(name).copied());
+        }
+
+        // NOTE: The kernel will look up the object type for us if we ask for any object type (0).
+        let ids = drm_mode_get_properties(self, 0, object_id)?
+            .into_iter()
+            .map(|(prop_id, _)| {
+                let property = drm_mode_get_property(self, prop_id)?;
+                let prop_name =
+                    std::str::from_utf8(&property.name)?.trim_end_matches(char::from(0));
+
+                Ok((prop_name.to_string(), prop_id))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        let id = ids.get(name).copied();
+        let _old = self.state().property_ids.insert(object_id, ids);
+
+        Ok(id)
+    }
+
+    /// Returns the name of the property `prop_id` of the KMS object `object_id`
+    ///
+    /// The name is looked up in the properties already retrieved for the object, and only
+    /// retrieved from the kernel if the object properties haven't been looked up yet.
+    pub(crate) fn property_name(&self, object_id: u32, prop_id: u32) -> Result<String> {
+        let cached = self.state().property_ids.get(&object_id).and_then(|ids| {
+            ids.iter()
+                .find(|(_, id)| **id == prop_id)
+                .map(|(name, _)| name.clone())
+        });
+
+        if let Some(name) = cached {
+            return Ok(name);
+        }
+
+        let property = drm_mode_get_property(self, prop_id)?;
+        let name = std::str::from_utf8(&property.name)?.trim_end_matches(char::from(0));
+
+        Ok(name.to_string())
+    }
+}
+
+/// The [Connector]s Added and Removed by a [`Device::rescan`]
+#[derive(Debug, Default)]
+pub struct ConnectorChanges {
+    added: Vec<Arc<Connector>>,
+    removed: Vec<Arc<Connector>>,
+}
+
+impl ConnectorChanges {
+    fn new(old: &[Arc<Connector>], new: &[Arc<Connector>]) -> Self {
+        let missing_from = |list: &[Arc<Connector>], con: &&Arc<Connector>| !list.contains(con);
+
+        Self {
+            added: new
+                .iter()
+                .filter(|con| missing_from(old, con))
+                .cloned()
+                .collect(),
+            removed: old
+                .iter()
+                .filter(|con| missing_from(new, con))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns the [Connector]s that appeared since the last scan
+    #[must_use]
+    pub fn added(&self) -> &[Arc<Connector>] {
+        &self.added
+    }
+
+    /// Returns the [Connector]s that disappeared since the last scan
+    #[must_use]
+    pub fn removed(&self) -> &[Arc<Connector>] {
+        &self.removed
+    }
+
+    /// Returns whether no [Connector] has been added or removed
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Builds the list of objects matching `ids`, reusing the ones from `current` that still exist
+fn reconcile<T>(
+    current: &[Arc<T>],
+    ids: &[u32],
+    id_of: impl Fn(&T) -> u32,
+    mut new: impl FnMut(usize, u32) -> Result<T>,
+) -> Result<Vec<Arc<T>>> {
+    ids.iter()
+        .enumerate()
+        .map(|(idx, &id)| {
+            current
+                .iter()
+                .find(|object| id_of(object) == id)
+                .map_or_else(
+                    || new(idx, id).map(Arc::new),
+                    |object| Ok(Arc::clone(object)),
+                )
+        })
+        .collect()
+}
+
+impl std::os::unix::io::AsRawFd for Device {
+    fn as_raw_fd(&self) -> std::os::unix::io::RawFd {
+        self.inner.file.as_raw_fd()
+    }
+}
+
+impl From<Arc<Inner>> for Device {
+    fn from(inner: Arc<Inner>) -> Self {
+        Self { inner }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs::OpenOptions, sync::Arc};
+
+    use super::Inner;
+    use crate::{
+        record::{RecordedCommit, RecordedProperty},
+        Buffer, Connector, Crtc, Device, DeviceUpdate, Framebuffer, Output, Plane, RecordedValue,
+        Recording, Update,
+    };
+
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_send_sync() {
+        assert_send_sync::<Device>();
+        assert_send_sync::<Connector>();
+        assert_send_sync::<Crtc>();
+        assert_send_sync::<Plane>();
+        assert_send_sync::<Output>();
+        assert_send_sync::<Update>();
+        assert_send_sync::<DeviceUpdate>();
+        assert_send_sync::<Buffer>();
+        assert_send_sync::<Framebuffer>();
+    }
+
+    #[test]
+    fn test_record_commit() {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/null")
+            .unwrap();
+
+        let device = Device::from(Arc::new(Inner::new(
+            file,
+            #[cfg(feature = "legacy")]
+            false,
+        )));
+
+        // NOTE: The property names are looked up in the cache, so no ioctl is needed.
+        let _old = device
+            .state()
+            .property_ids
+            .insert(31, HashMap::from([(String::from("ACTIVE"), 5)]));
+
+        let path = std::env::temp_dir().join(format!("nucleid-record-{}.log", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        device.start_recording(path).unwrap();
+        device.record_commit(0x400, &[(31, 5, 0)]).unwrap();
+        device.stop_recording();
+
+        let recording = Recording::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
 
-impl From<Rc<RefCell<Inner>>> for Device {
-    fn from(rc: Rc<RefCell<Inner>>) -> Self {
-        Self { inner: rc }
+        let expected = RecordedCommit::new(
+            0x400,
+            vec![RecordedProperty::new(31, "ACTIVE", RecordedValue::Value(0))],
+        );
+        assert_eq!(recording.commits().collect::<Vec<_>>(), vec![&expected]);
     }
 }
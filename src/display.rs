@@ -0,0 +1,128 @@
+use crate::{
+    surface::{Surface, Target},
+    Device, Error, FixedPoint, Framebuffer, Point, Result, Size,
+};
+
+/// A Ready-To-Use Display
+///
+/// The [Display] picks the first connected [Connector](crate::Connector), its preferred
+/// [Mode](crate::Mode), and a primary [Plane](crate::Plane) supporting
+/// [`Format::XRGB8888`](crate::Format::XRGB8888). It then allocates two [Framebuffer]s of the
+/// [Mode](crate::Mode) size to draw into in turns, while the other one is on screen.
+///
+/// It's meant to get a picture on screen with as little code as possible. Applications needing
+/// more control should use the [Output](crate::Output) and [Update](crate::Update) API directly.
+#[derive(Debug)]
+pub struct Display {
+    surface: Surface,
+    size: Size,
+}
+
+impl Display {
+    /// Creates a new [Display] on a [Device]
+    ///
+    /// The [Display] will only be enabled on the first call to [`Display::flip`].
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if no [Connector](crate::Connector) is connected, if the
+    /// [Connector](crate::Connector) doesn't have a preferred [Mode](crate::Mode), if no suitable
+    /// primary [Plane](crate::Plane) can be found, or if the [Framebuffer] allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Display};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let display = Display::new(&device).unwrap();
+    /// ```
+    pub fn new(device: &Device) -> Result<Self> {
+        let (connector, output) = device
+            .connected_connectors()
+            .into_iter()
+            .find_map(|con| {
+                device
+                    .output_from_connector(&con)
+                    .ok()
+                    .map(|output| (con, output))
+            })
+            .ok_or(Error::Empty)?;
+
+        let target = Target::new(output, &connector)?;
+        let size = Size::new(target.mode().width(), target.mode().height());
+
+        Ok(Self {
+            surface: Surface::new(device, vec![target], size)?,
+            size,
+        })
+    }
+
+    /// Returns the [Display] width, in pixels
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.size.width()
+    }
+
+    /// Returns the [Display] height, in pixels
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.size.height()
+    }
+
+    /// Draws into the [Framebuffer] that will be displayed on the next [`Display::flip`]
+    ///
+    /// The [Framebuffer] holds [`Format::XRGB8888`](crate::Format::XRGB8888) pixels. Its content
+    /// is left untouched since it was last displayed, two frames ago.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Display};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let mut display = Display::new(&device).unwrap();
+    ///
+    /// display.draw(|buffer| buffer.data().fill(0xff));
+    /// ```
+    pub fn draw<F>(&mut self, draw: F)
+    where
+        F: FnOnce(&mut Framebuffer),
+    {
+        self.surface.draw(draw);
+    }
+
+    /// Displays the [Framebuffer] drawn into with [`Display::draw`]
+    ///
+    /// The first call will enable the [Display]. This function will block until the new frame
+    /// is on screen.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the commit fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Display};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let mut display = Display::new(&device).unwrap();
+    ///
+    /// for frame in 0..=255 {
+    ///     display.draw(|buffer| buffer.data().fill(frame));
+    ///     display.flip().unwrap();
+    /// }
+    /// ```
+    pub fn flip(&mut self) -> Result<()> {
+        let size = self.size;
+
+        self.surface.flip(|_, _, update| {
+            update
+                .set_source_coordinates(FixedPoint::default())
+                .set_source_size(size.into())
+                .set_display_coordinates(Point::default())
+                .set_display_size(size)
+        })
+    }
+}
@@ -0,0 +1,136 @@
+use std::os::unix::io::OwnedFd;
+
+use crate::Format;
+
+/// The linear format modifier
+///
+/// Buffers without an explicit modifier, like dumb buffers, are laid out linearly.
+pub const DRM_FORMAT_MOD_LINEAR: u64 = 0;
+
+/// A plane of an exported dma-buf
+#[derive(Debug)]
+pub struct DmaBufPlane {
+    fd: OwnedFd,
+    offset: u32,
+    stride: u32,
+}
+
+impl DmaBufPlane {
+    pub(crate) const fn new(fd: OwnedFd, offset: u32, stride: u32) -> Self {
+        Self { fd, offset, stride }
+    }
+
+    /// Returns the dma-buf file descriptor holding the plane
+    ///
+    /// This is the file descriptor to pass as `EGL_DMA_BUF_PLANEn_FD_EXT`, or in a
+    /// `VkImportMemoryFdInfoKHR`.
+    #[must_use]
+    pub const fn fd(&self) -> &OwnedFd {
+        &self.fd
+    }
+
+    /// Returns the plane offset in the dma-buf, in bytes
+    #[must_use]
+    pub const fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Returns the plane stride, in bytes
+    #[must_use]
+    pub const fn stride(&self) -> u32 {
+        self.stride
+    }
+
+    /// Consumes the [`DmaBufPlane`] to retrieve its file descriptor
+    ///
+    /// Vulkan takes the ownership of the file descriptor on a successful import.
+    #[must_use]
+    pub fn into_fd(self) -> OwnedFd {
+        self.fd
+    }
+}
+
+/// An exported dma-buf
+///
+/// Holds everything a graphics API needs to import a [Framebuffer](crate::Framebuffer) and render
+/// into it, mirroring the `EGL_EXT_image_dma_buf_import_modifiers` attributes and the Vulkan
+/// `VK_EXT_image_drm_format_modifier` explicit layout.
+#[derive(Debug)]
+pub struct DmaBuf {
+    width: u32,
+    height: u32,
+    format: Format,
+    modifier: u64,
+    size: u64,
+    planes: Vec<DmaBufPlane>,
+}
+
+impl DmaBuf {
+    pub(crate) const fn new(
+        width: u32,
+        height: u32,
+        format: Format,
+        modifier: u64,
+        size: u64,
+        planes: Vec<DmaBufPlane>,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            modifier,
+            size,
+            planes,
+        }
+    }
+
+    /// Returns the width, in pixels
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height, in lines
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the [Format]
+    #[must_use]
+    pub const fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the raw fourcc code of the [Format], as expected by `EGL_LINUX_DRM_FOURCC_EXT`
+    #[must_use]
+    pub const fn fourcc(&self) -> u32 {
+        self.format as u32
+    }
+
+    /// Returns the format modifier
+    #[must_use]
+    pub const fn modifier(&self) -> u64 {
+        self.modifier
+    }
+
+    /// Returns the total size of the buffer, in bytes
+    ///
+    /// This is the allocation size to use for a Vulkan memory import.
+    #[must_use]
+    pub const fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the planes of the buffer
+    #[must_use]
+    pub fn planes(&self) -> &[DmaBufPlane] {
+        &self.planes
+    }
+
+    /// Consumes the [`DmaBuf`] to retrieve its planes
+    #[must_use]
+    pub fn into_planes(self) -> Vec<DmaBufPlane> {
+        self.planes
+    }
+}
@@ -0,0 +1,107 @@
+use std::convert::TryFrom;
+
+use crate::{raw::drm_get_version, Result};
+
+/// A Capability of a [Device](crate::Device)
+///
+/// Their value is retrieved with [`Device::capability`](crate::Device::capability).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(u64)]
+pub enum Capability {
+    /// Whether the device supports dumb buffers
+    DumbBuffer = 0x1,
+
+    /// Whether the vblank ioctls support the CRTCs beyond the second one
+    VblankHighCrtc = 0x2,
+
+    /// The preferred bit depth for the dumb buffers
+    DumbPreferredDepth = 0x3,
+
+    /// Whether rendering to a shadow buffer and copying it to a dumb buffer is preferred
+    DumbPreferShadow = 0x4,
+
+    /// The PRIME import and export capabilities, as a bitmask
+    Prime = 0x5,
+
+    /// Whether the timestamps of the vblank events use `CLOCK_MONOTONIC`
+    TimestampMonotonic = 0x6,
+
+    /// Whether the device supports asynchronous page flips with the legacy API
+    AsyncPageFlip = 0x7,
+
+    /// The width of the cursor buffers supported by the hardware
+    CursorWidth = 0x8,
+
+    /// The height of the cursor buffers supported by the hardware
+    CursorHeight = 0x9,
+
+    /// Whether the device supports framebuffers with a modifier
+    AddFb2Modifiers = 0x10,
+
+    /// Whether the page flips can target a specific vblank
+    PageFlipTarget = 0x11,
+
+    /// Whether the vblank events carry the CRTC ID
+    CrtcInVblankEvent = 0x12,
+
+    /// Whether the device supports the synchronization objects
+    SyncObj = 0x13,
+
+    /// Whether the device supports the timeline synchronization objects
+    SyncObjTimeline = 0x14,
+
+    /// Whether the device supports asynchronous page flips with the atomic API
+    AtomicAsyncPageFlip = 0x15,
+}
+
+/// The Kernel Driver Behind a [Device](crate::Device)
+///
+/// It's retrieved with [`Device::driver_info`](crate::Device::driver_info).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DriverInfo {
+    name: String,
+    date: String,
+    description: String,
+    version: (u32, u32, u32),
+}
+
+impl DriverInfo {
+    pub(crate) fn new(raw: &impl std::os::unix::io::AsRawFd) -> Result<Self> {
+        let (version, name, date, description) = drm_get_version(raw)?;
+
+        Ok(Self {
+            name,
+            date,
+            description,
+            version: (
+                u32::try_from(version.version_major)?,
+                u32::try_from(version.version_minor)?,
+                u32::try_from(version.version_patchlevel)?,
+            ),
+        })
+    }
+
+    /// Returns the name of the driver, like `i915` or `vc4`
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the date of the driver
+    #[must_use]
+    pub fn date(&self) -> &str {
+        &self.date
+    }
+
+    /// Returns the description of the driver
+    #[must_use]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the version of the driver, as a (major, minor, patchlevel) tuple
+    #[must_use]
+    pub const fn version(&self) -> (u32, u32, u32) {
+        self.version
+    }
+}
@@ -0,0 +1,536 @@
+use std::convert::TryFrom;
+
+use crate::{
+    raw::{
+        drm_mode_modeinfo, DRM_MODE_FLAG_INTERLACE, DRM_MODE_FLAG_NHSYNC, DRM_MODE_FLAG_NVSYNC,
+        DRM_MODE_FLAG_PHSYNC, DRM_MODE_FLAG_PVSYNC, DRM_MODE_TYPE_DRIVER, DRM_MODE_TYPE_PREFERRED,
+    },
+    Error, Mode, Result,
+};
+
+const BLOCK_SIZE: usize = 128;
+const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+const DETAILED_TIMING_SIZE: usize = 18;
+
+const CTA_EXTENSION_TAG: u8 = 0x02;
+const CTA_EXTENDED_TAG: u8 = 0x07;
+const CTA_COLORIMETRY_TAG: u8 = 0x05;
+const CTA_HDR_STATIC_METADATA_TAG: u8 = 0x06;
+
+const DESCRIPTOR_SERIAL: u8 = 0xff;
+const DESCRIPTOR_NAME: u8 = 0xfc;
+
+/// A colorimetry a sink can support, as reported by its CTA-861 Colorimetry Data Block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Colorimetry {
+    /// Standard Definition xvYCC
+    XvYcc601,
+
+    /// High Definition xvYCC
+    XvYcc709,
+
+    /// sYCC, derived from sRGB
+    SYcc601,
+
+    /// opYCC, derived from opRGB
+    OpYcc601,
+
+    /// opRGB, formerly known as `AdobeRGB`
+    OpRgb,
+
+    /// BT.2020 constant luminance YCbCr
+    Bt2020CYcc,
+
+    /// BT.2020 YCbCr
+    Bt2020Ycc,
+
+    /// BT.2020 RGB
+    Bt2020Rgb,
+
+    /// DCI-P3
+    DciP3,
+}
+
+impl Colorimetry {
+    const fn bit(self) -> u16 {
+        match self {
+            Self::XvYcc601 => 1 << 0,
+            Self::XvYcc709 => 1 << 1,
+            Self::SYcc601 => 1 << 2,
+            Self::OpYcc601 => 1 << 3,
+            Self::OpRgb => 1 << 4,
+            Self::Bt2020CYcc => 1 << 5,
+            Self::Bt2020Ycc => 1 << 6,
+            Self::Bt2020Rgb => 1 << 7,
+            Self::DciP3 => 1 << 15,
+        }
+    }
+}
+
+/// An Electro-Optical Transfer Function a sink can support
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Eotf {
+    /// The traditional gamma, with a SDR luminance range
+    TraditionalSdr,
+
+    /// The traditional gamma, with a HDR luminance range
+    TraditionalHdr,
+
+    /// SMPTE ST 2084, also known as PQ
+    SmpteSt2084,
+
+    /// Hybrid Log-Gamma
+    Hlg,
+}
+
+impl Eotf {
+    const fn bit(self) -> u8 {
+        match self {
+            Self::TraditionalSdr => 1 << 0,
+            Self::TraditionalHdr => 1 << 1,
+            Self::SmpteSt2084 => 1 << 2,
+            Self::Hlg => 1 << 3,
+        }
+    }
+}
+
+/// The HDR capabilities of a sink, as reported by its CTA-861 HDR Static Metadata Data Block
+#[derive(Clone, Debug, PartialEq)]
+pub struct HdrMetadata {
+    eotfs: u8,
+    max_luminance: Option<f64>,
+    max_frame_average_luminance: Option<f64>,
+    min_luminance: Option<f64>,
+}
+
+impl HdrMetadata {
+    fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < 2 {
+            return Err(Error::MalformedEdid);
+        }
+
+        // NOTE: The luminances are encoded as 50 * 2^(cv / 32) cd/m², and the minimum one
+        // relative to the maximum one.
+        let luminance = |cv: u8| 50.0 * (f64::from(cv) / 32.0).exp2();
+        let max_luminance = data.get(2).map(|&cv| luminance(cv));
+        let max_frame_average_luminance = data.get(3).map(|&cv| luminance(cv));
+        let min_luminance = data
+            .get(4)
+            .and_then(|&cv| max_luminance.map(|max| max * (f64::from(cv) / 255.0).powi(2) / 100.0));
+
+        Ok(Self {
+            eotfs: data[0],
+            max_luminance,
+            max_frame_average_luminance,
+            min_luminance,
+        })
+    }
+
+    /// Returns whether the sink supports an [Eotf]
+    #[must_use]
+    pub const fn supports_eotf(&self, eotf: Eotf) -> bool {
+        (self.eotfs & eotf.bit()) != 0
+    }
+
+    /// Returns the desired maximum luminance of the content, in cd/m², if reported
+    #[must_use]
+    pub const fn max_luminance(&self) -> Option<f64> {
+        self.max_luminance
+    }
+
+    /// Returns the desired maximum frame-average luminance of the content, in cd/m², if reported
+    #[must_use]
+    pub const fn max_frame_average_luminance(&self) -> Option<f64> {
+        self.max_frame_average_luminance
+    }
+
+    /// Returns the desired minimum luminance of the content, in cd/m², if reported
+    #[must_use]
+    pub const fn min_luminance(&self) -> Option<f64> {
+        self.min_luminance
+    }
+}
+
+fn descriptor_string(descriptor: &[u8]) -> String {
+    // NOTE: The strings are terminated by a line feed, and padded with spaces.
+    descriptor[5..]
+        .iter()
+        .take_while(|&&byte| byte != b'\n')
+        .map(|&byte| char::from(byte))
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+fn detailed_timing(descriptor: &[u8], preferred: bool) -> Mode {
+    let d = |idx: usize| u16::from(descriptor[idx]);
+
+    let hactive = d(2) | ((d(4) & 0xf0) << 4);
+    let hblank = d(3) | ((d(4) & 0x0f) << 8);
+    let vactive = d(5) | ((d(7) & 0xf0) << 4);
+    let vblank = d(6) | ((d(7) & 0x0f) << 8);
+    let hsync_offset = d(8) | ((d(11) & 0xc0) << 2);
+    let hsync_width = d(9) | ((d(11) & 0x30) << 4);
+    let vsync_offset = (d(10) >> 4) | ((d(11) & 0x0c) << 2);
+    let vsync_width = (d(10) & 0x0f) | ((d(11) & 0x03) << 4);
+
+    // NOTE: The pixel clock is stored in units of 10kHz, and the kernel expects kHz.
+    let clock = u32::from(u16::from_le_bytes([descriptor[0], descriptor[1]])) * 10;
+    let htotal = hactive + hblank;
+    let vtotal = vactive + vblank;
+
+    let features = descriptor[17];
+    let interlaced = (features & 0x80) != 0;
+
+    let mut flags = 0;
+    if interlaced {
+        flags |= DRM_MODE_FLAG_INTERLACE;
+    }
+
+    // NOTE: The sync polarities are only defined for the digital separate sync.
+    if (features & 0x18) == 0x18 {
+        flags |= if (features & 0x04) != 0 {
+            DRM_MODE_FLAG_PVSYNC
+        } else {
+            DRM_MODE_FLAG_NVSYNC
+        };
+
+        flags |= if (features & 0x02) != 0 {
+            DRM_MODE_FLAG_PHSYNC
+        } else {
+            DRM_MODE_FLAG_NHSYNC
+        };
+    }
+
+    let total = u32::from(htotal) * u32::from(vtotal);
+    let vrefresh = (clock * 1000 + total / 2).checked_div(total).unwrap_or(0);
+
+    let mut name = [0; 32];
+    let label = format!(
+        "{}x{}{}",
+        hactive,
+        vactive,
+        if interlaced { "i" } else { "" }
+    );
+    name[..label.len()].copy_from_slice(label.as_bytes());
+
+    Mode::new(drm_mode_modeinfo {
+        clock,
+        hdisplay: hactive,
+        hsync_start: hactive + hsync_offset,
+        hsync_end: hactive + hsync_offset + hsync_width,
+        htotal,
+        hskew: 0,
+        vdisplay: vactive,
+        vsync_start: vactive + vsync_offset,
+        vsync_end: vactive + vsync_offset + vsync_width,
+        vtotal,
+        vscan: 0,
+        vrefresh,
+        flags,
+        type_: if preferred {
+            DRM_MODE_TYPE_DRIVER | DRM_MODE_TYPE_PREFERRED
+        } else {
+            DRM_MODE_TYPE_DRIVER
+        },
+        name,
+    })
+}
+
+/// The parsed Extended Display Identification Data of a sink
+///
+/// Only the base block and the CTA-861 extension blocks are decoded. The [Mode]s are the ones
+/// described by the detailed timing descriptors, the first one being the preferred [Mode] of
+/// the sink.
+#[derive(Clone, Debug)]
+pub struct Edid {
+    manufacturer: String,
+    product_code: u16,
+    serial_number: u32,
+    name: Option<String>,
+    serial: Option<String>,
+    modes: Vec<Mode>,
+    colorimetry: u16,
+    hdr_metadata: Option<HdrMetadata>,
+}
+
+impl Edid {
+    /// Parses a raw EDID
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::MalformedEdid`] if the header or any checksum is invalid, or if a
+    /// block is truncated.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device, Edid};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let raw = connector.edid().unwrap().unwrap();
+    /// let edid = Edid::parse(&raw).unwrap();
+    /// println!("Monitor {:?}", edid.name());
+    /// ```
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < BLOCK_SIZE || data[..8] != HEADER {
+            return Err(Error::MalformedEdid);
+        }
+
+        let blocks = 1 + usize::from(data[126]);
+        if data.len() < blocks * BLOCK_SIZE {
+            return Err(Error::MalformedEdid);
+        }
+
+        for block in data[..blocks * BLOCK_SIZE].chunks_exact(BLOCK_SIZE) {
+            if block.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)) != 0 {
+                return Err(Error::MalformedEdid);
+            }
+        }
+
+        let vendor = u16::from_be_bytes([data[8], data[9]]);
+        let manufacturer = [10, 5, 0]
+            .iter()
+            .map(|shift| {
+                let letter = u8::try_from((vendor >> shift) & 0x1f).unwrap_or_default();
+                char::from(b'@' + letter)
+            })
+            .collect();
+
+        let mut edid = Self {
+            manufacturer,
+            product_code: u16::from_le_bytes([data[10], data[11]]),
+            serial_number: u32::from_le_bytes([data[12], data[13], data[14], data[15]]),
+            name: None,
+            serial: None,
+            modes: Vec::new(),
+            colorimetry: 0,
+            hdr_metadata: None,
+        };
+
+        for descriptor in data[54..126].chunks_exact(DETAILED_TIMING_SIZE) {
+            // NOTE: The display descriptors have a pixel clock of 0, the detailed timings don't.
+            if descriptor[0] != 0 || descriptor[1] != 0 {
+                let preferred = edid.modes.is_empty();
+
+                edid.modes.push(detailed_timing(descriptor, preferred));
+                continue;
+            }
+
+            match descriptor[3] {
+                DESCRIPTOR_NAME => edid.name = Some(descriptor_string(descriptor)),
+                DESCRIPTOR_SERIAL => edid.serial = Some(descriptor_string(descriptor)),
+                _ => {}
+            }
+        }
+
+        for block in data[BLOCK_SIZE..blocks * BLOCK_SIZE].chunks_exact(BLOCK_SIZE) {
+            if block[0] == CTA_EXTENSION_TAG {
+                edid.parse_cta_extension(block)?;
+            }
+        }
+
+        Ok(edid)
+    }
+
+    fn parse_cta_extension(&mut self, block: &[u8]) -> Result<()> {
+        // NOTE: The data block collection sits between the header and the detailed timings,
+        // whose offset is stored in the header. An offset of 0 means that there's neither.
+        let dtd_offset = usize::from(block[2]);
+        if dtd_offset == 0 {
+            return Ok(());
+        }
+
+        if !(4..BLOCK_SIZE).contains(&dtd_offset) {
+            return Err(Error::MalformedEdid);
+        }
+
+        let mut blocks = &block[4..dtd_offset];
+        while let Some((&header, rest)) = blocks.split_first() {
+            let len = usize::from(header & 0x1f);
+            let payload = rest.get(..len).ok_or(Error::MalformedEdid)?;
+            blocks = &rest[len..];
+
+            if (header >> 5) != CTA_EXTENDED_TAG {
+                continue;
+            }
+
+            match payload.split_first() {
+                Some((&CTA_COLORIMETRY_TAG, data)) => {
+                    self.colorimetry = u16::from_le_bytes([
+                        data.first().copied().unwrap_or_default(),
+                        data.get(1).copied().unwrap_or_default(),
+                    ]);
+                }
+                Some((&CTA_HDR_STATIC_METADATA_TAG, data)) => {
+                    self.hdr_metadata = Some(HdrMetadata::parse(data)?);
+                }
+                _ => {}
+            }
+        }
+
+        for descriptor in block[dtd_offset..127].chunks_exact(DETAILED_TIMING_SIZE) {
+            if descriptor[0] == 0 && descriptor[1] == 0 {
+                break;
+            }
+
+            self.modes.push(detailed_timing(descriptor, false));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the PNP ID of the sink manufacturer, for example `DEL`
+    #[must_use]
+    pub fn manufacturer(&self) -> &str {
+        &self.manufacturer
+    }
+
+    /// Returns the manufacturer product code of the sink
+    #[must_use]
+    pub const fn product_code(&self) -> u16 {
+        self.product_code
+    }
+
+    /// Returns the numeric serial number of the sink
+    ///
+    /// A lot of sinks leave it to 0, and report their serial number as a string instead.
+    #[must_use]
+    pub const fn serial_number(&self) -> u32 {
+        self.serial_number
+    }
+
+    /// Returns the name of the sink, if reported
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the serial number string of the sink, if reported
+    #[must_use]
+    pub fn serial(&self) -> Option<&str> {
+        self.serial.as_deref()
+    }
+
+    /// Returns the [Mode]s described by the detailed timings of the sink
+    #[must_use]
+    pub fn modes(&self) -> &[Mode] {
+        &self.modes
+    }
+
+    /// Returns whether the sink supports a [Colorimetry]
+    #[must_use]
+    pub const fn supports_colorimetry(&self, colorimetry: Colorimetry) -> bool {
+        (self.colorimetry & colorimetry.bit()) != 0
+    }
+
+    /// Returns the HDR capabilities of the sink, if any
+    #[must_use]
+    pub const fn hdr_metadata(&self) -> Option<&HdrMetadata> {
+        self.hdr_metadata.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::{Colorimetry, Edid, Eotf, BLOCK_SIZE, HEADER};
+    use crate::{mode::Type as ModeType, Error};
+
+    // NOTE: 1920x1080@60Hz, with positive syncs
+    const DTD_1080P: [u8; 18] = [
+        0x02, 0x3a, 0x80, 0x18, 0x71, 0x38, 0x2d, 0x40, 0x58, 0x2c, 0x45, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x1e,
+    ];
+
+    fn fix_checksum(block: &mut [u8]) {
+        let sum = block[..127]
+            .iter()
+            .fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+        block[127] = sum.wrapping_neg();
+    }
+
+    fn edid() -> Vec<u8> {
+        let mut base = vec![0; BLOCK_SIZE];
+        base[..8].copy_from_slice(&HEADER);
+
+        // NOTE: DEL, product 0xa0c4, serial 0x3142534c
+        base[8..16].copy_from_slice(&[0x10, 0xac, 0xc4, 0xa0, 0x4c, 0x53, 0x42, 0x31]);
+        base[54..72].copy_from_slice(&DTD_1080P);
+        base[72..90].copy_from_slice(&[
+            0x00, 0x00, 0x00, 0xfc, 0x00, b'D', b'E', b'L', b'L', b' ', b'U', b'2', b'7', b'2',
+            b'0', b'Q', b'\n', b' ',
+        ]);
+        base[126] = 1;
+        fix_checksum(&mut base);
+
+        let mut cta = vec![0; BLOCK_SIZE];
+        cta[..4].copy_from_slice(&[0x02, 0x03, 0x00, 0x00]);
+        let blocks = [
+            // Colorimetry: BT2020 YCC and RGB, DCI-P3
+            0xe3, 0x05, 0xc0, 0x80,
+            // HDR Static Metadata: SDR and PQ, max 0x62, avg 0x4b, min 0x1a
+            0xe6, 0x06, 0x05, 0x01, 0x62, 0x4b, 0x1a,
+        ];
+        cta[4..4 + blocks.len()].copy_from_slice(&blocks);
+        cta[2] = u8::try_from(4 + blocks.len()).unwrap();
+        cta[4 + blocks.len()..4 + blocks.len() + 18].copy_from_slice(&DTD_1080P);
+        fix_checksum(&mut cta);
+
+        base.extend(cta);
+        base
+    }
+
+    #[test]
+    fn test_parse() {
+        let edid = Edid::parse(&edid()).unwrap();
+
+        assert_eq!(edid.manufacturer(), "DEL");
+        assert_eq!(edid.product_code(), 0xa0c4);
+        assert_eq!(edid.serial_number(), 0x3142_534c);
+        assert_eq!(edid.name(), Some("DELL U2720Q"));
+        assert_eq!(edid.serial(), None);
+
+        let modes = edid.modes();
+        assert_eq!(modes.len(), 2);
+        assert_eq!(modes[0].width(), 1920);
+        assert_eq!(modes[0].height(), 1080);
+        assert_eq!(modes[0].refresh(), 60);
+        assert!(modes[0].has_type(ModeType::Preferred));
+        assert!(!modes[1].has_type(ModeType::Preferred));
+
+        assert!(edid.supports_colorimetry(Colorimetry::Bt2020Rgb));
+        assert!(edid.supports_colorimetry(Colorimetry::DciP3));
+        assert!(!edid.supports_colorimetry(Colorimetry::OpRgb));
+
+        let hdr = edid.hdr_metadata().unwrap();
+        assert!(hdr.supports_eotf(Eotf::TraditionalSdr));
+        assert!(hdr.supports_eotf(Eotf::SmpteSt2084));
+        assert!(!hdr.supports_eotf(Eotf::Hlg));
+        let max_luminance = 50.0 * (98.0_f64 / 32.0).exp2();
+        assert!((hdr.max_luminance().unwrap() - max_luminance).abs() < 0.01);
+        assert!(hdr.min_luminance().unwrap() < 1.0);
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(matches!(Edid::parse(&[0; 16]), Err(Error::MalformedEdid)));
+
+        let mut raw = edid();
+        raw[20] ^= 0xff;
+        assert!(matches!(Edid::parse(&raw), Err(Error::MalformedEdid)));
+
+        let raw = edid();
+        assert!(matches!(
+            Edid::parse(&raw[..BLOCK_SIZE + 10]),
+            Err(Error::MalformedEdid)
+        ));
+    }
+}
@@ -1,45 +1,74 @@
 use std::{
-    cell::RefCell,
     convert::TryFrom,
-    rc::{Rc, Weak},
+    sync::{Arc, Weak},
 };
 
 use num_enum::TryFromPrimitive;
 
 use crate::error::Result;
-use crate::{device::Inner, raw::drm_mode_get_encoder, Crtc, Device, Error};
+use crate::{
+    device::Inner, object::impl_object_identity, raw::drm_mode_get_encoder, Crtc, Device, Error,
+};
 
-#[derive(Debug, TryFromPrimitive)]
+/// The [Encoder] Type
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive)]
 #[repr(u32)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum Type {
+    /// The [Encoder] type couldn't be determined
     None,
+
+    /// A Digital to Analog Converter, for VGA
     DAC,
+
+    /// A TMDS [Encoder], for DVI and HDMI
     TMDS,
+
+    /// An LVDS [Encoder]
     LVDS,
+
+    /// A Digital to Analog Converter, for analog TV
     TVDAC,
+
+    /// A virtual [Encoder], for virtual machines
     Virtual,
+
+    /// A MIPI-DSI [Encoder]
     DSI,
+
+    /// A `DisplayPort` Multi-Stream Transport [Encoder]
     DPMST,
+
+    /// An [Encoder] for the DPI parallel interface
+    DPI,
 }
 
+/// A KMS Encoder
+///
+/// An Encoder converts the pixel stream of a [Crtc] to the signal expected by a
+/// [`Connector`](crate::Connector). Each [Encoder] can only be fed by a subset of the [Crtc]s,
+/// and can only be used along with a subset of the other [Encoder]s when cloning an output.
+///
+/// Two [Encoder]s are equal if they represent the same encoder of the same [Device], so they can
+/// be used as keys in a [`HashMap`](std::collections::HashMap).
 #[derive(Debug)]
-#[allow(dead_code)]
 pub struct Encoder {
-    dev: Weak<RefCell<Inner>>,
+    dev: Weak<Inner>,
     id: u32,
     type_: Type,
     possible_crtcs: u32,
     possible_clones: u32,
 }
 
+impl_object_identity!(Encoder);
+
 impl Encoder {
     pub(crate) fn new(device: &Device, id: u32) -> Result<Self> {
         let encoder = drm_mode_get_encoder(device, id)?;
-        let encoder_type = Type::try_from(encoder.encoder_type).unwrap();
+        let encoder_type = Type::try_from(encoder.encoder_type).unwrap_or(Type::None);
 
         Ok(Self {
-            dev: Rc::downgrade(&device.inner),
+            dev: Arc::downgrade(&device.inner),
             id,
             type_: encoder_type,
             possible_crtcs: encoder.possible_crtcs,
@@ -47,12 +76,50 @@ impl Encoder {
         })
     }
 
+    /// Returns the KMS object ID of the [Encoder]
+    #[must_use]
     pub const fn id(&self) -> u32 {
         self.id
     }
 
-    pub fn crtcs(self: &Rc<Self>) -> Result<Crtcs> {
-        let device: Device = self.dev.upgrade().ok_or(Error::Empty)?.into();
+    /// Returns the [Encoder] [Type]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, EncoderType};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let tmds = device.encoders()
+    ///     .filter(|encoder| encoder.encoder_type() == EncoderType::TMDS)
+    ///     .count();
+    /// ```
+    #[must_use]
+    pub const fn encoder_type(&self) -> Type {
+        self.type_
+    }
+
+    /// Returns the [Crtc]s that can feed the [Encoder]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] has been dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// for encoder in device.encoders() {
+    ///     let crtcs = encoder.crtcs().unwrap().into_iter().count();
+    ///     println!("Encoder {} can be fed by {} CRTCs", encoder.id(), crtcs);
+    /// }
+    /// ```
+    pub fn crtcs(self: &Arc<Self>) -> Result<Crtcs> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
 
         let crtcs = device
             .crtcs()
@@ -68,13 +135,61 @@ impl Encoder {
 
         Ok(Crtcs(crtcs))
     }
+
+    /// Returns the [Encoder]s that can be enabled at the same time than this one, to clone an
+    /// output
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] has been dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// for encoder in device.encoders() {
+    ///     for clone in encoder.clones().unwrap() {
+    ///         println!("Encoder {} can clone encoder {}", clone.id(), encoder.id());
+    ///     }
+    /// }
+    /// ```
+    pub fn clones(self: &Arc<Self>) -> Result<Encoders> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        // NOTE: The kernel always reports an encoder as a possible clone of itself.
+        let encoders = device
+            .encoders()
+            .enumerate()
+            .filter(|(idx, encoder)| {
+                ((1 << idx) & self.possible_clones) != 0 && encoder.id != self.id
+            })
+            .map(|(_, encoder)| encoder)
+            .collect();
+
+        Ok(Encoders(encoders))
+    }
 }
 
 #[derive(Debug)]
-pub struct Crtcs(Vec<Rc<Crtc>>);
+pub struct Crtcs(Vec<Arc<Crtc>>);
 
 impl IntoIterator for Crtcs {
-    type Item = Rc<Crtc>;
+    type Item = Arc<Crtc>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[derive(Debug)]
+pub struct Encoders(Vec<Arc<Encoder>>);
+
+impl IntoIterator for Encoders {
+    type Item = Arc<Encoder>;
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -3,6 +3,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 /// Error Type for nucleid
 #[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// An Error caused by a failed IOCTL
     #[error("Ioctl Failure")]
@@ -16,6 +17,24 @@ pub enum Error {
     #[error("Empty Data")]
     Empty,
 
+    /// The [Device](crate::Device) has been dropped while one of its objects was still in use
+    #[error("Device Gone")]
+    DeviceGone,
+
+    /// A KMS object doesn't expose a property
+    #[error("Property {name} not found on object {object}")]
+    PropertyNotFound {
+        /// The ID of the KMS object
+        object: u32,
+
+        /// The name of the missing property
+        name: String,
+    },
+
+    /// The kernel rejected an atomic commit
+    #[error("Commit Rejected: {0}")]
+    CommitRejected(nix::Error),
+
     /// An integer was out of its valid range
     #[error("Out of Range Value")]
     IntegerOutOfRange(#[from] std::num::TryFromIntError),
@@ -23,4 +42,115 @@ pub enum Error {
     /// An error occured while converting a String
     #[error("UTF-8 Convertion error")]
     StringConversion(#[from] std::str::Utf8Error),
+
+    /// A commit recording couldn't be parsed
+    #[error("Malformed recording at line {0}")]
+    MalformedRecording(usize),
+
+    /// The pixel format isn't supported by nucleid
+    #[error("Unsupported Format {0:#010x}")]
+    UnsupportedFormat(u32),
+
+    /// A 3D LUT couldn't be parsed
+    #[error("Malformed 3D LUT at line {0}")]
+    MalformedLut(usize),
+
+    /// The feature isn't supported by the driver
+    #[error("Unsupported Feature: {0}")]
+    Unsupported(&'static str),
+
+    /// The [Output](crate::Output) needs to be enabled for this operation
+    #[error("Inactive Output")]
+    InactiveOutput,
+
+    /// An imported dma-buf is too small for the buffer layout it's supposed to hold
+    #[error("dma-buf Too Small")]
+    DmaBufTooSmall,
+
+    /// The buffers of a framebuffer can only be accessed by the DRM master and the privileged
+    /// clients
+    #[error("Buffer Not Accessible")]
+    BufferNotAccessible,
+
+    /// The property is managed by nucleid and can't be set directly
+    #[error("Reserved Property: {0}")]
+    ReservedProperty(String),
+
+    /// A plane update through the legacy API is missing part of its geometry
+    #[error("Incomplete Plane Geometry: {0} missing")]
+    IncompleteGeometry(&'static str),
+
+    /// An operation didn't complete in time
+    #[error("Timed Out")]
+    Timeout,
+
+    /// The requested layout of the [Output](crate::Output)s can't be achieved
+    #[error("Invalid Layout: {0}")]
+    InvalidLayout(&'static str),
+
+    /// The timings of a [Mode](crate::Mode) are inconsistent
+    #[error("Invalid Mode: {0}")]
+    InvalidMode(&'static str),
+
+    /// A modeline couldn't be parsed
+    #[error("Malformed Modeline")]
+    MalformedModeline,
+
+    /// The [Buffer](crate::Buffer) layout doesn't allow to access it with that pixel type
+    #[error("Incompatible Pixel Layout")]
+    PixelLayout,
+
+    /// A property blob reported by the kernel couldn't be parsed
+    #[error("Malformed Property Blob")]
+    MalformedBlob,
+
+    /// A property description reported by the kernel is inconsistent with its type
+    #[error("Malformed Property")]
+    MalformedProperty,
+
+    /// No dma-buf heap of the requested kind is available
+    #[error("DMA Heap Not Found")]
+    HeapNotFound,
+
+    /// An EDID couldn't be parsed
+    #[error("Malformed EDID")]
+    MalformedEdid,
+
+    /// An output configuration profile couldn't be parsed or applied
+    #[error("Invalid Configuration: {0}")]
+    InvalidConfig(String),
+
+    /// A DDC/CI reply was corrupted or unexpected
+    #[error("Invalid DDC/CI Reply")]
+    InvalidDdcReply,
+
+    /// The monitor doesn't support a VCP feature
+    #[error("Unsupported VCP Feature {0:#04x}")]
+    UnsupportedVcpFeature(u8),
+}
+
+impl Error {
+    /// Returns the error number reported by the kernel, if any
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nix::errno::Errno;
+    /// use nucleid::Device;
+    ///
+    /// match Device::new("/dev/dri/card0") {
+    ///     Err(err) if err.errno() == Some(Errno::EACCES) => println!("Permission Denied"),
+    ///     ret => {
+    ///         let _device = ret.unwrap();
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn errno(&self) -> Option<nix::errno::Errno> {
+        match self {
+            Self::Ioctl(errno) | Self::CommitRejected(errno) => Some(*errno),
+            Self::Io(err) => err.raw_os_error().map(nix::errno::Errno::from_i32),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,274 @@
+use std::{
+    convert::{TryFrom, TryInto},
+    time::Duration,
+};
+
+use crate::{
+    raw::{DRM_EVENT_CRTC_SEQUENCE, DRM_EVENT_FLIP_COMPLETE, DRM_EVENT_VBLANK},
+    Vblank,
+};
+
+// NOTE: Events start with a header made of their type and length as u32. All the events we
+// request then hold our user data as a u64.
+const EVENT_HEADER_SIZE: usize = 8;
+const EVENT_MIN_SIZE: usize = EVENT_HEADER_SIZE + 8;
+
+// NOTE: The vblank and flip events hold, after the header and user data, the time in seconds
+// and microseconds, the sequence number and the CRTC ID as u32.
+const VBLANK_EVENT_SIZE: usize = 32;
+
+// NOTE: The sequence events hold, after the header and user data, the time in ns and the
+// sequence number as u64.
+const SEQUENCE_EVENT_SIZE: usize = 32;
+
+/// Identifies a non-blocking commit
+///
+/// A [`CommitHandle`] is returned by [`Update::commit_nonblocking`](crate::Update::commit_nonblocking)
+/// and can be given to [`Device::wait_for_flip`](crate::Device::wait_for_flip) to wait for that
+/// commit to be presented, or matched against the [`Event::Flip`] events returned by
+/// [`Device::read_events`](crate::Device::read_events).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CommitHandle {
+    user_data: u64,
+}
+
+impl CommitHandle {
+    pub(crate) const fn new(user_data: u64) -> Self {
+        Self { user_data }
+    }
+
+    /// Returns the user data the commit has been submitted with
+    ///
+    /// This is also the commit sequence number, as found in the `tracing` spans.
+    #[must_use]
+    pub const fn user_data(&self) -> u64 {
+        self.user_data
+    }
+}
+
+/// The Completion of a Non-Blocking Commit
+///
+/// A [`FlipEvent`] is sent by the kernel once a commit submitted with
+/// [`Update::commit_nonblocking`](crate::Update::commit_nonblocking) has been presented, and can
+/// be retrieved with [`Device::read_events`](crate::Device::read_events).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FlipEvent {
+    handle: CommitHandle,
+    crtc_id: u32,
+    vblank: Vblank,
+}
+
+impl FlipEvent {
+    pub(crate) const fn new(handle: CommitHandle, crtc_id: u32, vblank: Vblank) -> Self {
+        Self {
+            handle,
+            crtc_id,
+            vblank,
+        }
+    }
+
+    /// Returns the [`CommitHandle`] of the commit that has been presented
+    #[must_use]
+    pub const fn handle(&self) -> CommitHandle {
+        self.handle
+    }
+
+    /// Returns the ID of the CRTC the commit has been presented on
+    ///
+    /// This matches [`Output::crtc_id`](crate::Output::crtc_id).
+    #[must_use]
+    pub const fn crtc_id(&self) -> u32 {
+        self.crtc_id
+    }
+
+    /// Returns the [Vblank] at which the commit has been presented
+    #[must_use]
+    pub const fn vblank(&self) -> Vblank {
+        self.vblank
+    }
+}
+
+/// An Event Sent by a [Device](crate::Device)
+///
+/// The events are retrieved with [`Device::read_events`](crate::Device::read_events).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// A vblank requested through the legacy vblank API has occured
+    Vblank {
+        /// The user data the vblank has been requested with
+        user_data: u64,
+
+        /// The ID of the CRTC the vblank occured on
+        crtc_id: u32,
+
+        /// The [Vblank] that occured
+        vblank: Vblank,
+    },
+
+    /// A non-blocking commit has been presented
+    Flip(FlipEvent),
+
+    /// A vblank queued with [`drm_crtc_queue_sequence`](crate::sys::drm_crtc_queue_sequence())
+    /// has been reached
+    Sequence {
+        /// The user data the vblank has been queued with
+        user_data: u64,
+
+        /// The [Vblank] that has been reached
+        vblank: Vblank,
+    },
+}
+
+pub fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+pub fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_ne_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+/// Iterates over the events, and their type, in a buffer read from the [Device](crate::Device)
+fn events(events: &[u8]) -> impl Iterator<Item = (u32, &[u8])> {
+    let mut offset = 0;
+
+    std::iter::from_fn(move || {
+        let kind = read_u32(events, offset)?;
+        let length = usize::try_from(read_u32(events, offset + 4)?).ok()?;
+        if length < EVENT_HEADER_SIZE {
+            return None;
+        }
+
+        let event = events.get(offset..offset + length)?;
+        offset += length;
+
+        Some((kind, event))
+    })
+}
+
+/// Returns the user data of an event, if it holds any
+fn user_data(event: &[u8]) -> Option<u64> {
+    if event.len() < EVENT_MIN_SIZE {
+        return None;
+    }
+
+    read_u64(event, EVENT_HEADER_SIZE)
+}
+
+/// Decodes the `drm_event_vblank` layout shared by the vblank and flip events
+fn parse_vblank_event(event: &[u8]) -> Option<(u64, u32, Vblank)> {
+    if event.len() < VBLANK_EVENT_SIZE {
+        return None;
+    }
+
+    let user_data = user_data(event)?;
+    let seconds = read_u32(event, 16)?;
+    let micros = read_u32(event, 20)?;
+    let sequence = read_u32(event, 24)?;
+    let crtc_id = read_u32(event, 28)?;
+
+    let time = Duration::from_secs(u64::from(seconds)) + Duration::from_micros(u64::from(micros));
+
+    Some((user_data, crtc_id, Vblank::new(u64::from(sequence), time)))
+}
+
+/// Decodes the `drm_event_crtc_sequence` layout of the sequence events
+fn parse_sequence_event(event: &[u8]) -> Option<(u64, Vblank)> {
+    if event.len() < SEQUENCE_EVENT_SIZE {
+        return None;
+    }
+
+    let user_data = user_data(event)?;
+    let time = read_u64(event, 16)?;
+    let sequence = read_u64(event, 24)?;
+
+    Some((user_data, Vblank::new(sequence, Duration::from_nanos(time))))
+}
+
+/// Decodes the events in a buffer read from the [Device](crate::Device)
+///
+/// The events of an unknown type, or too short for their type, are skipped.
+pub fn parse_events(events: &[u8]) -> Vec<Event> {
+    self::events(events)
+        .filter_map(|(kind, event)| match kind {
+            DRM_EVENT_VBLANK => {
+                parse_vblank_event(event).map(|(user_data, crtc_id, vblank)| Event::Vblank {
+                    user_data,
+                    crtc_id,
+                    vblank,
+                })
+            }
+            DRM_EVENT_FLIP_COMPLETE => {
+                parse_vblank_event(event).map(|(user_data, crtc_id, vblank)| {
+                    Event::Flip(FlipEvent::new(
+                        CommitHandle::new(user_data),
+                        crtc_id,
+                        vblank,
+                    ))
+                })
+            }
+            DRM_EVENT_CRTC_SEQUENCE => parse_sequence_event(event)
+                .map(|(user_data, vblank)| Event::Sequence { user_data, vblank }),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{parse_events, CommitHandle, Event, FlipEvent};
+    use crate::Vblank;
+
+    fn vblank_event(kind: u32, user_data: u64) -> Vec<u8> {
+        let mut event = Vec::new();
+
+        event.extend_from_slice(&kind.to_ne_bytes());
+        event.extend_from_slice(&32_u32.to_ne_bytes());
+        event.extend_from_slice(&user_data.to_ne_bytes());
+        event.extend_from_slice(&10_u32.to_ne_bytes());
+        event.extend_from_slice(&500_u32.to_ne_bytes());
+        event.extend_from_slice(&1234_u32.to_ne_bytes());
+        event.extend_from_slice(&31_u32.to_ne_bytes());
+
+        event
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let vblank = Vblank::new(1234, Duration::new(10, 500_000));
+
+        let mut events = vblank_event(1, 7);
+        events.extend(vblank_event(2, 42));
+        events.extend(vblank_event(0x8000_0000, 42));
+
+        events.extend_from_slice(&3_u32.to_ne_bytes());
+        events.extend_from_slice(&32_u32.to_ne_bytes());
+        events.extend_from_slice(&2_u64.to_ne_bytes());
+        events.extend_from_slice(&2000_u64.to_ne_bytes());
+        events.extend_from_slice(&43_u64.to_ne_bytes());
+
+        assert_eq!(
+            parse_events(&events),
+            vec![
+                Event::Vblank {
+                    user_data: 7,
+                    crtc_id: 31,
+                    vblank,
+                },
+                Event::Flip(FlipEvent::new(CommitHandle::new(42), 31, vblank)),
+                Event::Sequence {
+                    user_data: 2,
+                    vblank: Vblank::new(43, Duration::from_micros(2)),
+                },
+            ]
+        );
+
+        assert_eq!(parse_events(&events[..48]).len(), 1);
+        assert!(parse_events(&events[..24]).is_empty());
+    }
+}
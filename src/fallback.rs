@@ -0,0 +1,357 @@
+use std::sync::Arc;
+
+use crate::{
+    output::{commit_properties, PropertyBlobs},
+    pixel::read_pixel,
+    BufferType, CommitError, CommitFlags, CommitHandle, Error, Format, Framebuffer, Output, Plane,
+    PlaneUpdate, Point, Rect, Reflection, Result, Rotation, Size, Update,
+};
+
+// NOTE: The formats we know how to convert to, by order of preference.
+const FALLBACK_FORMATS: [Format; 3] = [Format::XRGB8888, Format::ARGB8888, Format::RGB888];
+
+/// Returns the size of a `size` area once rotated
+const fn rotated_size(size: Size, rotation: Rotation) -> Size {
+    match rotation {
+        Rotation::Rotate0 | Rotation::Rotate180 => size,
+        Rotation::Rotate90 | Rotation::Rotate270 => Size::new(size.height(), size.width()),
+    }
+}
+
+/// Returns the position of the `(x, y)` pixel of a `width` x `height` image once rotated
+const fn rotate(
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    rotation: Rotation,
+) -> (usize, usize) {
+    // NOTE: The KMS rotations are counter-clockwise.
+    match rotation {
+        Rotation::Rotate0 => (x, y),
+        Rotation::Rotate90 => (y, width - 1 - x),
+        Rotation::Rotate180 => (width - 1 - x, height - 1 - y),
+        Rotation::Rotate270 => (height - 1 - y, x),
+    }
+}
+
+fn write_pixel(dst: &mut [u8], format: Format, pixel: [u8; 4]) {
+    let len = format.bpp() / 8;
+
+    dst[..len].copy_from_slice(&pixel[..len]);
+}
+
+/// Rotates and converts a `size` image in `src` into `dst`
+fn transform(
+    src: (&[u8], Format, usize),
+    dst: (&mut [u8], Format, usize),
+    size: Size,
+    rotation: Rotation,
+) {
+    let (src, src_format, src_pitch) = src;
+    let (dst, dst_format, dst_pitch) = dst;
+    let src_cpp = src_format.bpp() / 8;
+    let dst_cpp = dst_format.bpp() / 8;
+
+    for y in 0..size.height() {
+        for x in 0..size.width() {
+            let (dst_x, dst_y) = rotate(x, y, size.width(), size.height(), rotation);
+            let src_offset = y * src_pitch + x * src_cpp;
+            let dst_offset = dst_y * dst_pitch + dst_x * dst_cpp;
+
+            let pixel = read_pixel(&src[src_offset..], src_format);
+            write_pixel(&mut dst[dst_offset..], dst_format, pixel);
+        }
+    }
+}
+
+/// Software Fallback for the [Plane] Transforms
+///
+/// Not all [Plane]s can rotate their content, or scan out every [Format]. [`Fallback`] will
+/// check whether the [Plane] can display a [Framebuffer] as requested and, if it can't, will
+/// rotate and convert it on the CPU into a scratch [Framebuffer] the [Plane] supports.
+///
+/// This allows the same application code to run on capable and minimal hardware, at the cost of
+/// some CPU time on the latter.
+#[derive(Debug, Default)]
+pub struct Fallback {
+    scratch: [Option<Framebuffer>; 2],
+    next: usize,
+}
+
+impl Fallback {
+    /// Creates a new [`Fallback`]
+    ///
+    /// The scratch [Framebuffer]s are only allocated when first needed.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            scratch: [None, None],
+            next: 0,
+        }
+    }
+
+    /// Presents a [Framebuffer] on a [Plane], rotated by `rotation`
+    ///
+    /// The [Framebuffer] is displayed in the top-left corner of the [Output]. If the [Plane] can't
+    /// display it as is, the [Framebuffer] is transformed in software first.
+    ///
+    /// # Errors
+    ///
+    /// Will return a [`CommitError`] if the [Device](crate::Device) can't be accessed, if the
+    /// [Plane] doesn't support any [Format] [`Fallback`] can convert to, if the scratch
+    /// [Framebuffer] allocation fails or if the commit fails. The [Update] can be retrieved from
+    /// it to try again.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, Fallback, Format, PlaneType, Rotation};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let fb = device.allocate_buffer(BufferType::Dumb, 1080, 1920, 24)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::RGB888)
+    ///     .unwrap();
+    ///
+    /// let mut fallback = Fallback::new();
+    /// let output = fallback
+    ///     .present_with_fallback(output.start_update(), &plane, &fb, Rotation::Rotate90)
+    ///     .unwrap();
+    /// ```
+    pub fn present_with_fallback(
+        &mut self,
+        update: Update,
+        plane: &Arc<Plane>,
+        framebuffer: &Framebuffer,
+        rotation: Rotation,
+    ) -> std::result::Result<Output, CommitError> {
+        match self.prepare(
+            &update,
+            plane,
+            framebuffer,
+            rotation,
+            CommitFlags::ALLOW_MODESET,
+        ) {
+            Ok(plane_update) => update.add_plane(plane_update).commit(),
+            Err(error) => Err(CommitError::new(error, update)),
+        }
+    }
+
+    /// Presents a [Framebuffer] on a [Plane], rotated by `rotation`, with a commit issued with
+    /// `flags`
+    ///
+    /// This works like [`Fallback::present_with_fallback`], but the [Plane] rotation is checked
+    /// for, and the [Update] committed with, [`Update::commit_with_flags`] and `flags`.
+    ///
+    /// # Errors
+    ///
+    /// Will return a [`CommitError`] if the [Device](crate::Device) can't be accessed, if the
+    /// [Plane] doesn't support any [Format] [`Fallback`] can convert to, if the scratch
+    /// [Framebuffer] allocation fails or if the commit fails. The [Update] can be retrieved from
+    /// it to try again.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, CommitFlags, Device, Fallback, Format, PlaneType, Rotation};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let fb = device.allocate_buffer(BufferType::Dumb, 1080, 1920, 24)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::RGB888)
+    ///     .unwrap();
+    ///
+    /// let mut fallback = Fallback::new();
+    /// let (output, handle) = fallback
+    ///     .present_with_flags(
+    ///         output.start_update(),
+    ///         &plane,
+    ///         &fb,
+    ///         Rotation::Rotate90,
+    ///         CommitFlags::NONBLOCK | CommitFlags::PAGE_FLIP_EVENT,
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn present_with_flags(
+        &mut self,
+        update: Update,
+        plane: &Arc<Plane>,
+        framebuffer: &Framebuffer,
+        rotation: Rotation,
+        flags: CommitFlags,
+    ) -> std::result::Result<(Output, Option<CommitHandle>), CommitError> {
+        match self.prepare(&update, plane, framebuffer, rotation, flags) {
+            Ok(plane_update) => update.add_plane(plane_update).commit_with_flags(flags),
+            Err(error) => Err(CommitError::new(error, update)),
+        }
+    }
+
+    fn prepare(
+        &mut self,
+        update: &Update,
+        plane: &Arc<Plane>,
+        framebuffer: &Framebuffer,
+        rotation: Rotation,
+        flags: CommitFlags,
+    ) -> Result<PlaneUpdate> {
+        let size = Size::new(framebuffer.width(), framebuffer.height());
+
+        if plane.formats().any(|fmt| fmt == framebuffer.format())
+            && Self::can_rotate(update, plane, framebuffer, rotation, flags)?
+        {
+            return Ok(Self::plane_update(plane, framebuffer, size, rotation));
+        }
+
+        let format = std::iter::once(framebuffer.format())
+            .chain(FALLBACK_FORMATS)
+            .find(|fmt| FALLBACK_FORMATS.contains(fmt) && plane.formats().any(|f| f == *fmt))
+            .ok_or(Error::Unsupported("Fallback Format"))?;
+
+        if !framebuffer.is_mapped() {
+            return Err(Error::Unsupported("Fallback Modifier"));
+        }
+
+        let rotated = rotated_size(size, rotation);
+        let scratch = self.scratch(update, rotated, format)?;
+        let pitch = scratch.pitch();
+
+        transform(
+            (
+                framebuffer.pixels(),
+                framebuffer.format(),
+                framebuffer.pitch(),
+            ),
+            (scratch.data(), format, pitch),
+            size,
+            rotation,
+        );
+
+        let plane_update = Self::plane_update(plane, scratch, rotated, Rotation::Rotate0);
+        self.next = (self.next + 1) % self.scratch.len();
+
+        Ok(plane_update)
+    }
+
+    fn plane_update(
+        plane: &Arc<Plane>,
+        framebuffer: &Framebuffer,
+        size: Size,
+        rotation: Rotation,
+    ) -> PlaneUpdate {
+        // NOTE: The rotation might have been set by a previous commit, so we need to reset it
+        // even if we don't need any.
+        PlaneUpdate::new(plane)
+            .set_framebuffer(framebuffer)
+            .set_source_rect(Rect::from_parts(Point::default(), size).into())
+            .set_display_rect(Rect::from_parts(
+                Point::default(),
+                rotated_size(size, rotation),
+            ))
+            .set_rotation(rotation)
+    }
+
+    fn can_rotate(
+        update: &Update,
+        plane: &Arc<Plane>,
+        framebuffer: &Framebuffer,
+        rotation: Rotation,
+        flags: CommitFlags,
+    ) -> Result<bool> {
+        if rotation == Rotation::Rotate0 {
+            return Ok(true);
+        }
+
+        if !plane.supports_rotation(rotation, Reflection::empty()) {
+            return Ok(false);
+        }
+
+        let device = update.device()?;
+        let size = Size::new(framebuffer.width(), framebuffer.height());
+        let mut blobs = PropertyBlobs::new();
+        let candidate = Self::plane_update(plane, framebuffer, size, rotation)
+            .properties(update.output().crtc_id(), &mut blobs)?;
+
+        let mut properties = update.properties(&device, &mut blobs)?;
+        properties.extend(candidate);
+
+        Ok(commit_properties(&device, flags.to_test_only().bits(), properties, blobs).is_ok())
+    }
+
+    fn scratch(&mut self, update: &Update, size: Size, format: Format) -> Result<&mut Framebuffer> {
+        let scratch = &mut self.scratch[self.next];
+
+        let reusable = scratch.as_ref().is_some_and(|fb| {
+            fb.width() == size.width() && fb.height() == size.height() && fb.format() == format
+        });
+
+        if !reusable {
+            // NOTE: The scratch Framebuffer is entirely overwritten by the transform, so there's
+            // no point in clearing it.
+            let device = update.device()?;
+            let fb = device
+                .allocate_buffer_uninit(
+                    BufferType::Dumb,
+                    size.width(),
+                    size.height(),
+                    format.bpp(),
+                )?
+                .into_framebuffer(format)?;
+
+            *scratch = Some(fb);
+        }
+
+        Ok(scratch.as_mut().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::transform;
+    use crate::{Format, Rotation, Size};
+
+    #[test]
+    fn test_transform() {
+        // NOTE: A 3x2 RGB888 image, each pixel holding its index in all its components.
+        let src: Vec<u8> = (0..6).flat_map(|idx| [idx; 3]).collect();
+        let mut dst = vec![0; 6 * 4];
+
+        transform(
+            (&src, Format::RGB888, 9),
+            (&mut dst, Format::XRGB8888, 8),
+            Size::new(3, 2),
+            Rotation::Rotate90,
+        );
+
+        let pixels: Vec<u8> = dst.chunks(4).map(|pixel| pixel[0]).collect();
+        assert_eq!(pixels, [2, 5, 1, 4, 0, 3]);
+        assert!(dst.chunks(4).all(|pixel| pixel[3] == 0xff));
+
+        let mut dst = vec![0; 6 * 3];
+        transform(
+            (&src, Format::RGB888, 9),
+            (&mut dst, Format::RGB888, 9),
+            Size::new(3, 2),
+            Rotation::Rotate180,
+        );
+
+        let pixels: Vec<u8> = dst.chunks(3).map(|pixel| pixel[0]).collect();
+        assert_eq!(pixels, [5, 4, 3, 2, 1, 0]);
+    }
+}
@@ -0,0 +1,176 @@
+use std::ops::{BitOr, BitOrAssign, Sub, SubAssign};
+
+use crate::raw::{
+    DRM_MODE_ATOMIC_ALLOW_MODESET, DRM_MODE_ATOMIC_NONBLOCK, DRM_MODE_ATOMIC_TEST_ONLY,
+    DRM_MODE_PAGE_FLIP_EVENT,
+};
+
+/// The Flags of an Atomic Commit
+///
+/// The flags can be combined with the `|` operator, and removed with the `-` operator. They are
+/// given to [`Update::commit_with_flags`](crate::Update::commit_with_flags).
+///
+/// # Example
+///
+/// ```
+/// use nucleid::CommitFlags;
+///
+/// let flags = CommitFlags::NONBLOCK | CommitFlags::PAGE_FLIP_EVENT;
+/// assert!(flags.contains(CommitFlags::NONBLOCK));
+/// assert!(!(flags - CommitFlags::NONBLOCK).contains(CommitFlags::NONBLOCK));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CommitFlags(u32);
+
+impl CommitFlags {
+    /// Sends an [`Event::Flip`](crate::Event::Flip) once the commit has been presented
+    pub const PAGE_FLIP_EVENT: Self = Self(DRM_MODE_PAGE_FLIP_EVENT);
+
+    /// Only checks whether the commit would be accepted, without applying it
+    pub const TEST_ONLY: Self = Self(DRM_MODE_ATOMIC_TEST_ONLY);
+
+    /// Returns without waiting for the commit to be presented
+    pub const NONBLOCK: Self = Self(DRM_MODE_ATOMIC_NONBLOCK);
+
+    /// Allows the commit to perform a full modeset
+    pub const ALLOW_MODESET: Self = Self(DRM_MODE_ATOMIC_ALLOW_MODESET);
+
+    /// Returns a [`CommitFlags`] without any flag set
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the raw value of the flags, as expected by the kernel
+    #[must_use]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns whether all the flags of `other` are set
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    /// Returns the flags set in either `self` or `other`
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Returns the flags set in `self` but not in `other`
+    #[must_use]
+    pub const fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// Returns the flags of a test-only commit checking a commit issued with `self`
+    ///
+    /// The kernel rejects the test-only commits asking for a page flip event, and there's no
+    /// point in them being non-blocking.
+    pub(crate) const fn to_test_only(self) -> Self {
+        self.difference(Self::NONBLOCK.union(Self::PAGE_FLIP_EVENT))
+            .union(Self::TEST_ONLY)
+    }
+}
+
+impl BitOr for CommitFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for CommitFlags {
+    fn bitor_assign(&mut self, other: Self) {
+        *self = self.union(other);
+    }
+}
+
+impl Sub for CommitFlags {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(other)
+    }
+}
+
+impl SubAssign for CommitFlags {
+    fn sub_assign(&mut self, other: Self) {
+        *self = self.difference(other);
+    }
+}
+
+/// The Flags of a dma-buf export
+///
+/// The flags can be combined with the `|` operator. They are given to
+/// [`Buffer::export_dmabuf`](crate::Buffer::export_dmabuf).
+///
+/// # Example
+///
+/// ```
+/// use nucleid::PrimeFlags;
+///
+/// let flags = PrimeFlags::CLOEXEC | PrimeFlags::RDWR;
+/// assert!(flags.contains(PrimeFlags::RDWR));
+/// assert_eq!(flags, PrimeFlags::default());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PrimeFlags(u32);
+
+impl PrimeFlags {
+    /// Closes the file descriptor when executing another program
+    #[allow(clippy::cast_sign_loss)]
+    pub const CLOEXEC: Self = Self(libc::O_CLOEXEC as u32);
+
+    /// Allows to map the dma-buf for writing. Some exporters reject it.
+    #[allow(clippy::cast_sign_loss)]
+    pub const RDWR: Self = Self(libc::O_RDWR as u32);
+
+    /// Returns a [`PrimeFlags`] without any flag set
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the raw value of the flags, as expected by the kernel
+    #[must_use]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns whether all the flags of `other` are set
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    /// Returns the flags set in either `self` or `other`
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl Default for PrimeFlags {
+    /// Returns [`PrimeFlags::CLOEXEC`] and [`PrimeFlags::RDWR`], which is what most users need
+    fn default() -> Self {
+        Self::CLOEXEC.union(Self::RDWR)
+    }
+}
+
+impl BitOr for PrimeFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for PrimeFlags {
+    fn bitor_assign(&mut self, other: Self) {
+        *self = self.union(other);
+    }
+}
@@ -15,6 +15,149 @@ pub enum Format {
 
     /// \[31:0\] x:R:G:B 8:8:8:8 little endian
     XRGB8888 = fourcc_code!('X', 'R', '2', '4'),
+
+    /// \[31:0\] A:R:G:B 8:8:8:8 little endian
+    ARGB8888 = fourcc_code!('A', 'R', '2', '4'),
+
+    /// 2x2 subsampled Y/CbCr, with a Y plane and an interleaved Cb:Cr 8:8 little endian plane
+    NV12 = fourcc_code!('N', 'V', '1', '2'),
+
+    /// 2x2 subsampled Y/Cb/Cr, with one plane for each component
+    YUV420 = fourcc_code!('Y', 'U', '1', '2'),
+}
+
+impl Format {
+    /// Returns the [`FormatInfo`] describing how the [Format] is laid out in memory
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nucleid::Format;
+    ///
+    /// let info = Format::NV12.info();
+    /// assert_eq!(info.planes(), 2);
+    /// assert_eq!(info.subsampling(), (2, 2));
+    /// assert!(!info.has_alpha());
+    /// ```
+    #[must_use]
+    pub const fn info(self) -> FormatInfo {
+        match self {
+            Self::RGB888 => FormatInfo::rgb(24, false),
+            Self::XRGB8888 => FormatInfo::rgb(32, false),
+            Self::ARGB8888 => FormatInfo::rgb(32, true),
+            Self::NV12 => FormatInfo::yuv(&[8, 16], (2, 2)),
+            Self::YUV420 => FormatInfo::yuv(&[8, 8, 8], (2, 2)),
+        }
+    }
+
+    /// Returns the number of bits per pixel of the first plane
+    pub(crate) const fn bpp(self) -> usize {
+        self.info().bpp()
+    }
+}
+
+/// The memory layout of a [Format]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FormatInfo {
+    bpp: [usize; 4],
+    planes: usize,
+    hsub: usize,
+    vsub: usize,
+    alpha: bool,
+    yuv: bool,
+}
+
+impl FormatInfo {
+    const fn rgb(bpp: usize, alpha: bool) -> Self {
+        Self {
+            bpp: [bpp, 0, 0, 0],
+            planes: 1,
+            hsub: 1,
+            vsub: 1,
+            alpha,
+            yuv: false,
+        }
+    }
+
+    const fn yuv(planes: &[usize], (hsub, vsub): (usize, usize)) -> Self {
+        let mut bpp = [0; 4];
+        let mut idx = 0;
+
+        while idx < planes.len() {
+            bpp[idx] = planes[idx];
+            idx += 1;
+        }
+
+        Self {
+            bpp,
+            planes: planes.len(),
+            hsub,
+            vsub,
+            alpha: false,
+            yuv: true,
+        }
+    }
+
+    /// Returns the number of bits per pixel of the first plane
+    #[must_use]
+    pub const fn bpp(&self) -> usize {
+        self.bpp[0]
+    }
+
+    /// Returns the number of bits per sample of a plane, or `None` if there's no such plane
+    ///
+    /// For the subsampled planes, a sample covers several pixels.
+    #[must_use]
+    pub const fn plane_bpp(&self, plane: usize) -> Option<usize> {
+        if plane >= self.planes {
+            return None;
+        }
+
+        Some(self.bpp[plane])
+    }
+
+    /// Returns the number of planes
+    #[must_use]
+    pub const fn planes(&self) -> usize {
+        self.planes
+    }
+
+    /// Returns the horizontal and vertical subsampling factors of the chroma planes
+    ///
+    /// The [Format]s without chroma planes aren't subsampled, and return `(1, 1)`.
+    #[must_use]
+    pub const fn subsampling(&self) -> (usize, usize) {
+        (self.hsub, self.vsub)
+    }
+
+    /// Returns whether the [Format] has an alpha component
+    #[must_use]
+    pub const fn has_alpha(&self) -> bool {
+        self.alpha
+    }
+
+    /// Returns whether the pixels are stored as Y/CbCr components
+    #[must_use]
+    pub const fn is_yuv(&self) -> bool {
+        self.yuv
+    }
+
+    /// Returns the number of lines of the first plane needed to store all the planes
+    ///
+    /// The planes are stored one after the other in a single buffer, whose pitch is the one of
+    /// the first plane.
+    pub(crate) const fn lines(&self, height: usize) -> usize {
+        let mut lines = height;
+        let mut plane = 1;
+
+        while plane < self.planes {
+            lines +=
+                (height.div_ceil(self.vsub) * self.bpp[plane]).div_ceil(self.hsub * self.bpp());
+            plane += 1;
+        }
+
+        lines
+    }
 }
 
 #[cfg(test)]
@@ -22,5 +165,14 @@ mod tests {
     #[test]
     fn test_format_enum() {
         assert_eq!(super::Format::RGB888 as u32, 0x34324752);
+        assert_eq!(super::Format::NV12 as u32, 0x3231564e);
+    }
+
+    #[test]
+    fn test_format_lines() {
+        assert_eq!(super::Format::XRGB8888.info().lines(480), 480);
+        assert_eq!(super::Format::NV12.info().lines(480), 720);
+        assert_eq!(super::Format::YUV420.info().lines(480), 720);
+        assert_eq!(super::Format::NV12.info().lines(481), 722);
     }
 }
@@ -0,0 +1,415 @@
+use std::sync::{Arc, Weak};
+
+use fixed::types::U16F16;
+
+use crate::{
+    device::Inner,
+    object::Object,
+    raw::{DRM_MODE_ATOMIC_ALLOW_MODESET, DRM_MODE_ATOMIC_TEST_ONLY},
+    record::{RecordedCommit, RecordedProperty, RecordedValue},
+    ConnectorStatus, Device, Error, Format, Plane, Replayer, Result,
+};
+
+const MAX_FRAMEBUFFERS: u32 = 8;
+
+// NOTE: A xorshift64* generator. We don't need anything cryptographically secure, but we need
+// the sequence to be reproducible from a seed so that failures can be investigated.
+#[derive(Debug)]
+struct Rng(u64);
+
+impl Rng {
+    const fn new(seed: u64) -> Self {
+        // xorshift gets stuck on 0
+        Self(if seed == 0 { 0x9e3779b97f4a7c15 } else { seed })
+    }
+
+    const fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    fn below(&mut self, max: u32) -> u32 {
+        if max == 0 {
+            return 0;
+        }
+
+        // NOTE: The modulo bias doesn't matter here, and the result is strictly lower than a u32.
+        #[allow(clippy::cast_possible_truncation)]
+        let val = (self.next_u64() % u64::from(max)) as u32;
+
+        val
+    }
+
+    fn range(&mut self, min: u32, max: u32) -> u32 {
+        if max <= min {
+            return min;
+        }
+
+        // NOTE: The result is between min and max, and thus fits in a u32.
+        #[allow(clippy::cast_possible_truncation)]
+        let val = (self.next_u64() % (u64::from(max - min) + 1)) as u32;
+
+        min + val
+    }
+
+    fn chance(&mut self, percent: u32) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        // NOTE: The modulo bias doesn't matter here, and the result is lower than the slice
+        // length.
+        #[allow(clippy::cast_possible_truncation)]
+        let idx = (self.next_u64() % (items.len().max(1) as u64)) as usize;
+
+        items.get(idx)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct FramebufferDesc {
+    id: u32,
+    width: u32,
+    height: u32,
+    format: u32,
+}
+
+/// Random Atomic Commit Generator
+///
+/// Generates randomized, but structurally valid, atomic commits: the connectors, CRTCs, modes,
+/// planes and formats used are all taken from what the [Device] reports, and the plane
+/// coordinates are kept within the [Framebuffer](crate::Framebuffer) and
+/// [Mode](crate::Mode) boundaries. The hardware might still reject the commits, for example
+/// because of scaling or bandwidth limitations.
+///
+/// The commits are generated as [`RecordedCommit`]s with the `TEST_ONLY` flag set, and can be
+/// applied through a [Replayer]. The sequence of commits only depends on the seed and on the
+/// [Device] topology, so a failure can be reproduced by using the same seed.
+///
+/// [`CommitGenerator::stress`] builds a stress test on top of it, to shake out bugs in both the
+/// drivers and nucleid.
+#[derive(Debug)]
+pub struct CommitGenerator {
+    dev: Weak<Inner>,
+    rng: Rng,
+    framebuffers: Vec<FramebufferDesc>,
+}
+
+impl CommitGenerator {
+    /// Creates a new [`CommitGenerator`]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{CommitGenerator, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let mut generator = CommitGenerator::new(&device, 42);
+    /// let commit = generator.generate().unwrap();
+    /// ```
+    #[must_use]
+    pub fn new(device: &Device, seed: u64) -> Self {
+        Self {
+            dev: Arc::downgrade(&device.inner),
+            rng: Rng::new(seed),
+            framebuffers: Vec::new(),
+        }
+    }
+
+    fn framebuffer(
+        &mut self,
+        formats: &[Format],
+        max_width: u32,
+        max_height: u32,
+    ) -> FramebufferDesc {
+        let format = *self.rng.pick(formats).unwrap_or(&Format::XRGB8888) as u32;
+
+        if self.framebuffers.len() == MAX_FRAMEBUFFERS as usize || self.rng.chance(50) {
+            let candidates: Vec<_> = self
+                .framebuffers
+                .iter()
+                .filter(|fb| fb.format == format)
+                .copied()
+                .collect();
+
+            if let Some(fb) = self.rng.pick(&candidates) {
+                return *fb;
+            }
+        }
+
+        let desc = FramebufferDesc {
+            id: self.rng.range(1, u32::MAX),
+            width: self.rng.range(1, max_width),
+            height: self.rng.range(1, max_height),
+            format,
+        };
+
+        if self.framebuffers.len() == MAX_FRAMEBUFFERS as usize {
+            let idx = self.rng.below(MAX_FRAMEBUFFERS) as usize;
+            self.framebuffers[idx] = desc;
+        } else {
+            self.framebuffers.push(desc);
+        }
+
+        desc
+    }
+
+    fn plane_properties(
+        &mut self,
+        plane: &Plane,
+        crtc_id: u32,
+        mode_width: u32,
+        mode_height: u32,
+        properties: &mut Vec<RecordedProperty>,
+    ) {
+        let formats: Vec<_> = plane.formats().collect();
+
+        if formats.is_empty() || self.rng.chance(30) {
+            return;
+        }
+
+        if self.rng.chance(10) {
+            properties.push(RecordedProperty::new(
+                plane.object_id(),
+                "CRTC_ID",
+                RecordedValue::Value(0),
+            ));
+            properties.push(RecordedProperty::new(
+                plane.object_id(),
+                "FB_ID",
+                RecordedValue::Value(0),
+            ));
+            return;
+        }
+
+        let fb = self.framebuffer(&formats, mode_width, mode_height);
+
+        let src_x = self.rng.below(fb.width);
+        let src_y = self.rng.below(fb.height);
+        let src_w = self.rng.range(1, fb.width - src_x);
+        let src_h = self.rng.range(1, fb.height - src_y);
+
+        let crtc_x = self.rng.below(mode_width);
+        let crtc_y = self.rng.below(mode_height);
+        let crtc_w = self.rng.range(1, mode_width - crtc_x);
+        let crtc_h = self.rng.range(1, mode_height - crtc_y);
+
+        let plane_values = [
+            ("CRTC_ID", u64::from(crtc_id)),
+            ("SRC_X", u64::from(U16F16::from_num(src_x).to_bits())),
+            ("SRC_Y", u64::from(U16F16::from_num(src_y).to_bits())),
+            ("SRC_W", u64::from(U16F16::from_num(src_w).to_bits())),
+            ("SRC_H", u64::from(U16F16::from_num(src_h).to_bits())),
+            ("CRTC_X", u64::from(crtc_x)),
+            ("CRTC_Y", u64::from(crtc_y)),
+            ("CRTC_W", u64::from(crtc_w)),
+            ("CRTC_H", u64::from(crtc_h)),
+        ];
+
+        for (name, value) in plane_values {
+            properties.push(RecordedProperty::new(
+                plane.object_id(),
+                name,
+                RecordedValue::Value(value),
+            ));
+        }
+
+        properties.push(RecordedProperty::new(
+            plane.object_id(),
+            "FB_ID",
+            RecordedValue::Framebuffer {
+                id: fb.id,
+                width: fb.width,
+                height: fb.height,
+                format: fb.format,
+            },
+        ));
+    }
+
+    /// Generates a new random commit
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails, or if there's
+    /// no connected [Connector](crate::Connector) to generate a commit for.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{CommitGenerator, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let mut generator = CommitGenerator::new(&device, 42);
+    /// let commit = generator.generate().unwrap();
+    /// ```
+    pub fn generate(&mut self) -> Result<RecordedCommit> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+        let mut properties = Vec::new();
+
+        let connectors: Vec<_> = device
+            .connectors()
+            .filter(|con| con.status().ok() == Some(ConnectorStatus::Connected))
+            .collect();
+
+        let connector = self.rng.pick(&connectors).ok_or(Error::Empty)?;
+
+        let encoders: Vec<_> = connector.encoders()?.into_iter().collect();
+        let encoder = self.rng.pick(&encoders).ok_or(Error::Empty)?;
+
+        let crtcs: Vec<_> = encoder.crtcs()?.into_iter().collect();
+        let crtc = self.rng.pick(&crtcs).ok_or(Error::Empty)?;
+
+        let modes: Vec<_> = connector.modes()?.into_iter().collect();
+        let mode = self.rng.pick(&modes).ok_or(Error::Empty)?;
+        let mode_width = u32::from(mode.inner().hdisplay);
+        let mode_height = u32::from(mode.inner().vdisplay);
+
+        // SAFETY: drm_mode_modeinfo is a plain-old-data structure.
+        let mode_data = unsafe {
+            std::slice::from_raw_parts(
+                std::ptr::from_ref(mode.inner()).cast::<u8>(),
+                std::mem::size_of_val(mode.inner()),
+            )
+        };
+
+        properties.push(RecordedProperty::new(
+            connector.object_id(),
+            "CRTC_ID",
+            RecordedValue::Value(u64::from(crtc.object_id())),
+        ));
+
+        properties.push(RecordedProperty::new(
+            crtc.object_id(),
+            "ACTIVE",
+            RecordedValue::Value(u64::from(self.rng.chance(90))),
+        ));
+
+        properties.push(RecordedProperty::new(
+            crtc.object_id(),
+            "MODE_ID",
+            RecordedValue::Blob(mode_data.to_vec()),
+        ));
+
+        let planes: Vec<_> = device
+            .planes()
+            .filter(|plane| ((1 << crtc.index()) & plane.possible_crtcs()) != 0)
+            .collect();
+
+        for plane in planes {
+            self.plane_properties(
+                &plane,
+                crtc.object_id(),
+                mode_width,
+                mode_height,
+                &mut properties,
+            );
+        }
+
+        Ok(RecordedCommit::new(
+            DRM_MODE_ATOMIC_TEST_ONLY | DRM_MODE_ATOMIC_ALLOW_MODESET,
+            properties,
+        ))
+    }
+
+    /// Stress-tests the [Device] with random commits
+    ///
+    /// Generates `iterations` random commits, and checks them with a `TEST_ONLY` commit. If
+    /// `apply` is set, the commits accepted are then applied for real.
+    ///
+    /// This is meant to be run against a virtual driver like VKMS, or a device that isn't used
+    /// for anything else.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if a commit couldn't be
+    /// generated. Commits rejected by the driver aren't considered as errors.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{CommitGenerator, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let report = CommitGenerator::new(&device, 42).stress(1000, true).unwrap();
+    /// assert!(report.inconsistent.is_empty());
+    /// ```
+    pub fn stress(&mut self, iterations: usize, apply: bool) -> Result<StressReport> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+        let mut replayer = Replayer::new(&device);
+        let mut report = StressReport::default();
+
+        for _ in 0..iterations {
+            let commit = self.generate()?;
+            report.tested += 1;
+
+            match replayer.replay(&commit) {
+                Ok(()) => report.accepted += 1,
+                Err(Error::CommitRejected(_)) => continue,
+                Err(err) => return Err(err),
+            }
+
+            if !apply {
+                continue;
+            }
+
+            let real = commit.with_flags(commit.flags() & !DRM_MODE_ATOMIC_TEST_ONLY);
+            match replayer.replay(&real) {
+                Ok(()) => report.committed += 1,
+                Err(Error::CommitRejected(_)) => report.inconsistent.push(commit),
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// The outcome of a [`CommitGenerator::stress`] run
+#[derive(Debug, Default)]
+pub struct StressReport {
+    /// Number of commits tested
+    pub tested: usize,
+
+    /// Number of commits accepted by the `TEST_ONLY` check
+    pub accepted: usize,
+
+    /// Number of commits actually applied
+    pub committed: usize,
+
+    /// Commits that have been accepted by the `TEST_ONLY` check, but have then been rejected
+    /// when actually applied. These are most likely driver bugs.
+    pub inconsistent: Vec<RecordedCommit>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn test_rng_reproducible() {
+        let mut first = Rng::new(42);
+        let mut second = Rng::new(42);
+
+        for _ in 0..100 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_rng_range() {
+        let mut rng = Rng::new(0);
+
+        for _ in 0..1000 {
+            let val = rng.range(3, 7);
+            assert!((3..=7).contains(&val));
+        }
+
+        assert_eq!(rng.range(5, 5), 5);
+        assert_eq!(rng.below(0), 0);
+    }
+}
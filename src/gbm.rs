@@ -0,0 +1,291 @@
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    ffi::c_void,
+    fs::{File, OpenOptions},
+    os::unix::{
+        fs::OpenOptionsExt,
+        io::{AsRawFd, FromRawFd, OwnedFd},
+    },
+    sync::PoisonError,
+};
+
+use crate::{Buffer, Device, Error, Format, FramebufferLayout, Modifier, PlaneType, Result};
+
+const LIBGBM: &[u8] = b"libgbm.so.1\0";
+
+const GBM_BO_USE_SCANOUT: u32 = 1 << 0;
+
+type GbmDevice = *mut c_void;
+type GbmBo = *mut c_void;
+
+// NOTE: The subset of the libgbm API we need. The library is loaded at runtime so that nucleid
+// doesn't need it to build, and only requires it if a GBM buffer is allocated.
+struct Functions {
+    create_device: unsafe extern "C" fn(i32) -> GbmDevice,
+    device_destroy: unsafe extern "C" fn(GbmDevice),
+    bo_create: unsafe extern "C" fn(GbmDevice, u32, u32, u32, u32) -> GbmBo,
+    bo_create_with_modifiers:
+        unsafe extern "C" fn(GbmDevice, u32, u32, u32, *const u64, u32) -> GbmBo,
+
+    // NOTE: Only available since Mesa 21.3, but the older variant drops the usage flags.
+    bo_create_with_modifiers2:
+        Option<unsafe extern "C" fn(GbmDevice, u32, u32, u32, *const u64, u32, u32) -> GbmBo>,
+    bo_destroy: unsafe extern "C" fn(GbmBo),
+    bo_get_fd: unsafe extern "C" fn(GbmBo) -> i32,
+    bo_get_plane_count: unsafe extern "C" fn(GbmBo) -> i32,
+    bo_get_stride_for_plane: unsafe extern "C" fn(GbmBo, i32) -> u32,
+    bo_get_offset: unsafe extern "C" fn(GbmBo, i32) -> u32,
+    bo_get_modifier: unsafe extern "C" fn(GbmBo) -> u64,
+}
+
+/// Looks up a function in a library opened with `dlopen`
+///
+/// # Safety
+///
+/// `T` must be a function pointer type matching the symbol prototype, and `name` must be NUL
+/// terminated.
+unsafe fn symbol<T>(lib: *mut c_void, name: &[u8]) -> Result<T> {
+    let sym = libc::dlsym(lib, name.as_ptr().cast());
+    if sym.is_null() {
+        return Err(Error::Unsupported("GBM"));
+    }
+
+    Ok(std::mem::transmute_copy(&sym))
+}
+
+impl Functions {
+    /// # Safety
+    ///
+    /// `lib` must be a valid handle to libgbm.
+    unsafe fn load(lib: *mut c_void) -> Result<Self> {
+        Ok(Self {
+            create_device: symbol(lib, b"gbm_create_device\0")?,
+            device_destroy: symbol(lib, b"gbm_device_destroy\0")?,
+            bo_create: symbol(lib, b"gbm_bo_create\0")?,
+            bo_create_with_modifiers: symbol(lib, b"gbm_bo_create_with_modifiers\0")?,
+            bo_create_with_modifiers2: symbol(lib, b"gbm_bo_create_with_modifiers2\0").ok(),
+            bo_destroy: symbol(lib, b"gbm_bo_destroy\0")?,
+            bo_get_fd: symbol(lib, b"gbm_bo_get_fd\0")?,
+            bo_get_plane_count: symbol(lib, b"gbm_bo_get_plane_count\0")?,
+            bo_get_stride_for_plane: symbol(lib, b"gbm_bo_get_stride_for_plane\0")?,
+            bo_get_offset: symbol(lib, b"gbm_bo_get_offset\0")?,
+            bo_get_modifier: symbol(lib, b"gbm_bo_get_modifier\0")?,
+        })
+    }
+}
+
+/// A GBM Device, opened on the same DRM device than a [Device]
+pub struct Gbm {
+    lib: *mut c_void,
+    device: GbmDevice,
+    fns: Functions,
+
+    // NOTE: GEM handles are per open file description, and libgbm closes the handle of its
+    // buffers when they're destroyed. We thus open the DRM device again so that the handles
+    // libgbm uses are never the ones we get when importing its buffers.
+    _file: File,
+}
+
+// SAFETY: The GBM device is only accessed through the Device, behind its own lock.
+unsafe impl Send for Gbm {}
+
+impl Gbm {
+    pub(crate) fn new(device: &Device) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_CLOEXEC)
+            .open(format!("/proc/self/fd/{}", device.as_raw_fd()))?;
+
+        let lib =
+            unsafe { libc::dlopen(LIBGBM.as_ptr().cast(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+        if lib.is_null() {
+            return Err(Error::Unsupported("GBM"));
+        }
+
+        let fns = match unsafe { Functions::load(lib) } {
+            Ok(fns) => fns,
+            Err(err) => {
+                unsafe { libc::dlclose(lib) };
+                return Err(err);
+            }
+        };
+
+        let gbm = unsafe { (fns.create_device)(file.as_raw_fd()) };
+        if gbm.is_null() {
+            unsafe { libc::dlclose(lib) };
+            return Err(Error::Unsupported("GBM"));
+        }
+
+        Ok(Self {
+            lib,
+            device: gbm,
+            fns,
+            _file: file,
+        })
+    }
+
+    /// Allocates a buffer object, and exports it as a dma-buf
+    ///
+    /// If `modifiers` is empty, libgbm will pick an implicit layout suitable for the scanout.
+    fn allocate(
+        &self,
+        width: usize,
+        height: usize,
+        format: Format,
+        modifiers: &[Modifier],
+    ) -> Result<(OwnedFd, FramebufferLayout)> {
+        let raw_modifiers: Vec<u64> = modifiers.iter().copied().map(u64::from).collect();
+        let (w, h) = (u32::try_from(width)?, u32::try_from(height)?);
+
+        let bo = unsafe {
+            if raw_modifiers.is_empty() {
+                (self.fns.bo_create)(self.device, w, h, format as u32, GBM_BO_USE_SCANOUT)
+            } else if let Some(bo_create_with_modifiers2) = self.fns.bo_create_with_modifiers2 {
+                bo_create_with_modifiers2(
+                    self.device,
+                    w,
+                    h,
+                    format as u32,
+                    raw_modifiers.as_ptr(),
+                    u32::try_from(raw_modifiers.len())?,
+                    GBM_BO_USE_SCANOUT,
+                )
+            } else {
+                (self.fns.bo_create_with_modifiers)(
+                    self.device,
+                    w,
+                    h,
+                    format as u32,
+                    raw_modifiers.as_ptr(),
+                    u32::try_from(raw_modifiers.len())?,
+                )
+            }
+        };
+
+        if bo.is_null() {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        let ret = self.export(bo, width, height, format, !raw_modifiers.is_empty());
+
+        // NOTE: The dma-buf keeps the memory around, so we don't need the buffer object anymore.
+        unsafe { (self.fns.bo_destroy)(bo) };
+
+        ret
+    }
+
+    fn export(
+        &self,
+        bo: GbmBo,
+        width: usize,
+        height: usize,
+        format: Format,
+        explicit: bool,
+    ) -> Result<(OwnedFd, FramebufferLayout)> {
+        let fd = unsafe { (self.fns.bo_get_fd)(bo) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        // SAFETY: libgbm hands us a new file descriptor.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let count = unsafe { (self.fns.bo_get_plane_count)(bo) };
+        let plane = |idx| -> Result<(usize, usize)> {
+            let pitch = unsafe { (self.fns.bo_get_stride_for_plane)(bo, idx) };
+            let offset = unsafe { (self.fns.bo_get_offset)(bo, idx) };
+
+            Ok((usize::try_from(pitch)?, usize::try_from(offset)?))
+        };
+
+        let (pitch, offset) = plane(0)?;
+        let mut layout = FramebufferLayout::new(width, height, format, pitch).set_offset(offset);
+        for idx in 1..count {
+            let (pitch, offset) = plane(idx)?;
+            layout = layout.add_plane(pitch, offset);
+        }
+
+        if explicit {
+            layout = layout.set_modifier(Modifier::new(unsafe { (self.fns.bo_get_modifier)(bo) }));
+        }
+
+        Ok((fd, layout))
+    }
+}
+
+impl std::fmt::Debug for Gbm {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt.debug_struct("Gbm").finish_non_exhaustive()
+    }
+}
+
+impl Drop for Gbm {
+    fn drop(&mut self) {
+        unsafe {
+            (self.fns.device_destroy)(self.device);
+            libc::dlclose(self.lib);
+        }
+    }
+}
+
+/// Returns the [Modifier]s supported for `format` by every non-cursor [Plane](crate::Plane)
+///
+/// Returns an empty list if any of these [Plane](crate::Plane)s doesn't report its
+/// [Modifier]s, in which case the layout has to be implicit.
+fn scanout_modifiers(device: &Device, format: Format) -> Result<Vec<Modifier>> {
+    let mut common: Option<HashSet<Modifier>> = None;
+
+    for plane in device.planes() {
+        if plane.plane_type() == PlaneType::Cursor {
+            continue;
+        }
+
+        let Some((_, modifiers)) = plane
+            .format_modifiers()?
+            .into_iter()
+            .find(|(fmt, _)| *fmt == format)
+        else {
+            continue;
+        };
+
+        if modifiers.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let modifiers: HashSet<_> = modifiers
+            .into_iter()
+            .filter(|modifier| *modifier != Modifier::INVALID)
+            .collect();
+
+        common = Some(match common {
+            Some(common) => common.intersection(&modifiers).copied().collect(),
+            None => modifiers,
+        });
+    }
+
+    Ok(common.unwrap_or_default().into_iter().collect())
+}
+
+/// Allocates a [Buffer] through libgbm, with a layout every [Plane](crate::Plane) can scan out
+pub fn allocate(device: &Device, width: usize, height: usize, format: Format) -> Result<Buffer> {
+    let modifiers = scanout_modifiers(device, format)?;
+
+    let mut gbm = device
+        .inner
+        .gbm
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if gbm.is_none() {
+        *gbm = Some(Gbm::new(device)?);
+    }
+
+    let (fd, layout) = gbm
+        .as_ref()
+        .ok_or(Error::Empty)?
+        .allocate(width, height, format, &modifiers)?;
+    drop(gbm);
+
+    Buffer::import_layout(device, &fd, layout)
+}
@@ -0,0 +1,86 @@
+use std::sync::{Arc, Weak};
+
+use crate::{device::Inner, Device};
+
+/// A reference to a GEM handle
+///
+/// GEM handles are specific to a [Device] file descriptor, and the kernel hands out the same
+/// handle when a dma-buf is imported several times. A handle might thus be shared by several
+/// [Buffer](crate::Buffer)s and [Framebuffer](crate::Framebuffer)s, so the [Device] counts the
+/// references to each handle and closes it when the last one is dropped.
+///
+/// A handle created outside of nucleid, for example through a vendor specific allocation ioctl,
+/// can also be handed over to nucleid with [`GemHandle::new`], so that it gets closed once the
+/// [`ForeignFramebuffer`](crate::ForeignFramebuffer)s using it are gone. See
+/// [`Device::framebuffer_from_gem_handles`].
+#[derive(Debug)]
+pub struct GemHandle {
+    dev: Weak<Inner>,
+    handle: u32,
+}
+
+impl GemHandle {
+    /// Takes a new reference to a handle created on the [Device]
+    ///
+    /// The handle will be closed once the last [`GemHandle`] referencing it is dropped, so it
+    /// mustn't be closed by anyone else.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, GemHandle};
+    ///
+    /// let device = Device::new("/dev/dri/card0")
+    ///     .unwrap();
+    ///
+    /// # fn vendor_allocate() -> u32 { unimplemented!() }
+    /// let handle = GemHandle::new(&device, vendor_allocate());
+    /// assert_eq!(device.gem_handles(), 1);
+    ///
+    /// drop(handle);
+    /// assert_eq!(device.gem_handles(), 0);
+    /// ```
+    #[must_use]
+    pub fn new(device: &Device, handle: u32) -> Self {
+        device.acquire_gem_handle(handle);
+
+        Self::from_acquired(device, handle)
+    }
+
+    /// Wraps a reference to a handle already accounted for by the [Device]
+    pub(crate) fn from_acquired(device: &Device, handle: u32) -> Self {
+        Self {
+            dev: Arc::downgrade(&device.inner),
+            handle,
+        }
+    }
+
+    /// Returns the raw GEM handle
+    #[must_use]
+    pub const fn handle(&self) -> u32 {
+        self.handle
+    }
+}
+
+impl Clone for GemHandle {
+    fn clone(&self) -> Self {
+        if let Some(inner) = self.dev.upgrade() {
+            Device::from(inner).acquire_gem_handle(self.handle);
+        }
+
+        Self {
+            dev: Weak::clone(&self.dev),
+            handle: self.handle,
+        }
+    }
+}
+
+impl Drop for GemHandle {
+    fn drop(&mut self) {
+        // NOTE: The kernel closes all the handles along with the file descriptor, so there's
+        // nothing left to do if the Device is gone.
+        if let Some(inner) = self.dev.upgrade() {
+            let _res = Device::from(inner).release_gem_handle(self.handle);
+        }
+    }
+}
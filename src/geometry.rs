@@ -0,0 +1,435 @@
+use fixed::types::U16F16;
+
+/// A position, in pixels
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Point {
+    x: usize,
+    y: usize,
+}
+
+impl Point {
+    /// Creates a new [Point]
+    #[must_use]
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the horizontal position of the [Point]
+    #[must_use]
+    pub const fn x(&self) -> usize {
+        self.x
+    }
+
+    /// Returns the vertical position of the [Point]
+    #[must_use]
+    pub const fn y(&self) -> usize {
+        self.y
+    }
+}
+
+/// A size, in pixels
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Size {
+    width: usize,
+    height: usize,
+}
+
+impl Size {
+    /// Creates a new [Size]
+    #[must_use]
+    pub const fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+
+    /// Returns the [Size] width
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the [Size] height
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+}
+
+/// A rectangle, in pixels
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Rect {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Rect {
+    /// Creates a new [Rect]
+    #[must_use]
+    pub const fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Creates a new [Rect] from its top-left corner position and its [Size]
+    #[must_use]
+    pub const fn from_parts(origin: Point, size: Size) -> Self {
+        Self::new(origin.x, origin.y, size.width, size.height)
+    }
+
+    /// Returns the position of the [Rect] top-left corner
+    #[must_use]
+    pub const fn origin(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    /// Returns the [Rect] [Size]
+    #[must_use]
+    pub const fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    /// Returns the horizontal position of the [Rect] left edge
+    #[must_use]
+    pub const fn x(&self) -> usize {
+        self.x
+    }
+
+    /// Returns the vertical position of the [Rect] top edge
+    #[must_use]
+    pub const fn y(&self) -> usize {
+        self.y
+    }
+
+    /// Returns the [Rect] width
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the [Rect] height
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    pub(crate) const fn right(&self) -> usize {
+        self.x + self.width
+    }
+
+    pub(crate) const fn bottom(&self) -> usize {
+        self.y + self.height
+    }
+
+    #[cfg_attr(not(feature = "compositor"), allow(dead_code))]
+    pub(crate) const fn translate(&self, x: usize, y: usize) -> Self {
+        Self::new(self.x + x, self.y + y, self.width, self.height)
+    }
+
+    pub(crate) fn intersect(&self, other: &Self) -> Option<Self> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        if right <= x || bottom <= y {
+            return None;
+        }
+
+        Some(Self::new(x, y, right - x, bottom - y))
+    }
+}
+
+// NOTE: The KMS source coordinates are 16.16 fixed point values. Any value outside of that
+// range can't be represented and is clamped.
+fn fixed(val: f32) -> U16F16 {
+    U16F16::saturating_from_num(val)
+}
+
+fn fixed_from_usize(val: usize) -> U16F16 {
+    U16F16::saturating_from_num(val)
+}
+
+/// A position, in sub-pixel precision
+///
+/// The coordinates are stored as 16.16 fixed point values, the format the KMS source
+/// coordinates are expressed in. Values outside of that range are clamped.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FixedPoint {
+    x: U16F16,
+    y: U16F16,
+}
+
+impl FixedPoint {
+    /// Creates a new [`FixedPoint`]
+    #[must_use]
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            x: fixed(x),
+            y: fixed(y),
+        }
+    }
+
+    /// Returns the horizontal position of the [`FixedPoint`]
+    #[must_use]
+    pub fn x(&self) -> f32 {
+        self.x.to_num()
+    }
+
+    /// Returns the vertical position of the [`FixedPoint`]
+    #[must_use]
+    pub fn y(&self) -> f32 {
+        self.y.to_num()
+    }
+
+    pub(crate) const fn to_bits(self) -> (u32, u32) {
+        (self.x.to_bits(), self.y.to_bits())
+    }
+}
+
+impl From<Point> for FixedPoint {
+    fn from(point: Point) -> Self {
+        Self {
+            x: fixed_from_usize(point.x),
+            y: fixed_from_usize(point.y),
+        }
+    }
+}
+
+/// A size, in sub-pixel precision
+///
+/// The dimensions are stored as 16.16 fixed point values, the format the KMS source
+/// coordinates are expressed in. Values outside of that range are clamped.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FixedSize {
+    width: U16F16,
+    height: U16F16,
+}
+
+impl FixedSize {
+    /// Creates a new [`FixedSize`]
+    #[must_use]
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width: fixed(width),
+            height: fixed(height),
+        }
+    }
+
+    /// Returns the [`FixedSize`] width
+    #[must_use]
+    pub fn width(&self) -> f32 {
+        self.width.to_num()
+    }
+
+    /// Returns the [`FixedSize`] height
+    #[must_use]
+    pub fn height(&self) -> f32 {
+        self.height.to_num()
+    }
+
+    pub(crate) const fn to_bits(self) -> (u32, u32) {
+        (self.width.to_bits(), self.height.to_bits())
+    }
+}
+
+impl From<Size> for FixedSize {
+    fn from(size: Size) -> Self {
+        Self {
+            width: fixed_from_usize(size.width),
+            height: fixed_from_usize(size.height),
+        }
+    }
+}
+
+/// A rectangle, in sub-pixel precision
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FixedRect {
+    origin: FixedPoint,
+    size: FixedSize,
+}
+
+impl FixedRect {
+    /// Creates a new [`FixedRect`]
+    #[must_use]
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self::from_parts(FixedPoint::new(x, y), FixedSize::new(width, height))
+    }
+
+    /// Creates a new [`FixedRect`] from its top-left corner position and its [`FixedSize`]
+    #[must_use]
+    pub const fn from_parts(origin: FixedPoint, size: FixedSize) -> Self {
+        Self { origin, size }
+    }
+
+    /// Returns the position of the [`FixedRect`] top-left corner
+    #[must_use]
+    pub const fn origin(&self) -> FixedPoint {
+        self.origin
+    }
+
+    /// Returns the [`FixedRect`] [`FixedSize`]
+    #[must_use]
+    pub const fn size(&self) -> FixedSize {
+        self.size
+    }
+}
+
+impl From<Rect> for FixedRect {
+    fn from(rect: Rect) -> Self {
+        Self::from_parts(rect.origin().into(), rect.size().into())
+    }
+}
+
+/// How to fit some content into an area of a different aspect ratio
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FitMode {
+    /// The content is scaled to fit entirely in the area, keeping its aspect ratio. The area
+    /// parts that aren't covered, on the sides or at the top and bottom, are left empty.
+    Contain,
+
+    /// The content is scaled to cover the whole area, keeping its aspect ratio. The content
+    /// parts that don't fit in the area are cropped.
+    Cover,
+
+    /// The content is scaled to cover the whole area, without keeping its aspect ratio.
+    Stretch,
+}
+
+impl FitMode {
+    /// Returns the part of `source` to display, and where to display it within `display`
+    pub(crate) fn fit(self, source: Size, display: Rect) -> (FixedRect, Rect) {
+        let full = Rect::from_parts(Point::default(), source).into();
+
+        if source.width == 0 || source.height == 0 || display.width == 0 || display.height == 0 {
+            return (full, display);
+        }
+
+        // NOTE: Comparing the cross products tells us which of the source and display is the
+        // widest without having to deal with floating point ratios.
+        let source_wider = source.width * display.height > display.width * source.height;
+
+        match self {
+            Self::Stretch => (full, display),
+            Self::Contain => {
+                let size = if source_wider {
+                    Size::new(
+                        display.width,
+                        (source.height * display.width).div_ceil(source.width),
+                    )
+                } else {
+                    Size::new(
+                        (source.width * display.height).div_ceil(source.height),
+                        display.height,
+                    )
+                };
+
+                let origin = Point::new(
+                    display.x + (display.width - size.width) / 2,
+                    display.y + (display.height - size.height) / 2,
+                );
+
+                (full, Rect::from_parts(origin, size))
+            }
+            Self::Cover => {
+                // NOTE: The source coordinates are 16.16 fixed point values, so there's no
+                // point in being more precise than an f32.
+                #[allow(clippy::cast_precision_loss)]
+                let (src_w, src_h, dst_w, dst_h) = (
+                    source.width as f32,
+                    source.height as f32,
+                    display.width as f32,
+                    display.height as f32,
+                );
+
+                let crop = if source_wider {
+                    let width = dst_w * src_h / dst_h;
+
+                    FixedRect::new((src_w - width) / 2.0, 0.0, width, src_h)
+                } else {
+                    let height = dst_h * src_w / dst_w;
+
+                    FixedRect::new(0.0, (src_h - height) / 2.0, src_w, height)
+                };
+
+                (crop, display)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FitMode, FixedPoint, FixedRect, FixedSize, Point, Rect, Size};
+
+    #[test]
+    fn test_rect_intersect() {
+        let rect = Rect::new(10, 10, 100, 100);
+
+        assert_eq!(
+            rect.intersect(&Rect::new(50, 0, 100, 20)),
+            Some(Rect::new(50, 10, 60, 10))
+        );
+        assert_eq!(rect.intersect(&Rect::new(110, 10, 10, 10)), None);
+    }
+
+    #[test]
+    fn test_rect_parts() {
+        let rect = Rect::from_parts(Point::new(10, 20), Size::new(30, 40));
+
+        assert_eq!(rect, Rect::new(10, 20, 30, 40));
+        assert_eq!(rect.origin(), Point::new(10, 20));
+        assert_eq!(rect.size(), Size::new(30, 40));
+    }
+
+    #[test]
+    fn test_fixed_rect() {
+        let rect = FixedRect::from(Rect::new(10, 20, 30, 40));
+
+        assert_eq!(rect, FixedRect::new(10.0, 20.0, 30.0, 40.0));
+        assert_eq!(rect.origin().to_bits(), (10 << 16, 20 << 16));
+        assert_eq!(rect.size().to_bits(), (30 << 16, 40 << 16));
+
+        assert_eq!(FixedPoint::new(0.5, -1.0).to_bits(), (1 << 15, 0));
+        assert_eq!(FixedSize::new(1e6, 2.25).to_bits(), (u32::MAX, 0x0002_4000));
+    }
+
+    #[test]
+    fn test_fit_mode() {
+        let source = Size::new(400, 300);
+        let display = Rect::new(0, 0, 1920, 1080);
+        let full = FixedRect::new(0.0, 0.0, 400.0, 300.0);
+
+        assert_eq!(FitMode::Stretch.fit(source, display), (full, display));
+        assert_eq!(
+            FitMode::Contain.fit(source, display),
+            (full, Rect::new(240, 0, 1440, 1080))
+        );
+        assert_eq!(
+            FitMode::Contain.fit(Size::new(300, 400), Rect::new(10, 10, 300, 100)),
+            (
+                FixedRect::new(0.0, 0.0, 300.0, 400.0),
+                Rect::new(122, 10, 75, 100)
+            )
+        );
+        assert_eq!(
+            FitMode::Cover.fit(source, display),
+            (FixedRect::new(0.0, 37.5, 400.0, 225.0), display)
+        );
+        assert_eq!(
+            FitMode::Cover.fit(Size::new(300, 100), Rect::new(0, 0, 100, 100)),
+            (
+                FixedRect::new(100.0, 0.0, 100.0, 100.0),
+                Rect::new(0, 0, 100, 100)
+            )
+        );
+        assert_eq!(
+            FitMode::Contain.fit(Size::new(0, 0), display),
+            (FixedRect::default(), display)
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::convert::{Infallible, TryFrom};
+
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    pixelcolor::{Rgb888, RgbColor},
+    Pixel,
+};
+
+use crate::{Format, Framebuffer};
+
+// NOTE: All the RGB formats we support store the blue, green and red components in that order
+// in memory, the only difference being the presence and meaning of the fourth byte. For the YUV
+// formats, we only draw into the luma plane, using the BT.601 limited range coefficients.
+fn encode(format: Format, color: Rgb888) -> [u8; 4] {
+    let alpha = match format {
+        Format::ARGB8888 => u8::MAX,
+        Format::RGB888 | Format::XRGB8888 => 0,
+        Format::NV12 | Format::YUV420 => {
+            let luma = (66 * u32::from(color.r())
+                + 129 * u32::from(color.g())
+                + 25 * u32::from(color.b())
+                + 128)
+                >> 8;
+
+            return [u8::try_from(luma + 16).unwrap_or(u8::MAX), 0, 0, 0];
+        }
+    };
+
+    [color.b(), color.g(), color.r(), alpha]
+}
+
+fn draw_pixel(
+    data: &mut [u8],
+    format: Format,
+    pitch: usize,
+    (width, height): (usize, usize),
+    Pixel(point, color): Pixel<Rgb888>,
+) {
+    let (Ok(x), Ok(y)) = (usize::try_from(point.x), usize::try_from(point.y)) else {
+        return;
+    };
+
+    if x >= width || y >= height {
+        return;
+    }
+
+    let cpp = format.bpp() / 8;
+    let offset = y * pitch + x * cpp;
+
+    data[offset..offset + cpp].copy_from_slice(&encode(format, color)[..cpp]);
+}
+
+impl OriginDimensions for Framebuffer {
+    fn size(&self) -> Size {
+        Size::new(
+            u32::try_from(self.width()).unwrap_or(u32::MAX),
+            u32::try_from(self.height()).unwrap_or(u32::MAX),
+        )
+    }
+}
+
+impl DrawTarget for Framebuffer {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let format = self.format();
+        let pitch = self.pitch();
+        let size = (self.width(), self.height());
+        let data = self.data();
+
+        for pixel in pixels {
+            draw_pixel(data, format, pitch, size, pixel);
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        let format = self.format();
+        let cpp = format.bpp() / 8;
+        let pitch = self.pitch();
+        let line = self.width() * cpp;
+        let pixel = encode(format, color);
+
+        for row in self.data().chunks_exact_mut(pitch) {
+            for dst in row[..line].chunks_exact_mut(cpp) {
+                dst.copy_from_slice(&pixel[..cpp]);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics_core::{geometry::Point, pixelcolor::Rgb888, Pixel};
+
+    use super::draw_pixel;
+    use crate::Format;
+
+    #[test]
+    fn test_draw_pixel() {
+        let color = Rgb888::new(0x11, 0x22, 0x33);
+        let mut data = vec![0; 4 * 8];
+
+        draw_pixel(
+            &mut data,
+            Format::XRGB8888,
+            8,
+            (2, 4),
+            Pixel(Point::new(1, 2), color),
+        );
+        assert_eq!(&data[20..24], &[0x33, 0x22, 0x11, 0x00]);
+
+        draw_pixel(
+            &mut data,
+            Format::ARGB8888,
+            8,
+            (2, 4),
+            Pixel(Point::new(0, 1), color),
+        );
+        assert_eq!(&data[8..12], &[0x33, 0x22, 0x11, 0xff]);
+
+        draw_pixel(
+            &mut data,
+            Format::RGB888,
+            8,
+            (2, 4),
+            Pixel(Point::new(1, 0), color),
+        );
+        assert_eq!(&data[3..7], &[0x33, 0x22, 0x11, 0x00]);
+
+        draw_pixel(
+            &mut data,
+            Format::NV12,
+            8,
+            (2, 4),
+            Pixel(Point::new(0, 3), Rgb888::new(0xff, 0xff, 0xff)),
+        );
+        assert_eq!(&data[24..26], &[235, 0]);
+
+        let before = data.clone();
+        for point in [Point::new(-1, 0), Point::new(2, 0), Point::new(0, 4)] {
+            draw_pixel(&mut data, Format::XRGB8888, 8, (2, 4), Pixel(point, color));
+        }
+        assert_eq!(data, before);
+    }
+}
@@ -0,0 +1,71 @@
+use std::{
+    fs::{File, OpenOptions},
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+};
+
+use crate::{raw::dma_heap_allocate, Error, Result};
+
+const DMA_HEAP_PATH: &str = "/dev/dma_heap";
+
+// NOTE: The default CMA heap is named after the CMA area, which depends on how it was declared.
+const CMA_HEAP_NAMES: [&str; 3] = ["linux,cma", "reserved", "default_cma_region"];
+
+/// A dma-buf Heap
+///
+/// The dma-buf heaps are exposed by the kernel in `/dev/dma_heap`, and allocate buffers that can
+/// be shared between devices. They're used through
+/// [`BufferType::DmaHeap`](crate::BufferType::DmaHeap).
+///
+/// # Example
+///
+/// ```no_run
+/// use nucleid::{BufferType, Device, DmaHeap, Format};
+///
+/// let device = Device::new("/dev/dri/card0")
+///     .unwrap();
+///
+/// let fb = device.allocate_buffer(BufferType::DmaHeap(DmaHeap::Cma), 640, 480, 32)
+///     .unwrap()
+///     .into_framebuffer(Format::XRGB8888)
+///     .unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Heap {
+    /// The system heap, backed by pages that might not be physically contiguous
+    System,
+
+    /// The default CMA heap, backed by physically contiguous memory
+    ///
+    /// The allocations fail with [`Error::HeapNotFound`] if the kernel doesn't expose it.
+    Cma,
+
+    /// A heap by its name in `/dev/dma_heap`
+    Named(&'static str),
+}
+
+impl Heap {
+    fn path(self) -> Result<PathBuf> {
+        let base = Path::new(DMA_HEAP_PATH);
+
+        match self {
+            Self::System => Ok(base.join("system")),
+            Self::Named(name) => Ok(base.join(name)),
+            Self::Cma => CMA_HEAP_NAMES
+                .iter()
+                .map(|name| base.join(name))
+                .find(|path| path.exists())
+                .ok_or(Error::HeapNotFound),
+        }
+    }
+
+    /// Allocates a dma-buf of `size` bytes from the [Heap]
+    pub(crate) fn allocate(self, size: usize) -> Result<File> {
+        let heap = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_CLOEXEC)
+            .open(self.path()?)?;
+
+        dma_heap_allocate(&heap, size)
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    os::unix::{
+        fs::MetadataExt,
+        io::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    },
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use nix::sys::socket::{
+    bind, recv, socket, AddressFamily, MsgFlags, NetlinkAddr, SockFlag, SockProtocol, SockType,
+};
+
+use crate::{object::Object, raw::drm_poll_events, Connector, Device, Result};
+
+// NOTE: The kernel broadcasts the uevents on the first group, udev on the second one.
+const KERNEL_UEVENT_GROUP: u32 = 1;
+const UEVENT_BUFFER_SIZE: usize = 8192;
+
+/// A Hotplug Event Sent by the Kernel
+///
+/// The kernel sends one whenever the status of a [Connector] might have changed, or when a
+/// [Connector] has been added or removed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HotplugEvent {
+    major: u32,
+    minor: u32,
+    connector_id: Option<u32>,
+    property_id: Option<u32>,
+}
+
+impl HotplugEvent {
+    /// Parses a raw uevent, and returns a [`HotplugEvent`] if it's a DRM hotplug one
+    ///
+    /// A uevent is made of a `ACTION@DEVPATH` header followed by `KEY=VALUE` pairs, all
+    /// separated by NUL bytes.
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut subsystem = None;
+        let mut hotplug = false;
+        let mut major = None;
+        let mut minor = None;
+        let mut connector_id = None;
+        let mut property_id = None;
+
+        for field in data.split(|&byte| byte == 0).skip(1) {
+            let Some((key, value)) = std::str::from_utf8(field).ok()?.split_once('=') else {
+                continue;
+            };
+
+            match key {
+                "SUBSYSTEM" => subsystem = Some(value),
+                "HOTPLUG" => hotplug = value == "1",
+                "MAJOR" => major = value.parse().ok(),
+                "MINOR" => minor = value.parse().ok(),
+                "CONNECTOR" => connector_id = value.parse().ok(),
+                "PROPERTY" => property_id = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        if subsystem != Some("drm") || !hotplug {
+            return None;
+        }
+
+        Some(Self {
+            major: major?,
+            minor: minor?,
+            connector_id,
+            property_id,
+        })
+    }
+
+    /// Returns whether the [`HotplugEvent`] has been sent for a given [Device]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error](crate::Error) if the [Device] file can't be accessed.
+    pub fn is_for(&self, device: &Device) -> Result<bool> {
+        let rdev = device.inner.file.metadata()?.rdev();
+
+        Ok(libc::major(rdev) == self.major && libc::minor(rdev) == self.minor)
+    }
+
+    /// Returns the ID of the [Connector] that changed, if the kernel reported it
+    ///
+    /// Most drivers don't report it, in which case any [Connector] could have changed.
+    #[must_use]
+    pub const fn connector_id(&self) -> Option<u32> {
+        self.connector_id
+    }
+
+    /// Returns the ID of the [Connector] property that changed, if any
+    ///
+    /// The kernel reports it when a property like `Content Protection` changes on its own.
+    #[must_use]
+    pub const fn property_id(&self) -> Option<u32> {
+        self.property_id
+    }
+
+    /// Returns the [Connector] that changed, if the kernel reported it
+    ///
+    /// The [Connector]s of the [Device] might need to be refreshed first with
+    /// [`Device::rescan`].
+    #[must_use]
+    pub fn connector(&self, device: &Device) -> Option<Arc<Connector>> {
+        let id = self.connector_id?;
+
+        device.connectors().find(|con| con.object_id() == id)
+    }
+}
+
+/// Monitors the DRM Hotplug Events
+///
+/// The events are received from the kernel uevents netlink socket, so this doesn't need udev to
+/// run. The socket file descriptor can be added to an event loop, and
+/// [`HotplugMonitor::next_event`] called once it's readable.
+#[derive(Debug)]
+pub struct HotplugMonitor {
+    fd: OwnedFd,
+}
+
+impl HotplugMonitor {
+    /// Creates a new [`HotplugMonitor`]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error](crate::Error) if the netlink socket can't be created.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use nucleid::{Device, HotplugMonitor};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let monitor = HotplugMonitor::new().unwrap();
+    ///
+    /// while let Some(event) = monitor.wait(Duration::from_secs(60)).unwrap() {
+    ///     if event.is_for(&device).unwrap() {
+    ///         device.rescan().unwrap();
+    ///         println!("{} connected", device.connected_connectors().len());
+    ///     }
+    /// }
+    /// ```
+    pub fn new() -> Result<Self> {
+        let fd = socket(
+            AddressFamily::Netlink,
+            SockType::Datagram,
+            SockFlag::SOCK_CLOEXEC | SockFlag::SOCK_NONBLOCK,
+            SockProtocol::NetlinkKObjectUEvent,
+        )?;
+
+        // SAFETY: The socket has just been created, so we're its only owner.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        bind(fd.as_raw_fd(), &NetlinkAddr::new(0, KERNEL_UEVENT_GROUP))?;
+
+        Ok(Self { fd })
+    }
+
+    /// Returns the next pending [`HotplugEvent`], if any
+    ///
+    /// This doesn't block, and skips the uevents that aren't DRM hotplug events.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error](crate::Error) if the socket can't be read.
+    pub fn next_event(&self) -> Result<Option<HotplugEvent>> {
+        let mut buffer = [0; UEVENT_BUFFER_SIZE];
+
+        loop {
+            let len = match recv(self.fd.as_raw_fd(), &mut buffer, MsgFlags::empty()) {
+                Ok(len) => len,
+                Err(nix::errno::Errno::EAGAIN) => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+
+            if let Some(event) = HotplugEvent::parse(&buffer[..len]) {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    /// Waits for the next [`HotplugEvent`]
+    ///
+    /// Returns `None` if no [`HotplugEvent`] has been received within `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error](crate::Error) if the socket can't be polled or read.
+    pub fn wait(&self, timeout: Duration) -> Result<Option<HotplugEvent>> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Some(event) = self.next_event()? {
+                return Ok(Some(event));
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !drm_poll_events(self, remaining)? {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+impl AsRawFd for HotplugMonitor {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HotplugEvent;
+
+    #[test]
+    fn test_parse() {
+        let uevent = b"change@/devices/pci0000:00/0000:00:02.0/drm/card0\0ACTION=change\0\
+            DEVPATH=/devices/pci0000:00/0000:00:02.0/drm/card0\0SUBSYSTEM=drm\0HOTPLUG=1\0\
+            CONNECTOR=95\0DEVNAME=dri/card0\0DEVTYPE=drm_minor\0SEQNUM=4242\0MAJOR=226\0MINOR=0\0";
+
+        let event = HotplugEvent::parse(uevent).unwrap();
+        assert_eq!(event.major, 226);
+        assert_eq!(event.minor, 0);
+        assert_eq!(event.connector_id(), Some(95));
+        assert_eq!(event.property_id(), None);
+    }
+
+    #[test]
+    fn test_parse_ignored() {
+        let uevent = b"add@/devices/virtual/net/lo\0ACTION=add\0SUBSYSTEM=net\0SEQNUM=12\0";
+        assert_eq!(HotplugEvent::parse(uevent), None);
+
+        let uevent = b"change@/devices/pci0000:00/0000:00:02.0/drm/card0\0ACTION=change\0\
+            SUBSYSTEM=drm\0MAJOR=226\0MINOR=0\0";
+        assert_eq!(HotplugEvent::parse(uevent), None);
+    }
+}
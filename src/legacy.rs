@@ -0,0 +1,254 @@
+use std::convert::TryFrom;
+
+use crate::{
+    object::Object,
+    raw::{
+        drm_mode_get_crtc, drm_mode_get_plane, drm_mode_page_flip, drm_mode_set_crtc,
+        drm_mode_set_plane, drm_mode_set_property, DRM_MODE_PAGE_FLIP_EVENT,
+    },
+    Connector, Device, DpmsMode, Error, Event, PlaneType, PlaneUpdate, Result, Update,
+};
+
+// NOTE: Those properties are only exposed to atomic clients, and are passed as arguments of the
+// legacy ioctls instead.
+const PLANE_GEOMETRY: [&str; 9] = [
+    "FB_ID", "CRTC_X", "CRTC_Y", "CRTC_W", "CRTC_H", "SRC_X", "SRC_Y", "SRC_W", "SRC_H",
+];
+
+// NOTE: An fb_id of -1 asks SETCRTC to keep the current framebuffer.
+const CURRENT_FB: u32 = u32::MAX;
+
+fn property(update: &PlaneUpdate, name: &str) -> Option<u64> {
+    update.named_properties().get(name).copied()
+}
+
+fn u32_property(update: &PlaneUpdate, name: &str) -> Result<u32> {
+    Ok(u32::try_from(property(update, name).unwrap_or(0))?)
+}
+
+// NOTE: SETPLANE sets the whole plane geometry at once, so we can't leave any of it to its
+// current value like an atomic commit would.
+fn geometry_property<T: TryFrom<u64, Error = std::num::TryFromIntError>>(
+    update: &PlaneUpdate,
+    name: &'static str,
+) -> Result<T> {
+    let value = property(update, name).ok_or(Error::IncompleteGeometry(name))?;
+
+    Ok(T::try_from(value)?)
+}
+
+fn set_properties<'a>(
+    device: &Device,
+    object: &impl Object,
+    properties: impl Iterator<Item = (&'a String, &'a u64)>,
+) -> Result<()> {
+    for (name, value) in properties {
+        let prop_id = object.required_property_id(name)?;
+
+        drm_mode_set_property(
+            device,
+            object.object_type() as u32,
+            object.object_id(),
+            prop_id,
+            *value,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn set_plane_properties(device: &Device, update: &PlaneUpdate) -> Result<()> {
+    set_properties(
+        device,
+        update.plane().as_ref(),
+        update
+            .named_properties()
+            .iter()
+            .filter(|(name, _)| !PLANE_GEOMETRY.contains(&name.as_str())),
+    )
+}
+
+/// Checks that the primary plane covers the whole [Mode](crate::Mode)
+///
+/// `SETCRTC` and `PAGE_FLIP` can only pan the primary plane framebuffer, so it can't be scaled or
+/// moved around.
+fn check_primary(device: &Device, update: &Update, primary: &PlaneUpdate) -> Result<()> {
+    const GEOMETRY: [&str; 6] = ["CRTC_X", "CRTC_Y", "CRTC_W", "CRTC_H", "SRC_W", "SRC_H"];
+
+    if !GEOMETRY
+        .iter()
+        .any(|name| property(primary, name).is_some())
+    {
+        return Ok(());
+    }
+
+    let mode = match update.mode() {
+        Some(mode) => *mode.inner(),
+        None => drm_mode_get_crtc(device, update.output().crtc_id())?.mode,
+    };
+
+    let width = u64::from(mode.hdisplay);
+    let height = u64::from(mode.vdisplay);
+    let expected = [0, 0, width, height, width << 16, height << 16];
+
+    for (name, value) in GEOMETRY.iter().zip(expected) {
+        if property(primary, name).is_some_and(|prop| prop != value) {
+            return Err(Error::Unsupported("Scaled or offset primary plane"));
+        }
+    }
+
+    Ok(())
+}
+
+fn set_primary(device: &Device, update: &Update, primary: Option<&PlaneUpdate>) -> Result<()> {
+    let output = update.output();
+    let crtc_id = output.crtc_id();
+
+    if let Some(primary) = primary {
+        check_primary(device, update, primary)?;
+    }
+
+    if let Some(mode) = update.mode() {
+        let (fb_id, x, y) = match primary {
+            Some(primary) => (
+                property(primary, "FB_ID").map_or(Ok(CURRENT_FB), u32::try_from)?,
+                u32_property(primary, "SRC_X")? >> 16,
+                u32_property(primary, "SRC_Y")? >> 16,
+            ),
+            None => (CURRENT_FB, 0, 0),
+        };
+
+        return drm_mode_set_crtc(
+            device,
+            crtc_id,
+            fb_id,
+            (x, y),
+            &[output.connector_id()],
+            mode.inner(),
+        );
+    }
+
+    // NOTE: Page flips can only change the framebuffer, and we wait for it to complete to
+    // behave like a blocking atomic commit.
+    if let Some(fb_id) = primary.and_then(|primary| property(primary, "FB_ID")) {
+        let user_data = u64::from(crtc_id);
+        let submitted = update.submitted_at();
+
+        drm_mode_page_flip(
+            device,
+            crtc_id,
+            u32::try_from(fb_id)?,
+            DRM_MODE_PAGE_FLIP_EVENT,
+            user_data,
+        )?;
+        let event = device.wait_for_event(
+            |event| matches!(event, Event::Flip(flip) if flip.handle().user_data() == user_data),
+            None,
+        )?;
+
+        if let Event::Flip(flip) = event {
+            device.record_frame(crtc_id, submitted, flip.vblank().sequence());
+        }
+    }
+
+    Ok(())
+}
+
+fn set_plane(device: &Device, crtc_id: u32, update: &PlaneUpdate) -> Result<()> {
+    let plane = update.plane();
+
+    if !PLANE_GEOMETRY
+        .iter()
+        .any(|name| property(update, name).is_some())
+    {
+        return Ok(());
+    }
+
+    let fb_id = match property(update, "FB_ID") {
+        Some(fb_id) => u32::try_from(fb_id)?,
+        None => drm_mode_get_plane(device, plane.object_id(), None)?.fb_id,
+    };
+
+    // NOTE: A plane without a framebuffer is disabled, whatever its geometry.
+    if fb_id == 0 {
+        return drm_mode_set_plane(
+            device,
+            drm_mode_set_plane {
+                plane_id: plane.object_id(),
+                ..drm_mode_set_plane::default()
+            },
+        );
+    }
+
+    drm_mode_set_plane(
+        device,
+        drm_mode_set_plane {
+            plane_id: plane.object_id(),
+            crtc_id,
+            fb_id,
+            flags: 0,
+            crtc_x: geometry_property(update, "CRTC_X")?,
+            crtc_y: geometry_property(update, "CRTC_Y")?,
+            crtc_w: geometry_property(update, "CRTC_W")?,
+            crtc_h: geometry_property(update, "CRTC_H")?,
+            src_x: geometry_property(update, "SRC_X")?,
+            src_y: geometry_property(update, "SRC_Y")?,
+            src_h: geometry_property(update, "SRC_H")?,
+            src_w: geometry_property(update, "SRC_W")?,
+        },
+    )
+}
+
+fn set_dpms(device: &Device, connector: &Connector, dpms: DpmsMode) -> Result<()> {
+    let prop_id = connector.required_property_id("DPMS")?;
+
+    drm_mode_set_property(
+        device,
+        connector.object_type() as u32,
+        connector.object_id(),
+        prop_id,
+        dpms as u64,
+    )
+}
+
+/// Turns an [Output](crate::Output) off through its [Connector] DPMS property
+pub fn power_off(device: &Device, connector: &Connector) -> Result<()> {
+    set_dpms(device, connector, DpmsMode::Off)
+}
+
+/// Applies an [Update] through the legacy KMS API
+///
+/// The connector and plane properties are set first, then the [Mode] and primary plane
+/// framebuffer through `SETCRTC` or `PAGE_FLIP`, and the other planes through `SETPLANE`.
+pub fn commit(update: &Update, device: &Device) -> Result<()> {
+    let crtc_id = update.output().crtc_id();
+
+    if let Some(connector) = update.connector() {
+        set_properties(
+            device,
+            connector.connector().as_ref(),
+            connector.named_properties().iter(),
+        )?;
+
+        if let Some(dpms) = connector.dpms() {
+            set_dpms(device, connector.connector(), dpms)?;
+        }
+    }
+
+    let (primary, planes): (Vec<_>, Vec<_>) = update
+        .planes()
+        .iter()
+        .partition(|plane| plane.plane().plane_type() == PlaneType::Primary);
+
+    for plane in update.planes() {
+        set_plane_properties(device, plane)?;
+    }
+
+    set_primary(device, update, primary.first().copied())?;
+
+    for plane in planes {
+        set_plane(device, crtc_id, plane)?;
+    }
+
+    Ok(())
+}
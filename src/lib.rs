@@ -12,38 +12,193 @@
 #![deny(clippy::cargo)]
 #![allow(clippy::unreadable_literal)]
 #![allow(clippy::use_self)]
+#![allow(clippy::multiple_crate_versions)]
 
+mod assign;
+#[cfg(feature = "tokio")]
+mod asynchronous;
+mod atomic;
+mod blob;
 mod buffer;
+mod color;
+#[cfg(feature = "compositor")]
+pub mod compositor;
+#[cfg(feature = "config")]
+pub mod config;
 mod connector;
 mod crtc;
+mod cvt;
+mod damage;
+#[cfg(all(feature = "i2c", target_os = "linux"))]
+mod ddc;
 mod device;
+mod display;
+mod dmabuf;
+mod driver;
+#[cfg(feature = "edid")]
+mod edid;
 mod encoder;
 mod error;
+mod event;
+mod fallback;
+mod flags;
 mod format;
+mod fuzz;
+#[cfg(all(feature = "gbm", target_os = "linux"))]
+mod gbm;
+mod gem;
+mod geometry;
+#[cfg(feature = "embedded-graphics")]
+mod graphics;
+#[cfg(target_os = "linux")]
+mod heap;
+#[cfg(all(feature = "hotplug", target_os = "linux"))]
+mod hotplug;
+#[cfg(feature = "legacy")]
+mod legacy;
+mod lut;
+mod mirror;
 mod mode;
+mod modeline;
+mod modifier;
+mod node;
 mod object;
 mod output;
+mod pixel;
 mod plane;
 mod property;
 mod raw;
+mod record;
+mod snapshot;
+mod span;
+mod stats;
+mod surface;
+pub mod sys;
+mod tiling;
+mod topology;
+mod trace;
+mod vblank;
+#[cfg(target_os = "linux")]
+mod vgem;
+#[cfg(feature = "raw-window-handle")]
+mod window;
 
+pub use crate::assign::LayerAssignment;
+pub use crate::assign::LayerRequest;
+#[cfg(feature = "tokio")]
+pub use crate::asynchronous::AsyncDevice;
+#[cfg(feature = "tokio")]
+pub use crate::asynchronous::EventStream;
+pub use crate::atomic::DeviceCommitError;
+pub use crate::atomic::DeviceUpdate;
+pub use crate::blob::{Blob, BlobData};
+pub use crate::buffer::AdoptedFramebuffer;
 pub use crate::buffer::Buffer;
+pub use crate::buffer::BufferPlane;
+pub use crate::buffer::ForeignFramebuffer;
 pub use crate::buffer::Framebuffer;
+pub use crate::buffer::FramebufferLayout;
 pub use crate::buffer::Type as BufferType;
+pub use crate::color::LutEntry;
+pub use crate::color::Matrix3x3;
 pub use crate::connector::Connector;
+pub use crate::connector::ContentProtection;
+pub use crate::connector::DpmsMode;
+pub use crate::connector::HdcpContentType;
 pub use crate::connector::Status as ConnectorStatus;
+pub use crate::connector::Subpixel;
 pub use crate::connector::Type as ConnectorType;
 pub use crate::crtc::Crtc;
+pub use crate::cvt::ReducedBlanking;
+pub use crate::damage::Damage;
+#[cfg(all(feature = "i2c", target_os = "linux"))]
+pub use crate::ddc::Ddc;
+#[cfg(all(feature = "i2c", target_os = "linux"))]
+pub use crate::ddc::VcpValue;
+pub use crate::device::ConnectorChanges;
 pub use crate::device::Device;
+pub use crate::display::Display;
+pub use crate::dmabuf::DmaBuf;
+pub use crate::dmabuf::DmaBufPlane;
+pub use crate::dmabuf::DRM_FORMAT_MOD_LINEAR;
+pub use crate::driver::Capability;
+pub use crate::driver::DriverInfo;
+#[cfg(feature = "edid")]
+pub use crate::edid::Colorimetry;
+#[cfg(feature = "edid")]
+pub use crate::edid::Edid;
+#[cfg(feature = "edid")]
+pub use crate::edid::Eotf;
+#[cfg(feature = "edid")]
+pub use crate::edid::HdrMetadata;
+pub use crate::encoder::Encoder;
+pub use crate::encoder::Type as EncoderType;
 pub use crate::error::Error;
 pub use crate::error::Result;
+pub use crate::event::CommitHandle;
+pub use crate::event::Event;
+pub use crate::event::FlipEvent;
+pub use crate::fallback::Fallback;
+pub use crate::flags::CommitFlags;
+pub use crate::flags::PrimeFlags;
 pub use crate::format::Format;
+pub use crate::format::FormatInfo;
+pub use crate::fuzz::CommitGenerator;
+pub use crate::fuzz::StressReport;
+pub use crate::gem::GemHandle;
+pub use crate::geometry::FitMode;
+pub use crate::geometry::FixedPoint;
+pub use crate::geometry::FixedRect;
+pub use crate::geometry::FixedSize;
+pub use crate::geometry::Point;
+pub use crate::geometry::Rect;
+pub use crate::geometry::Size;
+#[cfg(target_os = "linux")]
+pub use crate::heap::Heap as DmaHeap;
+#[cfg(all(feature = "hotplug", target_os = "linux"))]
+pub use crate::hotplug::HotplugEvent;
+#[cfg(all(feature = "hotplug", target_os = "linux"))]
+pub use crate::hotplug::HotplugMonitor;
+pub use crate::lut::Lut3d;
+pub use crate::mirror::Mirror;
 pub use crate::mode::Mode;
+pub use crate::mode::ModeBuilder;
+pub use crate::mode::PictureAspectRatio;
+pub use crate::mode::Stereo3dLayout;
+pub use crate::mode::SyncPolarity;
+pub use crate::modifier::Modifier;
+pub use crate::node::DeviceInfo;
+pub use crate::node::NodeType;
+pub use crate::output::CommitError;
 pub use crate::output::ConnectorUpdate;
+pub use crate::output::CrtcUpdate;
 pub use crate::output::ObjectUpdate;
 pub use crate::output::Output;
 pub use crate::output::PlaneUpdate;
+pub use crate::output::Planes;
 pub use crate::output::Update;
+pub use crate::pixel::Pixel;
+pub use crate::plane::ChromaSiting;
+pub use crate::plane::ColorEncoding;
+pub use crate::plane::ColorRange;
+pub use crate::plane::PixelBlendMode;
 pub use crate::plane::Plane;
+pub use crate::plane::Reflection;
+pub use crate::plane::Rotation;
 pub use crate::plane::Type as PlaneType;
+pub use crate::property::Properties;
 pub use crate::property::Property;
+pub use crate::property::PropertyValue;
+pub use crate::record::RecordedCommit;
+pub use crate::record::RecordedProperty;
+pub use crate::record::RecordedValue;
+pub use crate::record::Recording;
+pub use crate::record::Replayer;
+pub use crate::snapshot::Snapshot;
+pub use crate::span::Span;
+pub use crate::stats::FrameStats;
+pub use crate::tiling::Tiling;
+pub use crate::topology::Topology;
+pub use crate::vblank::Vblank;
+#[cfg(target_os = "linux")]
+pub use crate::vgem::VgemFence;
@@ -0,0 +1,309 @@
+use std::{convert::TryFrom, str::FromStr};
+
+use crate::{raw::drm_color_lut, Error, Result};
+
+const MAX_LUT_SIZE: usize = 256;
+
+// NOTE: The hardware 3D LUTs are at most a few dozens of grid points large, and bounding it
+// keeps the blob below a few megabytes even if the driver reports something bogus.
+const MAX_HW_LUT_SIZE: usize = 129;
+
+fn lerp(a: [f64; 3], b: [f64; 3], t: f64) -> [f64; 3] {
+    [0, 1, 2].map(|idx| (b[idx] - a[idx]).mul_add(t, a[idx]))
+}
+
+fn to_u16(val: f64) -> u16 {
+    // NOTE: The value is clamped to [0.0; 1.0] first, so the result fits in a u16
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let val = (val.clamp(0.0, 1.0) * f64::from(u16::MAX)).round() as u16;
+
+    val
+}
+
+/// A 3D Look-Up Table
+///
+/// A 3D LUT maps every input color to an output color, and is typically used to apply a
+/// display calibration. It's made of `size`³ entries, each of them being the output color for
+/// a point of a regular grid over the input colors. The colors between the grid points are
+/// interpolated.
+///
+/// A [Lut3d] is usually loaded from an Adobe / Resolve `.cube` file, using [`Lut3d::load`]. Only
+/// the default `[0.0; 1.0]` input domain is supported.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lut3d {
+    size: usize,
+
+    // NOTE: The entries are stored in the .cube order, with the red index changing the fastest.
+    entries: Vec<[f64; 3]>,
+}
+
+impl Lut3d {
+    /// Loads a [Lut3d] from a `.cube` file
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the file can't be read or if it isn't a valid 3D LUT.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Lut3d;
+    ///
+    /// let lut = Lut3d::load("calibration.cube").unwrap();
+    /// ```
+    pub fn load(path: &str) -> Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Returns the number of grid points along each axis of the [Lut3d]
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    fn entry(&self, red: usize, green: usize, blue: usize) -> [f64; 3] {
+        self.entries[red + green * self.size + blue * self.size * self.size]
+    }
+
+    fn sample(&self, color: [f64; 3]) -> [f64; 3] {
+        let max = self.size - 1;
+
+        // NOTE: The LUT size is at most 256, so the conversions are exact
+        #[allow(clippy::cast_precision_loss)]
+        let scaled = color.map(|val| val.clamp(0.0, 1.0) * max as f64);
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let low = scaled.map(|val| (val.floor() as usize).min(max - 1));
+
+        #[allow(clippy::cast_precision_loss)]
+        let frac = [0, 1, 2].map(|idx| scaled[idx] - low[idx] as f64);
+
+        let [r, g, b] = low;
+
+        let c00 = lerp(self.entry(r, g, b), self.entry(r + 1, g, b), frac[0]);
+        let c10 = lerp(
+            self.entry(r, g + 1, b),
+            self.entry(r + 1, g + 1, b),
+            frac[0],
+        );
+        let c01 = lerp(
+            self.entry(r, g, b + 1),
+            self.entry(r + 1, g, b + 1),
+            frac[0],
+        );
+        let c11 = lerp(
+            self.entry(r, g + 1, b + 1),
+            self.entry(r + 1, g + 1, b + 1),
+            frac[0],
+        );
+
+        let c0 = lerp(c00, c10, frac[1]);
+        let c1 = lerp(c01, c11, frac[1]);
+
+        lerp(c0, c1, frac[2])
+    }
+
+    /// Converts the [Lut3d] to the kernel layout, with `size` grid points along each axis
+    ///
+    /// The entry for the grid point (`red`, `green`, `blue`) is at the index
+    /// `red * size² + green * size + blue`, so the blue index changes the fastest. This is the
+    /// opposite of the `.cube` files, where the red index changes the fastest.
+    ///
+    /// The [Lut3d] will be interpolated if its size doesn't match the one supported by the
+    /// hardware.
+    pub(crate) fn to_raw(&self, size: usize) -> Result<Vec<drm_color_lut>> {
+        let entries = size
+            .checked_mul(size)
+            .and_then(|len| len.checked_mul(size))
+            .ok_or(Error::Unsupported("3D LUT Size"))?;
+
+        let mut raw = Vec::with_capacity(entries);
+        let max = size.max(2) - 1;
+
+        // NOTE: The LUT size is at most a few hundreds, so the conversions are exact
+        #[allow(clippy::cast_precision_loss)]
+        let coord = |idx: usize| idx as f64 / max as f64;
+
+        for red in 0..size {
+            for green in 0..size {
+                for blue in 0..size {
+                    let [r, g, b] = self.sample([coord(red), coord(green), coord(blue)]);
+
+                    raw.push(drm_color_lut {
+                        red: to_u16(r),
+                        green: to_u16(g),
+                        blue: to_u16(b),
+                        reserved: 0,
+                    });
+                }
+            }
+        }
+
+        Ok(raw)
+    }
+}
+
+/// Returns the number of grid points along each axis of a hardware 3D LUT
+///
+/// Like for `GAMMA_LUT_SIZE`, the size reported by the driver is the number of
+/// `struct drm_color_lut` entries the blob holds, so it needs to be a perfect cube.
+pub fn hw_lut_size(size: u64) -> Result<usize> {
+    let size = usize::try_from(size)?;
+
+    (2..=MAX_HW_LUT_SIZE)
+        .find(|edge| edge.checked_pow(3) == Some(size))
+        .ok_or(Error::Unsupported("3D LUT Size"))
+}
+
+fn parse_triplet(s: &str) -> Option<[f64; 3]> {
+    let mut values = s.split_whitespace().map(|val| val.parse::<f64>().ok());
+
+    let triplet = [values.next()??, values.next()??, values.next()??];
+    if values.next().is_some() {
+        return None;
+    }
+
+    Some(triplet)
+}
+
+impl FromStr for Lut3d {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut size = None;
+        let mut entries = Vec::new();
+        let mut last_line = 0;
+
+        for (idx, line) in s.lines().enumerate() {
+            let line = line.trim();
+            let line_num = idx + 1;
+            last_line = line_num;
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            match keyword {
+                "TITLE" => {}
+                "LUT_3D_SIZE" => {
+                    let lut_size = args
+                        .trim()
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|lut_size| (2..=MAX_LUT_SIZE).contains(lut_size))
+                        .ok_or(Error::MalformedLut(line_num))?;
+
+                    size = Some(lut_size);
+                }
+                "DOMAIN_MIN" => {
+                    if parse_triplet(args) != Some([0.0; 3]) {
+                        return Err(Error::MalformedLut(line_num));
+                    }
+                }
+                "DOMAIN_MAX" => {
+                    if parse_triplet(args) != Some([1.0; 3]) {
+                        return Err(Error::MalformedLut(line_num));
+                    }
+                }
+                _ => {
+                    if size.is_none() {
+                        return Err(Error::MalformedLut(line_num));
+                    }
+
+                    let entry = parse_triplet(line).ok_or(Error::MalformedLut(line_num))?;
+                    entries.push(entry);
+                }
+            }
+        }
+
+        let size = size.ok_or(Error::MalformedLut(last_line))?;
+        if entries.len() != size * size * size {
+            return Err(Error::MalformedLut(last_line));
+        }
+
+        Ok(Self { size, entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hw_lut_size, to_u16, Lut3d};
+    use crate::Error;
+
+    const IDENTITY: &str = "# Identity
+TITLE \"Identity\"
+LUT_3D_SIZE 2
+DOMAIN_MIN 0.0 0.0 0.0
+DOMAIN_MAX 1.0 1.0 1.0
+
+0.0 0.0 0.0
+1.0 0.0 0.0
+0.0 1.0 0.0
+1.0 1.0 0.0
+0.0 0.0 1.0
+1.0 0.0 1.0
+0.0 1.0 1.0
+1.0 1.0 1.0
+";
+
+    #[test]
+    fn test_cube_parsing() {
+        let lut: Lut3d = IDENTITY.parse().unwrap();
+
+        assert_eq!(lut.size(), 2);
+        assert_eq!(lut.entry(1, 0, 0).map(to_u16), [u16::MAX, 0, 0]);
+        assert_eq!(lut.entry(0, 0, 1).map(to_u16), [0, 0, u16::MAX]);
+    }
+
+    #[test]
+    fn test_cube_malformed() {
+        assert!(matches!(
+            "LUT_3D_SIZE 2\n0.0 0.0\n".parse::<Lut3d>(),
+            Err(Error::MalformedLut(2))
+        ));
+
+        assert!(matches!(
+            "LUT_3D_SIZE 2\n0.0 0.0 0.0\n".parse::<Lut3d>(),
+            Err(Error::MalformedLut(2))
+        ));
+
+        assert!(matches!(
+            "0.0 0.0 0.0\n".parse::<Lut3d>(),
+            Err(Error::MalformedLut(1))
+        ));
+
+        assert!(matches!(
+            "LUT_3D_SIZE 2\nDOMAIN_MAX 2.0 2.0 2.0\n".parse::<Lut3d>(),
+            Err(Error::MalformedLut(2))
+        ));
+    }
+
+    #[test]
+    fn test_resampling() {
+        let lut: Lut3d = IDENTITY.parse().unwrap();
+        let raw = lut.to_raw(3).unwrap();
+
+        assert_eq!(raw.len(), 27);
+
+        // Blue changes the fastest
+        assert_eq!(raw[1].blue, 32768);
+        assert_eq!(raw[1].red, 0);
+        assert_eq!(raw[9].red, 32768);
+        assert_eq!(raw[26].red, u16::MAX);
+        assert_eq!(raw[26].green, u16::MAX);
+        assert_eq!(raw[26].blue, u16::MAX);
+    }
+
+    #[test]
+    fn test_hw_lut_size() {
+        assert_eq!(hw_lut_size(8).unwrap(), 2);
+        assert_eq!(hw_lut_size(4913).unwrap(), 17);
+        assert_eq!(hw_lut_size(35_937).unwrap(), 33);
+
+        assert!(hw_lut_size(0).is_err());
+        assert!(hw_lut_size(17).is_err());
+        assert!(hw_lut_size(4914).is_err());
+        assert!(hw_lut_size(u64::MAX).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use crate::{
+    surface::{Surface, Target},
+    Connector, Device, FitMode, Framebuffer, Point, Rect, Result, Size,
+};
+
+/// Mirrors a Surface on Several Outputs
+///
+/// The [Mirror] drives every connected [Connector] it can find a [Crtc](crate::Crtc) and a primary
+/// [Plane](crate::Plane) supporting [`XRGB8888`](crate::Format::XRGB8888) for, using their
+/// preferred [Mode](crate::Mode). It then allocates two [Framebuffer]s of the surface size to draw
+/// into in turns, while the other one is on screen.
+///
+/// The surface is scaled by each primary [Plane](crate::Plane) to fit its [Output](crate::Output),
+/// following a [`FitMode`] that can be set for each [Output](crate::Output) independently. All the
+/// [Output](crate::Output)s are updated in a single commit, so they always display the same frame.
+#[derive(Debug)]
+pub struct Mirror {
+    surface: Surface,
+    size: Size,
+    fits: Vec<FitMode>,
+}
+
+impl Mirror {
+    /// Creates a new [Mirror] of a `width` x `height` surface on a [Device]
+    ///
+    /// Each [Output](crate::Output) letterboxes the surface, using [`FitMode::Contain`], by
+    /// default. The [Output](crate::Output)s will only be enabled on the first call to
+    /// [`Mirror::flip`].
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error](crate::Error) if no [Output](crate::Output) can be driven, or if the
+    /// [Framebuffer] allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Mirror};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let mirror = Mirror::new(&device, 1920, 1080).unwrap();
+    /// ```
+    pub fn new(device: &Device, width: usize, height: usize) -> Result<Self> {
+        let mut targets: Vec<Target> = Vec::new();
+
+        for connector in device.connected_connectors() {
+            let used_crtcs: Vec<u32> = targets.iter().filter_map(Target::crtc_id).collect();
+
+            let Ok(output) = device.output_from_connector_excluding(&connector, &used_crtcs) else {
+                continue;
+            };
+
+            let Ok(target) = Target::new(output, &connector) else {
+                continue;
+            };
+
+            targets.push(target);
+        }
+
+        let fits = vec![FitMode::Contain; targets.len()];
+        let size = Size::new(width, height);
+
+        Ok(Self {
+            surface: Surface::new(device, targets, size)?,
+            size,
+            fits,
+        })
+    }
+
+    /// Returns the number of [Output](crate::Output)s the surface is mirrored on
+    #[must_use]
+    pub const fn outputs(&self) -> usize {
+        self.fits.len()
+    }
+
+    /// Returns the [Connector] of the `idx`-th [Output](crate::Output)
+    #[must_use]
+    pub fn connector(&self, idx: usize) -> Option<&Arc<Connector>> {
+        self.surface.targets().get(idx).map(Target::connector)
+    }
+
+    /// Sets how the surface fits on the `idx`-th [Output](crate::Output)
+    ///
+    /// The change will be applied on the next [`Mirror::flip`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, FitMode, Mirror};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let mut mirror = Mirror::new(&device, 1920, 1080).unwrap();
+    ///
+    /// mirror.set_fit(0, FitMode::Stretch);
+    /// ```
+    pub fn set_fit(&mut self, idx: usize, fit: FitMode) {
+        if let Some(target_fit) = self.fits.get_mut(idx) {
+            *target_fit = fit;
+        }
+    }
+
+    /// Draws into the [Framebuffer] that will be displayed on the next [`Mirror::flip`]
+    ///
+    /// The [Framebuffer] has the surface size, whatever the [Mode](crate::Mode) of each
+    /// [Output](crate::Output), and still holds the frame flipped two calls ago.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Mirror};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let mut mirror = Mirror::new(&device, 1920, 1080).unwrap();
+    ///
+    /// mirror.draw(|buffer| buffer.data().fill(0xff));
+    /// ```
+    pub fn draw<F>(&mut self, draw: F)
+    where
+        F: FnOnce(&mut Framebuffer),
+    {
+        self.surface.draw(draw);
+    }
+
+    /// Displays the [Framebuffer] drawn into with [`Mirror::draw`] on every [Output](crate::Output)
+    ///
+    /// The first call will enable the [Output](crate::Output)s. This function will block until the
+    /// new frame is on screen.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error](crate::Error) if the commit fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Mirror};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let mut mirror = Mirror::new(&device, 1920, 1080).unwrap();
+    ///
+    /// for frame in 0..=255 {
+    ///     mirror.draw(|buffer| buffer.data().fill(frame));
+    ///     mirror.flip().unwrap();
+    /// }
+    /// ```
+    pub fn flip(&mut self) -> Result<()> {
+        let size = self.size;
+        let fits = &self.fits;
+
+        self.surface.flip(|idx, target, update| {
+            let mode = target.mode();
+            let screen = Rect::from_parts(Point::default(), Size::new(mode.width(), mode.height()));
+
+            update.fit(fits[idx], size, screen)
+        })
+    }
+}
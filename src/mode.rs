@@ -1,4 +1,24 @@
-use crate::raw::drm_mode_modeinfo;
+use std::{cmp::Reverse, convert::TryFrom, time::Duration};
+
+use num_enum::TryFromPrimitive;
+
+use crate::{
+    cvt::{cvt, ReducedBlanking},
+    modeline::parse_modeline,
+    raw::{
+        drm_mode_modeinfo, DRM_MODE_FLAG_3D_MASK, DRM_MODE_FLAG_DBLSCAN, DRM_MODE_FLAG_INTERLACE,
+        DRM_MODE_FLAG_NHSYNC, DRM_MODE_FLAG_NVSYNC, DRM_MODE_FLAG_PHSYNC,
+        DRM_MODE_FLAG_PIC_AR_MASK, DRM_MODE_FLAG_PVSYNC, DRM_MODE_TYPE_PREFERRED,
+        DRM_MODE_TYPE_USERDEF,
+    },
+    Error, Result,
+};
+
+// NOTE: The kernel mode name is 32 bytes long, including the NUL terminator.
+const MODE_NAME_LEN: usize = 31;
+
+const MODE_FLAG_3D_SHIFT: u32 = 14;
+const MODE_FLAG_PIC_AR_SHIFT: u32 = 19;
 
 #[allow(dead_code)]
 #[derive(Clone, Copy, Debug)]
@@ -12,11 +32,74 @@ pub enum Type {
     Driver,
 }
 
+/// The Polarity of a Sync Pulse
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyncPolarity {
+    /// The pulse is active high
+    Positive,
+
+    /// The pulse is active low
+    Negative,
+}
+
+/// The Stereoscopic 3D Layout of a [Mode]
+///
+/// The stereo [Mode]s are only reported for HDMI sinks supporting them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum Stereo3dLayout {
+    /// The [Mode] isn't a stereo one
+    None,
+
+    /// Both views are sent in a single frame, one above the other with a blanking in between
+    FramePacking,
+
+    /// Both views are sent in alternating fields
+    FieldAlternative,
+
+    /// Both views are sent in alternating lines
+    LineAlternative,
+
+    /// Both views are sent side by side, at their full resolution
+    SideBySideFull,
+
+    /// A 2D view is sent along with its depth
+    LDepth,
+
+    /// A 2D view is sent along with its depth, and a graphics plane along with its depth
+    LDepthGfxGfxDepth,
+
+    /// Both views are sent one above the other, at half the vertical resolution
+    TopAndBottom,
+
+    /// Both views are sent side by side, at half the horizontal resolution
+    SideBySideHalf,
+}
+
+/// The Picture Aspect Ratio Signalled to the Sink for a [Mode]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, TryFromPrimitive)]
+#[repr(u32)]
+pub enum PictureAspectRatio {
+    /// No aspect ratio is signalled
+    None,
+
+    /// The 4:3 Aspect Ratio
+    Ratio4x3,
+
+    /// The 16:9 Aspect Ratio
+    Ratio16x9,
+
+    /// The 64:27 Aspect Ratio
+    Ratio64x27,
+
+    /// The 256:135 Aspect Ratio
+    Ratio256x135,
+}
+
 /// Display Mode
 ///
 /// Contains the set of timings needed for a given display output
-#[derive(Debug)]
-#[allow(dead_code)]
+#[derive(Clone, Debug)]
 pub struct Mode {
     name: String,
     inner: drm_mode_modeinfo,
@@ -32,6 +115,84 @@ impl Mode {
         Self { name, inner: info }
     }
 
+    /// Returns a [`ModeBuilder`] to create a [Mode] from explicit timings
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nucleid::{Mode, SyncPolarity};
+    ///
+    /// let mode = Mode::builder()
+    ///     .set_clock(148_500)
+    ///     .set_horizontal(1920, 2008, 2052, 2200)
+    ///     .set_vertical(1080, 1084, 1089, 1125)
+    ///     .set_sync_polarity(SyncPolarity::Positive, SyncPolarity::Positive)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(mode.name(), "1920x1080");
+    /// assert_eq!(mode.refresh(), 60);
+    /// ```
+    #[must_use]
+    pub fn builder() -> ModeBuilder {
+        ModeBuilder::default()
+    }
+
+    /// Creates a [Mode] following the VESA Coordinated Video Timings
+    ///
+    /// The width is rounded down to a multiple of 8 pixels, except with
+    /// [`ReducedBlanking::V2`]. The refresh rate is in Hertz.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the size or the refresh rate can't be represented.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nucleid::{Mode, ReducedBlanking};
+    ///
+    /// let mode = Mode::cvt(2560, 1440, 60.0, ReducedBlanking::V2).unwrap();
+    /// assert_eq!(mode.width(), 2560);
+    /// assert_eq!(mode.refresh(), 60);
+    /// ```
+    pub fn cvt(
+        width: usize,
+        height: usize,
+        refresh: f64,
+        blanking: ReducedBlanking,
+    ) -> Result<Self> {
+        cvt(width, height, refresh, blanking)
+    }
+
+    /// Creates a [Mode] from an X11 modeline
+    ///
+    /// The `Modeline` keyword and the quotes around the name are optional. The pixel clock is
+    /// in MHz, and the flags can be `+hsync`, `-hsync`, `+vsync`, `-vsync`, `interlace` and
+    /// `doublescan`. The sync pulses are negative if their polarity isn't given.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::MalformedModeline`] if the modeline can't be parsed, or
+    /// [`Error::InvalidMode`] if its timings are inconsistent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nucleid::Mode;
+    ///
+    /// let mode = Mode::from_modeline(
+    ///     "Modeline \"1920x1080\" 148.5 1920 2008 2052 2200 1080 1084 1089 1125 +hsync +vsync",
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(mode.name(), "1920x1080");
+    /// assert_eq!(mode.refresh(), 60);
+    /// ```
+    pub fn from_modeline(line: &str) -> Result<Self> {
+        parse_modeline(line)
+    }
+
     pub(crate) const fn has_type(&self, arg: Type) -> bool {
         let mode_type = self.inner.type_;
 
@@ -52,6 +213,12 @@ impl Mode {
         &self.inner
     }
 
+    /// Returns the [Mode] name
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// Returns the active vertical size in pixels
     ///
     /// # Example
@@ -100,6 +267,126 @@ impl Mode {
         self.inner.vrefresh as usize
     }
 
+    /// Returns the duration of a frame, from one vblank to the next
+    ///
+    /// This is computed from the [Mode] timings, and is thus more accurate than the refresh rate.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let mode = connector.preferred_mode().unwrap();
+    /// let duration = mode.frame_duration();
+    /// ```
+    #[must_use]
+    pub const fn frame_duration(&self) -> Duration {
+        let clock = self.inner.clock as u64;
+        if clock == 0 {
+            return Duration::ZERO;
+        }
+
+        // NOTE: The pixel clock is in kHz
+        let pixels = self.inner.htotal as u64 * self.inner.vtotal as u64;
+
+        Duration::from_nanos(pixels * 1_000_000 / clock)
+    }
+
+    /// Returns whether the [Mode] is the preferred one of the sink
+    #[must_use]
+    pub const fn is_preferred(&self) -> bool {
+        (self.inner.type_ & DRM_MODE_TYPE_PREFERRED) != 0
+    }
+
+    /// Returns whether the [Mode] is interlaced
+    #[must_use]
+    pub const fn is_interlaced(&self) -> bool {
+        (self.inner.flags & DRM_MODE_FLAG_INTERLACE) != 0
+    }
+
+    /// Returns whether each line of the [Mode] is scanned out twice
+    #[must_use]
+    pub const fn is_doublescan(&self) -> bool {
+        (self.inner.flags & DRM_MODE_FLAG_DBLSCAN) != 0
+    }
+
+    /// Returns the [`Stereo3dLayout`] of the [Mode]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device, Stereo3dLayout};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let frame_packing = connector.modes().unwrap()
+    ///     .into_iter()
+    ///     .find(|mode| mode.stereo_layout() == Stereo3dLayout::FramePacking);
+    /// ```
+    #[must_use]
+    pub fn stereo_layout(&self) -> Stereo3dLayout {
+        let layout = (self.inner.flags & DRM_MODE_FLAG_3D_MASK) >> MODE_FLAG_3D_SHIFT;
+
+        Stereo3dLayout::try_from(layout).unwrap_or(Stereo3dLayout::None)
+    }
+
+    /// Returns the [`PictureAspectRatio`] signalled to the sink
+    ///
+    /// Unlike [`Mode::aspect_ratio`], this is the aspect ratio the content is meant to be
+    /// displayed at, which can differ from the one of the active area.
+    #[must_use]
+    pub fn picture_aspect_ratio(&self) -> PictureAspectRatio {
+        let ratio = (self.inner.flags & DRM_MODE_FLAG_PIC_AR_MASK) >> MODE_FLAG_PIC_AR_SHIFT;
+
+        PictureAspectRatio::try_from(ratio).unwrap_or(PictureAspectRatio::None)
+    }
+
+    /// Returns the aspect ratio of the active area, as a reduced fraction
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let widescreen = connector.modes().unwrap()
+    ///     .into_iter()
+    ///     .filter(|mode| mode.aspect_ratio() == (16, 9));
+    /// ```
+    #[must_use]
+    pub const fn aspect_ratio(&self) -> (usize, usize) {
+        let (width, height) = (self.width(), self.height());
+
+        let (mut a, mut b) = (width, height);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+
+        if a == 0 {
+            return (width, height);
+        }
+
+        (width / a, height / a)
+    }
+
     /// Returns the active horizontal size in pixels
     ///
     /// # Example
@@ -124,3 +411,351 @@ impl Mode {
         self.inner.hdisplay as usize
     }
 }
+
+const fn with_flag(flags: u32, flag: u32, set: bool) -> u32 {
+    if set {
+        flags | flag
+    } else {
+        flags & !flag
+    }
+}
+
+/// Builds a [Mode] from Explicit Timings
+///
+/// The timings are given in pixels for the horizontal ones, and in lines for the vertical ones.
+/// The refresh rate is computed from the timings, and the name defaults to the size of the
+/// [Mode].
+#[derive(Clone, Debug, Default)]
+pub struct ModeBuilder {
+    inner: drm_mode_modeinfo,
+    name: Option<String>,
+}
+
+impl ModeBuilder {
+    /// Sets the pixel clock, in kHz
+    #[must_use]
+    pub const fn set_clock(mut self, clock: u32) -> Self {
+        self.inner.clock = clock;
+        self
+    }
+
+    /// Sets the horizontal timings
+    #[must_use]
+    pub const fn set_horizontal(
+        mut self,
+        display: u16,
+        sync_start: u16,
+        sync_end: u16,
+        total: u16,
+    ) -> Self {
+        self.inner.hdisplay = display;
+        self.inner.hsync_start = sync_start;
+        self.inner.hsync_end = sync_end;
+        self.inner.htotal = total;
+        self
+    }
+
+    /// Sets the vertical timings
+    #[must_use]
+    pub const fn set_vertical(
+        mut self,
+        display: u16,
+        sync_start: u16,
+        sync_end: u16,
+        total: u16,
+    ) -> Self {
+        self.inner.vdisplay = display;
+        self.inner.vsync_start = sync_start;
+        self.inner.vsync_end = sync_end;
+        self.inner.vtotal = total;
+        self
+    }
+
+    /// Sets the [`SyncPolarity`] of the horizontal and vertical sync pulses
+    #[must_use]
+    pub const fn set_sync_polarity(mut self, hsync: SyncPolarity, vsync: SyncPolarity) -> Self {
+        let positive = matches!(hsync, SyncPolarity::Positive);
+        let flags = with_flag(self.inner.flags, DRM_MODE_FLAG_PHSYNC, positive);
+        let flags = with_flag(flags, DRM_MODE_FLAG_NHSYNC, !positive);
+
+        let positive = matches!(vsync, SyncPolarity::Positive);
+        let flags = with_flag(flags, DRM_MODE_FLAG_PVSYNC, positive);
+        let flags = with_flag(flags, DRM_MODE_FLAG_NVSYNC, !positive);
+
+        self.inner.flags = flags;
+        self
+    }
+
+    /// Sets whether the [Mode] is interlaced
+    #[must_use]
+    pub const fn set_interlaced(mut self, interlaced: bool) -> Self {
+        self.inner.flags = with_flag(self.inner.flags, DRM_MODE_FLAG_INTERLACE, interlaced);
+        self
+    }
+
+    /// Sets whether each line of the [Mode] is scanned out twice
+    #[must_use]
+    pub const fn set_doublescan(mut self, doublescan: bool) -> Self {
+        self.inner.flags = with_flag(self.inner.flags, DRM_MODE_FLAG_DBLSCAN, doublescan);
+        self
+    }
+
+    /// Sets the [`Stereo3dLayout`] of the [Mode]
+    #[must_use]
+    pub const fn set_stereo_layout(mut self, layout: Stereo3dLayout) -> Self {
+        self.inner.flags =
+            (self.inner.flags & !DRM_MODE_FLAG_3D_MASK) | ((layout as u32) << MODE_FLAG_3D_SHIFT);
+        self
+    }
+
+    /// Sets the [`PictureAspectRatio`] signalled to the sink
+    #[must_use]
+    pub const fn set_picture_aspect_ratio(mut self, ratio: PictureAspectRatio) -> Self {
+        self.inner.flags = (self.inner.flags & !DRM_MODE_FLAG_PIC_AR_MASK)
+            | ((ratio as u32) << MODE_FLAG_PIC_AR_SHIFT);
+        self
+    }
+
+    /// Sets the name of the [Mode]
+    ///
+    /// The name can be 31 bytes long at most.
+    #[must_use]
+    pub fn set_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Creates the [Mode]
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::InvalidMode`] if the pixel clock is null, if the sync pulses aren't
+    /// within the blanking periods, or if the name is too long.
+    pub fn build(self) -> Result<Mode> {
+        let mut info = self.inner;
+
+        if info.clock == 0 {
+            return Err(Error::InvalidMode("Null Pixel Clock"));
+        }
+
+        if info.hdisplay == 0
+            || info.hsync_start < info.hdisplay
+            || info.hsync_end < info.hsync_start
+            || info.htotal < info.hsync_end
+        {
+            return Err(Error::InvalidMode("Invalid Horizontal Timings"));
+        }
+
+        if info.vdisplay == 0
+            || info.vsync_start < info.vdisplay
+            || info.vsync_end < info.vsync_start
+            || info.vtotal < info.vsync_end
+        {
+            return Err(Error::InvalidMode("Invalid Vertical Timings"));
+        }
+
+        let interlaced = (info.flags & DRM_MODE_FLAG_INTERLACE) != 0;
+        let name = self.name.unwrap_or_else(|| {
+            format!(
+                "{}x{}{}",
+                info.hdisplay,
+                info.vdisplay,
+                if interlaced { "i" } else { "" }
+            )
+        });
+
+        if name.len() > MODE_NAME_LEN {
+            return Err(Error::InvalidMode("Name Too Long"));
+        }
+        info.name[..name.len()].copy_from_slice(name.as_bytes());
+
+        // NOTE: Each frame of an interlaced mode holds two fields, and each line of a
+        // doublescan mode is sent twice.
+        let mut pixels = u64::from(info.htotal) * u64::from(info.vtotal);
+        if (info.flags & DRM_MODE_FLAG_DBLSCAN) != 0 {
+            pixels *= 2;
+        }
+
+        let mut rate = u64::from(info.clock) * 1000;
+        if interlaced {
+            rate *= 2;
+        }
+
+        info.vrefresh = u32::try_from((rate + pixels / 2) / pixels)?;
+        info.type_ = DRM_MODE_TYPE_USERDEF;
+
+        Ok(Mode::new(info))
+    }
+}
+
+/// Sorts the [Mode]s by preference
+///
+/// The preferred [Mode] comes first, followed by the others from the largest to the smallest,
+/// and from the highest refresh rate to the lowest. Progressive [Mode]s come before interlaced
+/// ones, and the stereo [Mode]s come last.
+pub fn sort_modes(modes: &mut [Mode]) {
+    modes.sort_by_key(|mode| {
+        (
+            !mode.is_preferred(),
+            mode.stereo_layout() != Stereo3dLayout::None,
+            Reverse(mode.width() * mode.height()),
+            Reverse(mode.refresh()),
+            mode.is_interlaced(),
+        )
+    });
+}
+
+/// Returns the [Mode] the closest to the given size and refresh rate
+///
+/// [Mode]s larger than `width` x `height` are never picked. Among the others, the largest
+/// [Mode] is picked, with the refresh rate the closest to `refresh`, and the progressive and
+/// preferred [Mode]s win the ties. Stereo [Mode]s are never picked.
+pub fn best_mode(modes: &[Mode], width: usize, height: usize, refresh: usize) -> Option<&Mode> {
+    modes
+        .iter()
+        .filter(|mode| mode.stereo_layout() == Stereo3dLayout::None)
+        .filter(|mode| mode.width() <= width && mode.height() <= height)
+        .max_by_key(|mode| {
+            (
+                mode.width() * mode.height(),
+                Reverse(mode.refresh().abs_diff(refresh)),
+                !mode.is_interlaced(),
+                mode.is_preferred(),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_mode, sort_modes, Mode, PictureAspectRatio, Stereo3dLayout, SyncPolarity};
+    use crate::raw::{
+        drm_mode_modeinfo, DRM_MODE_FLAG_INTERLACE, DRM_MODE_FLAG_NHSYNC, DRM_MODE_FLAG_PVSYNC,
+        DRM_MODE_TYPE_PREFERRED,
+    };
+
+    fn mode(width: u16, height: u16, refresh: u32, flags: u32, type_: u32) -> Mode {
+        Mode::new(drm_mode_modeinfo {
+            hdisplay: width,
+            vdisplay: height,
+            vrefresh: refresh,
+            flags,
+            type_,
+            ..drm_mode_modeinfo::default()
+        })
+    }
+
+    fn sizes(modes: &[Mode]) -> Vec<(usize, usize, usize)> {
+        modes
+            .iter()
+            .map(|mode| (mode.width(), mode.height(), mode.refresh()))
+            .collect()
+    }
+
+    #[test]
+    fn test_builder() {
+        let mode = Mode::builder()
+            .set_clock(74_250)
+            .set_horizontal(1920, 2008, 2052, 2200)
+            .set_vertical(1080, 1084, 1094, 1125)
+            .set_sync_polarity(SyncPolarity::Negative, SyncPolarity::Positive)
+            .set_interlaced(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(mode.name(), "1920x1080i");
+        assert_eq!(mode.refresh(), 60);
+        assert!(mode.is_interlaced());
+        assert_eq!(
+            mode.inner().flags,
+            DRM_MODE_FLAG_NHSYNC | DRM_MODE_FLAG_PVSYNC | DRM_MODE_FLAG_INTERLACE
+        );
+
+        assert!(Mode::builder()
+            .set_clock(74_250)
+            .set_horizontal(1920, 1900, 2052, 2200)
+            .set_vertical(1080, 1084, 1094, 1125)
+            .build()
+            .is_err());
+
+        assert!(Mode::builder()
+            .set_horizontal(1920, 2008, 2052, 2200)
+            .set_vertical(1080, 1084, 1094, 1125)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_stereo_and_picture_aspect_ratio() {
+        let stereo = Mode::builder()
+            .set_clock(148_500)
+            .set_horizontal(1920, 2008, 2052, 2200)
+            .set_vertical(1080, 1084, 1089, 1125)
+            .set_stereo_layout(Stereo3dLayout::TopAndBottom)
+            .set_picture_aspect_ratio(PictureAspectRatio::Ratio16x9)
+            .build()
+            .unwrap();
+
+        assert_eq!(stereo.inner().flags, (7 << 14) | (2 << 19));
+        assert_eq!(stereo.stereo_layout(), Stereo3dLayout::TopAndBottom);
+        assert_eq!(stereo.picture_aspect_ratio(), PictureAspectRatio::Ratio16x9);
+
+        let plain = mode(1920, 1080, 60, 0, 0);
+        assert_eq!(plain.stereo_layout(), Stereo3dLayout::None);
+        assert_eq!(plain.picture_aspect_ratio(), PictureAspectRatio::None);
+    }
+
+    #[test]
+    fn test_aspect_ratio() {
+        assert_eq!(mode(1920, 1080, 60, 0, 0).aspect_ratio(), (16, 9));
+        assert_eq!(mode(1024, 768, 60, 0, 0).aspect_ratio(), (4, 3));
+        assert_eq!(mode(0, 0, 0, 0, 0).aspect_ratio(), (0, 0));
+    }
+
+    #[test]
+    fn test_sort_modes() {
+        let mut modes = vec![
+            mode(1280, 720, 60, 0, 0),
+            mode(1920, 1080, 60, DRM_MODE_FLAG_INTERLACE, 0),
+            mode(1920, 1080, 60, 0, 0),
+            mode(1920, 1080, 75, 0, 0),
+            mode(1280, 1024, 60, 0, DRM_MODE_TYPE_PREFERRED),
+        ];
+
+        sort_modes(&mut modes);
+        assert_eq!(
+            sizes(&modes),
+            vec![
+                (1280, 1024, 60),
+                (1920, 1080, 75),
+                (1920, 1080, 60),
+                (1920, 1080, 60),
+                (1280, 720, 60),
+            ]
+        );
+        assert!(!modes[2].is_interlaced());
+        assert!(modes[3].is_interlaced());
+    }
+
+    #[test]
+    fn test_best_mode() {
+        let modes = vec![
+            mode(1920, 1080, 60, DRM_MODE_FLAG_INTERLACE, 0),
+            mode(1920, 1080, 50, 0, 0),
+            mode(1920, 1080, 60, 0, 0),
+            mode(1280, 720, 60, 0, 0),
+            mode(3840, 2160, 30, 0, DRM_MODE_TYPE_PREFERRED),
+            mode(3840, 2160, 60, 1 << 14, DRM_MODE_TYPE_PREFERRED),
+        ];
+
+        let best = best_mode(&modes, 1920, 1080, 60).unwrap();
+        assert_eq!((best.refresh(), best.is_interlaced()), (60, false));
+
+        let best = best_mode(&modes, 1600, 900, 60).unwrap();
+        assert_eq!(best.width(), 1280);
+
+        let best = best_mode(&modes, 4096, 2160, 60).unwrap();
+        assert_eq!(best.width(), 3840);
+        assert_eq!(best.stereo_layout(), Stereo3dLayout::None);
+        assert!(best_mode(&modes, 640, 480, 60).is_none());
+    }
+}
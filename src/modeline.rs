@@ -0,0 +1,157 @@
+use crate::{Error, Mode, Result, SyncPolarity};
+
+/// Splits the name of the modeline from the rest of the line
+///
+/// The name is usually quoted, but xrandr and some datasheets omit the quotes.
+fn split_name(line: &str) -> Option<(&str, &str)> {
+    if let Some(line) = line.strip_prefix('"') {
+        let end = line.find('"')?;
+
+        Some((&line[..end], &line[end + 1..]))
+    } else {
+        let end = line.find(char::is_whitespace).unwrap_or(line.len());
+
+        Some((&line[..end], &line[end..]))
+    }
+}
+
+// NOTE: The pixel clock is checked to be positive and to fit in a u32 before the conversion.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn parse_clock(s: &str) -> Option<u32> {
+    let clock = (s.parse::<f64>().ok()? * 1000.0).round();
+    if !clock.is_finite() || clock <= 0.0 || clock > f64::from(u32::MAX) {
+        return None;
+    }
+
+    Some(clock as u32)
+}
+
+fn parse_timings<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Option<[u16; 4]> {
+    let mut timings = [0; 4];
+
+    for timing in &mut timings {
+        *timing = tokens.next()?.parse().ok()?;
+    }
+
+    Some(timings)
+}
+
+/// Parses an X11 modeline into a [Mode]
+pub fn parse_modeline(line: &str) -> Result<Mode> {
+    let line = line.trim();
+    let line = match line.split_once(char::is_whitespace) {
+        Some((keyword, rest)) if keyword.eq_ignore_ascii_case("modeline") => rest.trim_start(),
+        _ => line,
+    };
+
+    let (name, line) = split_name(line).ok_or(Error::MalformedModeline)?;
+    let mut tokens = line.split_whitespace();
+
+    let clock = tokens
+        .next()
+        .and_then(parse_clock)
+        .ok_or(Error::MalformedModeline)?;
+    let [hdisplay, hsync_start, hsync_end, htotal] =
+        parse_timings(&mut tokens).ok_or(Error::MalformedModeline)?;
+    let [vdisplay, vsync_start, vsync_end, vtotal] =
+        parse_timings(&mut tokens).ok_or(Error::MalformedModeline)?;
+
+    let mut builder = Mode::builder()
+        .set_clock(clock)
+        .set_horizontal(hdisplay, hsync_start, hsync_end, htotal)
+        .set_vertical(vdisplay, vsync_start, vsync_end, vtotal);
+
+    if !name.is_empty() {
+        builder = builder.set_name(name);
+    }
+
+    // NOTE: X defaults to negative sync pulses when the polarity isn't specified.
+    let mut hsync = SyncPolarity::Negative;
+    let mut vsync = SyncPolarity::Negative;
+
+    for flag in tokens {
+        match flag.to_ascii_lowercase().as_str() {
+            "+hsync" => hsync = SyncPolarity::Positive,
+            "-hsync" => hsync = SyncPolarity::Negative,
+            "+vsync" => vsync = SyncPolarity::Positive,
+            "-vsync" => vsync = SyncPolarity::Negative,
+            "interlace" => builder = builder.set_interlaced(true),
+            "doublescan" => builder = builder.set_doublescan(true),
+            _ => return Err(Error::MalformedModeline),
+        }
+    }
+
+    builder.set_sync_polarity(hsync, vsync).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_modeline;
+    use crate::raw::{
+        DRM_MODE_FLAG_DBLSCAN, DRM_MODE_FLAG_INTERLACE, DRM_MODE_FLAG_NHSYNC, DRM_MODE_FLAG_NVSYNC,
+        DRM_MODE_FLAG_PHSYNC, DRM_MODE_FLAG_PVSYNC,
+    };
+
+    #[test]
+    fn test_parse_modeline() {
+        let mode = parse_modeline(
+            "Modeline \"1920x1080\" 148.5 1920 2008 2052 2200 1080 1084 1089 1125 +hsync +vsync",
+        )
+        .unwrap();
+
+        let info = mode.inner();
+        assert_eq!(mode.name(), "1920x1080");
+        assert_eq!(info.clock, 148_500);
+        assert_eq!(
+            [info.hdisplay, info.hsync_start, info.hsync_end, info.htotal],
+            [1920, 2008, 2052, 2200]
+        );
+        assert_eq!(
+            [info.vdisplay, info.vsync_start, info.vsync_end, info.vtotal],
+            [1080, 1084, 1089, 1125]
+        );
+        assert_eq!(info.flags, DRM_MODE_FLAG_PHSYNC | DRM_MODE_FLAG_PVSYNC);
+        assert_eq!(mode.refresh(), 60);
+    }
+
+    #[test]
+    fn test_parse_modeline_flags() {
+        let mode = parse_modeline(
+            "\"1920x1080 interlaced\" 74.25 1920 2008 2052 2200 1080 1084 1094 1125 Interlace",
+        )
+        .unwrap();
+        assert_eq!(mode.name(), "1920x1080 interlaced");
+        assert_eq!(
+            mode.inner().flags,
+            DRM_MODE_FLAG_NHSYNC | DRM_MODE_FLAG_NVSYNC | DRM_MODE_FLAG_INTERLACE
+        );
+
+        let mode = parse_modeline(
+            "320x240 12.59 320 336 384 400 240 245 246 262 doublescan -hsync +vsync",
+        )
+        .unwrap();
+        assert_eq!(mode.name(), "320x240");
+        assert_eq!(
+            mode.inner().flags,
+            DRM_MODE_FLAG_NHSYNC | DRM_MODE_FLAG_PVSYNC | DRM_MODE_FLAG_DBLSCAN
+        );
+        assert_eq!(mode.refresh(), 60);
+    }
+
+    #[test]
+    fn test_parse_modeline_malformed() {
+        assert!(parse_modeline("").is_err());
+        assert!(parse_modeline("Modeline \"1920x1080 148.5 1920 2008 2052 2200").is_err());
+        assert!(parse_modeline("\"1920x1080\" 148.5 1920 2008 2052 2200 1080 1084 1089").is_err());
+        assert!(
+            parse_modeline("\"1920x1080\" -1 1920 2008 2052 2200 1080 1084 1089 1125").is_err()
+        );
+        assert!(parse_modeline(
+            "\"1920x1080\" 148.5 1920 2008 2052 2200 1080 1084 1089 1125 +csync"
+        )
+        .is_err());
+        assert!(
+            parse_modeline("\"1920x1080\" 148.5 2200 2008 2052 1920 1080 1084 1089 1125").is_err()
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use std::convert::TryFrom;
+
+use crate::{dmabuf::DRM_FORMAT_MOD_LINEAR, Error, Result};
+
+const DRM_FORMAT_MOD_INVALID: u64 = 0x00ff_ffff_ffff_ffff;
+
+// NOTE: The sizes of struct drm_format_modifier_blob and struct drm_format_modifier
+const IN_FORMATS_HEADER_SIZE: usize = 24;
+const IN_FORMATS_MODIFIER_SIZE: usize = 24;
+
+/// A DRM Format Modifier
+///
+/// A modifier describes the memory layout of a buffer beyond its [Format](crate::Format), like
+/// its tiling or compression. The 8 most significant bits hold the vendor of the layout.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Modifier(u64);
+
+impl Modifier {
+    /// The linear layout, where the pixels are stored line by line
+    pub const LINEAR: Self = Self(DRM_FORMAT_MOD_LINEAR);
+
+    /// An invalid modifier, used when the layout is implicit and driver-specific
+    pub const INVALID: Self = Self(DRM_FORMAT_MOD_INVALID);
+
+    /// Creates a new [Modifier] from its raw value
+    #[must_use]
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw value of the [Modifier]
+    #[must_use]
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the vendor code of the [Modifier]
+    #[must_use]
+    pub const fn vendor(self) -> u8 {
+        self.0.to_be_bytes()[0]
+    }
+}
+
+impl From<u64> for Modifier {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Modifier> for u64 {
+    fn from(modifier: Modifier) -> Self {
+        modifier.0
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(data.get(offset..offset + 4).ok_or(Error::MalformedBlob)?);
+
+    Ok(u32::from_ne_bytes(bytes))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(data.get(offset..offset + 8).ok_or(Error::MalformedBlob)?);
+
+    Ok(u64::from_ne_bytes(bytes))
+}
+
+/// Parses an `IN_FORMATS` blob into the list of formats and their supported [Modifier]s
+///
+/// The blob holds the list of formats, and the list of modifiers. Each modifier comes with a
+/// bitmask of the formats it supports, relative to an offset in the formats list so that the
+/// mask can hold 64 formats only.
+pub fn parse_in_formats(data: &[u8]) -> Result<Vec<(u32, Vec<Modifier>)>> {
+    let count_formats = usize::try_from(read_u32(data, 8)?)?;
+    let formats_offset = usize::try_from(read_u32(data, 12)?)?;
+    let count_modifiers = usize::try_from(read_u32(data, 16)?)?;
+    let modifiers_offset = usize::try_from(read_u32(data, 20)?)?;
+
+    if formats_offset < IN_FORMATS_HEADER_SIZE || modifiers_offset < IN_FORMATS_HEADER_SIZE {
+        return Err(Error::MalformedBlob);
+    }
+
+    let mut formats = (0..count_formats)
+        .map(|idx| Ok((read_u32(data, formats_offset + idx * 4)?, Vec::new())))
+        .collect::<Result<Vec<_>>>()?;
+
+    for idx in 0..count_modifiers {
+        let offset = modifiers_offset + idx * IN_FORMATS_MODIFIER_SIZE;
+        let mask = read_u64(data, offset)?;
+        let first = usize::try_from(read_u32(data, offset + 8)?)?;
+        let modifier = Modifier(read_u64(data, offset + 16)?);
+
+        for bit in (0..64).filter(|bit| (mask & (1 << bit)) != 0) {
+            let (_, modifiers) = formats.get_mut(first + bit).ok_or(Error::MalformedBlob)?;
+
+            modifiers.push(modifier);
+        }
+    }
+
+    Ok(formats)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::{parse_in_formats, Modifier};
+    use crate::{Error, Format};
+
+    fn in_formats(formats: &[u32], modifiers: &[(u64, u32, u64)]) -> Vec<u8> {
+        let formats_offset = 24u32;
+        let modifiers_offset = formats_offset + 4 * u32::try_from(formats.len()).unwrap();
+
+        let mut blob = Vec::new();
+        blob.extend(1u32.to_ne_bytes());
+        blob.extend(0u32.to_ne_bytes());
+        blob.extend(u32::try_from(formats.len()).unwrap().to_ne_bytes());
+        blob.extend(formats_offset.to_ne_bytes());
+        blob.extend(u32::try_from(modifiers.len()).unwrap().to_ne_bytes());
+        blob.extend(modifiers_offset.to_ne_bytes());
+
+        for format in formats {
+            blob.extend(format.to_ne_bytes());
+        }
+
+        for &(mask, offset, modifier) in modifiers {
+            blob.extend(mask.to_ne_bytes());
+            blob.extend(offset.to_ne_bytes());
+            blob.extend(0u32.to_ne_bytes());
+            blob.extend(modifier.to_ne_bytes());
+        }
+
+        blob
+    }
+
+    #[test]
+    fn test_parse_in_formats() {
+        let x_tiled = (1 << 56) | 1;
+        let blob = in_formats(
+            &[Format::XRGB8888 as u32, Format::ARGB8888 as u32],
+            &[(0b11, 0, 0), (0b01, 0, x_tiled)],
+        );
+
+        let formats = parse_in_formats(&blob).unwrap();
+        assert_eq!(formats.len(), 2);
+        assert_eq!(formats[0].0, Format::XRGB8888 as u32);
+        assert_eq!(formats[0].1, vec![Modifier::LINEAR, Modifier::new(x_tiled)]);
+        assert_eq!(formats[1].1, vec![Modifier::LINEAR]);
+        assert_eq!(Modifier::new(x_tiled).vendor(), 1);
+    }
+
+    #[test]
+    fn test_parse_in_formats_malformed() {
+        let blob = in_formats(&[Format::XRGB8888 as u32], &[(0b10, 0, 0)]);
+        assert!(matches!(parse_in_formats(&blob), Err(Error::MalformedBlob)));
+
+        assert!(matches!(
+            parse_in_formats(&[0; 8]),
+            Err(Error::MalformedBlob)
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+use std::{fs::OpenOptions, path::Path};
+
+use crate::{
+    raw::{drm_get_unique, drm_get_version},
+    Result,
+};
+
+pub const DRI_PATH: &str = "/dev/dri";
+
+/// The Type of a DRM Device Node
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub enum NodeType {
+    /// A primary node, named `cardN`, giving access to the KMS API
+    Primary,
+
+    /// A render node, named `renderDN`, only giving access to the buffer allocation and
+    /// rendering APIs
+    Render,
+}
+
+impl NodeType {
+    /// Returns the [`NodeType`] and the index of a node from its file name
+    fn from_name(name: &str) -> Option<(Self, u32)> {
+        if let Some(idx) = name.strip_prefix("card") {
+            return Some((Self::Primary, idx.parse().ok()?));
+        }
+
+        if let Some(idx) = name.strip_prefix("renderD") {
+            return Some((Self::Render, idx.parse().ok()?));
+        }
+
+        None
+    }
+}
+
+/// Describes a DRM Device Node Found on the System
+///
+/// The [`DeviceInfo`]s are returned by [`Device::enumerate`](crate::Device::enumerate), and the
+/// primary nodes can then be opened with [`Device::new`](crate::Device::new).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeviceInfo {
+    path: String,
+    node_type: NodeType,
+    driver: String,
+    bus_info: Option<String>,
+}
+
+impl DeviceInfo {
+    fn probe(path: &Path, node_type: NodeType) -> Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+
+        Ok(Self {
+            path: path.to_string_lossy().into_owned(),
+            node_type,
+            driver: drm_get_version(&file)?.1,
+            // NOTE: The render nodes aren't allowed to retrieve the bus identifier.
+            bus_info: drm_get_unique(&file)
+                .ok()
+                .filter(|unique| !unique.is_empty()),
+        })
+    }
+
+    /// Returns the path of the node
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the [`NodeType`] of the node
+    #[must_use]
+    pub const fn node_type(&self) -> NodeType {
+        self.node_type
+    }
+
+    /// Returns the name of the kernel driver behind the node, like `i915` or `vc4`
+    #[must_use]
+    pub fn driver(&self) -> &str {
+        &self.driver
+    }
+
+    /// Returns the identifier of the device on its bus, like `0000:00:02.0` for a PCI device
+    ///
+    /// It's only available for the primary nodes.
+    #[must_use]
+    pub fn bus_info(&self) -> Option<&str> {
+        self.bus_info.as_deref()
+    }
+}
+
+/// Lists the DRM nodes found in `dir`, primary nodes first, sorted by index
+///
+/// The nodes that can't be opened, usually for lack of permission, are skipped.
+pub fn enumerate(dir: &str) -> Result<Vec<DeviceInfo>> {
+    let mut nodes = Vec::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+
+        let Some((node_type, idx)) = name.to_str().and_then(NodeType::from_name) else {
+            continue;
+        };
+
+        if let Ok(info) = DeviceInfo::probe(&entry.path(), node_type) {
+            nodes.push((node_type, idx, info));
+        }
+    }
+
+    nodes.sort_by_key(|&(node_type, idx, _)| (node_type, idx));
+
+    Ok(nodes.into_iter().map(|(_, _, info)| info).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NodeType;
+
+    #[test]
+    fn test_node_name() {
+        assert_eq!(NodeType::from_name("card0"), Some((NodeType::Primary, 0)));
+        assert_eq!(NodeType::from_name("card12"), Some((NodeType::Primary, 12)));
+        assert_eq!(
+            NodeType::from_name("renderD128"),
+            Some((NodeType::Render, 128))
+        );
+        assert_eq!(NodeType::from_name("by-path"), None);
+        assert_eq!(NodeType::from_name("card0-HDMI-A-1"), None);
+    }
+}
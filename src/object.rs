@@ -1,4 +1,4 @@
-use crate::{raw::drm_mode_get_properties, Device, Property, Result};
+use crate::{raw::drm_mode_get_properties, Device, Error, Properties, Property, Result};
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -20,31 +20,54 @@ pub trait Object {
     fn object_id(&self) -> u32;
     fn object_type(&self) -> Type;
 
-    fn properties(&self) -> Result<Vec<Property>> {
+    fn properties(&self) -> Result<Properties> {
         let dev = self.device()?;
         let object_id = self.object_id();
 
-        let properties = drm_mode_get_properties(&dev, self.object_type() as u32, object_id)?;
-
-        let mut ret = Vec::new();
-        for (prop_id, prop_value) in properties {
-            let property = Property::new(&dev, object_id, prop_id, prop_value)?;
-
-            ret.push(property);
-        }
-
-        Ok(ret)
+        drm_mode_get_properties(&dev, self.object_type() as u32, object_id)?
+            .into_iter()
+            .map(|(prop_id, prop_value)| Property::new(&dev, object_id, prop_id, prop_value))
+            .collect()
     }
 
     fn property_id(&self, property: &str) -> Option<u32> {
-        self.properties().map_or(None, |properties| {
-            properties.into_iter().find_map(|prop| {
-                if prop.name() == property {
-                    Some(prop.id())
-                } else {
-                    None
-                }
+        self.device()
+            .ok()?
+            .property_id(self.object_id(), property)
+            .ok()
+            .flatten()
+    }
+
+    fn required_property_id(&self, property: &str) -> Result<u32> {
+        self.property_id(property)
+            .ok_or_else(|| Error::PropertyNotFound {
+                object: self.object_id(),
+                name: property.to_string(),
             })
-        })
     }
 }
+
+/// Implements [`PartialEq`], [`Eq`] and [`Hash`](std::hash::Hash) for a KMS object
+///
+/// Two objects are equal if they have the same ID and belong to the same [Device], so the
+/// object needs to have `dev` and `id` fields.
+macro_rules! impl_object_identity {
+    ($type:ty) => {
+        impl PartialEq for $type {
+            fn eq(&self, other: &Self) -> bool {
+                std::sync::Weak::ptr_eq(&self.dev, &other.dev) && self.id == other.id
+            }
+        }
+
+        impl Eq for $type {}
+
+        impl std::hash::Hash for $type {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                std::ptr::hash(self.dev.as_ptr(), state);
+                self.id.hash(state);
+            }
+        }
+    };
+}
+
+pub(crate) use impl_object_identity;
@@ -1,39 +1,73 @@
 use std::{
-    cell::RefCell,
     collections::HashMap,
-    rc::{Rc, Weak},
+    convert::TryFrom,
+    os::unix::io::{FromRawFd, OwnedFd},
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Arc, Weak,
+    },
+    time::Duration,
 };
 
-use fixed::types::U16F16;
-
 use crate::{
-    buffer::Framebuffer, device::Inner, encoder::Encoder, object::Object,
-    raw::drm_mode_atomic_commit, raw::drm_mode_create_property_blob, Connector, Crtc, Device,
-    Error, Mode, Plane, Result,
+    assign::{assign_layers, LayerAssignment, LayerRequest},
+    blob::as_bytes,
+    buffer::{AdoptedFramebuffer, Buffer, ForeignFramebuffer, Framebuffer},
+    color::{gamma_lut, gamma_ramp, resample_lut, white_point},
+    damage::mode_rects,
+    device::Inner,
+    encoder::Encoder,
+    lut::hw_lut_size,
+    object::Object,
+    raw::drm_mode_atomic_commit,
+    raw::drm_mode_get_crtc,
+    raw::drm_mode_set_gamma,
+    raw::{drm_crtc_get_sequence, drm_crtc_queue_sequence},
+    raw::{drm_mode_cursor, drm_mode_cursor2, DRM_MODE_CURSOR_BO, DRM_MODE_CURSOR_MOVE},
+    raw::{
+        DRM_CRTC_SEQUENCE_NEXT_ON_MISS, DRM_MODE_ATOMIC_ALLOW_MODESET, DRM_MODE_ATOMIC_TEST_ONLY,
+    },
+    record::RecordedCommit,
+    trace::CommitTrace,
+    Blob, BlobData, ChromaSiting, ColorEncoding, ColorRange, CommitFlags, CommitHandle, Connector,
+    ContentProtection, Crtc, Damage, Device, DpmsMode, Error, Event, FitMode, FixedPoint,
+    FixedRect, FixedSize, FrameStats, HdcpContentType, Lut3d, LutEntry, Matrix3x3, Mode,
+    PixelBlendMode, Plane, PlaneType, Point, Property, RecordedProperty, Rect, Reflection, Result,
+    Rotation, Size, Vblank,
 };
 
+#[cfg(feature = "legacy")]
+use crate::legacy::{commit as legacy_commit, power_off as legacy_power_off};
+
+// NOTE: The fence properties are only valid for the commit they're part of, so they're never
+// kept in the committed state.
+const FENCE_PROPERTIES: &[&str] = &["IN_FENCE_FD", "OUT_FENCE_PTR"];
+
+const MAX_BPC: &str = "max bpc";
+
 /// Display Pipeline Output Abstraction
 #[derive(Debug)]
-#[allow(dead_code)]
 pub struct Output {
-    dev: Weak<RefCell<Inner>>,
-    connector: Rc<Connector>,
-    crtc: Rc<Crtc>,
-    encoder: Rc<Encoder>,
+    dev: Weak<Inner>,
+    connector: Arc<Connector>,
+    crtc: Arc<Crtc>,
+    encoder: Arc<Encoder>,
+    out_fence: Option<OwnedFd>,
 }
 
 impl Output {
     pub(crate) fn new(
         device: &Device,
-        crtc: &Rc<Crtc>,
-        encoder: &Rc<Encoder>,
-        connector: &Rc<Connector>,
+        crtc: &Arc<Crtc>,
+        encoder: &Arc<Encoder>,
+        connector: &Arc<Connector>,
     ) -> Self {
         Self {
-            dev: Rc::downgrade(&device.inner),
-            connector: Rc::clone(connector),
-            crtc: Rc::clone(crtc),
-            encoder: Rc::clone(encoder),
+            dev: Arc::downgrade(&device.inner),
+            connector: Arc::clone(connector),
+            crtc: Arc::clone(crtc),
+            encoder: Arc::clone(encoder),
+            out_fence: None,
         }
     }
 
@@ -55,20 +89,38 @@ impl Output {
     /// let crtc = output.crtc();
     /// ```
     #[must_use]
-    pub fn crtc(self) -> Rc<Crtc> {
-        Rc::clone(&self.crtc)
+    pub fn crtc(&self) -> Arc<Crtc> {
+        Arc::clone(&self.crtc)
     }
 
-    /// Returns an iterator over the [Plane]s available
+    /// Returns the [Connector] the [Output] drives
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
-    /// # Panics
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
     ///
-    /// If the back-pointer to the DRM device isn't valid anymore.
+    /// let output = device.output_from_connector(&connector).unwrap();
+    /// assert_eq!(output.connector(), connector);
+    /// ```
+    #[must_use]
+    pub fn connector(&self) -> Arc<Connector> {
+        Arc::clone(&self.connector)
+    }
+
+    /// Returns the backing [Encoder]
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, Device, Format};
+    /// use nucleid::{ConnectorStatus, Device};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -78,31 +130,69 @@ impl Output {
     ///     .unwrap();
     ///
     /// let output = device.output_from_connector(&connector).unwrap();
-    /// let plane = output
-    ///     .planes()
+    /// let encoder_type = output.encoder().encoder_type();
+    /// ```
+    #[must_use]
+    pub fn encoder(&self) -> Arc<Encoder> {
+        Arc::clone(&self.encoder)
+    }
+
+    /// Returns the ID of the [Output] CRTC
+    ///
+    /// This allows to match the [`FlipEvent`](crate::FlipEvent)s with their [Output].
+    #[must_use]
+    pub fn crtc_id(&self) -> u32 {
+        self.crtc.object_id()
+    }
+
+    #[cfg_attr(not(feature = "raw-window-handle"), allow(dead_code))]
+    pub(crate) fn device(&self) -> Result<Device> {
+        Ok(self.dev.upgrade().ok_or(Error::DeviceGone)?.into())
+    }
+
+    #[cfg(feature = "legacy")]
+    pub(crate) fn connector_id(&self) -> u32 {
+        self.connector.object_id()
+    }
+
+    /// Returns the [Mode] currently set on the [Output], if any
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
     ///     .into_iter()
-    ///     .find(|plane| {
-    ///         plane
-    ///             .formats()
-    ///             .find(|fmt| *fmt == Format::XRGB8888)
-    ///             .is_some()
-    ///     })
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
     ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    /// let mode = output.current_mode().unwrap();
     /// ```
-    #[must_use]
-    pub fn planes(&self) -> Planes {
-        let device: Device = self.dev.upgrade().ok_or(Error::Empty).unwrap().into();
-        let crtc_idx = self.crtc.index();
+    pub fn current_mode(&self) -> Result<Option<Mode>> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+        let crtc = drm_mode_get_crtc(&device, self.crtc.object_id())?;
 
-        let planes = device
-            .planes()
-            .filter(|plane| (((1 << crtc_idx) & plane.possible_crtcs()) != 0))
-            .collect();
+        if crtc.mode_valid == 0 {
+            return Ok(None);
+        }
 
-        Planes(planes)
+        Ok(Some(Mode::new(crtc.mode)))
     }
 
-    /// Starts an [Update] of the current [Output]
+    /// Returns the last [Vblank] of the [Output]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the [Output] is disabled or if
+    /// the ioctl fails.
     ///
     /// # Example
     ///
@@ -116,48 +206,2867 @@ impl Output {
     ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
     ///     .unwrap();
     ///
-    /// let output = device.output_from_connector(&connector).unwrap();
-    /// let output = output.start_update().commit().unwrap();
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    /// let vblank = output.vblank().unwrap();
     /// ```
-    #[must_use]
-    pub const fn start_update(self) -> Update {
-        Update {
-            mode: None,
-            output: self,
-            connector: None,
-            planes: Vec::new(),
+    pub fn vblank(&self) -> Result<Vblank> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+        let seq = drm_crtc_get_sequence(&device, self.crtc.object_id())?;
+
+        if seq.active == 0 {
+            return Err(Error::InactiveOutput);
         }
+
+        Ok(Vblank::new(
+            seq.sequence,
+            Duration::from_nanos(u64::try_from(seq.sequence_ns)?),
+        ))
     }
-}
 
-#[derive(Debug)]
-pub struct Planes(Vec<Rc<Plane>>);
+    /// Returns the [`FrameStats`] of the [Output]
+    ///
+    /// The blocking commits are accounted for once they return, and the commits sending a flip
+    /// event once it has been received, through [`Device::wait_for_flip`] or
+    /// [`Device::read_events`]. The non-blocking commits without a flip event are never
+    /// accounted for.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    ///
+    /// let stats = output.frame_stats().unwrap();
+    /// println!("{} frames skipped", stats.skipped());
+    /// ```
+    pub fn frame_stats(&self) -> Result<FrameStats> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
 
-impl IntoIterator for Planes {
-    type Item = Rc<Plane>;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
+        Ok(device.frame_stats(self.crtc.object_id()))
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+    /// Resets the [`FrameStats`] of the [Output]
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed.
+    pub fn reset_frame_stats(&self) -> Result<()> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        device.reset_frame_stats(self.crtc.object_id());
+        Ok(())
     }
-}
 
-/// [Output] state modification abstraction
-#[derive(Debug)]
-pub struct Update {
-    mode: Option<Mode>,
-    output: Output,
-    connector: Option<ConnectorUpdate>,
-    planes: Vec<PlaneUpdate>,
-}
+    /// Sets the color temperature of the [Output]
+    ///
+    /// This shifts the white point of the [Output] to the given temperature, in Kelvin, by
+    /// scaling each color channel of the gamma ramp. 6500K is neutral, lower temperatures will
+    /// give a warmer, more red, image and higher temperatures a cooler, more blue, image.
+    ///
+    /// The `GAMMA_LUT` [Crtc] property will be used if the driver supports it, and the legacy
+    /// gamma ramp otherwise. Any gamma correction previously set will be overwritten.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the [Crtc] doesn't have a gamma
+    /// LUT, or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    /// output.set_color_temperature(3400).unwrap();
+    /// ```
+    pub fn set_color_temperature(&self, kelvin: u32) -> Result<()> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+        let crtc_id = self.crtc.object_id();
+        let factors = white_point(kelvin);
+
+        let properties = self.crtc.properties()?;
+        let lut_prop_id = properties.get("GAMMA_LUT").map(Property::id);
+        let lut_size = properties.get_u64("GAMMA_LUT_SIZE");
+
+        if let (Some(prop_id), Some(size)) = (lut_prop_id, lut_size) {
+            if size == 0 {
+                return Err(Error::Unsupported("Gamma LUT"));
+            }
+
+            let lut = gamma_lut(usize::try_from(size)?, factors);
+            let mut blobs = PropertyBlobs::new();
+            let value = blobs.create(&device, crtc_id, prop_id, lut.as_slice())?;
+            let properties = vec![(crtc_id, prop_id, value)];
+
+            return commit_properties(&device, 0, properties, blobs);
+        }
+
+        let size = drm_mode_get_crtc(&device, crtc_id)?.gamma_size;
+        if size == 0 {
+            return Err(Error::Unsupported("Gamma LUT"));
+        }
+
+        let [red, green, blue] = gamma_ramp(usize::try_from(size)?, factors);
+
+        drm_mode_set_gamma(&device, crtc_id, &red, &green, &blue)
+    }
+
+    /// Sets a 3D LUT on the [Output]
+    ///
+    /// The [Lut3d] will be interpolated if its size doesn't match the one supported by the
+    /// hardware. This relies on the driver exposing a `LUT3D` [Crtc] property, possibly with a
+    /// vendor prefix, along with a matching `LUT3D_SIZE` property holding its number of entries,
+    /// as found in some downstream kernels. The per-plane 3D LUTs, such as `AMD_PLANE_LUT3D`,
+    /// and the generic color pipeline aren't supported.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::Unsupported`] if the driver doesn't support [Crtc] 3D LUTs, even if
+    /// it supports per-plane ones, or [Error] if the [Device] can't be accessed or if the ioctl
+    /// fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device, Lut3d};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    ///
+    /// let lut = Lut3d::load("calibration.cube").unwrap();
+    /// output.set_3d_lut(&lut).unwrap();
+    /// ```
+    pub fn set_3d_lut(&self, lut: &Lut3d) -> Result<()> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+        let crtc_id = self.crtc.object_id();
+        let properties = self.crtc.properties()?;
+
+        // NOTE: There's no generic 3D LUT property, but the CRTC ones are all named
+        // <PREFIX>LUT3D, with the size exposed by a <PREFIX>LUT3D_SIZE property.
+        let crtc_lut = properties
+            .iter()
+            .filter(|prop| prop.name().ends_with("LUT3D"))
+            .find_map(|prop| {
+                let size_name = format!("{}_SIZE", prop.name());
+
+                properties.get_u64(&size_name).map(|size| (prop.id(), size))
+            });
+
+        let Some((prop_id, size)) = crtc_lut else {
+            // NOTE: The per-plane 3D LUTs apply before blending and have their own semantics, so
+            // we don't fall back to them, but we let the user know they're there.
+            let plane_lut = self.planes().into_iter().any(|plane| {
+                plane
+                    .properties()
+                    .is_ok_and(|props| props.iter().any(|prop| prop.name().ends_with("LUT3D")))
+            });
+
+            return Err(Error::Unsupported(if plane_lut {
+                "Plane 3D LUT"
+            } else {
+                "3D LUT"
+            }));
+        };
+
+        let raw = lut.to_raw(hw_lut_size(size)?)?;
+        let mut blobs = PropertyBlobs::new();
+        let value = blobs.create(&device, crtc_id, prop_id, raw.as_slice())?;
+        let properties = vec![(crtc_id, prop_id, value)];
+
+        commit_properties(&device, 0, properties, blobs)
+    }
+
+    /// Turns the [Output] off
+    ///
+    /// The [Crtc] is deactivated and all the [Plane]s attached to it are detached, in a single
+    /// atomic commit. The [Mode] is kept, and committing any [Update] will turn the [Output] back
+    /// on.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the commit fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// output.power_off().unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    pub fn power_off(&self) -> Result<()> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        #[cfg(feature = "legacy")]
+        if device.is_legacy() {
+            return legacy_power_off(&device, &self.connector);
+        }
+
+        let crtc_id = self.crtc.object_id();
+        let active_prop_id = self.crtc.required_property_id("ACTIVE")?;
+
+        let mut properties = vec![(crtc_id, active_prop_id, 0)];
+        for plane in self.planes() {
+            if plane.properties()?.get_u64("CRTC_ID") != Some(u64::from(crtc_id)) {
+                continue;
+            }
+
+            let plane_id = plane.object_id();
+            properties.push((plane_id, plane.required_property_id("CRTC_ID")?, 0));
+            properties.push((plane_id, plane.required_property_id("FB_ID")?, 0));
+        }
+
+        commit_properties(
+            &device,
+            DRM_MODE_ATOMIC_ALLOW_MODESET,
+            properties,
+            PropertyBlobs::new(),
+        )
+    }
+
+    /// Sets the image of the [Output] cursor
+    ///
+    /// This goes through the legacy cursor ioctls, that work whether the driver exposes a cursor
+    /// [Plane] or not, and that are much cheaper than an atomic commit. The [Buffer] usually needs
+    /// to be in the ARGB8888 format, and most drivers only support a few sizes like 64x64.
+    ///
+    /// The hotspot is the position, within the cursor image, of the pointer tip. Paravirtualized
+    /// drivers need it to match the host cursor. The cursor is shown at its last position, or at
+    /// the top-left corner otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    ///
+    /// let cursor = device.allocate_buffer(BufferType::Dumb, 64, 64, 32).unwrap();
+    /// output.set_cursor(&cursor, 0, 0).unwrap();
+    /// ```
+    pub fn set_cursor(&self, buffer: &Buffer, hot_x: i32, hot_y: i32) -> Result<()> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+        let crtc_id = self.crtc.object_id();
+        let width = u32::try_from(buffer.width())?;
+        let height = u32::try_from(buffer.height())?;
+
+        // NOTE: CURSOR2 is only needed to pass a hotspot, and some drivers only implement CURSOR.
+        if hot_x == 0 && hot_y == 0 {
+            return drm_mode_cursor(
+                &device,
+                drm_mode_cursor {
+                    flags: DRM_MODE_CURSOR_BO,
+                    crtc_id,
+                    width,
+                    height,
+                    handle: buffer.handle(),
+                    ..drm_mode_cursor::default()
+                },
+            );
+        }
+
+        drm_mode_cursor2(
+            &device,
+            drm_mode_cursor2 {
+                flags: DRM_MODE_CURSOR_BO,
+                crtc_id,
+                width,
+                height,
+                handle: buffer.handle(),
+                hot_x,
+                hot_y,
+                ..drm_mode_cursor2::default()
+            },
+        )
+    }
+
+    /// Moves the [Output] cursor
+    ///
+    /// The position is the one of the cursor image top-left corner, and can be negative for the
+    /// cursor to be partially off-screen.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    /// output.move_cursor(100, 200).unwrap();
+    /// ```
+    pub fn move_cursor(&self, x: i32, y: i32) -> Result<()> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        drm_mode_cursor(
+            &device,
+            drm_mode_cursor {
+                flags: DRM_MODE_CURSOR_MOVE,
+                crtc_id: self.crtc.object_id(),
+                x,
+                y,
+                ..drm_mode_cursor::default()
+            },
+        )
+    }
+
+    /// Hides the [Output] cursor
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    /// output.hide_cursor().unwrap();
+    /// ```
+    pub fn hide_cursor(&self) -> Result<()> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        // NOTE: Setting a null buffer handle disables the cursor.
+        drm_mode_cursor(
+            &device,
+            drm_mode_cursor {
+                flags: DRM_MODE_CURSOR_BO,
+                crtc_id: self.crtc.object_id(),
+                ..drm_mode_cursor::default()
+            },
+        )
+    }
+
+    /// Returns an iterator over the [Plane]s available
+    ///
+    /// The [Plane]s are returned in the order the kernel reports them. Use
+    /// [`Planes::sorted_by_zpos`] to get them in stacking order instead.
+    ///
+    /// If the [Device] has been dropped, no [Plane] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device, Format};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_connector(&connector).unwrap();
+    /// let plane = output
+    ///     .planes()
+    ///     .into_iter()
+    ///     .find(|plane| {
+    ///         plane
+    ///             .formats()
+    ///             .find(|fmt| *fmt == Format::XRGB8888)
+    ///             .is_some()
+    ///     })
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn planes(&self) -> Planes {
+        let Some(inner) = self.dev.upgrade() else {
+            return Planes(Vec::new());
+        };
+
+        let crtc_idx = self.crtc.index();
+        let planes = Device::from(inner)
+            .planes()
+            .filter(|plane| ((1 << crtc_idx) & plane.possible_crtcs()) != 0)
+            .collect();
+
+        Planes(planes)
+    }
+
+    /// Returns an iterator over the [Plane]s of a given [Type](PlaneType) available
+    ///
+    /// If the [Device] has been dropped, no [Plane] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device, PlaneType};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_connector(&connector).unwrap();
+    /// let cursor = output
+    ///     .planes_of(PlaneType::Cursor)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn planes_of(&self, plane_type: PlaneType) -> Planes {
+        let planes = self
+            .planes()
+            .into_iter()
+            .filter(|plane| plane.plane_type() == plane_type)
+            .collect();
+
+        Planes(planes)
+    }
+
+    /// Takes the out fence of the last [Update] committed to the [Output]
+    ///
+    /// The out fence is a sync file that signals once the [Update] has been presented, and is
+    /// only available if it has been requested through [`Update::request_out_fence`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let mut output = output
+    ///     .start_update()
+    ///     .request_out_fence()
+    ///     .commit()
+    ///     .unwrap();
+    ///
+    /// let fence = output.take_out_fence().unwrap();
+    /// ```
+    pub const fn take_out_fence(&mut self) -> Option<OwnedFd> {
+        self.out_fence.take()
+    }
+
+    /// Starts an [Update] of the current [Output]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_connector(&connector).unwrap();
+    /// let output = output.start_update().commit().unwrap();
+    /// ```
+    #[must_use]
+    pub fn start_update(self) -> Update {
+        Update {
+            mode: None,
+            output: self,
+            connector: None,
+            crtc: None,
+            planes: Vec::new(),
+            properties: HashMap::new(),
+            blobs: HashMap::new(),
+            out_fence: None,
+        }
+    }
+}
+
+/// The [Plane]s of an [Output], as returned by [`Output::planes`]
+#[derive(Debug)]
+pub struct Planes(Vec<Arc<Plane>>);
+
+impl Planes {
+    /// Orders the [Planes] by their current zpos, from the bottom to the top
+    ///
+    /// Planes sharing the same zpos are ordered by [Type](crate::PlaneType), primary first and
+    /// cursor last, and otherwise keep their original order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_connector(&connector).unwrap();
+    /// let top = output
+    ///     .planes()
+    ///     .sorted_by_zpos()
+    ///     .into_iter()
+    ///     .last()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn sorted_by_zpos(mut self) -> Self {
+        self.0.sort_by_cached_key(|plane| {
+            let rank = match plane.plane_type() {
+                PlaneType::Primary => 0,
+                PlaneType::Overlay => 1,
+                PlaneType::Cursor => 2,
+            };
+
+            (plane.zpos(), rank)
+        });
+
+        self
+    }
+}
+
+impl IntoIterator for Planes {
+    type Item = Arc<Plane>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// An Error returned by a failed [Update] commit
+///
+/// Committing an [Update] consumes it, along with its [Output]. The [`CommitError`] gives them
+/// back, so that transient failures, such as an `EBUSY` while a previous commit is still
+/// pending, can simply be retried.
+///
+/// It converts into an [Error], so the `?` operator can be used in functions returning one.
+///
+/// # Example
+///
+/// ```no_run
+/// use nucleid::{ConnectorStatus, Device, PlaneType, PlaneUpdate};
+///
+/// let device = Device::new("/dev/dri/card0").unwrap();
+///
+/// let connector = device.connectors()
+///     .into_iter()
+///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+///     .unwrap();
+///
+/// let output = device.output_from_current_state(&connector).unwrap();
+///
+/// let plane = output
+///     .planes_of(PlaneType::Primary)
+///     .into_iter()
+///     .next()
+///     .unwrap();
+///
+/// let mut update = output.start_update().add_plane(PlaneUpdate::new(&plane));
+/// let output = loop {
+///     match update.commit() {
+///         Ok(output) => break output,
+///         Err(err) => update = err.into_update(),
+///     }
+/// };
+/// ```
+#[derive(Debug)]
+pub struct CommitError {
+    error: Error,
+    update: Box<Update>,
+}
+
+impl CommitError {
+    pub(crate) fn new(error: Error, update: Update) -> Self {
+        Self {
+            error,
+            update: Box::new(update),
+        }
+    }
+
+    /// Returns the [Error] the commit failed with
+    #[must_use]
+    pub const fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Consumes the [`CommitError`] to retrieve the [Update] that failed to be committed
+    #[must_use]
+    pub fn into_update(self) -> Update {
+        *self.update
+    }
+
+    /// Consumes the [`CommitError`] to retrieve the [Output] of the failed [Update]
+    ///
+    /// The [Output] is left in the state it had before the commit.
+    #[must_use]
+    pub fn into_output(self) -> Output {
+        self.update.into_output()
+    }
+
+    /// Consumes the [`CommitError`] to retrieve both the [Error] and the [Update]
+    #[must_use]
+    pub fn into_parts(self) -> (Error, Update) {
+        (self.error, *self.update)
+    }
+}
+
+impl std::fmt::Display for CommitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for CommitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<CommitError> for Error {
+    fn from(err: CommitError) -> Self {
+        err.error
+    }
+}
+
+/// [Output] state modification abstraction
+#[derive(Debug)]
+pub struct Update {
+    mode: Option<Mode>,
+    output: Output,
+    connector: Option<ConnectorUpdate>,
+    crtc: Option<CrtcUpdate>,
+    planes: Vec<PlaneUpdate>,
+    properties: HashMap<String, u64>,
+    blobs: HashMap<String, Vec<u8>>,
+
+    // NOTE: The kernel writes the out fence file descriptor at the address given in the
+    // OUT_FENCE_PTR property, so it needs to be stable and writable through a shared reference.
+    out_fence: Option<Box<AtomicI32>>,
+}
+
+impl Update {
+    /// Adds a [`ConnectorUpdate`] to the pending [Update]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, ConnectorUpdate, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_connector(ConnectorUpdate::new(&connector))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn add_connector(mut self, connector: ConnectorUpdate) -> Self {
+        self.connector = Some(connector);
+        self
+    }
+
+    /// Adds a [`CrtcUpdate`] to the pending [Update]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{CrtcUpdate, Device, Matrix3x3};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let crtc = output.crtc();
+    /// let output = output
+    ///     .start_update()
+    ///     .add_crtc(CrtcUpdate::new(&crtc).set_ctm(&Matrix3x3::IDENTITY))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn add_crtc(mut self, crtc: CrtcUpdate) -> Self {
+        self.crtc = Some(crtc);
+        self
+    }
+
+    /// Assigns a list of layers to the [Output] [Plane]s
+    ///
+    /// The [`LayerRequest`]s are ordered from the bottom-most to the top-most, and are assigned
+    /// to the available [Plane]s, sorted by their `zpos`, while preserving that order. Only the
+    /// [Plane]s supporting the [Framebuffer] [Format](crate::Format), and the `alpha` property
+    /// if needed, are considered, and each assignment is then validated by the hardware through
+    /// a test-only commit, taking care of the scaling and bandwidth constraints. The test-only
+    /// commits use the flags of [`Update::commit`], see [`Update::assign_layers_with_flags`] to
+    /// commit the [Update] differently.
+    ///
+    /// The [Plane]s already part of the [Update] are left untouched, and the [`LayerRequest`]s
+    /// that couldn't be assigned to any [Plane] are reported by
+    /// [`LayerAssignment::fallback`]. The other [Plane]s still enabled on the [Output] are
+    /// disabled. The assigned [Plane]s with a mutable `zpos` are given one matching the layers
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{
+    ///     BufferType, ConnectorStatus, ConnectorUpdate, Device, Format, LayerRequest, Rect,
+    /// };
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let mode = connector.preferred_mode().unwrap();
+    /// let (width, height) = (mode.width(), mode.height());
+    ///
+    /// let background = device.allocate_buffer(BufferType::Dumb, width, height, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// let video = device.allocate_buffer(BufferType::Dumb, 1280, 720, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// let layers = [
+    ///     LayerRequest::new(&background, Rect::new(0, 0, width, height)),
+    ///     LayerRequest::new(&video, Rect::new(0, 0, width, height)),
+    /// ];
+    ///
+    /// let assignment = output
+    ///     .start_update()
+    ///     .set_mode(mode)
+    ///     .add_connector(ConnectorUpdate::new(&connector))
+    ///     .assign_layers(&layers)
+    ///     .unwrap();
+    ///
+    /// for idx in assignment.fallback() {
+    ///     println!("Layer {} needs to be composited", idx);
+    /// }
+    ///
+    /// let output = assignment
+    ///     .into_update()
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    pub fn assign_layers(self, layers: &[LayerRequest<'_>]) -> Result<LayerAssignment> {
+        assign_layers(self, layers, CommitFlags::ALLOW_MODESET)
+    }
+
+    /// Assigns a list of layers to the [Output] [Plane]s, for an [Update] committed with `flags`
+    ///
+    /// This works like [`Update::assign_layers`], but the assignments are validated for a
+    /// commit issued with [`Update::commit_with_flags`] and `flags`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, CommitFlags, Device, Format, LayerRequest, Rect};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let video = device.allocate_buffer(BufferType::Dumb, 1280, 720, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// let layers = [LayerRequest::new(&video, Rect::new(0, 0, 1280, 720))];
+    /// let flags = CommitFlags::NONBLOCK | CommitFlags::PAGE_FLIP_EVENT;
+    /// let assignment = output
+    ///     .start_update()
+    ///     .assign_layers_with_flags(&layers, flags)
+    ///     .unwrap();
+    ///
+    /// let (output, handle) = assignment
+    ///     .into_update()
+    ///     .commit_with_flags(flags)
+    ///     .unwrap();
+    /// ```
+    pub fn assign_layers_with_flags(
+        self,
+        layers: &[LayerRequest<'_>],
+        flags: CommitFlags,
+    ) -> Result<LayerAssignment> {
+        assign_layers(self, layers, flags)
+    }
+
+    /// Returns the IDs of the KMS objects the pending [Update] will change
+    ///
+    /// The [Crtc] always comes first, followed by the [Connector], if any, and the [Plane]s in
+    /// the order they were added.
+    #[must_use]
+    pub fn objects(&self) -> Vec<u32> {
+        let mut objects = vec![self.output.crtc_id()];
+
+        if let Some(connector) = &self.connector {
+            objects.push(connector.connector.object_id());
+        }
+
+        objects.extend(self.planes.iter().map(|plane| plane.plane.object_id()));
+        objects
+    }
+
+    /// Returns the properties set by the pending [Update]
+    ///
+    /// The properties are returned as they would be recorded, so the blob properties will hold
+    /// their content and the framebuffers their geometry. No blob is created to do so.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if any ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, ConnectorUpdate, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let update = output
+    ///     .start_update()
+    ///     .set_mode(connector.preferred_mode().unwrap())
+    ///     .add_connector(ConnectorUpdate::new(&connector));
+    ///
+    /// for property in update.staged_properties().unwrap() {
+    ///     println!("{}: {} = {:?}", property.object_id(), property.name(), property.value());
+    /// }
+    /// ```
+    pub fn staged_properties(&self) -> Result<Vec<RecordedProperty>> {
+        let device = self.device()?;
+        let mut blobs = PropertyBlobs::staged();
+        let properties = self.properties(&device, &mut blobs)?;
+
+        let commit = RecordedCommit::from_staged(
+            &device,
+            DRM_MODE_ATOMIC_ALLOW_MODESET,
+            &properties,
+            &blobs,
+        )?;
+
+        Ok(commit.properties().cloned().collect())
+    }
+
+    /// Returns whether committing the pending [Update] would imply a modeset
+    ///
+    /// A modeset is needed to change the [Mode], to route a [Connector] to the [Crtc] or to
+    /// enable a disabled [Crtc]. Modesets take much longer than regular updates, and usually
+    /// result in a visible glitch.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if any ioctl fails.
+    pub fn needs_modeset(&self) -> Result<bool> {
+        let device = self.device()?;
+        let crtc = drm_mode_get_crtc(&device, self.output.crtc_id())?;
+
+        if crtc.mode_valid == 0 {
+            return Ok(true);
+        }
+
+        if let Some(mode) = &self.mode {
+            if *mode.inner() != crtc.mode {
+                return Ok(true);
+            }
+        }
+
+        if let Some(connector) = &self.connector {
+            let current = connector
+                .connector
+                .properties()?
+                .get_u64("CRTC_ID")
+                .unwrap_or(0);
+
+            if current != u64::from(self.output.crtc_id()) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    pub(crate) const fn output(&self) -> &Output {
+        &self.output
+    }
+
+    pub(crate) fn into_output(self) -> Output {
+        let mut output = self.output;
+
+        // NOTE: The out fence is only filled if the commit succeeded, and the property is reset
+        // before each commit, so a file descriptor here is always ours to own.
+        if let Some(fd) = self.out_fence.map(|fence| fence.load(Ordering::Acquire)) {
+            if fd >= 0 {
+                output.out_fence = Some(unsafe { OwnedFd::from_raw_fd(fd) });
+            }
+        }
+
+        output
+    }
+
+    #[cfg(feature = "legacy")]
+    pub(crate) const fn mode(&self) -> Option<&Mode> {
+        self.mode.as_ref()
+    }
+
+    #[cfg(feature = "legacy")]
+    pub(crate) const fn connector(&self) -> Option<&ConnectorUpdate> {
+        self.connector.as_ref()
+    }
+
+    #[cfg(feature = "legacy")]
+    pub(crate) fn planes(&self) -> &[PlaneUpdate] {
+        &self.planes
+    }
+
+    pub(crate) fn contains_plane(&self, plane: &Plane) -> bool {
+        self.planes
+            .iter()
+            .any(|update| update.plane.object_id() == plane.object_id())
+    }
+
+    /// Adds a [`PlaneUpdate`] to the pending [Update]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes()
+    ///     .into_iter()
+    ///     .find(|plane| {
+    ///         plane
+    ///             .formats()
+    ///             .find(|fmt| *fmt == Format::XRGB8888)
+    ///             .is_some()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn add_plane(mut self, plane: PlaneUpdate) -> Self {
+        self.planes.push(plane);
+        self
+    }
+
+    /// Commits the pending [Update]
+    ///
+    /// With the `legacy` feature enabled, the [Update] will be applied through the legacy KMS API
+    /// on drivers without atomic support. It won't be atomic anymore then, and the primary [Plane]
+    /// will always cover the whole [Output], only its source position being honored.
+    ///
+    /// # Errors
+    ///
+    /// Will return a [`CommitError`] if the [Device] can't be accessed, if the ioctl fails, or if
+    /// the [Update] is rejected by the hardware. The [Update] can be retrieved from it to retry
+    /// the commit.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, ConnectorUpdate, Device, Format, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes()
+    ///     .into_iter()
+    ///     .find(|plane| {
+    ///         plane
+    ///             .formats()
+    ///             .find(|fmt| *fmt == Format::XRGB8888)
+    ///             .is_some()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_connector(ConnectorUpdate::new(&connector))
+    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    pub fn commit(self) -> std::result::Result<Output, CommitError> {
+        match self.try_commit() {
+            Ok(()) => Ok(self.into_output()),
+            Err(error) => Err(CommitError::new(error, self)),
+        }
+    }
+
+    fn try_commit(&self) -> Result<()> {
+        #[cfg(feature = "legacy")]
+        {
+            let device = self.device()?;
+            if device.is_legacy() {
+                return legacy_commit(self, &device);
+            }
+        }
+
+        self.try_commit_with_flags(CommitFlags::ALLOW_MODESET)
+            .map(|_| ())
+    }
+
+    /// Checks whether the pending [Update] would be accepted by the hardware
+    ///
+    /// The [Update] is submitted as a test-only commit, so nothing changes on screen, and can be
+    /// committed afterwards if it's been accepted. This allows to probe whether a combination of
+    /// [Plane]s, [Mode] and formats is supported before committing it.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the [Device] doesn't support
+    /// atomic commits, or if the [Update] is rejected by the hardware.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Format, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Overlay)
+    ///     .into_iter()
+    ///     .find(|plane| plane.formats().any(|fmt| fmt == Format::ARGB8888))
+    ///     .unwrap();
+    ///
+    /// let update = output.start_update().add_plane(PlaneUpdate::new(&plane));
+    ///
+    /// if update.test().is_ok() {
+    ///     let output = update.commit().unwrap();
+    /// }
+    /// ```
+    pub fn test(&self) -> Result<()> {
+        self.try_commit_with_flags(CommitFlags::TEST_ONLY | CommitFlags::ALLOW_MODESET)
+            .map(|_| ())
+    }
+
+    /// Sets the highest maximum number of bits per color component the hardware accepts
+    ///
+    /// The depths from `bpc` down to the lowest one the [Output] [Connector] accepts are tried
+    /// through test-only commits, and the first accepted is set in the [`ConnectorUpdate`] of
+    /// the pending [Update], along with any [Property](crate::Property) already set.
+    ///
+    /// Returns the [Update] and the depth that has been set.
+    ///
+    /// # Errors
+    ///
+    /// Will return a [`CommitError`] if the [Connector] doesn't have the `max bpc` property, or if
+    /// the [Update] is rejected with every depth. The [Update] can be retrieved from it, with its
+    /// [`ConnectorUpdate`] left as it was.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let (update, bpc) = output.start_update().negotiate_max_bpc(12).unwrap();
+    /// println!("Using up to {} bits per component", bpc);
+    ///
+    /// let output = update.commit().unwrap();
+    /// ```
+    pub fn negotiate_max_bpc(mut self, bpc: u8) -> std::result::Result<(Self, u8), CommitError> {
+        let connector = Arc::clone(&self.output.connector);
+        let Some((min, max)) = connector.max_bpc_range() else {
+            let error = Error::PropertyNotFound {
+                object: connector.object_id(),
+                name: String::from(MAX_BPC),
+            };
+
+            return Err(CommitError::new(error, self));
+        };
+
+        // NOTE: The value set by the caller, if any, is put back if every depth is rejected.
+        let previous = self.connector.as_mut().map(|update| {
+            (
+                update.properties.remove(MAX_BPC),
+                update.blobs.remove(MAX_BPC),
+            )
+        });
+
+        let mut last_error = Error::Unsupported("Max BPC");
+        for candidate in (min..=bpc.min(max)).rev() {
+            let update = self
+                .connector
+                .take()
+                .unwrap_or_else(|| ConnectorUpdate::new(&connector));
+            self.connector = Some(update.set_max_bpc(candidate));
+
+            match self.test() {
+                Ok(()) => return Ok((self, candidate)),
+                Err(err) => last_error = err,
+            }
+        }
+
+        self.connector = previous.and_then(|(value, blob)| {
+            let mut update = self.connector.take()?;
+            let _old = update.properties.remove(MAX_BPC);
+            update
+                .properties
+                .extend(value.map(|value| (String::from(MAX_BPC), value)));
+            update
+                .blobs
+                .extend(blob.map(|blob| (String::from(MAX_BPC), blob)));
+
+            Some(update)
+        });
+
+        Err(CommitError::new(last_error, self))
+    }
+
+    /// Commits the pending [Update] without waiting for it to be presented
+    ///
+    /// The returned [`CommitHandle`] can be given to [`Device::wait_for_flip`] to wait for the
+    /// [Update] to be presented. No other [Update] can be committed to the [Output] until then.
+    ///
+    /// # Errors
+    ///
+    /// Will return a [`CommitError`] if the [Device] can't be accessed, if the [Device] doesn't
+    /// support atomic commits or if the ioctl fails. The [Update] can be retrieved from it to
+    /// retry the commit.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use nucleid::{ConnectorStatus, Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let (output, handle) = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .commit_nonblocking()
+    ///     .unwrap();
+    ///
+    /// let vblank = device.wait_for_flip(handle, Duration::from_millis(100)).unwrap();
+    /// ```
+    pub fn commit_nonblocking(self) -> std::result::Result<(Output, CommitHandle), CommitError> {
+        match self.try_commit_nonblocking() {
+            Ok(handle) => Ok((self.into_output(), handle)),
+            Err(error) => Err(CommitError::new(error, self)),
+        }
+    }
+
+    fn try_commit_nonblocking(&self) -> Result<CommitHandle> {
+        self.try_commit_with_flags(CommitFlags::NONBLOCK | CommitFlags::PAGE_FLIP_EVENT)?
+            .ok_or(Error::Empty)
+    }
+
+    /// Commits the pending [Update] with explicit [`CommitFlags`]
+    ///
+    /// [`Update::commit`], [`Update::commit_nonblocking`] and [`Update::test`] cover the most
+    /// common cases, but this allows for example to commit without allowing a modeset, so that
+    /// the commit fails instead of blanking the [Output].
+    ///
+    /// If [`CommitFlags::PAGE_FLIP_EVENT`] is set, the returned [`CommitHandle`] can be given to
+    /// [`Device::wait_for_flip`] to wait for the [Update] to be presented.
+    ///
+    /// # Errors
+    ///
+    /// Will return a [`CommitError`] if the [Device] can't be accessed, if the [Device] doesn't
+    /// support atomic commits, or if the ioctl fails. The [Update] can be retrieved from it to
+    /// retry the commit.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{CommitFlags, Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let (output, handle) = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .commit_with_flags(CommitFlags::PAGE_FLIP_EVENT)
+    ///     .unwrap();
+    /// ```
+    pub fn commit_with_flags(
+        self,
+        flags: CommitFlags,
+    ) -> std::result::Result<(Output, Option<CommitHandle>), CommitError> {
+        match self.try_commit_with_flags(flags) {
+            Ok(handle) => Ok((self.into_output(), handle)),
+            Err(error) => Err(CommitError::new(error, self)),
+        }
+    }
+
+    fn try_commit_with_flags(&self, flags: CommitFlags) -> Result<Option<CommitHandle>> {
+        let device = self.device()?;
+
+        #[cfg(feature = "legacy")]
+        if device.is_legacy() {
+            return Err(Error::Unsupported("Atomic commits"));
+        }
+
+        // NOTE: The commit sequence number is also used as the user data of its flip event, so
+        // that we can tell when it completes.
+        let sequence = device.next_commit_sequence();
+        let handle = flags
+            .contains(CommitFlags::PAGE_FLIP_EVENT)
+            .then(|| CommitHandle::new(sequence));
+        let user_data = handle.map_or(0, |handle| handle.user_data());
+
+        let trace = CommitTrace::new(sequence, flags.bits());
+        let mut blobs = PropertyBlobs::new();
+        let properties = trace.stage("staging", || self.properties(&device, &mut blobs))?;
+
+        // NOTE: We can only tell when a commit has been presented if it blocks, or if it sends
+        // a flip event.
+        let presents = !flags.contains(CommitFlags::TEST_ONLY)
+            && (handle.is_some() || !flags.contains(CommitFlags::NONBLOCK));
+        let submitted = presents.then(|| self.submitted_at()).flatten();
+
+        if let Some(handle) = handle {
+            device.track_flip(handle, self.output.crtc_id(), submitted);
+        }
+
+        if let Err(err) = commit_traced(&device, &trace, flags.bits(), user_data, properties, blobs)
+        {
+            if let Some(handle) = handle {
+                device.untrack_flip(handle);
+            }
+
+            return Err(err);
+        }
+
+        if presents && handle.is_none() {
+            self.record_presented(&device, submitted);
+        }
+
+        Ok(handle)
+    }
+
+    /// Returns the vblank sequence the [Update] is submitted at
+    ///
+    /// The [Output] might not be enabled yet, in which case we can't tell whether the first
+    /// frame has been skipped.
+    pub(crate) fn submitted_at(&self) -> Option<u64> {
+        self.output.vblank().ok().map(|vblank| vblank.sequence())
+    }
+
+    /// Accounts for the [Update] in the [`FrameStats`] of its [Output], once a blocking commit
+    /// has returned
+    pub(crate) fn record_presented(&self, device: &Device, submitted: Option<u64>) {
+        // NOTE: A blocking commit only returns once it's been presented, so the current vblank
+        // is the one it's been presented at.
+        if let Ok(vblank) = self.output.vblank() {
+            device.record_frame(self.output.crtc_id(), submitted, vblank.sequence());
+        }
+    }
+
+    /// Commits the pending [Update] to be presented at a given [Vblank] sequence
+    ///
+    /// This will block until the [Update] is presented. If the `sequence` is already over, the
+    /// [Update] will be presented as soon as possible. The [Output] needs to be enabled already.
+    ///
+    /// # Errors
+    ///
+    /// Will return a [`CommitError`] if the [Device] can't be accessed, if the [Output] is
+    /// disabled or if any ioctl fails. The [Update] can be retrieved from it to retry the commit.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use nucleid::{ConnectorStatus, Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    /// let mode = output.current_mode().unwrap().unwrap();
+    ///
+    /// let plane = output
+    ///     .planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.plane_type() == PlaneType::Primary)
+    ///     .unwrap();
+    ///
+    /// let vblank = output.vblank().unwrap();
+    /// let sequence = vblank.sequence_at(&mode, vblank.time() + Duration::from_millis(40));
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .commit_at(sequence)
+    ///     .unwrap();
+    /// ```
+    pub fn commit_at(self, sequence: u64) -> std::result::Result<Output, CommitError> {
+        if let Err(error) = self.wait_before(sequence) {
+            return Err(CommitError::new(error, self));
+        }
+
+        self.commit()
+    }
+
+    fn wait_before(&self, sequence: u64) -> Result<()> {
+        let device = self.device()?;
+        let crtc_id = self.output.crtc_id();
+        let current = self.output.vblank()?;
+
+        // NOTE: A blocking commit is applied on the next vblank, so we need to wake up one frame
+        // before the target one.
+        if sequence > current.sequence() + 1 {
+            let user_data = u64::from(crtc_id);
+
+            drm_crtc_queue_sequence(
+                &device,
+                crtc_id,
+                DRM_CRTC_SEQUENCE_NEXT_ON_MISS,
+                sequence - 1,
+                user_data,
+            )?;
+            let _event = device.wait_for_event(
+                |event| matches!(event, Event::Sequence { user_data: data, .. } if *data == user_data),
+                None,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn device(&self) -> Result<Device> {
+        Ok(self.output.dev.upgrade().ok_or(Error::DeviceGone)?.into())
+    }
+
+    pub(crate) fn properties(
+        &self,
+        device: &Device,
+        blobs: &mut PropertyBlobs,
+    ) -> Result<Vec<(u32, u32, u64)>> {
+        let mut properties = Vec::new();
+        let crtc_object_id = self.output.crtc.object_id();
+
+        for plane in &self.planes {
+            properties.extend(plane.properties(crtc_object_id, blobs)?);
+        }
+
+        // NOTE: Atomic drivers don't allow to set the DPMS property, and turn the CRTC on or off
+        // instead.
+        let active = self
+            .connector
+            .as_ref()
+            .and_then(|connector| connector.dpms)
+            .unwrap_or(DpmsMode::On)
+            == DpmsMode::On;
+        let active_prop_id = self.output.crtc.required_property_id("ACTIVE")?;
+        properties.push((crtc_object_id, active_prop_id, u64::from(active)));
+
+        properties.extend(named_properties(
+            device,
+            self.output.crtc.as_ref(),
+            &self.properties,
+            &self.blobs,
+            blobs,
+        )?);
+
+        if let Some(crtc) = &self.crtc {
+            properties.extend(crtc.properties(device, blobs)?);
+        }
+
+        if let Some(fence) = &self.out_fence {
+            let fence_prop_id = self
+                .output
+                .crtc
+                .property_id("OUT_FENCE_PTR")
+                .ok_or(Error::Unsupported("Out fences"))?;
+
+            fence.store(-1, Ordering::Release);
+            properties.push((crtc_object_id, fence_prop_id, fence.as_ptr() as u64));
+        }
+
+        if let Some(mode) = &self.mode {
+            let mode_prop_id = self.output.crtc.required_property_id("MODE_ID")?;
+            let value = blobs.create(
+                device,
+                crtc_object_id,
+                mode_prop_id,
+                std::slice::from_ref(mode.inner()),
+            )?;
+            properties.push((crtc_object_id, mode_prop_id, value));
+        }
+
+        if let Some(connector) = &self.connector {
+            let crtc_prop_id = connector.connector.required_property_id("CRTC_ID")?;
+            properties.push((
+                connector.connector.object_id(),
+                crtc_prop_id,
+                u64::from(crtc_object_id),
+            ));
+
+            properties.extend(named_properties(
+                device,
+                connector.connector.as_ref(),
+                &connector.properties,
+                &connector.blobs,
+                blobs,
+            )?);
+        }
+
+        Ok(properties)
+    }
+
+    /// Changes the [Mode] of the pending [Update]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, ConnectorUpdate, Device, Format, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let mode = connector
+    ///     .preferred_mode()
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .set_mode(mode)
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn set_mode(mut self, mode: Mode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Requests an out fence for the pending [Update]
+    ///
+    /// Once the [Update] is committed, the out fence can be retrieved with
+    /// [`Output::take_out_fence`]. It's a sync file that signals once the [Update] has been
+    /// presented, so that GPU work can be synchronized with the scanout. This is only supported
+    /// by atomic commits.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let (mut output, _) = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .request_out_fence()
+    ///     .commit_nonblocking()
+    ///     .unwrap();
+    ///
+    /// let fence = output.take_out_fence().unwrap();
+    /// ```
+    #[must_use]
+    pub fn request_out_fence(mut self) -> Self {
+        self.out_fence = Some(Box::new(AtomicI32::new(-1)));
+        self
+    }
+}
+
+/// The blobs created for the properties of a pending commit, by object and property ID
+///
+/// The blobs are handed over to the committed state once the commit succeeds, and are destroyed
+/// along with the pending commit otherwise.
+///
+/// The blobs of a staged commit are never created, and only their content is kept so that the
+/// commit can be looked at without any side effect.
+#[derive(Debug, Default)]
+pub struct PropertyBlobs {
+    created: HashMap<(u32, u32), Blob>,
+    staged: Option<HashMap<(u32, u32), Vec<u8>>>,
+}
+
+impl PropertyBlobs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn staged() -> Self {
+        Self {
+            created: HashMap::new(),
+            staged: Some(HashMap::new()),
+        }
+    }
+
+    /// Creates the blob of the property `prop_id` of `object_id`, and returns the property value
+    ///
+    /// The value of a staged blob is always 0, since it doesn't have an ID.
+    pub fn create<T: BlobData>(
+        &mut self,
+        device: &Device,
+        object_id: u32,
+        prop_id: u32,
+        data: &[T],
+    ) -> Result<u64> {
+        if let Some(staged) = &mut self.staged {
+            let _old = staged.insert((object_id, prop_id), as_bytes(data).to_vec());
+            return Ok(0);
+        }
+
+        let blob = Blob::new(device, data)?;
+        let id = u64::from(blob.id());
+        let _old = self.created.insert((object_id, prop_id), blob);
+
+        Ok(id)
+    }
+
+    /// Returns the content of the staged blob of the property `prop_id` of `object_id`
+    pub fn staged_data(&self, object_id: u32, prop_id: u32) -> Option<&[u8]> {
+        self.staged
+            .as_ref()
+            .and_then(|staged| staged.get(&(object_id, prop_id)))
+            .map(Vec::as_slice)
+    }
+
+    pub fn extend(&mut self, other: Self) {
+        self.created.extend(other.created);
+
+        if let (Some(staged), Some(other)) = (&mut self.staged, other.staged) {
+            staged.extend(other);
+        }
+    }
+}
+
+impl IntoIterator for PropertyBlobs {
+    type Item = ((u32, u32), Blob);
+    type IntoIter = std::collections::hash_map::IntoIter<(u32, u32), Blob>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.created.into_iter()
+    }
+}
+
+pub fn commit_properties(
+    device: &Device,
+    flags: u32,
+    properties: Vec<(u32, u32, u64)>,
+    blobs: PropertyBlobs,
+) -> Result<()> {
+    let trace = CommitTrace::new(device.next_commit_sequence(), flags);
+
+    commit_traced(device, &trace, flags, 0, properties, blobs)
+}
+
+fn commit_traced(
+    device: &Device,
+    trace: &CommitTrace,
+    flags: u32,
+    user_data: u64,
+    properties: Vec<(u32, u32, u64)>,
+    blobs: PropertyBlobs,
+) -> Result<()> {
+    let ret = commit_properties_with_user_data(device, trace, flags, user_data, properties, blobs);
+    trace.finish(&ret);
+
+    ret
+}
+
+fn commit_properties_with_user_data(
+    device: &Device,
+    trace: &CommitTrace,
+    flags: u32,
+    user_data: u64,
+    mut properties: Vec<(u32, u32, u64)>,
+    blobs: PropertyBlobs,
+) -> Result<()> {
+    let (objs_ptr, count_props_ptr, props_ptr, prop_values_ptr) =
+        trace.stage("validation", || {
+            let mut count_props = 0;
+            let mut objs_ptr: Vec<u32> = Vec::new();
+            let mut count_props_ptr: Vec<u32> = Vec::new();
+            let mut props_ptr: Vec<u32> = Vec::new();
+            let mut prop_values_ptr: Vec<u64> = Vec::new();
+
+            properties.sort_unstable();
+            properties.dedup();
+
+            let first_obj = properties.first().ok_or(Error::Empty)?.0;
+            let mut last_obj = first_obj;
+
+            objs_ptr.push(first_obj);
+            for property in &properties {
+                let oid = property.0;
+
+                if oid != last_obj {
+                    objs_ptr.push(oid);
+                    count_props_ptr.push(count_props);
+
+                    last_obj = oid;
+                    count_props = 0;
+                }
+
+                count_props += 1;
+                props_ptr.push(property.1);
+                prop_values_ptr.push(property.2);
+            }
+            count_props_ptr.push(count_props);
+
+            Ok::<_, Error>((objs_ptr, count_props_ptr, props_ptr, prop_values_ptr))
+        })?;
+
+    let ret = trace.stage("ioctl", || {
+        // SAFETY: The arrays have been built together from the same property list above, and the
+        // only pointer we commit is the OUT_FENCE_PTR one, which points to the fences owned by
+        // the caller.
+        unsafe {
+            drm_mode_atomic_commit(
+                device,
+                flags,
+                &objs_ptr,
+                &count_props_ptr,
+                &props_ptr,
+                &prop_values_ptr,
+                user_data,
+            )
+        }
+        .map_err(|err| match err {
+            Error::Ioctl(errno) => Error::CommitRejected(errno),
+            err => err,
+        })
+    });
+    #[cfg(feature = "tracing")]
+    if let Err(err) = &ret {
+        trace.dump(device, flags, &properties, err);
+    }
+    ret?;
+
+    trace.stage("completion", || {
+        if (flags & DRM_MODE_ATOMIC_TEST_ONLY) == 0 {
+            let mut state = device.state();
+            let mut replaced = Vec::new();
+
+            for &(object_id, prop_id, value) in &properties {
+                // NOTE: Re-applying a fence would make the kernel wait on a stale file
+                // descriptor, or write to a stale address.
+                let is_fence = state.property_ids.get(&object_id).is_some_and(|ids| {
+                    FENCE_PROPERTIES
+                        .iter()
+                        .any(|name| ids.get(*name) == Some(&prop_id))
+                });
+                if is_fence {
+                    continue;
+                }
+
+                let key = (object_id, prop_id);
+                let _old = state.committed.insert(key, value);
+
+                if state
+                    .blobs
+                    .get(&key)
+                    .is_some_and(|blob| u64::from(blob.id()) != value)
+                {
+                    replaced.extend(state.blobs.remove(&key));
+                }
+            }
+
+            for (key, blob) in blobs {
+                replaced.extend(state.blobs.insert(key, blob));
+            }
+            drop(state);
+
+            // NOTE: The blobs are destroyed when dropped, which needs to happen without the
+            // state lock held.
+            drop(replaced);
+        }
+
+        // NOTE: The commit has already been applied at this point, so a recording failure
+        // must not be reported as a failed commit.
+        if (flags & DRM_MODE_ATOMIC_TEST_ONLY) == 0 {
+            #[cfg(feature = "tracing")]
+            if let Err(err) = device.record_commit(flags, &properties) {
+                tracing::warn!(%err, "couldn't record the commit");
+            }
+
+            #[cfg(not(feature = "tracing"))]
+            let _res = device.record_commit(flags, &properties);
+        }
+
+        Ok::<_, Error>(())
+    })
+}
+
+/// Resolves the properties set by name on an object update, and creates their blobs
+///
+/// The blobs are added to `created`, and need to be kept around until the commit is done.
+fn named_properties(
+    device: &Device,
+    object: &impl Object,
+    properties: &HashMap<String, u64>,
+    blobs: &HashMap<String, Vec<u8>>,
+    created: &mut PropertyBlobs,
+) -> Result<Vec<(u32, u32, u64)>> {
+    let object_id = object.object_id();
+    let mut ret = Vec::with_capacity(properties.len() + blobs.len());
+
+    for (prop_name, prop_value) in properties {
+        // NOTE: The kernel writes to the address set in OUT_FENCE_PTR, so it can only be set by
+        // nucleid itself, through Update::request_out_fence.
+        if prop_name == "OUT_FENCE_PTR" {
+            return Err(Error::ReservedProperty(prop_name.clone()));
+        }
+
+        let prop_id = object.required_property_id(prop_name)?;
+
+        ret.push((object_id, prop_id, *prop_value));
+    }
+
+    for (prop_name, data) in blobs {
+        let prop_id = object.required_property_id(prop_name)?;
+        let value = created.create(device, object_id, prop_id, data.as_slice())?;
+
+        ret.push((object_id, prop_id, value));
+    }
+
+    Ok(ret)
+}
+
+/// Used to update the state of any KMS Object
+///
+/// For an [Update], the properties are the ones of the [Output] [Crtc], like `GAMMA_LUT` or
+/// `CTM`.
+pub trait ObjectUpdate {
+    /// Adds a [Property](crate::Property) to the new state update  
+    #[must_use]
+    fn set_property(self, property: &str, val: u64) -> Self;
+
+    /// Removes a [Property](crate::Property) previously added to the new state update
+    ///
+    /// The [Property](crate::Property) will keep its current value.
+    #[must_use]
+    fn clear_property(self, property: &str) -> Self;
+
+    /// Sets a blob [Property](crate::Property) to the content of a [Blob](crate::Blob)
+    ///
+    /// The [Blob](crate::Blob) must be kept alive until the update has been committed.
+    #[must_use]
+    fn set_blob_property(self, property: &str, blob: &crate::Blob) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_property(property, u64::from(blob.id()))
+    }
+
+    /// Sets a blob [Property](crate::Property) to `data`
+    ///
+    /// The blob is created when the update is committed, and destroyed once it's not part of
+    /// the committed state anymore.
+    #[must_use]
+    fn set_property_blob(self, property: &str, data: &[u8]) -> Self;
+
+    /// Sets a blob [Property](crate::Property) to the content of a structure
+    ///
+    /// `T` must have the layout the kernel expects for this property, like
+    /// [`drm_color_ctm`](crate::sys::drm_color_ctm) for `CTM`.
+    #[must_use]
+    fn set_property_blob_typed<T: BlobData>(self, property: &str, data: &T) -> Self
+    where
+        Self: Sized,
+    {
+        let bytes = as_bytes(std::slice::from_ref(data));
+
+        self.set_property_blob(property, bytes)
+    }
+}
+
+impl ObjectUpdate for Update {
+    fn set_property(mut self, property: &str, val: u64) -> Self {
+        let _old = self.blobs.remove(property);
+        self.properties.insert(property.to_string(), val);
+        self
+    }
+
+    fn clear_property(mut self, property: &str) -> Self {
+        let _old = self.properties.remove(property);
+        let _old = self.blobs.remove(property);
+        self
+    }
+
+    fn set_property_blob(mut self, property: &str, data: &[u8]) -> Self {
+        let _old = self.properties.remove(property);
+        self.blobs.insert(property.to_string(), data.to_vec());
+        self
+    }
+}
+
+/// [Crtc] state update abstraction
+///
+/// On top of the generic [`ObjectUpdate`] properties, it provides helpers for the color
+/// management properties.
+#[derive(Debug)]
+pub struct CrtcUpdate {
+    crtc: Arc<Crtc>,
+    properties: HashMap<String, u64>,
+    blobs: HashMap<String, Vec<u8>>,
+    gamma_lut: Option<Vec<LutEntry>>,
+    degamma_lut: Option<Vec<LutEntry>>,
+}
+
+impl CrtcUpdate {
+    /// Creates a new [Crtc] state
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{CrtcUpdate, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let crtc = output.crtc();
+    /// let output = output
+    ///     .start_update()
+    ///     .add_crtc(CrtcUpdate::new(&crtc))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn new(crtc: &Arc<Crtc>) -> Self {
+        Self {
+            crtc: Arc::clone(crtc),
+            properties: HashMap::new(),
+            blobs: HashMap::new(),
+            gamma_lut: None,
+            degamma_lut: None,
+        }
+    }
+
+    /// Sets the gamma LUT, applied after the Color Transformation Matrix
+    ///
+    /// The LUT will be interpolated when the update is committed if its size doesn't match the
+    /// `GAMMA_LUT_SIZE` advertised by the [Crtc]. An empty LUT is replaced by a linear ramp. The
+    /// commit fails with [`Error::Unsupported`] if the [Crtc] advertises a size of 0.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{CrtcUpdate, Device, LutEntry};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// // Inverts the colors
+    /// let lut = [
+    ///     LutEntry::new(u16::MAX, u16::MAX, u16::MAX),
+    ///     LutEntry::new(0, 0, 0),
+    /// ];
+    ///
+    /// let crtc = output.crtc();
+    /// let output = output
+    ///     .start_update()
+    ///     .add_crtc(CrtcUpdate::new(&crtc).set_gamma_lut(&lut))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn set_gamma_lut(mut self, lut: &[LutEntry]) -> Self {
+        self.forget("GAMMA_LUT");
+        self.gamma_lut = Some(lut.to_vec());
+        self
+    }
+
+    /// Sets the degamma LUT, applied before the Color Transformation Matrix
+    ///
+    /// The LUT will be interpolated when the update is committed if its size doesn't match the
+    /// `DEGAMMA_LUT_SIZE` advertised by the [Crtc]. An empty LUT is replaced by a linear ramp. The
+    /// commit fails with [`Error::Unsupported`] if the [Crtc] advertises a size of 0.
+    #[must_use]
+    pub fn set_degamma_lut(mut self, lut: &[LutEntry]) -> Self {
+        self.forget("DEGAMMA_LUT");
+        self.degamma_lut = Some(lut.to_vec());
+        self
+    }
+
+    /// Sets the Color Transformation Matrix
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{CrtcUpdate, Device, Matrix3x3};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// // Converts to grayscale
+    /// let ctm = Matrix3x3([[0.2126, 0.7152, 0.0722]; 3]);
+    ///
+    /// let crtc = output.crtc();
+    /// let output = output
+    ///     .start_update()
+    ///     .add_crtc(CrtcUpdate::new(&crtc).set_ctm(&ctm))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn set_ctm(self, ctm: &Matrix3x3) -> Self {
+        self.set_property_blob_typed("CTM", &ctm.to_raw())
+    }
+
+    fn forget(&mut self, property: &str) {
+        let _old = self.properties.remove(property);
+        let _old = self.blobs.remove(property);
+
+        match property {
+            "GAMMA_LUT" => self.gamma_lut = None,
+            "DEGAMMA_LUT" => self.degamma_lut = None,
+            _ => {}
+        }
+    }
+
+    fn properties(
+        &self,
+        device: &Device,
+        blobs: &mut PropertyBlobs,
+    ) -> Result<Vec<(u32, u32, u64)>> {
+        let object_id = self.crtc.object_id();
+        let mut properties = named_properties(
+            device,
+            self.crtc.as_ref(),
+            &self.properties,
+            &self.blobs,
+            blobs,
+        )?;
+
+        let luts = [
+            ("GAMMA_LUT", "GAMMA_LUT_SIZE", &self.gamma_lut),
+            ("DEGAMMA_LUT", "DEGAMMA_LUT_SIZE", &self.degamma_lut),
+        ];
+
+        for (name, size_name, lut) in luts {
+            let Some(lut) = lut else {
+                continue;
+            };
+
+            let prop_id = self.crtc.required_property_id(name)?;
+            let size = self.crtc.properties()?.get_u64(size_name).ok_or_else(|| {
+                Error::PropertyNotFound {
+                    object: object_id,
+                    name: size_name.to_string(),
+                }
+            })?;
+
+            if size == 0 {
+                return Err(Error::Unsupported(name));
+            }
+
+            let raw = resample_lut(lut, usize::try_from(size)?);
+            let value = blobs.create(device, object_id, prop_id, raw.as_slice())?;
+            properties.push((object_id, prop_id, value));
+        }
+
+        Ok(properties)
+    }
+}
+
+impl ObjectUpdate for CrtcUpdate {
+    fn set_property(mut self, property: &str, val: u64) -> Self {
+        self.forget(property);
+        self.properties.insert(property.to_string(), val);
+        self
+    }
+
+    fn clear_property(mut self, property: &str) -> Self {
+        self.forget(property);
+        self
+    }
+
+    fn set_property_blob(mut self, property: &str, data: &[u8]) -> Self {
+        self.forget(property);
+        self.blobs.insert(property.to_string(), data.to_vec());
+        self
+    }
+}
+
+/// [Connector] state update abstraction
+#[derive(Debug)]
+pub struct ConnectorUpdate {
+    connector: Arc<Connector>,
+    properties: HashMap<String, u64>,
+    blobs: HashMap<String, Vec<u8>>,
+    dpms: Option<DpmsMode>,
+}
+
+impl ConnectorUpdate {
+    /// Creates a new [Connector] state
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, ConnectorUpdate, Device, Format, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_connector(ConnectorUpdate::new(&connector))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn new(connector: &Arc<Connector>) -> Self {
+        Self {
+            connector: Arc::clone(connector),
+            properties: HashMap::new(),
+            blobs: HashMap::new(),
+            dpms: None,
+        }
+    }
+
+    /// Sets the [Connector] [`DpmsMode`]
+    ///
+    /// On atomic drivers, this turns the [Output] [Crtc] on or off.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorUpdate, Device, DpmsMode};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.dpms().unwrap() == DpmsMode::On)
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_connector(ConnectorUpdate::new(&connector).set_dpms(DpmsMode::Off))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub const fn set_dpms(mut self, dpms: DpmsMode) -> Self {
+        self.dpms = Some(dpms);
+        self
+    }
+
+    /// Sets the maximum number of bits per color component the [Connector] can output
+    ///
+    /// The accepted values can be retrieved through [`Connector::max_bpc_range`]. The driver
+    /// might still use a lower depth if the sink or the link can't sustain it, and some drivers
+    /// reject the commit instead, see [`Update::negotiate_max_bpc`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorUpdate, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.max_bpc_range().is_some_and(|(_, max)| max >= 10))
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_connector(ConnectorUpdate::new(&connector).set_max_bpc(10))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn set_max_bpc(self, bpc: u8) -> Self {
+        self.set_property(MAX_BPC, u64::from(bpc))
+    }
+
+    /// Requests or releases the content protection of the [Connector] link
+    ///
+    /// Only the kernel can move to [`ContentProtection::Enabled`], so requesting it is the same
+    /// as requesting [`ContentProtection::Desired`]. Once committed, the protection can be
+    /// waited for with [`Connector::wait_for_content_protection`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorUpdate, ContentProtection, Device, HdcpContentType};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.content_protection().is_ok())
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_connector(
+    ///         ConnectorUpdate::new(&connector)
+    ///             .set_content_protection(ContentProtection::Desired)
+    ///             .set_hdcp_content_type(HdcpContentType::Type1)
+    ///     )
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn set_content_protection(self, state: ContentProtection) -> Self {
+        // NOTE: The kernel rejects Enabled, but keeps the protection enabled if it already is
+        // and Desired is set again.
+        let state = match state {
+            ContentProtection::Enabled => ContentProtection::Desired,
+            state => state,
+        };
+
+        self.set_property("Content Protection", state as u64)
+    }
+
+    /// Sets the [`HdcpContentType`] the [Connector] link must be protected for
+    ///
+    /// Changing the type while the protection is enabled makes the kernel establish it again.
+    #[must_use]
+    pub fn set_hdcp_content_type(self, content_type: HdcpContentType) -> Self {
+        self.set_property("HDCP Content Type", content_type as u64)
+    }
+
+    #[cfg(feature = "legacy")]
+    pub(crate) const fn connector(&self) -> &Arc<Connector> {
+        &self.connector
+    }
+
+    #[cfg(feature = "legacy")]
+    pub(crate) const fn named_properties(&self) -> &HashMap<String, u64> {
+        &self.properties
+    }
+
+    #[cfg(feature = "legacy")]
+    pub(crate) const fn dpms(&self) -> Option<DpmsMode> {
+        self.dpms
+    }
+}
+
+impl ObjectUpdate for PlaneUpdate {
+    fn set_property(self, property: &str, val: u64) -> Self {
+        Self::set_property(self, property, val)
+    }
+
+    fn clear_property(self, property: &str) -> Self {
+        Self::clear_property(self, property)
+    }
+
+    fn set_property_blob(mut self, property: &str, data: &[u8]) -> Self {
+        let _old = self.properties.remove(property);
+        let _old = self.enums.remove(property);
+        self.blobs.insert(property.to_string(), data.to_vec());
+        self
+    }
+}
+
+impl ObjectUpdate for ConnectorUpdate {
+    fn set_property(mut self, property: &str, val: u64) -> Self {
+        let _old = self.blobs.remove(property);
+        self.properties.insert(property.to_string(), val);
+        self
+    }
+
+    fn clear_property(mut self, property: &str) -> Self {
+        let _old = self.properties.remove(property);
+        let _old = self.blobs.remove(property);
+        self
+    }
+
+    fn set_property_blob(mut self, property: &str, data: &[u8]) -> Self {
+        let _old = self.properties.remove(property);
+        self.blobs.insert(property.to_string(), data.to_vec());
+        self
+    }
+}
+
+/// [Plane] state update abstraction
+#[derive(Debug)]
+pub struct PlaneUpdate {
+    plane: Arc<Plane>,
+    properties: HashMap<String, u64>,
+    blobs: HashMap<String, Vec<u8>>,
+    damage: Vec<Rect>,
+    rotation: Option<(Rotation, Reflection)>,
+
+    // NOTE: The enum properties set by name, whose values are only looked up at commit time.
+    enums: HashMap<&'static str, &'static str>,
+
+    disabled: bool,
+}
+
+impl PlaneUpdate {
+    /// Creates a new [Plane] state
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes()
+    ///     .into_iter()
+    ///     .find(|plane| {
+    ///         plane
+    ///             .formats()
+    ///             .find(|fmt| *fmt == Format::XRGB8888)
+    ///             .is_some()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn new(plane: &Arc<Plane>) -> Self {
+        Self {
+            plane: Arc::clone(plane),
+            properties: HashMap::new(),
+            blobs: HashMap::new(),
+            damage: Vec::new(),
+            rotation: None,
+            enums: HashMap::new(),
+            disabled: false,
+        }
+    }
+
+    /// Creates a new [Plane] state detaching the [Plane] from its [Crtc]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Overlay)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::disabled(&plane))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn disabled(plane: &Arc<Plane>) -> Self {
+        let mut update = Self::new(plane).set_property("FB_ID", 0);
+        update.disabled = true;
+        update
+    }
+
+    #[cfg(feature = "legacy")]
+    pub(crate) const fn plane(&self) -> &Arc<Plane> {
+        &self.plane
+    }
+
+    #[cfg(feature = "legacy")]
+    pub(crate) const fn named_properties(&self) -> &HashMap<String, u64> {
+        &self.properties
+    }
+
+    pub(crate) fn properties(
+        &self,
+        crtc_id: u32,
+        blobs: &mut PropertyBlobs,
+    ) -> Result<Vec<(u32, u32, u64)>> {
+        let object_id = self.plane.object_id();
+        let crtc_prop_id = self.plane.required_property_id("CRTC_ID")?;
+        let crtc_id = if self.disabled { 0 } else { crtc_id };
+
+        let mut properties = vec![(object_id, crtc_prop_id, u64::from(crtc_id))];
+        properties.extend(named_properties(
+            &self.plane.device()?,
+            self.plane.as_ref(),
+            &self.properties,
+            &self.blobs,
+            blobs,
+        )?);
+
+        // NOTE: The damage is only a hint, drivers without FB_DAMAGE_CLIPS will update the
+        // whole framebuffer.
+        if let Some(prop_id) = self.plane.property_id("FB_DAMAGE_CLIPS") {
+            if !self.damage.is_empty() {
+                let device = self.plane.device()?;
+                let rects = mode_rects(&self.damage)?;
+                let value = blobs.create(&device, object_id, prop_id, rects.as_slice())?;
+
+                properties.push((object_id, prop_id, value));
+            }
+        }
+
+        if let Some((rotation, reflection)) = self.rotation {
+            if let Some(value) = self.plane.rotation_value(rotation, reflection)? {
+                let prop_id = self.plane.required_property_id("rotation")?;
+
+                properties.push((object_id, prop_id, value));
+            }
+        }
+
+        if !self.enums.is_empty() {
+            let plane_properties = self.plane.properties()?;
+
+            for (prop_name, entry) in &self.enums {
+                let property =
+                    plane_properties
+                        .get(prop_name)
+                        .ok_or_else(|| Error::PropertyNotFound {
+                            object: object_id,
+                            name: (*prop_name).to_string(),
+                        })?;
+
+                let value = property
+                    .typed_value()
+                    .enum_value(entry)
+                    .ok_or(Error::Unsupported(entry))?;
+
+                properties.push((object_id, property.id(), value));
+            }
+        }
+
+        Ok(properties)
+    }
+
+    /// Attaches a new [Framebuffer] to the pending [Plane] update
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, ConnectorStatus, Device, Format, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes()
+    ///     .into_iter()
+    ///     .find(|plane| {
+    ///         plane
+    ///             .formats()
+    ///             .find(|fmt| *fmt == Format::XRGB8888)
+    ///             .is_some()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let buffer = device
+    ///     .allocate_buffer(BufferType::Dumb, 1920, 1080, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(
+    ///         PlaneUpdate::new(&plane)
+    ///             .set_framebuffer(&buffer)
+    ///     )
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn set_framebuffer(self, fb: &Framebuffer) -> Self {
+        // NOTE: The Framebuffer is about to be scanned out, so the CPU must be done with it.
+        fb.end_cpu_access();
+
+        let fb_id = fb.id();
+        self.set_property("FB_ID", u64::from(fb_id))
+    }
+
+    /// Sets the [Framebuffer] areas that changed since the previous commit
+    ///
+    /// The current frame of the [Damage] is used, and allows the hardware to only update those
+    /// areas. Without any damage, the whole [Framebuffer] is considered changed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, ConnectorStatus, Damage, Device, Format, PlaneUpdate, Rect};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes()
+    ///     .into_iter()
+    ///     .find(|plane| {
+    ///         plane
+    ///             .formats()
+    ///             .find(|fmt| *fmt == Format::XRGB8888)
+    ///             .is_some()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let mut fb = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// let mut damage = Damage::new(640, 480);
+    ///
+    /// fb.data()[..640 * 4].fill(0xff);
+    /// damage.add(Rect::new(0, 0, 640, 1));
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(
+    ///         PlaneUpdate::new(&plane)
+    ///             .set_framebuffer(&fb)
+    ///             .set_damage(&damage)
+    ///     )
+    ///     .commit()
+    ///     .unwrap();
+    ///
+    /// damage.end_frame();
+    /// ```
+    #[must_use]
+    pub fn set_damage(self, damage: &Damage) -> Self {
+        self.set_damage_rects(damage.rects())
+    }
+
+    /// Sets the [Framebuffer] areas that changed since the previous commit, from a list of
+    /// [Rect]s
+    ///
+    /// The [Rect]s are submitted through the `FB_DAMAGE_CLIPS` property, if the [Plane] exposes
+    /// it. Drivers without it will update the whole [Framebuffer], and an empty list means the
+    /// whole [Framebuffer] changed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, ConnectorStatus, Device, Format, PlaneType, PlaneUpdate, Rect};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let mut fb = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap()
+    ///     .into_framebuffer(Format::XRGB8888)
+    ///     .unwrap();
+    ///
+    /// fb.data()[..640 * 4].fill(0xff);
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(
+    ///         PlaneUpdate::new(&plane)
+    ///             .set_framebuffer(&fb)
+    ///             .set_damage_rects(&[Rect::new(0, 0, 640, 1)])
+    ///     )
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn set_damage_rects(mut self, rects: &[Rect]) -> Self {
+        self.damage = rects.to_vec();
+        self
+    }
+
+    /// Sets the horizontal and vertical [`ChromaSiting`] in the pending [Plane] update
+    ///
+    /// The siting will only be applied if the [Plane] has the `CHROMA_SITING_H` and
+    /// `CHROMA_SITING_V` properties, and ignored otherwise. Their values are looked up when the
+    /// update is committed, and the commit will fail if they don't have a [`ChromaSiting`]
+    /// entry.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ChromaSiting, ConnectorStatus, Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Overlay)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(
+    ///         PlaneUpdate::new(&plane)
+    ///             .set_chroma_siting(ChromaSiting::Cosited, ChromaSiting::Midpoint)
+    ///     )
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn set_chroma_siting(self, horizontal: ChromaSiting, vertical: ChromaSiting) -> Self {
+        // NOTE: Few drivers expose the chroma siting, and the others will use their default
+        // one, so we don't want to fail the whole commit over it.
+        if self.plane.property_id("CHROMA_SITING_H").is_none()
+            || self.plane.property_id("CHROMA_SITING_V").is_none()
+        {
+            return self;
+        }
+
+        self.set_enum_property("CHROMA_SITING_H", horizontal.name())
+            .set_enum_property("CHROMA_SITING_V", vertical.name())
+    }
+
+    /// Sets the position of the [Plane] in the stack of planes, from the bottom
+    ///
+    /// The accepted positions can be retrieved through [`Plane::zpos_range`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::convert::TryFrom;
+    ///
+    /// use nucleid::{ConnectorStatus, Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Overlay)
+    ///     .into_iter()
+    ///     .find(|plane| plane.zpos_range().is_some())
+    ///     .unwrap();
+    ///
+    /// let (_, max) = plane.zpos_range().unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane).set_zpos(u32::try_from(max).unwrap()))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn set_zpos(self, zpos: u32) -> Self {
+        self.set_property("zpos", u64::from(zpos))
+    }
+
+    /// Sets the global alpha of the [Plane], from 0.0 (transparent) to 1.0 (opaque)
+    ///
+    /// The value is clamped to that range. Only the [Plane]s for which
+    /// [`Plane::supports_alpha`] returns `true` accept it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Overlay)
+    ///     .into_iter()
+    ///     .find(|plane| plane.supports_alpha())
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane).set_alpha(0.5))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn set_alpha(self, alpha: f32) -> Self {
+        // NOTE: The value is clamped to [0.0; 1.0] first, so the result fits in a u16
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let alpha = (alpha.clamp(0.0, 1.0) * f32::from(u16::MAX)).round() as u16;
+
+        self.set_property("alpha", u64::from(alpha))
+    }
+
+    /// Sets how the pixels of the [Plane] are blended with the content below it
+    ///
+    /// The value of the `pixel blend mode` property is looked up when the update is committed,
+    /// and the commit will fail if the [Plane] doesn't support the [`PixelBlendMode`], see
+    /// [`Plane::blend_modes`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device, PixelBlendMode, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Overlay)
+    ///     .into_iter()
+    ///     .find(|plane| plane.blend_modes().contains(&PixelBlendMode::Coverage))
+    ///     .unwrap();
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(PlaneUpdate::new(&plane).set_blend_mode(PixelBlendMode::Coverage))
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn set_blend_mode(self, mode: PixelBlendMode) -> Self {
+        self.set_enum_property("pixel blend mode", mode.name())
+    }
 
-impl Update {
-    /// Adds a [`ConnectorUpdate`] to the pending [Update]
+    /// Sets the [`ColorEncoding`] used to convert the YUV content of the [Plane] to RGB
+    ///
+    /// The value of the `COLOR_ENCODING` property is looked up when the update is committed,
+    /// and the commit will fail if the [Plane] doesn't support the [`ColorEncoding`], see
+    /// [`Plane::color_encodings`].
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, ConnectorUpdate, Device};
+    /// use nucleid::{
+    ///     ColorEncoding, ColorRange, ConnectorStatus, Device, Format, PlaneType, PlaneUpdate,
+    /// };
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -170,25 +3079,57 @@ impl Update {
     ///     .output_from_connector(&connector)
     ///     .unwrap();
     ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Overlay)
+    ///     .into_iter()
+    ///     .find(|plane| plane.formats().any(|fmt| fmt == Format::NV12))
+    ///     .unwrap();
+    ///
     /// let output = output
     ///     .start_update()
-    ///     .add_connector(ConnectorUpdate::new(&connector))
+    ///     .add_plane(
+    ///         PlaneUpdate::new(&plane)
+    ///             .set_color_encoding(ColorEncoding::Bt709)
+    ///             .set_color_range(ColorRange::Limited)
+    ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
     #[must_use]
-    #[allow(clippy::missing_const_for_fn)]
-    pub fn add_connector(mut self, connector: ConnectorUpdate) -> Self {
-        self.connector = Some(connector);
+    pub fn set_color_encoding(self, encoding: ColorEncoding) -> Self {
+        self.set_enum_property("COLOR_ENCODING", encoding.name())
+    }
+
+    /// Sets the [`ColorRange`] of the YUV content of the [Plane]
+    ///
+    /// The value of the `COLOR_RANGE` property is looked up when the update is committed, and
+    /// the commit will fail if the [Plane] doesn't support the [`ColorRange`], see
+    /// [`Plane::color_ranges`].
+    #[must_use]
+    pub fn set_color_range(self, range: ColorRange) -> Self {
+        self.set_enum_property("COLOR_RANGE", range.name())
+    }
+
+    fn set_enum_property(mut self, property: &'static str, entry: &'static str) -> Self {
+        let _old = self.properties.remove(property);
+        let _old = self.blobs.remove(property);
+        self.enums.insert(property, entry);
         self
     }
 
-    /// Adds a [`PlaneUpdate`] to the pending [Update]
+    /// Sets the [Rotation] of the content in the pending [Plane] update
+    ///
+    /// The [Rotation] is combined with the [Reflection] set through
+    /// [`PlaneUpdate::set_reflection`], if any, and mapped onto the bits of the `rotation`
+    /// property advertised by the [Plane].
+    ///
+    /// The commit will fail if the [Plane] doesn't support the combination, see
+    /// [`Plane::supports_rotation`].
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate};
+    /// use nucleid::{ConnectorStatus, Device, PlaneType, PlaneUpdate, Reflection, Rotation};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -202,44 +3143,52 @@ impl Update {
     ///     .unwrap();
     ///
     /// let plane = output
-    ///     .planes()
+    ///     .planes_of(PlaneType::Primary)
     ///     .into_iter()
-    ///     .find(|plane| {
-    ///         plane
-    ///             .formats()
-    ///             .find(|fmt| *fmt == Format::XRGB8888)
-    ///             .is_some()
-    ///     })
+    ///     .find(|plane| plane.supports_rotation(Rotation::Rotate180, Reflection::X))
     ///     .unwrap();
     ///
     /// let output = output
     ///     .start_update()
-    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .add_plane(
+    ///         PlaneUpdate::new(&plane)
+    ///             .set_rotation(Rotation::Rotate180)
+    ///             .set_reflection(Reflection::X)
+    ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
     #[must_use]
-    #[allow(clippy::missing_const_for_fn)]
-    pub fn add_plane(mut self, plane: PlaneUpdate) -> Self {
-        self.planes.push(plane);
+    pub fn set_rotation(mut self, rotation: Rotation) -> Self {
+        let (_, reflection) = self.rotation.unwrap_or_default();
+
+        let _old = self.properties.remove("rotation");
+        self.rotation = Some((rotation, reflection));
         self
     }
 
-    /// Commits the pending [Update]
-    ///
-    /// # Errors
+    /// Sets the [Reflection] of the content in the pending [Plane] update
     ///
-    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails, or if the
-    /// [Update] is rejected by the hardware.
+    /// The [Reflection] is combined with the [Rotation] set through
+    /// [`PlaneUpdate::set_rotation`], or [`Rotation::Rotate0`] otherwise.
     ///
-    /// # Panics
-    ///
-    /// If the back-pointer to the DRM device isn't valid anymore.
+    /// The commit will fail if the [Plane] doesn't support the combination, see
+    /// [`Plane::supports_rotation`].
+    #[must_use]
+    pub fn set_reflection(mut self, reflection: Reflection) -> Self {
+        let (rotation, _) = self.rotation.unwrap_or_default();
+
+        let _old = self.properties.remove("rotation");
+        self.rotation = Some((rotation, reflection));
+        self
+    }
+
+    /// Attaches an [`AdoptedFramebuffer`] to the pending [Plane] update
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, ConnectorUpdate, Device, Format, PlaneUpdate};
+    /// use nucleid::{ConnectorStatus, Device, PlaneType, PlaneUpdate};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -249,121 +3198,41 @@ impl Update {
     ///     .unwrap();
     ///
     /// let output = device
-    ///     .output_from_connector(&connector)
+    ///     .output_from_current_state(&connector)
     ///     .unwrap();
     ///
     /// let plane = output
     ///     .planes()
     ///     .into_iter()
-    ///     .find(|plane| {
-    ///         plane
-    ///             .formats()
-    ///             .find(|fmt| *fmt == Format::XRGB8888)
-    ///             .is_some()
-    ///     })
+    ///     .find(|plane| plane.plane_type() == PlaneType::Primary)
+    ///     .unwrap();
+    ///
+    /// let boot_fb = plane
+    ///     .adopt_framebuffer()
+    ///     .unwrap()
     ///     .unwrap();
     ///
     /// let output = output
     ///     .start_update()
-    ///     .add_connector(ConnectorUpdate::new(&connector))
-    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .add_plane(
+    ///         PlaneUpdate::new(&plane)
+    ///             .set_adopted_framebuffer(&boot_fb)
+    ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
-    pub fn commit(self) -> Result<Output> {
-        let device: Device = self.output.dev.upgrade().ok_or(Error::Empty)?.into();
-        let mut properties = Vec::new();
-        let crtc_object_id = self.output.crtc.object_id();
-
-        for plane in self.planes {
-            let crtc_prop_id = plane.plane.property_id("CRTC_ID").unwrap();
-            properties.push((
-                plane.plane.object_id(),
-                crtc_prop_id,
-                u64::from(crtc_object_id),
-            ));
-
-            for (prop_name, prop_value) in plane.properties {
-                let prop_id = plane.plane.property_id(&prop_name).ok_or(Error::Empty)?;
-
-                properties.push((plane.plane.object_id(), prop_id, prop_value));
-            }
-        }
-
-        let active_prop_id = self.output.crtc.property_id("ACTIVE").unwrap();
-        properties.push((crtc_object_id, active_prop_id, 1));
-
-        if let Some(mode) = self.mode {
-            let mode_id = u64::from(drm_mode_create_property_blob(&device, mode.inner())?);
-            let mode_prop_id = self.output.crtc.property_id("MODE_ID").unwrap();
-            properties.push((crtc_object_id, mode_prop_id, mode_id));
-        }
-
-        if let Some(connector) = self.connector {
-            let crtc_prop_id = connector.connector.property_id("CRTC_ID").unwrap();
-            properties.push((
-                connector.connector.object_id(),
-                crtc_prop_id,
-                u64::from(crtc_object_id),
-            ));
-
-            for (prop_name, prop_value) in connector.properties {
-                let prop_id = connector
-                    .connector
-                    .property_id(&prop_name)
-                    .ok_or(Error::Empty)?;
-
-                properties.push((connector.connector.object_id(), prop_id, prop_value));
-            }
-        }
-
-        let mut count_props = 0;
-        let mut objs_ptr: Vec<u32> = Vec::new();
-        let mut count_props_ptr: Vec<u32> = Vec::new();
-        let mut props_ptr: Vec<u32> = Vec::new();
-        let mut prop_values_ptr: Vec<u64> = Vec::new();
-
-        properties.sort_unstable();
-        properties.dedup();
-
-        let first_obj = properties[0].0;
-        let mut last_obj = first_obj;
-
-        objs_ptr.push(first_obj);
-        for property in properties {
-            let oid = property.0;
-
-            if oid != last_obj {
-                objs_ptr.push(oid);
-                count_props_ptr.push(count_props);
-
-                last_obj = oid;
-                count_props = 0;
-            }
-
-            count_props += 1;
-            props_ptr.push(property.1);
-            prop_values_ptr.push(property.2);
-        }
-        count_props_ptr.push(count_props);
-
-        drm_mode_atomic_commit(
-            &device,
-            &objs_ptr,
-            &count_props_ptr,
-            &props_ptr,
-            &prop_values_ptr,
-        )?;
-
-        Ok(self.output)
+    #[must_use]
+    pub fn set_adopted_framebuffer(self, fb: &AdoptedFramebuffer) -> Self {
+        let fb_id = fb.id();
+        self.set_property("FB_ID", u64::from(fb_id))
     }
 
-    /// Changes the [Mode] of the pending [Update]
+    /// Attaches a [`ForeignFramebuffer`] to the pending [Plane] update
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, ConnectorUpdate, Device, Format, PlaneUpdate};
+    /// use nucleid::{ConnectorStatus, Device, Format, FramebufferLayout, PlaneType, PlaneUpdate};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -372,49 +3241,42 @@ impl Update {
     ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
     ///     .unwrap();
     ///
-    /// let mode = connector
-    ///     .preferred_mode()
-    ///     .unwrap();
-    ///
     /// let output = device
     ///     .output_from_connector(&connector)
     ///     .unwrap();
     ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// # fn vendor_allocate() -> u32 { unimplemented!() }
+    /// let handle = vendor_allocate();
+    /// let layout = FramebufferLayout::new(1920, 1080, Format::XRGB8888, 1920 * 4);
+    /// let fb = device.framebuffer_from_handle(handle, layout).unwrap();
+    ///
     /// let output = output
     ///     .start_update()
-    ///     .set_mode(mode)
+    ///     .add_plane(
+    ///         PlaneUpdate::new(&plane)
+    ///             .set_foreign_framebuffer(&fb)
+    ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
     #[must_use]
-    #[allow(clippy::missing_const_for_fn)]
-    pub fn set_mode(mut self, mode: Mode) -> Self {
-        self.mode = Some(mode);
-        self
+    pub fn set_foreign_framebuffer(self, fb: &ForeignFramebuffer) -> Self {
+        let fb_id = fb.id();
+        self.set_property("FB_ID", u64::from(fb_id))
     }
-}
-
-/// Used to update the state of any KMS Object
-pub trait ObjectUpdate {
-    /// Adds a [Property](crate::Property) to the new state update  
-    #[must_use]
-    fn set_property(self, property: &str, val: u64) -> Self;
-}
-
-/// [Connector] state update abstraction
-#[derive(Debug)]
-pub struct ConnectorUpdate {
-    connector: Rc<Connector>,
-    properties: HashMap<String, u64>,
-}
 
-impl ConnectorUpdate {
-    /// Creates a new [Connector] state
+    /// Sets the display coordinates in the pending [Plane] update
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, ConnectorUpdate, Device, Format, PlaneUpdate};
+    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate, Point};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -427,42 +3289,38 @@ impl ConnectorUpdate {
     ///     .output_from_connector(&connector)
     ///     .unwrap();
     ///
+    /// let plane = output
+    ///     .planes()
+    ///     .into_iter()
+    ///     .find(|plane| {
+    ///         plane
+    ///             .formats()
+    ///             .find(|fmt| *fmt == Format::XRGB8888)
+    ///             .is_some()
+    ///     })
+    ///     .unwrap();
+    ///
     /// let output = output
     ///     .start_update()
-    ///     .add_connector(ConnectorUpdate::new(&connector))
+    ///     .add_plane(
+    ///         PlaneUpdate::new(&plane)
+    ///             .set_display_coordinates(Point::new(640, 0))
+    ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
     #[must_use]
-    pub fn new(connector: &Rc<Connector>) -> Self {
-        Self {
-            connector: Rc::clone(connector),
-            properties: HashMap::new(),
-        }
-    }
-}
-
-impl ObjectUpdate for ConnectorUpdate {
-    fn set_property(mut self, property: &str, val: u64) -> Self {
-        self.properties.insert(property.to_string(), val);
-        self
+    pub fn set_display_coordinates(self, position: Point) -> Self {
+        self.set_property("CRTC_X", position.x() as u64)
+            .set_property("CRTC_Y", position.y() as u64)
     }
-}
-
-/// [Plane] state update abstraction
-#[derive(Debug)]
-pub struct PlaneUpdate {
-    plane: Rc<Plane>,
-    properties: HashMap<String, u64>,
-}
 
-impl PlaneUpdate {
-    /// Creates a new [Plane] state
+    /// Sets the display size in the pending [Plane] update
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate};
+    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate, Size};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -488,24 +3346,27 @@ impl PlaneUpdate {
     ///
     /// let output = output
     ///     .start_update()
-    ///     .add_plane(PlaneUpdate::new(&plane))
+    ///     .add_plane(
+    ///         PlaneUpdate::new(&plane)
+    ///             .set_display_size(Size::new(1920, 1080))
+    ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
     #[must_use]
-    pub fn new(plane: &Rc<Plane>) -> Self {
-        Self {
-            plane: Rc::clone(plane),
-            properties: HashMap::new(),
-        }
+    pub fn set_display_size(self, size: Size) -> Self {
+        self.set_property("CRTC_H", size.height() as u64)
+            .set_property("CRTC_W", size.width() as u64)
     }
 
-    /// Attaches a new [Framebuffer] to the pending [Plane] update
+    /// Sets the source coordinates in the pending [Plane] update
+    ///
+    /// The coordinates are a [`FixedPoint`] to support sub-pixel positioning.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{BufferType, ConnectorStatus, Device, Format, PlaneUpdate};
+    /// use nucleid::{ConnectorStatus, Device, FixedPoint, Format, PlaneUpdate};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -529,33 +3390,31 @@ impl PlaneUpdate {
     ///     })
     ///     .unwrap();
     ///
-    /// let buffer = device
-    ///     .allocate_buffer(BufferType::Dumb, 1920, 1080, 32)
-    ///     .unwrap()
-    ///     .into_framebuffer(Format::XRGB8888)
-    ///     .unwrap();
-    ///
     /// let output = output
     ///     .start_update()
     ///     .add_plane(
     ///         PlaneUpdate::new(&plane)
-    ///             .set_framebuffer(&buffer)
+    ///             .set_source_coordinates(FixedPoint::new(860.0, 0.0))
     ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
     #[must_use]
-    pub fn set_framebuffer(self, fb: &Framebuffer) -> Self {
-        let fb_id = fb.id();
-        self.set_property("FB_ID", u64::from(fb_id))
+    pub fn set_source_coordinates(self, position: FixedPoint) -> Self {
+        let (x, y) = position.to_bits();
+
+        self.set_property("SRC_X", u64::from(x))
+            .set_property("SRC_Y", u64::from(y))
     }
 
-    /// Sets the display coordinates in the pending [Plane] update
+    /// Sets the source size in the pending [Plane] update
+    ///
+    /// The dimensions are a [`FixedSize`] to support sub-pixel positioning.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate};
+    /// use nucleid::{ConnectorStatus, Device, FixedSize, Format, PlaneUpdate};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -583,23 +3442,28 @@ impl PlaneUpdate {
     ///     .start_update()
     ///     .add_plane(
     ///         PlaneUpdate::new(&plane)
-    ///             .set_display_coordinates(640, 0)
+    ///             .set_source_size(FixedSize::new(860.0, 0.0))
     ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
     #[must_use]
-    pub fn set_display_coordinates(self, x: usize, y: usize) -> Self {
-        self.set_property("CRTC_X", x as u64)
-            .set_property("CRTC_Y", y as u64)
+    pub fn set_source_size(self, size: FixedSize) -> Self {
+        let (width, height) = size.to_bits();
+
+        self.set_property("SRC_H", u64::from(height))
+            .set_property("SRC_W", u64::from(width))
     }
 
-    /// Sets the display size in the pending [Plane] update
+    /// Sets the source area in the pending [Plane] update
+    ///
+    /// This is equivalent to calling both [`PlaneUpdate::set_source_coordinates`] and
+    /// [`PlaneUpdate::set_source_size`].
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate};
+    /// use nucleid::{ConnectorStatus, Device, FixedRect, Format, PlaneUpdate};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -627,25 +3491,26 @@ impl PlaneUpdate {
     ///     .start_update()
     ///     .add_plane(
     ///         PlaneUpdate::new(&plane)
-    ///             .set_display_size(1920, 1080)
+    ///             .set_source_rect(FixedRect::new(0.0, 0.0, 1920.0, 1080.0))
     ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
     #[must_use]
-    pub fn set_display_size(self, width: usize, height: usize) -> Self {
-        self.set_property("CRTC_H", height as u64)
-            .set_property("CRTC_W", width as u64)
+    pub fn set_source_rect(self, rect: FixedRect) -> Self {
+        self.set_source_coordinates(rect.origin())
+            .set_source_size(rect.size())
     }
 
-    /// Sets the source coordinates in the pending [Plane] update
+    /// Sets the display area in the pending [Plane] update
     ///
-    /// The coordinates are [f32] to support sub-pixel positioning.
+    /// This is equivalent to calling both [`PlaneUpdate::set_display_coordinates`] and
+    /// [`PlaneUpdate::set_display_size`].
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate};
+    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate, Rect};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -673,28 +3538,29 @@ impl PlaneUpdate {
     ///     .start_update()
     ///     .add_plane(
     ///         PlaneUpdate::new(&plane)
-    ///             .set_source_coordinates(860.0, 0.0)
+    ///             .set_display_rect(Rect::new(0, 0, 1920, 1080))
     ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
     #[must_use]
-    pub fn set_source_coordinates(self, x: f32, y: f32) -> Self {
-        let fixed_x = U16F16::from_num(x);
-        let fixed_y = U16F16::from_num(y);
-
-        self.set_property("SRC_X", u64::from(fixed_x.to_bits()))
-            .set_property("SRC_Y", u64::from(fixed_y.to_bits()))
+    pub fn set_display_rect(self, rect: Rect) -> Self {
+        self.set_display_coordinates(rect.origin())
+            .set_display_size(rect.size())
     }
 
-    /// Sets the source size in the pending [Plane] update
+    /// Sets the source and display areas in the pending [Plane] update to fit content of a
+    /// given size into an area of the display
     ///
-    /// The dimensions are [f32] to support sub-pixel positioning.
+    /// `source` is the size of the content, usually the size of the
+    /// [Framebuffer](crate::Framebuffer), and `display` the area to display it in. The scaling
+    /// and cropping needed are computed according to the [`FitMode`]. With
+    /// [`FitMode::Contain`], the content is centered in the `display` area.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use nucleid::{ConnectorStatus, Device, Format, PlaneUpdate};
+    /// use nucleid::{ConnectorStatus, Device, FitMode, Format, PlaneUpdate, Rect, Size};
     ///
     /// let device = Device::new("/dev/dri/card0").unwrap();
     ///
@@ -722,18 +3588,16 @@ impl PlaneUpdate {
     ///     .start_update()
     ///     .add_plane(
     ///         PlaneUpdate::new(&plane)
-    ///             .set_source_size(860.0, 0.0)
+    ///             .fit(FitMode::Contain, Size::new(640, 480), Rect::new(0, 0, 1920, 1080))
     ///     )
     ///     .commit()
     ///     .unwrap();
     /// ```
     #[must_use]
-    pub fn set_source_size(self, width: f32, height: f32) -> Self {
-        let fixed_width = U16F16::from_num(width);
-        let fixed_height = U16F16::from_num(height);
+    pub fn fit(self, mode: FitMode, source: Size, display: Rect) -> Self {
+        let (source, display) = mode.fit(source, display);
 
-        self.set_property("SRC_H", u64::from(fixed_height.to_bits()))
-            .set_property("SRC_W", u64::from(fixed_width.to_bits()))
+        self.set_source_rect(source).set_display_rect(display)
     }
 
     /// Attaches an arbitrary property to the pending [Plane] update
@@ -776,7 +3640,68 @@ impl PlaneUpdate {
     /// ```
     #[must_use]
     pub fn set_property(mut self, property: &str, val: u64) -> Self {
+        if property == "rotation" {
+            self.rotation = None;
+        }
+
+        let _old = self.enums.remove(property);
+        let _old = self.blobs.remove(property);
         self.properties.insert(property.to_string(), val);
         self
     }
+
+    /// Removes a property previously attached to the pending [Plane] update
+    ///
+    /// The property will keep its current value. This also works for the properties set through
+    /// dedicated setters, such as `FB_DAMAGE_CLIPS`, `CHROMA_SITING_H` or `rotation`. Only the
+    /// property named is cleared, so clearing `CHROMA_SITING_H` keeps `CHROMA_SITING_V`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device, PlaneType, PlaneUpdate};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device
+    ///     .output_from_connector(&connector)
+    ///     .unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Overlay)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let opaque = true;
+    ///
+    /// let mut update = PlaneUpdate::new(&plane).set_property("alpha", 0x8000);
+    /// if opaque {
+    ///     update = update.clear_property("alpha");
+    /// }
+    ///
+    /// let output = output
+    ///     .start_update()
+    ///     .add_plane(update)
+    ///     .commit()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn clear_property(mut self, property: &str) -> Self {
+        match property {
+            "FB_DAMAGE_CLIPS" => self.damage.clear(),
+            "rotation" => self.rotation = None,
+            _ => {}
+        }
+
+        let _old = self.properties.remove(property);
+        let _old = self.blobs.remove(property);
+        let _old = self.enums.remove(property);
+        self
+    }
 }
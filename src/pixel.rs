@@ -0,0 +1,109 @@
+use crate::{Error, Format, Result};
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+}
+
+/// A type a [Buffer](crate::Buffer) content can be accessed as
+///
+/// This is implemented for the unsigned integer types matching the size of a pixel in the
+/// supported formats, [u32] being the one to use for 32-bit formats such as
+/// [`Format::XRGB8888`](crate::Format::XRGB8888).
+pub trait Pixel: private::Sealed + Copy + 'static {}
+
+impl Pixel for u8 {}
+impl Pixel for u16 {}
+impl Pixel for u32 {}
+
+/// Reinterprets a byte slice as a slice of [Pixel]s
+///
+/// Fails if the slice isn't aligned for, or its length isn't a multiple of, the [Pixel] type.
+pub fn cast_mut<T: Pixel>(bytes: &mut [u8]) -> Result<&mut [T]> {
+    // SAFETY: Pixel is only implemented for integer types, for which any bit pattern is valid.
+    let (prefix, pixels, suffix) = unsafe { bytes.align_to_mut::<T>() };
+
+    if !prefix.is_empty() || !suffix.is_empty() {
+        return Err(Error::PixelLayout);
+    }
+
+    Ok(pixels)
+}
+
+/// Fills a line of `bpp` bits per pixel with `color`
+///
+/// The DRM formats are little-endian, so the lower bytes of `color` are stored first, and its
+/// upper bytes are dropped for the formats with less than 32 bits per pixel.
+pub fn fill_row(row: &mut [u8], bpp: usize, color: u32) -> Result<()> {
+    if bpp == 0 || bpp > 32 || !bpp.is_multiple_of(8) {
+        return Err(Error::PixelLayout);
+    }
+
+    let cpp = bpp / 8;
+    let bytes = color.to_le_bytes();
+    for pixel in row.chunks_exact_mut(cpp) {
+        pixel.copy_from_slice(&bytes[..cpp]);
+    }
+
+    Ok(())
+}
+
+/// Returns the blue, green, red and alpha components of the pixel at the start of `src`
+///
+/// The pixels without an alpha channel are returned as opaque.
+pub fn read_pixel(src: &[u8], format: Format) -> [u8; 4] {
+    match format {
+        Format::RGB888 | Format::XRGB8888 => [src[0], src[1], src[2], u8::MAX],
+        Format::ARGB8888 => [src[0], src[1], src[2], src[3]],
+
+        // NOTE: We only read the luma plane of the YUV formats, which gives a grayscale image.
+        Format::NV12 | Format::YUV420 => [src[0], src[0], src[0], u8::MAX],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cast_mut, fill_row, read_pixel};
+    use crate::Format;
+
+    #[test]
+    fn test_cast() {
+        let mut data = [0_u8; 20];
+        let offset = data.as_ptr().align_offset(4);
+        let bytes = &mut data[offset..offset + 16];
+
+        cast_mut::<u32>(bytes).unwrap()[1] = 0x1122_3344;
+        assert_eq!(&bytes[4..8], &0x1122_3344_u32.to_ne_bytes());
+
+        assert!(cast_mut::<u32>(&mut bytes[1..5]).is_err());
+        assert!(cast_mut::<u32>(&mut bytes[..6]).is_err());
+        assert_eq!(cast_mut::<u16>(&mut bytes[..6]).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_fill_row() {
+        let mut row = [0_u8; 7];
+
+        fill_row(&mut row, 24, 0x0011_2233).unwrap();
+        assert_eq!(row, [0x33, 0x22, 0x11, 0x33, 0x22, 0x11, 0x00]);
+
+        fill_row(&mut row, 16, 0xabcd).unwrap();
+        assert_eq!(row, [0xcd, 0xab, 0xcd, 0xab, 0xcd, 0xab, 0x00]);
+
+        assert!(fill_row(&mut row, 12, 0).is_err());
+        assert!(fill_row(&mut row, 64, 0).is_err());
+    }
+
+    #[test]
+    fn test_read_pixel() {
+        let src = [0x11, 0x22, 0x33, 0x44];
+
+        assert_eq!(read_pixel(&src, Format::XRGB8888), [0x11, 0x22, 0x33, 0xff]);
+        assert_eq!(read_pixel(&src, Format::RGB888), [0x11, 0x22, 0x33, 0xff]);
+        assert_eq!(read_pixel(&src, Format::ARGB8888), [0x11, 0x22, 0x33, 0x44]);
+        assert_eq!(read_pixel(&src, Format::NV12), [0x11, 0x11, 0x11, 0xff]);
+    }
+}
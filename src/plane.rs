@@ -1,21 +1,23 @@
 use std::{
-    cell::RefCell,
-    convert::{TryFrom, TryInto},
-    rc::{Rc, Weak},
+    convert::TryFrom,
+    ops::{BitOr, BitOrAssign},
+    sync::{Arc, Weak},
 };
 
 use num_enum::TryFromPrimitive;
 
 use crate::{
+    buffer::AdoptedFramebuffer,
     device::Inner,
-    object::{Object, Type as ObjectType},
+    modifier::parse_in_formats,
+    object::{impl_object_identity, Object, Type as ObjectType},
     raw::drm_mode_get_plane,
-    Device, Error, Format, Property, Result,
+    Device, Error, Format, Modifier, Properties, PropertyValue, Result,
 };
 
 /// The [Plane] types
-#[derive(Debug, Eq, PartialEq, TryFromPrimitive)]
-#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, TryFromPrimitive)]
+#[repr(u64)]
 pub enum Type {
     /// The [Plane] is an overlay, aka a sprite. Any plane that is neither a primary nor a cursor
     /// plane
@@ -28,28 +30,257 @@ pub enum Type {
     Cursor,
 }
 
+/// The position of the chroma samples relative to the luma samples, along one axis
+///
+/// This only matters for the YUV formats with a subsampled chroma.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChromaSiting {
+    /// The chroma samples are aligned with the even luma samples
+    Cosited,
+
+    /// The chroma samples are halfway between two luma samples
+    Midpoint,
+}
+
+impl ChromaSiting {
+    const ALL: [Self; 2] = [Self::Cosited, Self::Midpoint];
+
+    /// Returns the name of the `CHROMA_SITING_H` and `CHROMA_SITING_V` property entry for this
+    /// [`ChromaSiting`]
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Cosited => "Cosited",
+            Self::Midpoint => "Midpoint",
+        }
+    }
+}
+
+/// How the pixels of a [Plane] are blended with the content below it
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PixelBlendMode {
+    /// The alpha channel of the pixels is ignored, and only the [Plane] global alpha is applied
+    None,
+
+    /// The color channels of the pixels have already been multiplied by their alpha
+    Premultiplied,
+
+    /// The color channels of the pixels haven't been multiplied by their alpha
+    Coverage,
+}
+
+impl PixelBlendMode {
+    const ALL: [Self; 3] = [Self::None, Self::Premultiplied, Self::Coverage];
+
+    /// Returns the name of the `pixel blend mode` property entry for this [`PixelBlendMode`]
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Premultiplied => "Pre-multiplied",
+            Self::Coverage => "Coverage",
+        }
+    }
+}
+
+/// The standard used to convert the YUV pixels of a [Plane] to RGB
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorEncoding {
+    /// ITU-R BT.601, used by the standard definition content
+    Bt601,
+
+    /// ITU-R BT.709, used by the high definition content
+    Bt709,
+
+    /// ITU-R BT.2020, used by the ultra high definition content
+    Bt2020,
+}
+
+impl ColorEncoding {
+    const ALL: [Self; 3] = [Self::Bt601, Self::Bt709, Self::Bt2020];
+
+    /// Returns the name of the `COLOR_ENCODING` property entry for this [`ColorEncoding`]
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Bt601 => "ITU-R BT.601 YCbCr",
+            Self::Bt709 => "ITU-R BT.709 YCbCr",
+            Self::Bt2020 => "ITU-R BT.2020 YCbCr",
+        }
+    }
+}
+
+/// The range of the values of the YUV pixels of a [Plane]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorRange {
+    /// The luma is between 16 and 235, and the chroma between 16 and 240, for 8 bits formats
+    Limited,
+
+    /// The components use the whole range of values
+    Full,
+}
+
+impl ColorRange {
+    const ALL: [Self; 2] = [Self::Limited, Self::Full];
+
+    /// Returns the name of the `COLOR_RANGE` property entry for this [`ColorRange`]
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Limited => "YCbCr limited range",
+            Self::Full => "YCbCr full range",
+        }
+    }
+}
+
+/// The rotation to apply to the content of a [Plane], counter-clockwise
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "config", serde(rename_all = "kebab-case"))]
+pub enum Rotation {
+    /// No rotation
+    #[default]
+    Rotate0,
+
+    /// Rotation by 90 degrees
+    Rotate90,
+
+    /// Rotation by 180 degrees
+    Rotate180,
+
+    /// Rotation by 270 degrees
+    Rotate270,
+}
+
+impl Rotation {
+    /// Returns the name of the `rotation` property bit for this [Rotation]
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Rotate0 => "rotate-0",
+            Self::Rotate90 => "rotate-90",
+            Self::Rotate180 => "rotate-180",
+            Self::Rotate270 => "rotate-270",
+        }
+    }
+}
+
+/// The reflections to apply to the content of a [Plane]
+///
+/// The reflections can be combined with the `|` operator, and are applied before the
+/// [Rotation].
+///
+/// # Example
+///
+/// ```
+/// use nucleid::Reflection;
+///
+/// let flip = Reflection::X | Reflection::Y;
+/// assert!(flip.contains(Reflection::X));
+/// assert!(!Reflection::empty().contains(Reflection::Y));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Reflection(u8);
+
+impl Reflection {
+    /// Mirrors the content along the X axis, swapping the left and right sides
+    pub const X: Self = Self(1 << 0);
+
+    /// Mirrors the content along the Y axis, swapping the top and bottom sides
+    pub const Y: Self = Self(1 << 1);
+
+    /// Returns a [`Reflection`] without any reflection set
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns whether no reflection is set
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether all the reflections of `other` are set
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    /// Returns the reflections set in either `self` or `other`
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Returns the names of the `rotation` property bits for this [Reflection]
+    fn names(self) -> impl Iterator<Item = &'static str> {
+        [(Self::X, "reflect-x"), (Self::Y, "reflect-y")]
+            .iter()
+            .filter(move |(reflection, _)| self.contains(*reflection))
+            .map(|(_, name)| *name)
+    }
+}
+
+impl BitOr for Reflection {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for Reflection {
+    fn bitor_assign(&mut self, other: Self) {
+        *self = self.union(other);
+    }
+}
+
+/// Computes the value of a `rotation` property from its bits advertised by the kernel
+///
+/// Returns `None` if one of the requested bits isn't part of `entries`.
+fn rotation_value(
+    entries: &[(String, u64)],
+    rotation: Rotation,
+    reflection: Reflection,
+) -> Option<u64> {
+    std::iter::once(rotation.name())
+        .chain(reflection.names())
+        .try_fold(0, |value, name| {
+            let (_, bit) = entries.iter().find(|(entry, _)| entry == name)?;
+
+            (*bit < 64).then(|| value | (1 << bit))
+        })
+}
+
 /// A representation of a image source sent to the CRTC
 ///
 /// A Plane represents an image that will be blended by the CRTC during the scanout.
+///
+/// Two [Plane]s are equal if they represent the same plane of the same [Device], so they can be
+/// used as keys in a [`HashMap`](std::collections::HashMap).
 #[derive(Debug)]
 pub struct Plane {
-    dev: Weak<RefCell<Inner>>,
+    dev: Weak<Inner>,
     id: u32,
     possible_crtcs: u32,
+    kind: Type,
     formats: Vec<Format>,
 }
 
+impl_object_identity!(Plane);
+
 impl Plane {
     pub(crate) fn new(device: &Device, id: u32) -> Result<Self> {
         let mut formats = Vec::new();
         let raw_plane = drm_mode_get_plane(device, id, Some(&mut formats))?;
         let mut plane = Self {
-            dev: Rc::downgrade(&device.inner),
+            dev: Arc::downgrade(&device.inner),
             id,
             possible_crtcs: raw_plane.possible_crtcs,
+            kind: Type::Overlay,
             formats: Vec::new(),
         };
 
+        // NOTE: The plane type can't change, so we retrieve it once instead of going through
+        // the whole list of properties every time we need it.
+        plane.kind = plane.properties()?.get_enum("type").ok_or(Error::Empty)?;
+
         for raw_fmt in formats {
             let fmt = Format::try_from(raw_fmt);
 
@@ -93,7 +324,54 @@ impl Plane {
         }
     }
 
-    /// Returns a list of the [Properties](Property) available
+    /// Returns the [Formats](Format) supported by this plane, along with their [Modifier]s
+    ///
+    /// The modifiers are retrieved from the `IN_FORMATS` property. If the [Plane] doesn't
+    /// expose it, the layout of the buffers is implicit and every [Format] is returned with an
+    /// empty list of [Modifier]s.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails, or if the
+    /// `IN_FORMATS` blob is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Format, Modifier};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| {
+    ///         plane
+    ///             .format_modifiers()
+    ///             .unwrap()
+    ///             .into_iter()
+    ///             .any(|(fmt, mods)| fmt == Format::XRGB8888 && mods.contains(&Modifier::LINEAR))
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn format_modifiers(&self) -> Result<Vec<(Format, Vec<Modifier>)>> {
+        let blob = match self.properties()?.get("IN_FORMATS") {
+            Some(prop) => prop.blob_data()?,
+            None => None,
+        };
+
+        let Some(blob) = blob else {
+            return Ok(self.formats().map(|fmt| (fmt, Vec::new())).collect());
+        };
+
+        Ok(parse_in_formats(&blob)?
+            .into_iter()
+            .filter_map(|(raw_fmt, modifiers)| {
+                Format::try_from(raw_fmt).ok().map(|fmt| (fmt, modifiers))
+            })
+            .collect())
+    }
+
+    /// Returns the [Properties] available
     ///
     /// # Errors
     ///
@@ -118,15 +396,46 @@ impl Plane {
     ///     })
     ///     .unwrap();
     /// ```
-    pub fn properties(&self) -> Result<Vec<Property>> {
+    pub fn properties(&self) -> Result<Properties> {
         Object::properties(self)
     }
 
-    /// Returns the [Plane] [Type]
+    /// Returns the [Framebuffer](crate::Framebuffer) currently scanned out by the [Plane], if any
     ///
-    /// # Panics
+    /// This allows to keep the content set up by a boot splash or the firmware console on
+    /// screen until the first frame of the application is ready. See [`AdoptedFramebuffer`] for
+    /// more details.
     ///
-    /// If the properties retrieval ioctl fails, or if the plane type property isn't found.
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.plane_type() == PlaneType::Primary)
+    ///     .unwrap();
+    ///
+    /// let boot_fb = plane.adopt_framebuffer().unwrap();
+    /// ```
+    pub fn adopt_framebuffer(&self) -> Result<Option<AdoptedFramebuffer>> {
+        let device = self.device()?;
+        let fb_id = drm_mode_get_plane(&device, self.id, None)?.fb_id;
+
+        if fb_id == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(AdoptedFramebuffer::new(&device, fb_id)?))
+    }
+
+    /// Returns the [Plane] [Type]
     ///
     /// # Example
     ///
@@ -141,25 +450,277 @@ impl Plane {
     ///     .unwrap();
     /// ```
     #[must_use]
-    pub fn plane_type(&self) -> Type {
-        let type_prop = self
-            .properties()
-            .unwrap()
-            .into_iter()
-            .find(|prop| prop.name() == "type")
-            .unwrap();
+    pub const fn plane_type(&self) -> Type {
+        self.kind
+    }
+
+    /// Returns the [Plane] position in the stack of planes, from the bottom
+    ///
+    /// Not every driver exposes the zpos property, so if it's missing the position is derived
+    /// from the [Plane] [Type]: the primary plane is at the bottom, the overlays above it, and
+    /// the cursor plane at the top.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .min_by_key(|plane| plane.zpos())
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn zpos(&self) -> u64 {
+        self.properties()
+            .ok()
+            .and_then(|props| props.get_u64("zpos"))
+            .unwrap_or_else(|| match self.plane_type() {
+                Type::Primary => 0,
+                Type::Overlay => 1,
+                Type::Cursor => u64::MAX,
+            })
+    }
+
+    /// Returns the lowest and highest zpos the [Plane] can be given
+    ///
+    /// Returns `None` if the [Plane] doesn't have a zpos property, or if it can't be changed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.zpos_range().is_some())
+    ///     .unwrap();
+    ///
+    /// let (min, max) = plane.zpos_range().unwrap();
+    /// ```
+    #[must_use]
+    pub fn zpos_range(&self) -> Option<(u64, u64)> {
+        let properties = self.properties().ok()?;
+        let property = properties.get("zpos")?;
+
+        if property.is_immutable() {
+            return None;
+        }
+
+        match *property.typed_value() {
+            PropertyValue::Range { min, max } => Some((min, max)),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the [Plane] has a global alpha
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.supports_alpha())
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn supports_alpha(&self) -> bool {
+        self.property_id("alpha").is_some()
+    }
+
+    /// Returns the [`PixelBlendMode`]s supported by the [Plane]
+    ///
+    /// The [Plane]s without the `pixel blend mode` property are considered to only support
+    /// [`PixelBlendMode::Premultiplied`], like the kernel does.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PixelBlendMode};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.blend_modes().contains(&PixelBlendMode::Coverage))
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn blend_modes(&self) -> Vec<PixelBlendMode> {
+        if self.property_id("pixel blend mode").is_none() {
+            return vec![PixelBlendMode::Premultiplied];
+        }
 
-        // NOTE: the plane type returned by the kernel is an enum between 0 and 2. If we have
-        // something that underflows or overflows an u32, we have a serious issue.
-        let val: u32 = type_prop.value().try_into().unwrap();
+        self.enum_entries(
+            "pixel blend mode",
+            &PixelBlendMode::ALL,
+            PixelBlendMode::name,
+        )
+    }
 
-        Type::try_from(val).unwrap()
+    /// Returns the [`ColorEncoding`]s supported by the [Plane]
+    ///
+    /// Returns an empty list if the [Plane] doesn't have the `COLOR_ENCODING` property.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ColorEncoding, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.color_encodings().contains(&ColorEncoding::Bt709))
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn color_encodings(&self) -> Vec<ColorEncoding> {
+        self.enum_entries("COLOR_ENCODING", &ColorEncoding::ALL, ColorEncoding::name)
+    }
+
+    /// Returns the [`ColorRange`]s supported by the [Plane]
+    ///
+    /// Returns an empty list if the [Plane] doesn't have the `COLOR_RANGE` property.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ColorRange, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.color_ranges().contains(&ColorRange::Full))
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn color_ranges(&self) -> Vec<ColorRange> {
+        self.enum_entries("COLOR_RANGE", &ColorRange::ALL, ColorRange::name)
+    }
+
+    /// Returns the `candidates` whose name is an entry of the enum property called `property`
+    fn enum_entries<T: Copy>(
+        &self,
+        property: &str,
+        candidates: &[T],
+        name: impl Fn(T) -> &'static str,
+    ) -> Vec<T> {
+        let Some(properties) = self.properties().ok() else {
+            return Vec::new();
+        };
+
+        let Some(property) = properties.get(property) else {
+            return Vec::new();
+        };
+
+        candidates
+            .iter()
+            .copied()
+            .filter(|candidate| {
+                property
+                    .typed_value()
+                    .enum_value(name(*candidate))
+                    .is_some()
+            })
+            .collect()
+    }
+
+    /// Returns the current horizontal and vertical [`ChromaSiting`] of the [Plane]
+    ///
+    /// Returns `None` if the [Plane] doesn't have the `CHROMA_SITING_H` and `CHROMA_SITING_V`
+    /// properties, or if their values are unknown.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Device;
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.chroma_siting().is_some())
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn chroma_siting(&self) -> Option<(ChromaSiting, ChromaSiting)> {
+        let properties = self.properties().ok()?;
+
+        let siting = |name| {
+            let property = properties.get(name)?;
+
+            ChromaSiting::ALL.iter().copied().find(|siting| {
+                property.typed_value().enum_value(siting.name()) == Some(property.value())
+            })
+        };
+
+        Some((siting("CHROMA_SITING_H")?, siting("CHROMA_SITING_V")?))
+    }
+
+    /// Returns whether the [Plane] supports a [Rotation] along with a [Reflection]
+    ///
+    /// The [Plane]s without the `rotation` property only support [`Rotation::Rotate0`] without
+    /// any [Reflection]. This only checks the values advertised by the kernel, so the driver
+    /// might still reject some combinations with some [Format]s or [Modifier]s.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Reflection, Rotation};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.supports_rotation(Rotation::Rotate180, Reflection::X))
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn supports_rotation(&self, rotation: Rotation, reflection: Reflection) -> bool {
+        self.rotation_value(rotation, reflection).is_ok()
+    }
+
+    /// Returns the value of the `rotation` property for a [Rotation] and a [Reflection]
+    ///
+    /// Returns `None` if the [Plane] doesn't have a `rotation` property, and none is needed.
+    pub(crate) fn rotation_value(
+        &self,
+        rotation: Rotation,
+        reflection: Reflection,
+    ) -> Result<Option<u64>> {
+        let properties = self.properties()?;
+
+        let Some(property) = properties.get("rotation") else {
+            if rotation == Rotation::Rotate0 && reflection.is_empty() {
+                return Ok(None);
+            }
+
+            return Err(Error::Unsupported("Rotation"));
+        };
+
+        let PropertyValue::Bitmask { entries } = property.typed_value() else {
+            return Err(Error::Unsupported("Rotation"));
+        };
+
+        rotation_value(entries, rotation, reflection)
+            .map(Some)
+            .ok_or(Error::Unsupported("Rotation"))
     }
 }
 
 impl Object for Plane {
     fn device(&self) -> Result<Device> {
-        Ok(self.dev.upgrade().ok_or(Error::Empty)?.into())
+        Ok(self.dev.upgrade().ok_or(Error::DeviceGone)?.into())
     }
 
     fn object_id(&self) -> u32 {
@@ -176,10 +737,51 @@ pub struct Formats<'a> {
     iter: std::slice::Iter<'a, Format>,
 }
 
-impl<'a> Iterator for Formats<'a> {
+impl Iterator for Formats<'_> {
     type Item = Format;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next().copied()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{rotation_value, Reflection, Rotation};
+
+    fn entries() -> Vec<(String, u64)> {
+        [
+            "rotate-0",
+            "rotate-90",
+            "rotate-180",
+            "rotate-270",
+            "reflect-x",
+        ]
+        .iter()
+        .zip([0, 1, 2, 3, 4].iter())
+        .map(|(name, bit)| ((*name).to_string(), *bit))
+        .collect()
+    }
+
+    #[test]
+    fn test_rotation_value() {
+        let entries = entries();
+
+        assert_eq!(
+            rotation_value(&entries, Rotation::Rotate0, Reflection::empty()),
+            Some(1)
+        );
+        assert_eq!(
+            rotation_value(&entries, Rotation::Rotate270, Reflection::X),
+            Some((1 << 3) | (1 << 4))
+        );
+        assert_eq!(
+            rotation_value(&entries, Rotation::Rotate90, Reflection::X | Reflection::Y),
+            None
+        );
+        assert_eq!(
+            rotation_value(&entries[..1], Rotation::Rotate180, Reflection::empty()),
+            None
+        );
+    }
+}
@@ -1,27 +1,240 @@
-use crate::{raw::drm_mode_get_property, Device, Result};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    iter::FromIterator,
+    sync::{Arc, Weak},
+};
+
+use crate::{
+    device::Inner,
+    raw::{
+        drm_mode_get_property_blob, drm_mode_get_property_with_values, drm_mode_property_enum,
+        DRM_MODE_PROP_ATOMIC, DRM_MODE_PROP_BITMASK, DRM_MODE_PROP_BLOB, DRM_MODE_PROP_ENUM,
+        DRM_MODE_PROP_EXTENDED_TYPE, DRM_MODE_PROP_IMMUTABLE, DRM_MODE_PROP_OBJECT,
+        DRM_MODE_PROP_RANGE, DRM_MODE_PROP_SIGNED_RANGE,
+    },
+    Device, Error, Result,
+};
+
+/// The Type of a [Property], Along With the Values it Accepts
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PropertyValue {
+    /// An unsigned integer between `min` and `max`, inclusive
+    Range {
+        /// The lowest accepted value
+        min: u64,
+
+        /// The highest accepted value
+        max: u64,
+    },
+
+    /// A signed integer between `min` and `max`, inclusive
+    SignedRange {
+        /// The lowest accepted value
+        min: i64,
+
+        /// The highest accepted value
+        max: i64,
+    },
+
+    /// One of the named values in `entries`
+    Enum {
+        /// The names of the accepted values, and their value
+        entries: Vec<(String, u64)>,
+    },
+
+    /// A combination of the named bits in `entries`
+    Bitmask {
+        /// The names of the accepted bits, and their index
+        entries: Vec<(String, u64)>,
+    },
+
+    /// The ID of the blob currently set, 0 if there's none
+    Blob(u32),
+
+    /// A KMS object ID, the payload being the raw type of the objects accepted, such as
+    /// `0xcccccccc` for the CRTCs
+    Object(u32),
+}
+
+impl PropertyValue {
+    fn new(
+        flags: u32,
+        value: u64,
+        values: &[u64],
+        enums: &[drm_mode_property_enum],
+    ) -> Result<Self> {
+        let entries = || {
+            enums
+                .iter()
+                .map(|entry| {
+                    let name = std::str::from_utf8(&entry.name)?.trim_end_matches(char::from(0));
+
+                    Ok((name.to_string(), entry.value))
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+
+        let bounds = || match values {
+            [min, max] => Ok((*min, *max)),
+            _ => Err(Error::MalformedProperty),
+        };
+
+        if (flags & DRM_MODE_PROP_RANGE) != 0 {
+            let (min, max) = bounds()?;
+            return Ok(Self::Range { min, max });
+        }
+
+        if (flags & DRM_MODE_PROP_ENUM) != 0 {
+            return Ok(Self::Enum {
+                entries: entries()?,
+            });
+        }
+
+        if (flags & DRM_MODE_PROP_BITMASK) != 0 {
+            return Ok(Self::Bitmask {
+                entries: entries()?,
+            });
+        }
+
+        if (flags & DRM_MODE_PROP_BLOB) != 0 {
+            return Ok(Self::Blob(u32::try_from(value)?));
+        }
+
+        match flags & DRM_MODE_PROP_EXTENDED_TYPE {
+            DRM_MODE_PROP_SIGNED_RANGE => {
+                let (min, max) = bounds()?;
+
+                // NOTE: The kernel stores the signed bounds in the same u64 array, so they
+                // need to be reinterpreted.
+                #[allow(clippy::cast_possible_wrap)]
+                let (min, max) = (min as i64, max as i64);
+
+                Ok(Self::SignedRange { min, max })
+            }
+            DRM_MODE_PROP_OBJECT => Ok(Self::Object(u32::try_from(
+                values.first().copied().ok_or(Error::MalformedProperty)?,
+            )?)),
+            _ => Err(Error::Unsupported("Property Type")),
+        }
+    }
+
+    /// Returns whether `value` would be accepted by the [Property]
+    ///
+    /// The blob and object properties accept any value, the kernel being the only one to know
+    /// which IDs are valid.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.plane_type() == PlaneType::Primary)
+    ///     .unwrap();
+    ///
+    /// let properties = plane.properties().unwrap();
+    /// let alpha = properties.get("alpha").unwrap();
+    /// assert!(alpha.typed_value().accepts(0xffff));
+    /// ```
+    #[must_use]
+    pub fn accepts(&self, value: u64) -> bool {
+        match self {
+            Self::Range { min, max } => (*min..=*max).contains(&value),
+            #[allow(clippy::cast_possible_wrap)]
+            Self::SignedRange { min, max } => (*min..=*max).contains(&(value as i64)),
+            Self::Enum { entries } => entries.iter().any(|(_, val)| *val == value),
+            Self::Bitmask { entries } => {
+                let mask = entries
+                    .iter()
+                    .filter(|(_, bit)| *bit < 64)
+                    .fold(0, |mask, (_, bit)| mask | (1 << bit));
+
+                (value & !mask) == 0
+            }
+            Self::Blob(_) | Self::Object(_) => true,
+        }
+    }
+
+    /// Returns the value of the enum entry called `name`
+    ///
+    /// Returns `None` if the [Property] isn't an enum, or if it doesn't have such an entry.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.plane_type() == PlaneType::Primary)
+    ///     .unwrap();
+    ///
+    /// let properties = plane.properties().unwrap();
+    /// let blend_mode = properties.get("pixel blend mode").unwrap();
+    /// let coverage = blend_mode.typed_value().enum_value("Coverage").unwrap();
+    /// ```
+    #[must_use]
+    pub fn enum_value(&self, name: &str) -> Option<u64> {
+        let Self::Enum { entries } = self else {
+            return None;
+        };
+
+        entries
+            .iter()
+            .find(|(entry, _)| entry == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Returns the name of the enum entry whose value is `value`
+    ///
+    /// Returns `None` if the [Property] isn't an enum, or if it doesn't have such an entry.
+    #[must_use]
+    pub fn enum_name(&self, value: u64) -> Option<&str> {
+        let Self::Enum { entries } = self else {
+            return None;
+        };
+
+        entries
+            .iter()
+            .find(|(_, val)| *val == value)
+            .map(|(name, _)| name.as_str())
+    }
+}
 
 /// A KMS property
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct Property {
+    dev: Weak<Inner>,
     object_id: u32,
     id: u32,
     name: String,
     value: u64,
+    flags: u32,
+    typed_value: PropertyValue,
 }
 
 impl Property {
     pub(crate) fn new(device: &Device, object_id: u32, id: u32, value: u64) -> Result<Self> {
-        let property = drm_mode_get_property(device, id)?;
+        let (property, values, enums) = drm_mode_get_property_with_values(device, id)?;
         let name = std::str::from_utf8(&property.name)?
             .trim_end_matches(char::from(0))
             .to_string();
 
         Ok(Self {
+            dev: Arc::downgrade(&device.inner),
             object_id,
             id,
             name,
             value,
+            flags: property.flags,
+            typed_value: PropertyValue::new(property.flags, value, &values, &enums)?,
         })
     }
 
@@ -83,4 +296,307 @@ impl Property {
     pub const fn value(&self) -> u64 {
         self.value
     }
+
+    /// Returns the type of the [Property], along with the values it accepts
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PropertyValue};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// for plane in device.planes() {
+    ///     for property in plane.properties().unwrap() {
+    ///         if let PropertyValue::Enum { entries } = property.typed_value() {
+    ///             for (name, value) in entries {
+    ///                 println!("{}: {} = {}", property.name(), name, value);
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn typed_value(&self) -> &PropertyValue {
+        &self.typed_value
+    }
+
+    /// Returns the content of the blob set to the [Property]
+    ///
+    /// Returns `None` if the [Property] isn't a blob, or if no blob is set.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let properties = connector.properties().unwrap();
+    /// let edid = properties
+    ///     .get("EDID")
+    ///     .unwrap()
+    ///     .blob_data()
+    ///     .unwrap();
+    /// ```
+    pub fn blob_data(&self) -> Result<Option<Vec<u8>>> {
+        let PropertyValue::Blob(blob_id) = self.typed_value else {
+            return Ok(None);
+        };
+
+        if blob_id == 0 {
+            return Ok(None);
+        }
+
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        Ok(Some(drm_mode_get_property_blob(&device, blob_id)?))
+    }
+
+    /// Returns whether the [Property] can only be read
+    #[must_use]
+    pub const fn is_immutable(&self) -> bool {
+        (self.flags & DRM_MODE_PROP_IMMUTABLE) != 0
+    }
+
+    /// Returns whether the [Property] can only be set through atomic commits
+    #[must_use]
+    pub const fn is_atomic(&self) -> bool {
+        (self.flags & DRM_MODE_PROP_ATOMIC) != 0
+    }
+}
+
+/// The [Properties](Property) of a KMS object, indexed by name
+#[derive(Debug, Default)]
+pub struct Properties {
+    properties: HashMap<String, Property>,
+}
+
+impl Properties {
+    /// Returns the [Property] called `name`, if any
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Property> {
+        self.properties.get(name)
+    }
+
+    /// Returns whether the object has a [Property] called `name`
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.properties.contains_key(name)
+    }
+
+    /// Returns the value of the [Property] called `name`, if any
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .find(|plane| plane.plane_type() == PlaneType::Primary)
+    ///     .unwrap();
+    ///
+    /// let crtc_id = plane
+    ///     .properties()
+    ///     .unwrap()
+    ///     .get_u64("CRTC_ID")
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn get_u64(&self, name: &str) -> Option<u64> {
+        self.get(name).map(Property::value)
+    }
+
+    /// Returns the value of the enum [Property] called `name`, converted to `T`
+    ///
+    /// Returns `None` if there's no such [Property], or if its value isn't a valid `T`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let plane = device.planes()
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let plane_type: PlaneType = plane
+    ///     .properties()
+    ///     .unwrap()
+    ///     .get_enum("type")
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn get_enum<T: TryFrom<u64>>(&self, name: &str) -> Option<T> {
+        T::try_from(self.get_u64(name)?).ok()
+    }
+
+    /// Returns an iterator over the [Properties](Property), in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Property> {
+        self.properties.values()
+    }
+
+    /// Returns the number of [Properties](Property)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.properties.len()
+    }
+
+    /// Returns whether there's no [Property] at all
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+}
+
+impl FromIterator<Property> for Properties {
+    fn from_iter<I: IntoIterator<Item = Property>>(iter: I) -> Self {
+        Self {
+            properties: iter
+                .into_iter()
+                .map(|prop| (prop.name.clone(), prop))
+                .collect(),
+        }
+    }
+}
+
+impl IntoIterator for Properties {
+    type Item = Property;
+    type IntoIter = std::collections::hash_map::IntoValues<String, Property>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.properties.into_values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Weak;
+
+    use super::{Properties, Property, PropertyValue};
+    use crate::{
+        raw::{
+            drm_mode_property_enum, DRM_MODE_PROP_ATOMIC, DRM_MODE_PROP_BITMASK,
+            DRM_MODE_PROP_BLOB, DRM_MODE_PROP_ENUM, DRM_MODE_PROP_OBJECT, DRM_MODE_PROP_RANGE,
+            DRM_MODE_PROP_SIGNED_RANGE,
+        },
+        PlaneType,
+    };
+
+    fn property(id: u32, name: &str, value: u64) -> Property {
+        Property {
+            dev: Weak::new(),
+            object_id: 42,
+            id,
+            name: name.to_string(),
+            value,
+            flags: DRM_MODE_PROP_RANGE,
+            typed_value: PropertyValue::Range {
+                min: 0,
+                max: u64::MAX,
+            },
+        }
+    }
+
+    fn entry(name: &str, value: u64) -> drm_mode_property_enum {
+        let mut entry = drm_mode_property_enum {
+            value,
+            ..drm_mode_property_enum::default()
+        };
+        entry.name[..name.len()].copy_from_slice(name.as_bytes());
+        entry
+    }
+
+    #[test]
+    fn test_properties() {
+        let properties: Properties = vec![property(1, "type", 1), property(2, "zpos", 5)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(properties.len(), 2);
+        assert_eq!(properties.get("zpos").map(Property::id), Some(2));
+        assert_eq!(properties.get_u64("zpos"), Some(5));
+        assert_eq!(properties.get_u64("alpha"), None);
+        assert_eq!(properties.get_enum("type"), Some(PlaneType::Primary));
+        assert_eq!(properties.get_enum::<PlaneType>("zpos"), None);
+    }
+
+    #[test]
+    fn test_property_value() {
+        let range = PropertyValue::new(DRM_MODE_PROP_RANGE, 0, &[0, 0xffff], &[]).unwrap();
+        assert_eq!(
+            range,
+            PropertyValue::Range {
+                min: 0,
+                max: 0xffff
+            }
+        );
+        assert!(range.accepts(0x8000));
+        assert!(!range.accepts(0x10000));
+
+        let signed = PropertyValue::new(
+            DRM_MODE_PROP_SIGNED_RANGE | DRM_MODE_PROP_ATOMIC,
+            0,
+            &[0xffff_ffff_ffff_fff6, 10],
+            &[],
+        )
+        .unwrap();
+        assert_eq!(signed, PropertyValue::SignedRange { min: -10, max: 10 });
+        assert!(signed.accepts(0xffff_ffff_ffff_fffb));
+        assert!(!signed.accepts(11));
+
+        let enums = PropertyValue::new(
+            DRM_MODE_PROP_ENUM,
+            1,
+            &[0, 1],
+            &[entry("Overlay", 0), entry("Primary", 1)],
+        )
+        .unwrap();
+        assert_eq!(
+            enums,
+            PropertyValue::Enum {
+                entries: vec![("Overlay".to_string(), 0), ("Primary".to_string(), 1)]
+            }
+        );
+        assert!(enums.accepts(1));
+        assert!(!enums.accepts(2));
+        assert_eq!(enums.enum_value("Primary"), Some(1));
+        assert_eq!(enums.enum_value("Cursor"), None);
+        assert_eq!(enums.enum_name(0), Some("Overlay"));
+        assert_eq!(range.enum_name(0), None);
+
+        let bitmask = PropertyValue::new(
+            DRM_MODE_PROP_BITMASK,
+            1,
+            &[0, 4],
+            &[entry("rotate-0", 0), entry("reflect-x", 4)],
+        )
+        .unwrap();
+        assert!(bitmask.accepts(0x11));
+        assert!(!bitmask.accepts(0x2));
+
+        assert_eq!(
+            PropertyValue::new(DRM_MODE_PROP_BLOB, 42, &[], &[]).unwrap(),
+            PropertyValue::Blob(42)
+        );
+        assert_eq!(
+            PropertyValue::new(DRM_MODE_PROP_OBJECT, 0, &[0xcccc_cccc], &[]).unwrap(),
+            PropertyValue::Object(0xcccc_cccc)
+        );
+    }
 }
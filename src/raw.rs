@@ -1,28 +1,151 @@
-use std::{convert::TryInto, os::unix::io::AsRawFd};
-
-use nix::{ioctl_readwrite, ioctl_write_ptr};
+use std::{
+    convert::{TryFrom, TryInto},
+    os::unix::io::{AsRawFd, FromRawFd, OwnedFd},
+    time::Duration,
+};
+
+#[cfg(all(feature = "i2c", target_os = "linux"))]
+use nix::ioctl_write_int_bad;
+use nix::{
+    ioctl_none, ioctl_readwrite, ioctl_write_ptr,
+    poll::{poll, PollFd, PollFlags},
+};
 
 use crate::Result;
 
 const DRM_IOCTL_BASE: u32 = 'd' as u32;
+#[cfg(target_os = "linux")]
+const DRM_COMMAND_BASE: u32 = 0x40;
+const DRM_IOCTL_VERSION: u32 = 0x00;
+const DRM_IOCTL_GET_UNIQUE: u32 = 0x01;
+const DRM_IOCTL_GEM_CLOSE: u32 = 0x09;
+const DRM_IOCTL_GET_CAP: u32 = 0x0c;
 const DRM_IOCTL_SET_CLIENT_CAP: u32 = 0x0d;
+const DRM_IOCTL_AUTH_MAGIC: u32 = 0x11;
+const DRM_IOCTL_SET_MASTER: u32 = 0x1e;
+const DRM_IOCTL_DROP_MASTER: u32 = 0x1f;
+const DRM_IOCTL_PRIME_HANDLE_TO_FD: u32 = 0x2d;
+const DRM_IOCTL_PRIME_FD_TO_HANDLE: u32 = 0x2e;
+const DRM_IOCTL_CRTC_GET_SEQUENCE: u32 = 0x3b;
+const DRM_IOCTL_CRTC_QUEUE_SEQUENCE: u32 = 0x3c;
 const DRM_IOCTL_MODE_GETRESOURCES: u32 = 0xa0;
 const DRM_IOCTL_MODE_GETCRTC: u32 = 0xa1;
+#[cfg(feature = "legacy")]
+const DRM_IOCTL_MODE_SETCRTC: u32 = 0xa2;
+const DRM_IOCTL_MODE_CURSOR: u32 = 0xa3;
+const DRM_IOCTL_MODE_SETGAMMA: u32 = 0xa5;
 const DRM_IOCTL_MODE_GETENCODER: u32 = 0xa6;
 const DRM_IOCTL_MODE_GETCONNECTOR: u32 = 0xa7;
 const DRM_IOCTL_MODE_GETPROPERTY: u32 = 0xaa;
+const DRM_IOCTL_MODE_GETPROPBLOB: u32 = 0xac;
 const DRM_IOCTL_MODE_RMFB: u32 = 0xaf;
+#[cfg(feature = "legacy")]
+const DRM_IOCTL_MODE_PAGE_FLIP: u32 = 0xb0;
+const DRM_IOCTL_MODE_DIRTYFB: u32 = 0xb1;
 const DRM_IOCTL_MODE_CREATE_DUMB: u32 = 0xb2;
 const DRM_IOCTL_MODE_MAP_DUMB: u32 = 0xb3;
-const DRM_IOCTL_MODE_DESTROY_DUMB: u32 = 0xb4;
 const DRM_IOCTL_MODE_GETPLANERESOURCES: u32 = 0xb5;
 const DRM_IOCTL_MODE_GETPLANE: u32 = 0xb6;
+#[cfg(feature = "legacy")]
+const DRM_IOCTL_MODE_SETPLANE: u32 = 0xb7;
 const DRM_IOCTL_MODE_ADDFB2: u32 = 0xb8;
 const DRM_IOCTL_MODE_OBJ_GETPROPERTIES: u32 = 0xb9;
+#[cfg(feature = "legacy")]
+const DRM_IOCTL_MODE_OBJ_SETPROPERTY: u32 = 0xba;
+const DRM_IOCTL_MODE_CURSOR2: u32 = 0xbb;
 const DRM_IOCTL_MODE_ATOMIC: u32 = 0xbc;
 const DRM_IOCTL_MODE_CREATEPROPBLOB: u32 = 0xbd;
-
-#[derive(Clone, Copy, Debug, Default)]
+const DRM_IOCTL_MODE_DESTROYPROPBLOB: u32 = 0xbe;
+const DRM_IOCTL_MODE_GETFB2: u32 = 0xce;
+const DRM_IOCTL_MODE_CLOSEFB: u32 = 0xd0;
+#[cfg(target_os = "linux")]
+const DRM_IOCTL_VGEM_FENCE_ATTACH: u32 = DRM_COMMAND_BASE + 0x01;
+#[cfg(target_os = "linux")]
+const DRM_IOCTL_VGEM_FENCE_SIGNAL: u32 = DRM_COMMAND_BASE + 0x02;
+
+const DMA_BUF_BASE: u32 = 'b' as u32;
+const DMA_BUF_IOCTL_SYNC: u32 = 0x00;
+
+pub const DMA_BUF_SYNC_READ: u64 = 1 << 0;
+pub const DMA_BUF_SYNC_WRITE: u64 = 1 << 1;
+pub const DMA_BUF_SYNC_RW: u64 = DMA_BUF_SYNC_READ | DMA_BUF_SYNC_WRITE;
+pub const DMA_BUF_SYNC_START: u64 = 0 << 2;
+pub const DMA_BUF_SYNC_END: u64 = 1 << 2;
+
+#[cfg(target_os = "linux")]
+const DMA_HEAP_IOC_MAGIC: u32 = 'H' as u32;
+#[cfg(target_os = "linux")]
+const DMA_HEAP_IOCTL_ALLOC: u32 = 0x00;
+
+#[cfg(all(feature = "i2c", target_os = "linux"))]
+const I2C_SLAVE: u32 = 0x0703;
+
+#[cfg(all(feature = "i2c", target_os = "linux"))]
+pub const DDC_CI_ADDRESS: u16 = 0x37;
+
+pub const DRM_MODE_PROP_RANGE: u32 = 1 << 1;
+pub const DRM_MODE_PROP_IMMUTABLE: u32 = 1 << 2;
+pub const DRM_MODE_PROP_ENUM: u32 = 1 << 3;
+/// Property type flag of the blob properties
+pub const DRM_MODE_PROP_BLOB: u32 = 1 << 4;
+pub const DRM_MODE_PROP_BITMASK: u32 = 1 << 5;
+pub const DRM_MODE_PROP_EXTENDED_TYPE: u32 = 0x0000_ffc0;
+pub const DRM_MODE_PROP_OBJECT: u32 = 1 << 6;
+pub const DRM_MODE_PROP_SIGNED_RANGE: u32 = 2 << 6;
+pub const DRM_MODE_PROP_ATOMIC: u32 = 0x8000_0000;
+
+pub const DRM_CRTC_SEQUENCE_NEXT_ON_MISS: u32 = 0x02;
+
+/// Event type sent when a vblank requested through the legacy vblank API occurs
+pub const DRM_EVENT_VBLANK: u32 = 0x01;
+/// Event type sent when a page flip or an atomic commit completes
+pub const DRM_EVENT_FLIP_COMPLETE: u32 = 0x02;
+/// Event type sent when a queued CRTC sequence is reached
+pub const DRM_EVENT_CRTC_SEQUENCE: u32 = 0x03;
+
+pub const DRM_MODE_CURSOR_BO: u32 = 0x01;
+pub const DRM_MODE_CURSOR_MOVE: u32 = 0x02;
+
+/// Requests an event once the commit has completed
+pub const DRM_MODE_PAGE_FLIP_EVENT: u32 = 0x01;
+
+/// Only checks the commit, without applying it
+pub const DRM_MODE_ATOMIC_TEST_ONLY: u32 = 0x0100;
+/// Returns without waiting for the commit to complete
+pub const DRM_MODE_ATOMIC_NONBLOCK: u32 = 0x0200;
+/// Allows the commit to perform a full modeset
+pub const DRM_MODE_ATOMIC_ALLOW_MODESET: u32 = 0x0400;
+
+/// Uses the format modifiers set for each plane of the framebuffer
+pub const DRM_MODE_FB_MODIFIERS: u32 = 1 << 1;
+
+/// The horizontal sync pulse of the mode is active high
+pub const DRM_MODE_FLAG_PHSYNC: u32 = 1 << 0;
+/// The horizontal sync pulse of the mode is active low
+pub const DRM_MODE_FLAG_NHSYNC: u32 = 1 << 1;
+/// The vertical sync pulse of the mode is active high
+pub const DRM_MODE_FLAG_PVSYNC: u32 = 1 << 2;
+/// The vertical sync pulse of the mode is active low
+pub const DRM_MODE_FLAG_NVSYNC: u32 = 1 << 3;
+/// The mode is interlaced
+pub const DRM_MODE_FLAG_INTERLACE: u32 = 1 << 4;
+/// Each line of the mode is scanned out twice
+pub const DRM_MODE_FLAG_DBLSCAN: u32 = 1 << 5;
+/// The bits holding the stereoscopic 3D layout of the mode
+pub const DRM_MODE_FLAG_3D_MASK: u32 = 0x1f << 14;
+/// The bits holding the picture aspect ratio of the mode
+pub const DRM_MODE_FLAG_PIC_AR_MASK: u32 = 0x0f << 19;
+
+/// The mode is the preferred one of the sink
+pub const DRM_MODE_TYPE_PREFERRED: u32 = 1 << 3;
+/// The mode has been defined by the user
+pub const DRM_MODE_TYPE_USERDEF: u32 = 1 << 5;
+/// The mode has been created by the driver
+pub const DRM_MODE_TYPE_DRIVER: u32 = 1 << 6;
+
+/// A display mode, as stored in the `MODE_ID` blobs
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 #[repr(C)]
 pub struct drm_mode_modeinfo {
     pub clock: u32,
@@ -42,6 +165,77 @@ pub struct drm_mode_modeinfo {
     pub name: [u8; 32],
 }
 
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_version {
+    pub version_major: i32,
+    pub version_minor: i32,
+    pub version_patchlevel: i32,
+    pub name_len: usize,
+    pub name: usize,
+    pub date_len: usize,
+    pub date: usize,
+    pub desc_len: usize,
+    pub desc: usize,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_version,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_VERSION,
+    drm_version
+);
+
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_unique {
+    pub unique_len: usize,
+    pub unique: usize,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_get_unique,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_GET_UNIQUE,
+    drm_unique
+);
+
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_gem_close {
+    pub handle: u32,
+    pub pad: u32,
+}
+
+ioctl_write_ptr!(
+    drm_ioctl_gem_close,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_GEM_CLOSE,
+    drm_gem_close
+);
+
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_prime_handle {
+    pub handle: u32,
+    pub flags: u32,
+    pub fd: i32,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_prime_handle_to_fd,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_PRIME_HANDLE_TO_FD,
+    drm_prime_handle
+);
+
+ioctl_readwrite!(
+    drm_ioctl_prime_fd_to_handle,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_PRIME_FD_TO_HANDLE,
+    drm_prime_handle
+);
+
 #[repr(C)]
 pub struct drm_set_client_cap {
     pub capability: u64,
@@ -55,6 +249,38 @@ ioctl_write_ptr!(
     drm_set_client_cap
 );
 
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_get_cap {
+    pub capability: u64,
+    pub value: u64,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_get_cap,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_GET_CAP,
+    drm_get_cap
+);
+
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_auth {
+    pub magic: u32,
+}
+
+ioctl_write_ptr!(
+    drm_ioctl_auth_magic,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_AUTH_MAGIC,
+    drm_auth
+);
+
+ioctl_none!(drm_ioctl_set_master, DRM_IOCTL_BASE, DRM_IOCTL_SET_MASTER);
+ioctl_none!(drm_ioctl_drop_master, DRM_IOCTL_BASE, DRM_IOCTL_DROP_MASTER);
+
+/// The KMS resources of a device, filled by [`drm_mode_get_resources()`]
+#[allow(missing_docs)]
 #[derive(Debug, Default)]
 #[repr(C)]
 pub struct drm_mode_card_res {
@@ -79,6 +305,8 @@ ioctl_readwrite!(
     drm_mode_card_res
 );
 
+/// The legacy state of a CRTC, filled by [`drm_mode_get_crtc()`]
+#[allow(missing_docs)]
 #[derive(Debug, Default)]
 #[repr(C)]
 pub struct drm_mode_crtc {
@@ -100,6 +328,54 @@ ioctl_readwrite!(
     drm_mode_crtc
 );
 
+#[cfg(feature = "legacy")]
+ioctl_readwrite!(
+    drm_ioctl_mode_setcrtc,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_SETCRTC,
+    drm_mode_crtc
+);
+
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_mode_crtc_lut {
+    pub crtc_id: u32,
+    pub gamma_size: u32,
+    pub red: u64,
+    pub green: u64,
+    pub blue: u64,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_mode_setgamma,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_SETGAMMA,
+    drm_mode_crtc_lut
+);
+
+/// An entry of the `GAMMA_LUT` and `DEGAMMA_LUT` blobs
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct drm_color_lut {
+    pub red: u16,
+    pub green: u16,
+    pub blue: u16,
+    pub reserved: u16,
+}
+
+/// The content of the `CTM` blob
+///
+/// The coefficients are stored in row-major order, in the S31.32 sign-magnitude format.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct drm_color_ctm {
+    pub matrix: [u64; 9],
+}
+
+/// An encoder, filled by [`drm_mode_get_encoder()`]
+#[allow(missing_docs)]
 #[derive(Debug, Default)]
 #[repr(C)]
 pub struct drm_mode_get_encoder {
@@ -117,6 +393,8 @@ ioctl_readwrite!(
     drm_mode_get_encoder
 );
 
+/// A connector, filled by [`drm_mode_get_connector()`]
+#[allow(missing_docs)]
 #[derive(Debug, Default)]
 #[repr(C)]
 pub struct drm_mode_get_connector {
@@ -135,8 +413,7 @@ pub struct drm_mode_get_connector {
     pub mm_width: u32,
     pub mm_height: u32,
     pub subpixel: u32,
-
-    pub _pad: u32,
+    pub pad: u32,
 }
 
 ioctl_readwrite!(
@@ -146,6 +423,8 @@ ioctl_readwrite!(
     drm_mode_get_connector
 );
 
+/// A property, filled by [`drm_mode_get_property()`]
+#[allow(missing_docs)]
 #[derive(Debug, Default)]
 #[repr(C)]
 pub struct drm_mode_get_property {
@@ -165,6 +444,28 @@ ioctl_readwrite!(
     drm_mode_get_property
 );
 
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct drm_mode_property_enum {
+    pub value: u64,
+    pub name: [u8; 32],
+}
+
+#[derive(Default)]
+#[repr(C)]
+pub struct drm_mode_get_blob {
+    pub blob_id: u32,
+    pub length: u32,
+    pub data: u64,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_mode_getpropblob,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_GETPROPBLOB,
+    drm_mode_get_blob
+);
+
 ioctl_readwrite!(
     drm_ioctl_mode_rmfb,
     DRM_IOCTL_BASE,
@@ -172,6 +473,47 @@ ioctl_readwrite!(
     libc::c_uint
 );
 
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_mode_cursor {
+    pub flags: u32,
+    pub crtc_id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub handle: u32,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_mode_cursor,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_CURSOR,
+    drm_mode_cursor
+);
+
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_mode_cursor2 {
+    pub flags: u32,
+    pub crtc_id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub handle: u32,
+    pub hot_x: i32,
+    pub hot_y: i32,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_mode_cursor2,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_CURSOR2,
+    drm_mode_cursor2
+);
+
+#[cfg_attr(not(feature = "legacy"), allow(dead_code))]
 #[derive(Default)]
 #[repr(C)]
 pub struct drm_mode_crtc_page_flip {
@@ -182,6 +524,50 @@ pub struct drm_mode_crtc_page_flip {
     pub user_data: u64,
 }
 
+#[cfg(feature = "legacy")]
+ioctl_readwrite!(
+    drm_ioctl_mode_page_flip,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_PAGE_FLIP,
+    drm_mode_crtc_page_flip
+);
+
+/// The current vblank sequence of a CRTC, filled by [`drm_crtc_get_sequence()`]
+#[allow(missing_docs)]
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_crtc_get_sequence {
+    pub crtc_id: u32,
+    pub active: u32,
+    pub sequence: u64,
+    pub sequence_ns: i64,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_crtc_get_sequence,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_CRTC_GET_SEQUENCE,
+    drm_crtc_get_sequence
+);
+
+/// A vblank event request, sent by [`drm_crtc_queue_sequence()`]
+#[allow(missing_docs)]
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_crtc_queue_sequence {
+    pub crtc_id: u32,
+    pub flags: u32,
+    pub sequence: u64,
+    pub user_data: u64,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_crtc_queue_sequence,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_CRTC_QUEUE_SEQUENCE,
+    drm_crtc_queue_sequence
+);
+
 #[derive(Debug, Default)]
 #[repr(C)]
 pub struct drm_mode_create_dumb {
@@ -216,19 +602,6 @@ ioctl_readwrite!(
     drm_mode_map_dumb
 );
 
-#[derive(Default)]
-#[repr(C)]
-pub struct drm_mode_destroy_dumb {
-    pub handle: u32,
-}
-
-ioctl_readwrite!(
-    drm_ioctl_mode_destroy_dumb,
-    DRM_IOCTL_BASE,
-    DRM_IOCTL_MODE_DESTROY_DUMB,
-    drm_mode_destroy_dumb
-);
-
 #[derive(Debug, Default)]
 #[repr(C)]
 pub struct drm_mode_get_plane_res {
@@ -243,6 +616,8 @@ ioctl_readwrite!(
     drm_mode_get_plane_res
 );
 
+/// A plane, filled by [`drm_mode_get_plane()`]
+#[allow(missing_docs)]
 #[derive(Debug, Default)]
 #[repr(C)]
 pub struct drm_mode_get_plane {
@@ -262,7 +637,36 @@ ioctl_readwrite!(
     drm_mode_get_plane
 );
 
-#[derive(Default)]
+#[cfg(feature = "legacy")]
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_mode_set_plane {
+    pub plane_id: u32,
+    pub crtc_id: u32,
+    pub fb_id: u32,
+    pub flags: u32,
+    pub crtc_x: i32,
+    pub crtc_y: i32,
+    pub crtc_w: u32,
+    pub crtc_h: u32,
+    pub src_x: u32,
+    pub src_y: u32,
+    pub src_h: u32,
+    pub src_w: u32,
+}
+
+#[cfg(feature = "legacy")]
+ioctl_readwrite!(
+    drm_ioctl_mode_setplane,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_SETPLANE,
+    drm_mode_set_plane
+);
+
+/// A framebuffer, filled by [`drm_mode_get_framebuffer2()`] or passed to
+/// [`drm_mode_add_framebuffer()`]
+#[allow(missing_docs)]
+#[derive(Debug, Default)]
 #[repr(C)]
 pub struct drm_mode_fb_cmd2 {
     pub fb_id: u32,
@@ -283,6 +687,27 @@ ioctl_readwrite!(
     drm_mode_fb_cmd2
 );
 
+ioctl_readwrite!(
+    drm_ioctl_mode_getfb2,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_GETFB2,
+    drm_mode_fb_cmd2
+);
+
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_mode_closefb {
+    pub fb_id: u32,
+    pub pad: u32,
+}
+
+ioctl_readwrite!(
+    drm_ioctl_mode_closefb,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_CLOSEFB,
+    drm_mode_closefb
+);
+
 #[derive(Default)]
 #[repr(C)]
 pub struct drm_mode_obj_get_properties {
@@ -300,6 +725,24 @@ ioctl_readwrite!(
     drm_mode_obj_get_properties
 );
 
+#[cfg(feature = "legacy")]
+#[derive(Default)]
+#[repr(C)]
+pub struct drm_mode_obj_set_property {
+    value: u64,
+    prop_id: u32,
+    obj_id: u32,
+    obj_type: u32,
+}
+
+#[cfg(feature = "legacy")]
+ioctl_readwrite!(
+    drm_ioctl_mode_obj_setproperty,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_OBJ_SETPROPERTY,
+    drm_mode_obj_set_property
+);
+
 #[derive(Default)]
 #[repr(C)]
 pub struct drm_mode_atomic {
@@ -335,107 +778,416 @@ ioctl_readwrite!(
     drm_mode_create_blob
 );
 
-pub fn drm_mode_create_dumb_buffer(
-    raw: &impl AsRawFd,
-    width: usize,
-    height: usize,
-    bpp: usize,
-) -> Result<drm_mode_create_dumb> {
-    let fd = raw.as_raw_fd();
-
-    let mut create = drm_mode_create_dumb {
-        width: width.try_into()?,
-        height: height.try_into()?,
-        bpp: bpp.try_into()?,
-        ..drm_mode_create_dumb::default()
-    };
+#[derive(Default)]
+#[repr(C)]
+pub struct drm_mode_destroy_blob {
+    pub blob_id: u32,
+}
 
-    unsafe { drm_ioctl_mode_create_dumb(fd, &mut create) }?;
+ioctl_readwrite!(
+    drm_ioctl_mode_destroypropblob,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_DESTROYPROPBLOB,
+    drm_mode_destroy_blob
+);
 
-    Ok(create)
+/// A rectangle, as stored in the `FB_DAMAGE_CLIPS` blobs
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct drm_mode_rect {
+    pub x1: i32,
+    pub y1: i32,
+    pub x2: i32,
+    pub y2: i32,
 }
 
-pub fn drm_mode_add_framebuffer(
-    raw: &impl AsRawFd,
-    handle: u32,
-    width: u32,
-    pitch: u32,
-    height: u32,
-    fmt: u32,
-) -> Result<u32> {
-    let fd = raw.as_raw_fd();
+/// A rectangle, as passed to [`drm_mode_dirty_framebuffer`]
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct drm_clip_rect {
+    pub x1: u16,
+    pub y1: u16,
+    pub x2: u16,
+    pub y2: u16,
+}
 
-    let mut fb = drm_mode_fb_cmd2 {
-        width,
-        height,
-        pixel_format: fmt,
-        ..drm_mode_fb_cmd2::default()
-    };
-    fb.handles[0] = handle;
-    fb.pitches[0] = pitch;
+#[derive(Default)]
+#[repr(C)]
+pub struct drm_mode_fb_dirty_cmd {
+    pub fb_id: u32,
+    pub flags: u32,
+    pub color: u32,
+    pub num_clips: u32,
+    pub clips_ptr: u64,
+}
 
-    unsafe { drm_ioctl_mode_addfb2(fd, &mut fb) }?;
+ioctl_readwrite!(
+    drm_ioctl_mode_dirtyfb,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_MODE_DIRTYFB,
+    drm_mode_fb_dirty_cmd
+);
 
-    Ok(fb.fb_id)
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct dma_buf_sync {
+    pub flags: u64,
 }
 
-pub fn drm_mode_atomic_commit(
-    raw: &impl AsRawFd,
-    objs_ptr: &[u32],
-    count_props_ptr: &[u32],
-    props_ptr: &[u32],
-    prop_values_ptr: &[u64],
-) -> Result<()> {
-    let fd = raw.as_raw_fd();
+ioctl_write_ptr!(
+    dma_buf_ioctl_sync,
+    DMA_BUF_BASE,
+    DMA_BUF_IOCTL_SYNC,
+    dma_buf_sync
+);
 
-    let mut atomic: drm_mode_atomic = drm_mode_atomic {
-        flags: 0x0400,
-        count_objs: objs_ptr.len().try_into()?,
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct dma_heap_allocation_data {
+    pub len: u64,
+    pub fd: u32,
+    pub fd_flags: u32,
+    pub heap_flags: u64,
+}
+
+#[cfg(target_os = "linux")]
+ioctl_readwrite!(
+    dma_heap_ioctl_alloc,
+    DMA_HEAP_IOC_MAGIC,
+    DMA_HEAP_IOCTL_ALLOC,
+    dma_heap_allocation_data
+);
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_vgem_fence_attach {
+    pub handle: u32,
+    pub flags: u32,
+    pub out_fence: u32,
+    pub pad: u32,
+}
+
+#[cfg(target_os = "linux")]
+ioctl_readwrite!(
+    drm_ioctl_vgem_fence_attach,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_VGEM_FENCE_ATTACH,
+    drm_vgem_fence_attach
+);
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default)]
+#[repr(C)]
+pub struct drm_vgem_fence_signal {
+    pub fence: u32,
+    pub flags: u32,
+}
+
+#[cfg(target_os = "linux")]
+ioctl_write_ptr!(
+    drm_ioctl_vgem_fence_signal,
+    DRM_IOCTL_BASE,
+    DRM_IOCTL_VGEM_FENCE_SIGNAL,
+    drm_vgem_fence_signal
+);
+
+pub fn drm_mode_create_dumb_buffer(
+    raw: &impl AsRawFd,
+    width: usize,
+    height: usize,
+    bpp: usize,
+) -> Result<drm_mode_create_dumb> {
+    let fd = raw.as_raw_fd();
+
+    let mut create = drm_mode_create_dumb {
+        width: width.try_into()?,
+        height: height.try_into()?,
+        bpp: bpp.try_into()?,
+        ..drm_mode_create_dumb::default()
+    };
+
+    unsafe { drm_ioctl_mode_create_dumb(fd, &raw mut create) }?;
+
+    Ok(create)
+}
+
+/// Adds a framebuffer
+///
+/// The handles, pitches, offsets and modifiers of each plane are set in `fb`, along with
+/// [`DRM_MODE_FB_MODIFIERS`] if the modifiers are to be used.
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails, for example if the layout doesn't fit
+/// in the buffers.
+pub fn drm_mode_add_framebuffer(raw: &impl AsRawFd, mut fb: drm_mode_fb_cmd2) -> Result<u32> {
+    let fd = raw.as_raw_fd();
+
+    unsafe { drm_ioctl_mode_addfb2(fd, &raw mut fb) }?;
+
+    Ok(fb.fb_id)
+}
+
+/// Commits an atomic state
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails, for example if the state isn't valid.
+///
+/// # Safety
+///
+/// The kernel reads the arrays using the counts it's given, so `count_props_ptr` must be as
+/// long as `objs_ptr`, and its counts must add up to the length of both `props_ptr` and
+/// `prop_values_ptr`.
+///
+/// Some property values are pointers the kernel writes to, like `OUT_FENCE_PTR`, and must point
+/// to memory valid for that write until the commit returns.
+pub unsafe fn drm_mode_atomic_commit(
+    raw: &impl AsRawFd,
+    flags: u32,
+    objs_ptr: &[u32],
+    count_props_ptr: &[u32],
+    props_ptr: &[u32],
+    prop_values_ptr: &[u64],
+    user_data: u64,
+) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    let mut atomic: drm_mode_atomic = drm_mode_atomic {
+        flags,
+        count_objs: objs_ptr.len().try_into()?,
         objs_ptr: objs_ptr.as_ptr() as u64,
         count_props_ptr: count_props_ptr.as_ptr() as u64,
         props_ptr: props_ptr.as_ptr() as u64,
         prop_values_ptr: prop_values_ptr.as_ptr() as u64,
         reserved: 0,
-        user_data: 0,
+        user_data,
     };
 
-    unsafe { drm_ioctl_mode_atomic(fd, &mut atomic) }?;
+    unsafe { drm_ioctl_mode_atomic(fd, &raw mut atomic) }?;
 
     Ok(())
 }
 
-pub fn drm_mode_create_property_blob<T: Sized>(raw: &impl AsRawFd, data: &T) -> Result<u32> {
+/// Creates a blob holding `data`, and returns its ID
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
+pub fn drm_mode_create_property_blob(raw: &impl AsRawFd, data: &[u8]) -> Result<u32> {
     let fd = raw.as_raw_fd();
 
     let mut blob = drm_mode_create_blob {
-        length: std::mem::size_of::<T>().try_into()?,
-        data: (data as *const T) as u64,
+        length: data.len().try_into()?,
+        data: data.as_ptr() as u64,
         ..drm_mode_create_blob::default()
     };
 
-    unsafe { drm_ioctl_mode_createpropblob(fd, &mut blob) }?;
+    unsafe { drm_ioctl_mode_createpropblob(fd, &raw mut blob) }?;
 
     Ok(blob.blob_id)
 }
 
-pub fn drm_mode_remove_framebuffer(raw: &impl AsRawFd, id: u32) -> Result<()> {
+/// Destroys the blob `id`
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
+pub fn drm_mode_destroy_property_blob(raw: &impl AsRawFd, id: u32) -> Result<()> {
     let fd = raw.as_raw_fd();
-    let mut fb_id = id;
+    let mut blob = drm_mode_destroy_blob { blob_id: id };
+
+    unsafe { drm_ioctl_mode_destroypropblob(fd, &raw mut blob) }?;
+
+    Ok(())
+}
+
+/// Flushes the `clips` areas of the framebuffer `id` to the display
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
+pub fn drm_mode_dirty_framebuffer(
+    raw: &impl AsRawFd,
+    id: u32,
+    clips: &[drm_clip_rect],
+) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    let mut dirty = drm_mode_fb_dirty_cmd {
+        fb_id: id,
+        num_clips: clips.len().try_into()?,
+        clips_ptr: clips.as_ptr() as u64,
+        ..drm_mode_fb_dirty_cmd::default()
+    };
+
+    unsafe { drm_ioctl_mode_dirtyfb(fd, &raw mut dirty) }?;
+
+    Ok(())
+}
+
+pub fn drm_gem_close(raw: &impl AsRawFd, handle: u32) -> Result<()> {
+    let fd = raw.as_raw_fd();
+    let close = drm_gem_close { handle, pad: 0 };
+
+    unsafe { drm_ioctl_gem_close(fd, &raw const close) }?;
+
+    Ok(())
+}
+
+/// Exports the GEM handle `handle` as a dma-buf
+///
+/// `flags` are the `O_CLOEXEC` and `O_RDWR` flags of the new file descriptor.
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
+pub fn drm_prime_handle_to_fd(raw: &impl AsRawFd, handle: u32, flags: u32) -> Result<OwnedFd> {
+    let fd = raw.as_raw_fd();
+
+    let mut prime = drm_prime_handle {
+        handle,
+        flags,
+        ..drm_prime_handle::default()
+    };
+
+    unsafe { drm_ioctl_prime_handle_to_fd(fd, &raw mut prime) }?;
+
+    Ok(unsafe { OwnedFd::from_raw_fd(prime.fd) })
+}
+
+/// Imports the dma-buf `prime`, and returns its GEM handle
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
+pub fn drm_prime_fd_to_handle(raw: &impl AsRawFd, prime: &impl AsRawFd) -> Result<u32> {
+    let fd = raw.as_raw_fd();
+
+    let mut handle = drm_prime_handle {
+        fd: prime.as_raw_fd(),
+        ..drm_prime_handle::default()
+    };
+
+    unsafe { drm_ioctl_prime_fd_to_handle(fd, &raw mut handle) }?;
+
+    Ok(handle.handle)
+}
+
+#[cfg(target_os = "linux")]
+pub fn dma_heap_allocate(raw: &impl AsRawFd, len: usize) -> Result<std::fs::File> {
+    let fd = raw.as_raw_fd();
+
+    let mut data = dma_heap_allocation_data {
+        len: len.try_into()?,
+        fd_flags: (libc::O_RDWR | libc::O_CLOEXEC).try_into()?,
+        ..dma_heap_allocation_data::default()
+    };
 
-    unsafe { drm_ioctl_mode_rmfb(fd, &mut fb_id) }?;
+    unsafe { dma_heap_ioctl_alloc(fd, &raw mut data) }?;
+
+    Ok(unsafe { std::fs::File::from_raw_fd(data.fd.try_into()?) })
+}
+
+/// Brackets a CPU access to a dma-buf
+///
+/// `flags` is a combination of [`DMA_BUF_SYNC_START`] or [`DMA_BUF_SYNC_END`], and of the
+/// access direction.
+pub fn dma_buf_sync(raw: &impl AsRawFd, flags: u64) -> Result<()> {
+    let fd = raw.as_raw_fd();
+    let sync = dma_buf_sync { flags };
+
+    unsafe { dma_buf_ioctl_sync(fd, &raw const sync) }?;
 
     Ok(())
 }
 
-pub fn drm_mode_destroy_dumb_buffer(raw: &impl AsRawFd, handle: u32) -> Result<()> {
+/// Returns the size of a dma-buf, in bytes
+pub fn dma_buf_size(raw: &impl AsRawFd) -> Result<usize> {
+    let size = unsafe { libc::lseek(raw.as_raw_fd(), 0, libc::SEEK_END) };
+    if size < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    Ok(usize::try_from(size)?)
+}
+
+pub fn drm_mode_close_framebuffer(raw: &impl AsRawFd, id: u32) -> Result<()> {
     let fd = raw.as_raw_fd();
-    let mut destroy = drm_mode_destroy_dumb { handle };
+    let mut close = drm_mode_closefb { fb_id: id, pad: 0 };
 
-    unsafe { drm_ioctl_mode_destroy_dumb(fd, &mut destroy) }?;
+    unsafe { drm_ioctl_mode_closefb(fd, &raw mut close) }?;
 
     Ok(())
 }
 
+/// Retrieves the framebuffer `id`
+///
+/// The GEM handles of the framebuffer are created for the caller, who is in charge of closing
+/// them.
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
+pub fn drm_mode_get_framebuffer2(raw: &impl AsRawFd, id: u32) -> Result<drm_mode_fb_cmd2> {
+    let fd = raw.as_raw_fd();
+
+    let mut fb = drm_mode_fb_cmd2 {
+        fb_id: id,
+        ..drm_mode_fb_cmd2::default()
+    };
+
+    unsafe { drm_ioctl_mode_getfb2(fd, &raw mut fb) }?;
+
+    Ok(fb)
+}
+
+/// Retrieves the content of the blob `id`
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
+pub fn drm_mode_get_property_blob(raw: &impl AsRawFd, id: u32) -> Result<Vec<u8>> {
+    let fd = raw.as_raw_fd();
+
+    let mut count = drm_mode_get_blob {
+        blob_id: id,
+        ..drm_mode_get_blob::default()
+    };
+
+    unsafe { drm_ioctl_mode_getpropblob(fd, &raw mut count) }?;
+
+    let mut data: Vec<u8> = vec![0; count.length as usize];
+
+    let mut blob = drm_mode_get_blob {
+        blob_id: id,
+        length: count.length,
+        data: data.as_mut_ptr() as u64,
+    };
+
+    unsafe { drm_ioctl_mode_getpropblob(fd, &raw mut blob) }?;
+
+    Ok(data)
+}
+
+pub fn drm_mode_remove_framebuffer(raw: &impl AsRawFd, id: u32) -> Result<()> {
+    let fd = raw.as_raw_fd();
+    let mut fb_id = id;
+
+    unsafe { drm_ioctl_mode_rmfb(fd, &raw mut fb_id) }?;
+
+    Ok(())
+}
+
+/// Retrieves the encoder `id`
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
 pub fn drm_mode_get_encoder(raw: &impl AsRawFd, id: u32) -> Result<drm_mode_get_encoder> {
     let fd = raw.as_raw_fd();
 
@@ -444,11 +1196,16 @@ pub fn drm_mode_get_encoder(raw: &impl AsRawFd, id: u32) -> Result<drm_mode_get_
         ..drm_mode_get_encoder::default()
     };
 
-    unsafe { drm_ioctl_mode_getencoder(fd, &mut encoder) }?;
+    unsafe { drm_ioctl_mode_getencoder(fd, &raw mut encoder) }?;
 
     Ok(encoder)
 }
 
+/// Retrieves the connector `id`, and optionally its modes and encoders
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
 pub fn drm_mode_get_connector(
     raw: &impl AsRawFd,
     id: u32,
@@ -462,7 +1219,7 @@ pub fn drm_mode_get_connector(
         ..drm_mode_get_connector::default()
     };
 
-    unsafe { drm_ioctl_mode_getconnector(fd, &mut count) }?;
+    unsafe { drm_ioctl_mode_getconnector(fd, &raw mut count) }?;
 
     if modes.is_none() && encoders.is_none() {
         return Ok(count);
@@ -487,11 +1244,47 @@ pub fn drm_mode_get_connector(
         conn.encoders_ptr = enc_ids.as_mut_ptr() as u64;
     }
 
-    unsafe { drm_ioctl_mode_getconnector(fd, &mut conn) }?;
+    unsafe { drm_ioctl_mode_getconnector(fd, &raw mut conn) }?;
 
     Ok(conn)
 }
 
+/// Retrieves the connector `id` without probing it
+///
+/// The kernel only probes the connector when asked for zero modes, so we pass a placeholder
+/// one, and the connector state is the one from the last probe.
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
+pub fn drm_mode_get_connector_current(
+    raw: &impl AsRawFd,
+    id: u32,
+) -> Result<drm_mode_get_connector> {
+    let fd = raw.as_raw_fd();
+
+    let mut mode = drm_mode_modeinfo::default();
+    let mut conn = drm_mode_get_connector {
+        connector_id: id,
+        count_modes: 1,
+        modes_ptr: std::ptr::from_mut(&mut mode) as u64,
+        ..drm_mode_get_connector::default()
+    };
+
+    unsafe { drm_ioctl_mode_getconnector(fd, &raw mut conn) }?;
+
+    // NOTE: The kernel reports the actual number of modes, but only filled the first one at
+    // most.
+    conn.modes_ptr = 0;
+
+    Ok(conn)
+}
+
+/// Retrieves the CRTC `id`
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
 pub fn drm_mode_get_crtc(raw: &impl AsRawFd, id: u32) -> Result<drm_mode_crtc> {
     let fd = raw.as_raw_fd();
 
@@ -500,11 +1293,214 @@ pub fn drm_mode_get_crtc(raw: &impl AsRawFd, id: u32) -> Result<drm_mode_crtc> {
         ..drm_mode_crtc::default()
     };
 
-    unsafe { drm_ioctl_mode_getcrtc(fd, &mut crtc) }?;
+    unsafe { drm_ioctl_mode_getcrtc(fd, &raw mut crtc) }?;
 
     Ok(crtc)
 }
 
+/// Retrieves the current vblank sequence of the CRTC `crtc_id`
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
+pub fn drm_crtc_get_sequence(raw: &impl AsRawFd, crtc_id: u32) -> Result<drm_crtc_get_sequence> {
+    let fd = raw.as_raw_fd();
+
+    let mut seq = drm_crtc_get_sequence {
+        crtc_id,
+        ..drm_crtc_get_sequence::default()
+    };
+
+    unsafe { drm_ioctl_crtc_get_sequence(fd, &raw mut seq) }?;
+
+    Ok(seq)
+}
+
+/// Requests an event when the CRTC `crtc_id` reaches the vblank `sequence`, and returns the
+/// sequence the event will be sent at
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
+pub fn drm_crtc_queue_sequence(
+    raw: &impl AsRawFd,
+    crtc_id: u32,
+    flags: u32,
+    sequence: u64,
+    user_data: u64,
+) -> Result<u64> {
+    let fd = raw.as_raw_fd();
+
+    let mut seq = drm_crtc_queue_sequence {
+        crtc_id,
+        flags,
+        sequence,
+        user_data,
+    };
+
+    unsafe { drm_ioctl_crtc_queue_sequence(fd, &raw mut seq) }?;
+
+    Ok(seq.sequence)
+}
+
+/// Reads the pending events
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the read fails.
+pub fn drm_read_events(raw: &impl AsRawFd) -> Result<Vec<u8>> {
+    let fd = raw.as_raw_fd();
+
+    // NOTE: The kernel will never return partial events, and our events are small enough for
+    // several of them to fit.
+    let mut buf = vec![0; 1024];
+    let len = nix::unistd::read(fd, &mut buf)?;
+    buf.truncate(len);
+
+    Ok(buf)
+}
+
+/// Waits for events to be available for reading, for at most `timeout`
+///
+/// Returns `false` if the `timeout` expired without any event.
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the poll fails.
+pub fn drm_poll_events(raw: &impl AsRawFd, timeout: Duration) -> Result<bool> {
+    let fd = raw.as_raw_fd();
+
+    let mut fds = [PollFd::new(fd, PollFlags::POLLIN)];
+    let timeout = libc::c_int::try_from(timeout.as_millis()).unwrap_or(libc::c_int::MAX);
+
+    Ok(poll(&mut fds, timeout)? > 0)
+}
+
+#[cfg(feature = "legacy")]
+pub fn drm_mode_set_crtc(
+    raw: &impl AsRawFd,
+    crtc_id: u32,
+    fb_id: u32,
+    position: (u32, u32),
+    connectors: &[u32],
+    mode: &drm_mode_modeinfo,
+) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    let mut crtc = drm_mode_crtc {
+        set_connectors_ptr: connectors.as_ptr() as u64,
+        count_connectors: connectors.len().try_into()?,
+        crtc_id,
+        fb_id,
+        x: position.0,
+        y: position.1,
+        mode_valid: 1,
+        mode: *mode,
+        ..drm_mode_crtc::default()
+    };
+
+    unsafe { drm_ioctl_mode_setcrtc(fd, &raw mut crtc) }?;
+
+    Ok(())
+}
+
+#[cfg(feature = "legacy")]
+pub fn drm_mode_page_flip(
+    raw: &impl AsRawFd,
+    crtc_id: u32,
+    fb_id: u32,
+    flags: u32,
+    user_data: u64,
+) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    let mut flip = drm_mode_crtc_page_flip {
+        crtc_id,
+        fb_id,
+        flags,
+        reserved: 0,
+        user_data,
+    };
+
+    unsafe { drm_ioctl_mode_page_flip(fd, &raw mut flip) }?;
+
+    Ok(())
+}
+
+#[cfg(feature = "legacy")]
+pub fn drm_mode_set_plane(raw: &impl AsRawFd, mut plane: drm_mode_set_plane) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    unsafe { drm_ioctl_mode_setplane(fd, &raw mut plane) }?;
+
+    Ok(())
+}
+
+#[cfg(feature = "legacy")]
+pub fn drm_mode_set_property(
+    raw: &impl AsRawFd,
+    obj_type: u32,
+    obj_id: u32,
+    prop_id: u32,
+    value: u64,
+) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    let mut prop = drm_mode_obj_set_property {
+        value,
+        prop_id,
+        obj_id,
+        obj_type,
+    };
+
+    unsafe { drm_ioctl_mode_obj_setproperty(fd, &raw mut prop) }?;
+
+    Ok(())
+}
+
+pub fn drm_mode_cursor(raw: &impl AsRawFd, mut cursor: drm_mode_cursor) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    unsafe { drm_ioctl_mode_cursor(fd, &raw mut cursor) }?;
+
+    Ok(())
+}
+
+pub fn drm_mode_cursor2(raw: &impl AsRawFd, mut cursor: drm_mode_cursor2) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    unsafe { drm_ioctl_mode_cursor2(fd, &raw mut cursor) }?;
+
+    Ok(())
+}
+
+pub fn drm_mode_set_gamma(
+    raw: &impl AsRawFd,
+    crtc_id: u32,
+    red: &[u16],
+    green: &[u16],
+    blue: &[u16],
+) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    let mut lut = drm_mode_crtc_lut {
+        crtc_id,
+        gamma_size: red.len().try_into()?,
+        red: red.as_ptr() as u64,
+        green: green.as_ptr() as u64,
+        blue: blue.as_ptr() as u64,
+    };
+
+    unsafe { drm_ioctl_mode_setgamma(fd, &raw mut lut) }?;
+
+    Ok(())
+}
+
+/// Retrieves the plane `id`, and optionally its formats
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
 pub fn drm_mode_get_plane(
     raw: &impl AsRawFd,
     id: u32,
@@ -517,7 +1513,7 @@ pub fn drm_mode_get_plane(
         ..drm_mode_get_plane::default()
     };
 
-    unsafe { drm_ioctl_mode_getplane(fd, &mut count) }?;
+    unsafe { drm_ioctl_mode_getplane(fd, &raw mut count) }?;
 
     if let Some(formats) = formats {
         formats.resize_with(count.count_format_types as usize, Default::default);
@@ -530,7 +1526,7 @@ pub fn drm_mode_get_plane(
             ..drm_mode_get_plane::default()
         };
 
-        unsafe { drm_ioctl_mode_getplane(fd, &mut plane) }?;
+        unsafe { drm_ioctl_mode_getplane(fd, &raw mut plane) }?;
 
         Ok(plane)
     } else {
@@ -538,12 +1534,17 @@ pub fn drm_mode_get_plane(
     }
 }
 
+/// Retrieves the IDs of all the planes
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
 pub fn drm_mode_get_planes(raw: &impl AsRawFd) -> Result<Vec<u32>> {
     let fd = raw.as_raw_fd();
 
     let mut count = drm_mode_get_plane_res::default();
 
-    unsafe { drm_ioctl_mode_getplaneresources(fd, &mut count) }?;
+    unsafe { drm_ioctl_mode_getplaneresources(fd, &raw mut count) }?;
 
     let mut plane_ids: Vec<u32> = Vec::with_capacity(count.count_planes as usize);
 
@@ -552,13 +1553,18 @@ pub fn drm_mode_get_planes(raw: &impl AsRawFd) -> Result<Vec<u32>> {
         plane_id_ptr: plane_ids.as_mut_ptr() as u64,
     };
 
-    unsafe { drm_ioctl_mode_getplaneresources(fd, &mut resources) }?;
+    unsafe { drm_ioctl_mode_getplaneresources(fd, &raw mut resources) }?;
 
     unsafe { plane_ids.set_len(count.count_planes as usize) };
 
     Ok(plane_ids)
 }
 
+/// Retrieves the property `id`
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
 pub fn drm_mode_get_property(raw: &impl AsRawFd, id: u32) -> Result<drm_mode_get_property> {
     let fd = raw.as_raw_fd();
 
@@ -567,11 +1573,45 @@ pub fn drm_mode_get_property(raw: &impl AsRawFd, id: u32) -> Result<drm_mode_get
         ..drm_mode_get_property::default()
     };
 
-    unsafe { drm_ioctl_mode_getproperty(fd, &mut count) }?;
+    unsafe { drm_ioctl_mode_getproperty(fd, &raw mut count) }?;
 
     Ok(count)
 }
 
+pub fn drm_mode_get_property_with_values(
+    raw: &impl AsRawFd,
+    id: u32,
+) -> Result<(drm_mode_get_property, Vec<u64>, Vec<drm_mode_property_enum>)> {
+    let fd = raw.as_raw_fd();
+
+    let count = drm_mode_get_property(raw, id)?;
+
+    let mut values: Vec<u64> = vec![0; count.count_values as usize];
+    let mut enums: Vec<drm_mode_property_enum> =
+        vec![drm_mode_property_enum::default(); count.count_enum_blobs as usize];
+
+    let mut property = drm_mode_get_property {
+        prop_id: id,
+        values_ptr: values.as_mut_ptr() as u64,
+        count_values: count.count_values,
+        enum_blob_ptr: enums.as_mut_ptr() as u64,
+        count_enum_blobs: count.count_enum_blobs,
+        ..drm_mode_get_property::default()
+    };
+
+    unsafe { drm_ioctl_mode_getproperty(fd, &raw mut property) }?;
+
+    values.truncate(property.count_values as usize);
+    enums.truncate(property.count_enum_blobs as usize);
+
+    Ok((property, values, enums))
+}
+
+/// Retrieves the IDs and values of the properties of an object
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
 pub fn drm_mode_get_properties(
     raw: &impl AsRawFd,
     object_type: u32,
@@ -585,7 +1625,7 @@ pub fn drm_mode_get_properties(
         ..drm_mode_obj_get_properties::default()
     };
 
-    unsafe { drm_ioctl_mode_obj_getproperties(fd, &mut count) }?;
+    unsafe { drm_ioctl_mode_obj_getproperties(fd, &raw mut count) }?;
 
     let mut prop_ids: Vec<u32> = Vec::with_capacity(count.count_props as usize);
     let mut prop_values: Vec<u64> = Vec::with_capacity(count.count_props as usize);
@@ -598,14 +1638,19 @@ pub fn drm_mode_get_properties(
         prop_values_ptr: prop_values.as_mut_ptr() as u64,
     };
 
-    unsafe { drm_ioctl_mode_obj_getproperties(fd, &mut properties) }?;
+    unsafe { drm_ioctl_mode_obj_getproperties(fd, &raw mut properties) }?;
 
     unsafe { prop_ids.set_len(count.count_props as usize) };
     unsafe { prop_values.set_len(count.count_props as usize) };
 
-    Ok(prop_ids.into_iter().zip(prop_values.into_iter()).collect())
+    Ok(prop_ids.into_iter().zip(prop_values).collect())
 }
 
+/// Retrieves the KMS resources, and optionally the IDs of the CRTCs, encoders and connectors
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails.
 pub fn drm_mode_get_resources(
     raw: &impl AsRawFd,
     crtc_ids: Option<&mut Vec<u32>>,
@@ -616,7 +1661,7 @@ pub fn drm_mode_get_resources(
 
     let mut count = drm_mode_card_res::default();
 
-    unsafe { drm_ioctl_mode_getresources(fd, &mut count) }?;
+    unsafe { drm_ioctl_mode_getresources(fd, &raw mut count) }?;
 
     if crtc_ids.is_none() && encoder_ids.is_none() && connector_ids.is_none() {
         return Ok(count);
@@ -645,7 +1690,7 @@ pub fn drm_mode_get_resources(
         resources.connector_id_ptr = connectors.as_mut_ptr() as u64;
     }
 
-    unsafe { drm_ioctl_mode_getresources(fd, &mut resources) }?;
+    unsafe { drm_ioctl_mode_getresources(fd, &raw mut resources) }?;
 
     Ok(resources)
 }
@@ -658,11 +1703,16 @@ pub fn drm_mode_map_dumb_buffer(raw: &impl AsRawFd, handle: u32) -> Result<drm_m
         ..drm_mode_map_dumb::default()
     };
 
-    unsafe { drm_ioctl_mode_map_dump(fd, &mut map) }?;
+    unsafe { drm_ioctl_mode_map_dump(fd, &raw mut map) }?;
 
     Ok(map)
 }
 
+/// Enables the client capability `cap`
+///
+/// # Errors
+///
+/// Will return [Error](crate::Error) if the ioctl fails, for example if the capability isn't supported.
 pub fn drm_set_client_capability(raw: &impl AsRawFd, cap: u64) -> Result<()> {
     let fd = raw.as_raw_fd();
     let caps = drm_set_client_cap {
@@ -670,7 +1720,206 @@ pub fn drm_set_client_capability(raw: &impl AsRawFd, cap: u64) -> Result<()> {
         value: 1,
     };
 
-    unsafe { drm_ioctl_set_client_cap(fd, &caps) }?;
+    unsafe { drm_ioctl_set_client_cap(fd, &raw const caps) }?;
 
     Ok(())
 }
+
+/// Returns the version of the driver behind the device, along with its name, date and
+/// description
+pub fn drm_get_version(raw: &impl AsRawFd) -> Result<(drm_version, String, String, String)> {
+    let fd = raw.as_raw_fd();
+
+    let mut count = drm_version::default();
+
+    unsafe { drm_ioctl_version(fd, &raw mut count) }?;
+
+    let mut name: Vec<u8> = vec![0; count.name_len];
+    let mut date: Vec<u8> = vec![0; count.date_len];
+    let mut desc: Vec<u8> = vec![0; count.desc_len];
+
+    let mut version = drm_version {
+        name_len: count.name_len,
+        name: name.as_mut_ptr() as usize,
+        date_len: count.date_len,
+        date: date.as_mut_ptr() as usize,
+        desc_len: count.desc_len,
+        desc: desc.as_mut_ptr() as usize,
+        ..drm_version::default()
+    };
+
+    unsafe { drm_ioctl_version(fd, &raw mut version) }?;
+
+    name.truncate(version.name_len);
+    date.truncate(version.date_len);
+    desc.truncate(version.desc_len);
+
+    Ok((
+        version,
+        std::str::from_utf8(&name)?.to_string(),
+        std::str::from_utf8(&date)?.to_string(),
+        std::str::from_utf8(&desc)?.to_string(),
+    ))
+}
+
+/// Returns the value of the device capability `cap`
+pub fn drm_get_capability(raw: &impl AsRawFd, cap: u64) -> Result<u64> {
+    let fd = raw.as_raw_fd();
+    let mut caps = drm_get_cap {
+        capability: cap,
+        value: 0,
+    };
+
+    unsafe { drm_ioctl_get_cap(fd, &raw mut caps) }?;
+
+    Ok(caps.value)
+}
+
+/// Returns the bus identifier of the device
+pub fn drm_get_unique(raw: &impl AsRawFd) -> Result<String> {
+    let fd = raw.as_raw_fd();
+
+    let mut count = drm_unique::default();
+
+    unsafe { drm_ioctl_get_unique(fd, &raw mut count) }?;
+
+    let mut data: Vec<u8> = vec![0; count.unique_len];
+
+    let mut unique = drm_unique {
+        unique_len: count.unique_len,
+        unique: data.as_mut_ptr() as usize,
+    };
+
+    unsafe { drm_ioctl_get_unique(fd, &raw mut unique) }?;
+
+    data.truncate(unique.unique_len);
+    Ok(std::str::from_utf8(&data)?.to_string())
+}
+
+pub fn drm_auth_magic(raw: &impl AsRawFd, magic: u32) -> Result<()> {
+    let fd = raw.as_raw_fd();
+    let auth = drm_auth { magic };
+
+    unsafe { drm_ioctl_auth_magic(fd, &raw const auth) }?;
+
+    Ok(())
+}
+
+pub fn drm_set_master(raw: &impl AsRawFd) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    unsafe { drm_ioctl_set_master(fd) }?;
+
+    Ok(())
+}
+
+pub fn drm_drop_master(raw: &impl AsRawFd) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    unsafe { drm_ioctl_drop_master(fd) }?;
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub fn drm_vgem_attach_fence(raw: &impl AsRawFd, handle: u32, write: bool) -> Result<u32> {
+    let fd = raw.as_raw_fd();
+
+    let mut attach = drm_vgem_fence_attach {
+        handle,
+        flags: u32::from(write),
+        ..drm_vgem_fence_attach::default()
+    };
+
+    unsafe { drm_ioctl_vgem_fence_attach(fd, &raw mut attach) }?;
+
+    Ok(attach.out_fence)
+}
+
+#[cfg(target_os = "linux")]
+pub fn drm_vgem_signal_fence(raw: &impl AsRawFd, fence: u32) -> Result<()> {
+    let fd = raw.as_raw_fd();
+    let signal = drm_vgem_fence_signal { fence, flags: 0 };
+
+    unsafe { drm_ioctl_vgem_fence_signal(fd, &raw const signal) }?;
+
+    Ok(())
+}
+
+#[cfg(all(feature = "i2c", target_os = "linux"))]
+ioctl_write_int_bad!(i2c_ioctl_set_slave, I2C_SLAVE);
+
+#[cfg(all(feature = "i2c", target_os = "linux"))]
+pub fn i2c_set_target_address(raw: &impl AsRawFd, address: u16) -> Result<()> {
+    let fd = raw.as_raw_fd();
+
+    unsafe { i2c_ioctl_set_slave(fd, address.into()) }?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use super::*;
+
+    #[test]
+    fn test_struct_layouts() {
+        assert_eq!(size_of::<drm_gem_close>(), 8);
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(size_of::<drm_version>(), 64);
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(size_of::<drm_unique>(), 16);
+        assert_eq!(size_of::<drm_prime_handle>(), 12);
+        assert_eq!(size_of::<drm_mode_modeinfo>(), 68);
+        assert_eq!(size_of::<drm_set_client_cap>(), 16);
+        assert_eq!(size_of::<drm_mode_property_enum>(), 40);
+        assert_eq!(size_of::<drm_get_cap>(), 16);
+        assert_eq!(size_of::<drm_auth>(), 4);
+        assert_eq!(size_of::<drm_mode_card_res>(), 64);
+        assert_eq!(size_of::<drm_mode_crtc>(), 104);
+        assert_eq!(size_of::<drm_mode_get_encoder>(), 20);
+        assert_eq!(size_of::<drm_mode_get_connector>(), 80);
+        assert_eq!(size_of::<drm_mode_get_property>(), 64);
+        assert_eq!(size_of::<drm_mode_get_blob>(), 16);
+        assert_eq!(size_of::<drm_mode_cursor>(), 28);
+        assert_eq!(size_of::<drm_mode_cursor2>(), 36);
+        assert_eq!(size_of::<drm_mode_crtc_page_flip>(), 24);
+        assert_eq!(size_of::<drm_mode_create_dumb>(), 32);
+        assert_eq!(size_of::<drm_mode_map_dumb>(), 16);
+        assert_eq!(size_of::<drm_mode_get_plane_res>(), 16);
+        assert_eq!(size_of::<drm_mode_get_plane>(), 32);
+        assert_eq!(size_of::<drm_mode_fb_cmd2>(), 104);
+        assert_eq!(size_of::<drm_mode_obj_get_properties>(), 32);
+        assert_eq!(size_of::<drm_mode_atomic>(), 56);
+        assert_eq!(size_of::<drm_mode_create_blob>(), 16);
+        assert_eq!(size_of::<drm_mode_crtc_lut>(), 32);
+        assert_eq!(size_of::<drm_color_lut>(), 8);
+        assert_eq!(size_of::<drm_color_ctm>(), 72);
+        assert_eq!(size_of::<drm_mode_destroy_blob>(), 4);
+        assert_eq!(size_of::<drm_mode_closefb>(), 8);
+        assert_eq!(size_of::<drm_mode_rect>(), 16);
+        #[cfg(feature = "legacy")]
+        assert_eq!(size_of::<drm_mode_set_plane>(), 48);
+        #[cfg(feature = "legacy")]
+        assert_eq!(size_of::<drm_mode_obj_set_property>(), 24);
+        assert_eq!(size_of::<drm_crtc_get_sequence>(), 24);
+        assert_eq!(size_of::<drm_crtc_queue_sequence>(), 24);
+        assert_eq!(size_of::<drm_clip_rect>(), 8);
+        assert_eq!(size_of::<drm_mode_fb_dirty_cmd>(), 24);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_vgem_struct_layouts() {
+        assert_eq!(size_of::<drm_vgem_fence_attach>(), 16);
+        assert_eq!(size_of::<drm_vgem_fence_signal>(), 8);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_dma_heap_struct_layouts() {
+        assert_eq!(size_of::<dma_heap_allocation_data>(), 24);
+    }
+}
@@ -0,0 +1,554 @@
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fs::File,
+    io::{BufWriter, Write},
+    str::FromStr,
+    sync::{Arc, Weak},
+};
+
+use crate::{
+    device::Inner,
+    output::{commit_properties, PropertyBlobs},
+    raw::{
+        drm_mode_get_framebuffer2, drm_mode_get_property, drm_mode_get_property_blob,
+        DRM_MODE_ATOMIC_NONBLOCK, DRM_MODE_PAGE_FLIP_EVENT, DRM_MODE_PROP_BLOB,
+    },
+    BufferType, Device, Error, Format, Framebuffer, Result,
+};
+
+// NOTE: The fence properties hold a file descriptor and a userspace address, which are only
+// valid in the process that committed them. Replaying them would make the kernel wait on
+// whatever file now has that number, and write to an arbitrary address.
+const UNRECORDED_PROPERTIES: &[&str] = &["IN_FENCE_FD", "OUT_FENCE_PTR"];
+
+/// The value of a [`RecordedProperty`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RecordedValue {
+    /// A plain property value, stored as is
+    Value(u64),
+
+    /// The content of a blob property. A new blob will be created with that content at replay
+    /// time.
+    Blob(Vec<u8>),
+
+    /// A [Framebuffer] reference. The content of the framebuffer isn't recorded, only its
+    /// geometry: a blank [Framebuffer] with the same dimensions and format will be created at
+    /// replay time.
+    Framebuffer {
+        /// The recorded [Framebuffer] ID
+        id: u32,
+
+        /// The [Framebuffer] width, in pixels
+        width: u32,
+
+        /// The [Framebuffer] height, in lines
+        height: u32,
+
+        /// The [Framebuffer] fourcc format
+        format: u32,
+    },
+}
+
+/// A property set part of a [`RecordedCommit`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordedProperty {
+    object_id: u32,
+    name: String,
+    value: RecordedValue,
+}
+
+impl RecordedProperty {
+    pub(crate) fn new(object_id: u32, name: &str, value: RecordedValue) -> Self {
+        Self {
+            object_id,
+            name: name.to_string(),
+            value,
+        }
+    }
+
+    /// Returns the ID of the KMS object the property was set on
+    #[must_use]
+    pub const fn object_id(&self) -> u32 {
+        self.object_id
+    }
+
+    /// Returns the property name
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the recorded property value
+    #[must_use]
+    pub const fn value(&self) -> &RecordedValue {
+        &self.value
+    }
+}
+
+/// A recorded atomic commit
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordedCommit {
+    flags: u32,
+    properties: Vec<RecordedProperty>,
+}
+
+impl RecordedCommit {
+    pub(crate) const fn new(flags: u32, properties: Vec<RecordedProperty>) -> Self {
+        Self { flags, properties }
+    }
+
+    pub(crate) fn with_flags(&self, flags: u32) -> Self {
+        Self {
+            flags,
+            properties: self.properties.clone(),
+        }
+    }
+
+    pub(crate) fn from_raw(
+        device: &Device,
+        flags: u32,
+        properties: &[(u32, u32, u64)],
+    ) -> Result<Self> {
+        Self::from_staged(device, flags, properties, &PropertyBlobs::new())
+    }
+
+    /// Same as [`RecordedCommit::from_raw`], for a commit whose blobs have only been staged
+    ///
+    /// The content of the staged blobs is used instead of retrieving it from the kernel.
+    pub(crate) fn from_staged(
+        device: &Device,
+        flags: u32,
+        properties: &[(u32, u32, u64)],
+        blobs: &PropertyBlobs,
+    ) -> Result<Self> {
+        let mut recorded = Vec::with_capacity(properties.len());
+
+        for &(object_id, prop_id, prop_value) in properties {
+            let name = device.property_name(object_id, prop_id)?;
+
+            if UNRECORDED_PROPERTIES.contains(&name.as_str()) {
+                continue;
+            }
+
+            let value = if let Some(data) = blobs.staged_data(object_id, prop_id) {
+                RecordedValue::Blob(data.to_vec())
+            } else if prop_value == 0 {
+                RecordedValue::Value(prop_value)
+            } else if (drm_mode_get_property(device, prop_id)?.flags & DRM_MODE_PROP_BLOB) != 0 {
+                RecordedValue::Blob(drm_mode_get_property_blob(
+                    device,
+                    u32::try_from(prop_value)?,
+                )?)
+            } else if name == "FB_ID" {
+                let id = u32::try_from(prop_value)?;
+                let fb = drm_mode_get_framebuffer2(device, id)?;
+
+                RecordedValue::Framebuffer {
+                    id,
+                    width: fb.width,
+                    height: fb.height,
+                    format: fb.pixel_format,
+                }
+            } else {
+                RecordedValue::Value(prop_value)
+            };
+
+            recorded.push(RecordedProperty {
+                object_id,
+                name,
+                value,
+            });
+        }
+
+        Ok(Self {
+            flags,
+            properties: recorded,
+        })
+    }
+
+    /// Returns the flags the commit was issued with
+    #[must_use]
+    pub const fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    /// Returns an iterator over the [`RecordedProperty`]s set in this commit
+    pub fn properties(&self) -> impl Iterator<Item = &RecordedProperty> {
+        self.properties.iter()
+    }
+}
+
+impl std::fmt::Display for RecordedCommit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "commit {:#x}", self.flags)?;
+
+        for prop in &self.properties {
+            match &prop.value {
+                RecordedValue::Value(val) => {
+                    writeln!(f, "property {} {} {}", prop.object_id, val, prop.name)?;
+                }
+                RecordedValue::Blob(data) => {
+                    write!(f, "blob {} ", prop.object_id)?;
+
+                    for byte in data {
+                        write!(f, "{byte:02x}")?;
+                    }
+
+                    writeln!(f, " {}", prop.name)?;
+                }
+                RecordedValue::Framebuffer {
+                    id,
+                    width,
+                    height,
+                    format,
+                } => {
+                    writeln!(
+                        f,
+                        "framebuffer {} {} {} {} {:#010x} {}",
+                        prop.object_id, id, width, height, format, prop.name
+                    )?;
+                }
+            }
+        }
+
+        writeln!(f, "end")
+    }
+}
+
+/// A sequence of [`RecordedCommit`]s
+///
+/// A [Recording] is created by [`Device::start_recording`], and can be loaded back with
+/// [`Recording::load`] to be replayed by a [Replayer].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Recording {
+    commits: Vec<RecordedCommit>,
+}
+
+impl Recording {
+    /// Loads a [Recording] from a file
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the file can't be read or if it isn't a valid recording.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::Recording;
+    ///
+    /// let recording = Recording::load("commits.log").unwrap();
+    /// ```
+    pub fn load(path: &str) -> Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Returns an iterator over the [`RecordedCommit`]s
+    pub fn commits(&self) -> impl Iterator<Item = &RecordedCommit> {
+        self.commits.iter()
+    }
+}
+
+fn parse_u32(s: &str) -> Option<u32> {
+    s.strip_prefix("0x")
+        .map_or_else(|| s.parse().ok(), |hex| u32::from_str_radix(hex, 16).ok())
+}
+
+fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..s.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(s.get(idx..idx + 2)?, 16).ok())
+        .collect()
+}
+
+fn parse_property(line: &str) -> Option<RecordedProperty> {
+    let (kind, rest) = line.split_once(' ')?;
+    let (object_id, rest) = rest.split_once(' ')?;
+    let object_id = object_id.parse().ok()?;
+
+    let (value, name) = match kind {
+        "property" => {
+            let (value, name) = rest.split_once(' ')?;
+
+            (RecordedValue::Value(value.parse().ok()?), name)
+        }
+        "blob" => {
+            let (data, name) = rest.split_once(' ')?;
+
+            (RecordedValue::Blob(parse_hex(data)?), name)
+        }
+        "framebuffer" => {
+            let mut fields = rest.splitn(5, ' ');
+            let id = parse_u32(fields.next()?)?;
+            let width = parse_u32(fields.next()?)?;
+            let height = parse_u32(fields.next()?)?;
+            let format = parse_u32(fields.next()?)?;
+
+            (
+                RecordedValue::Framebuffer {
+                    id,
+                    width,
+                    height,
+                    format,
+                },
+                fields.next()?,
+            )
+        }
+        _ => return None,
+    };
+
+    if UNRECORDED_PROPERTIES.contains(&name) {
+        return None;
+    }
+
+    Some(RecordedProperty {
+        object_id,
+        name: name.to_string(),
+        value,
+    })
+}
+
+impl FromStr for Recording {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut commits = Vec::new();
+        let mut current: Option<RecordedCommit> = None;
+
+        for (idx, line) in s.lines().enumerate() {
+            let line_nr = idx + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line == "end" {
+                commits.push(current.take().ok_or(Error::MalformedRecording(line_nr))?);
+                continue;
+            }
+
+            if let Some(flags) = line.strip_prefix("commit ") {
+                if current.is_some() {
+                    return Err(Error::MalformedRecording(line_nr));
+                }
+
+                current = Some(RecordedCommit {
+                    flags: parse_u32(flags).ok_or(Error::MalformedRecording(line_nr))?,
+                    properties: Vec::new(),
+                });
+
+                continue;
+            }
+
+            current
+                .as_mut()
+                .ok_or(Error::MalformedRecording(line_nr))?
+                .properties
+                .push(parse_property(line).ok_or(Error::MalformedRecording(line_nr))?);
+        }
+
+        if current.is_some() {
+            return Err(Error::MalformedRecording(s.lines().count()));
+        }
+
+        Ok(Self { commits })
+    }
+}
+
+#[derive(Debug)]
+pub struct Recorder {
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    pub(crate) fn new(path: &str) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "# nucleid commit recording")?;
+
+        Ok(Self { writer })
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        device: &Device,
+        flags: u32,
+        properties: &[(u32, u32, u64)],
+    ) -> Result<()> {
+        let commit = RecordedCommit::from_raw(device, flags, properties)?;
+
+        write!(self.writer, "{commit}")?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Re-applies [`RecordedCommit`]s to a [Device]
+///
+/// The [Framebuffer]s referenced in the [Recording] are replaced by blank [Framebuffer]s of the
+/// same geometry. They are owned by the [Replayer], and will thus be released when it's dropped.
+#[derive(Debug)]
+pub struct Replayer {
+    dev: Weak<Inner>,
+    framebuffers: HashMap<u32, Framebuffer>,
+}
+
+impl Replayer {
+    /// Creates a new [Replayer] for a [Device]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Recording, Replayer};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let recording = Recording::load("commits.log").unwrap();
+    ///
+    /// let mut replayer = Replayer::new(&device);
+    /// for commit in recording.commits() {
+    ///     replayer.replay(commit).unwrap();
+    /// }
+    /// ```
+    #[must_use]
+    pub fn new(device: &Device) -> Self {
+        Self {
+            dev: Arc::downgrade(&device.inner),
+            framebuffers: HashMap::new(),
+        }
+    }
+
+    fn property_id(device: &Device, object_id: u32, name: &str) -> Result<u32> {
+        device
+            .property_id(object_id, name)?
+            .ok_or_else(|| Error::PropertyNotFound {
+                object: object_id,
+                name: name.to_string(),
+            })
+    }
+
+    fn framebuffer_id(
+        &mut self,
+        device: &Device,
+        id: u32,
+        width: u32,
+        height: u32,
+        format: u32,
+    ) -> Result<u32> {
+        if let Some(fb) = self.framebuffers.get(&id) {
+            return Ok(fb.id());
+        }
+
+        let format = Format::try_from(format).map_err(|_| Error::UnsupportedFormat(format))?;
+        let fb = device
+            .allocate_buffer_with_format(BufferType::Dumb, width as usize, height as usize, format)?
+            .into_framebuffer(format)?;
+        let fb_id = fb.id();
+
+        self.framebuffers.insert(id, fb);
+
+        Ok(fb_id)
+    }
+
+    /// Re-applies a [`RecordedCommit`]
+    ///
+    /// The commit is always applied synchronously, and without requesting a page flip event,
+    /// whatever the recorded flags were.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if one of the recorded objects or
+    /// properties can't be found, or if the commit is rejected.
+    pub fn replay(&mut self, commit: &RecordedCommit) -> Result<()> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+        let mut properties = Vec::with_capacity(commit.properties.len());
+        let mut blobs = PropertyBlobs::new();
+
+        for prop in &commit.properties {
+            let prop_id = Self::property_id(&device, prop.object_id, &prop.name)?;
+
+            let value = match &prop.value {
+                RecordedValue::Value(val) => *val,
+                RecordedValue::Blob(data) => {
+                    blobs.create(&device, prop.object_id, prop_id, data.as_slice())?
+                }
+                RecordedValue::Framebuffer {
+                    id,
+                    width,
+                    height,
+                    format,
+                } => u64::from(self.framebuffer_id(&device, *id, *width, *height, *format)?),
+            };
+
+            properties.push((prop.object_id, prop_id, value));
+        }
+
+        // NOTE: A non-blocking commit would fail with EBUSY if the previous one is still
+        // pending, and nobody would read its page flip event.
+        let flags = commit.flags & !(DRM_MODE_ATOMIC_NONBLOCK | DRM_MODE_PAGE_FLIP_EVENT);
+
+        commit_properties(&device, flags, properties, blobs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RecordedCommit, RecordedProperty, RecordedValue, Recording};
+
+    #[test]
+    fn test_recording_roundtrip() {
+        let commit = RecordedCommit {
+            flags: 0x400,
+            properties: vec![
+                RecordedProperty {
+                    object_id: 31,
+                    name: String::from("top margin"),
+                    value: RecordedValue::Value(0),
+                },
+                RecordedProperty {
+                    object_id: 42,
+                    name: String::from("MODE_ID"),
+                    value: RecordedValue::Blob(vec![0x00, 0x12, 0xab, 0xff]),
+                },
+                RecordedProperty {
+                    object_id: 33,
+                    name: String::from("FB_ID"),
+                    value: RecordedValue::Framebuffer {
+                        id: 56,
+                        width: 1920,
+                        height: 1080,
+                        format: 0x34325258,
+                    },
+                },
+            ],
+        };
+
+        let recording: Recording = format!("# test\n{commit}\n{commit}").parse().unwrap();
+
+        assert_eq!(recording.commits().count(), 2);
+        assert!(recording.commits().all(|c| *c == commit));
+    }
+
+    #[test]
+    fn test_recording_malformed() {
+        assert!("commit 0x400\nproperty 31 0 CRTC_ID\n"
+            .parse::<Recording>()
+            .is_err());
+        assert!("property 31 0 CRTC_ID\nend\n".parse::<Recording>().is_err());
+        assert!("commit 0x400\nblob 31 abc MODE_ID\nend\n"
+            .parse::<Recording>()
+            .is_err());
+        assert!(
+            "commit 0x400\nproperty 31 140737488355328 OUT_FENCE_PTR\nend\n"
+                .parse::<Recording>()
+                .is_err()
+        );
+        assert!("commit 0x400\nproperty 31 3 IN_FENCE_FD\nend\n"
+            .parse::<Recording>()
+            .is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use std::str::FromStr;
+
+use crate::{
+    object::Object,
+    output::{commit_properties, PropertyBlobs},
+    raw::DRM_MODE_ATOMIC_ALLOW_MODESET,
+    record::{RecordedCommit, RecordedProperty, RecordedValue, Recording},
+    ContentProtection, Device, Error, Properties, Result,
+};
+
+// NOTE: Those properties can't be set back through an atomic commit: DPMS is a legacy property
+// the kernel rejects, and the others only make sense for a single commit.
+const SKIPPED_PROPERTIES: &[&str] = &["DPMS", "FB_DAMAGE_CLIPS", "IN_FENCE_FD", "OUT_FENCE_PTR"];
+
+/// A Snapshot of the Display Pipeline State
+///
+/// A [Snapshot] holds the value of every property that can be set on the
+/// [Crtc](crate::Crtc)s, [Connector](crate::Connector)s and [Plane](crate::Plane)s of a
+/// [Device]. It is created by [`Device::save_state`], and can be restored with
+/// [`Device::restore_state`].
+///
+/// A [Snapshot] can be converted to a string and parsed back, using the same format than a
+/// [Recording]. The content of the blobs is part of the [Snapshot], but the framebuffers are only
+/// referenced by their ID, so they need to still exist when it's restored.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Snapshot {
+    commit: RecordedCommit,
+}
+
+impl Snapshot {
+    /// Returns an iterator over the [`RecordedProperty`]s of the [Snapshot]
+    pub fn properties(&self) -> impl Iterator<Item = &RecordedProperty> {
+        self.commit.properties()
+    }
+}
+
+impl std::fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.commit)
+    }
+}
+
+impl FromStr for Snapshot {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let recording: Recording = s.parse()?;
+
+        let mut commits = recording.commits();
+        match (commits.next(), commits.next()) {
+            (Some(commit), None) => Ok(Self {
+                commit: commit.clone(),
+            }),
+            _ => Err(Error::MalformedRecording(s.lines().count())),
+        }
+    }
+}
+
+fn saved_properties(object_id: u32, properties: &Properties) -> Vec<(u32, u32, u64)> {
+    properties
+        .iter()
+        .filter(|property| !property.is_immutable())
+        .filter(|property| !SKIPPED_PROPERTIES.contains(&property.name()))
+        .map(|property| {
+            // NOTE: Only the kernel can enable the content protection, so we need to request it
+            // again instead.
+            let value = if property.name() == "Content Protection"
+                && property.value() == ContentProtection::Enabled as u64
+            {
+                ContentProtection::Desired as u64
+            } else {
+                property.value()
+            };
+
+            (object_id, property.id(), value)
+        })
+        .collect()
+}
+
+pub fn save_state(device: &Device) -> Result<Snapshot> {
+    let mut properties = Vec::new();
+
+    for crtc in device.crtcs() {
+        properties.extend(saved_properties(crtc.object_id(), &crtc.properties()?));
+    }
+
+    for connector in device.connectors() {
+        properties.extend(saved_properties(
+            connector.object_id(),
+            &connector.properties()?,
+        ));
+    }
+
+    for plane in device.planes() {
+        properties.extend(saved_properties(plane.object_id(), &plane.properties()?));
+    }
+
+    Ok(Snapshot {
+        commit: RecordedCommit::from_raw(device, DRM_MODE_ATOMIC_ALLOW_MODESET, &properties)?,
+    })
+}
+
+pub fn restore_state(device: &Device, snapshot: &Snapshot) -> Result<()> {
+    let mut properties = Vec::new();
+    let mut blobs = PropertyBlobs::new();
+
+    for property in snapshot.properties() {
+        let object_id = property.object_id();
+        let prop_id = device
+            .property_id(object_id, property.name())?
+            .ok_or_else(|| Error::PropertyNotFound {
+                object: object_id,
+                name: property.name().to_string(),
+            })?;
+
+        let value = match property.value() {
+            RecordedValue::Value(val) => *val,
+            RecordedValue::Blob(data) => {
+                blobs.create(device, object_id, prop_id, data.as_slice())?
+            }
+            RecordedValue::Framebuffer { id, .. } => u64::from(*id),
+        };
+
+        properties.push((object_id, prop_id, value));
+    }
+
+    commit_properties(device, snapshot.commit.flags(), properties, blobs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Snapshot;
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let text = "commit 0x400\n\
+                    property 31 1 ACTIVE\n\
+                    blob 31 0012abff MODE_ID\n\
+                    framebuffer 33 56 1920 1080 0x34325258 FB_ID\n\
+                    end\n";
+
+        let snapshot: Snapshot = text.parse().unwrap();
+        assert_eq!(snapshot.properties().count(), 3);
+        assert_eq!(snapshot.to_string(), text);
+    }
+
+    #[test]
+    fn test_snapshot_malformed() {
+        assert!("".parse::<Snapshot>().is_err());
+        assert!("commit 0x400\nend\ncommit 0x400\nend\n"
+            .parse::<Snapshot>()
+            .is_err());
+        assert!("commit 0x400\nproperty 31 1 ACTIVE\n"
+            .parse::<Snapshot>()
+            .is_err());
+    }
+}
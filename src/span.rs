@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use crate::{
+    surface::{Surface, Target},
+    Connector, Device, Error, Framebuffer, Point, Rect, Result, Size,
+};
+
+// NOTE: The source coordinates are 16.16 fixed point values, so the framebuffer can't be
+// larger than that.
+const MAX_SIZE: usize = u16::MAX as usize;
+
+/// Checks that the [Output](crate::Output)s areas make up a valid wall, and returns its size
+///
+/// The areas can't overlap, the wall needs to fit in the source coordinates range, and all the
+/// [Output](crate::Output)s need to refresh at the same rate for the frames to be presented at the
+/// same time.
+fn check_layout(areas: &[(Rect, usize)]) -> Result<Size> {
+    for (idx, (area, refresh)) in areas.iter().enumerate() {
+        for (other, other_refresh) in &areas[idx + 1..] {
+            if area.intersect(other).is_some() {
+                return Err(Error::InvalidLayout("Overlapping Outputs"));
+            }
+
+            if refresh != other_refresh {
+                return Err(Error::InvalidLayout("Mismatched Refresh Rates"));
+            }
+        }
+    }
+
+    let width = areas
+        .iter()
+        .map(|(area, _)| area.right())
+        .max()
+        .unwrap_or(0);
+    let height = areas
+        .iter()
+        .map(|(area, _)| area.bottom())
+        .max()
+        .unwrap_or(0);
+    if width > MAX_SIZE || height > MAX_SIZE {
+        return Err(Error::InvalidLayout("Wall Too Large"));
+    }
+
+    Ok(Size::new(width, height))
+}
+
+/// Spans a Surface Across Several Outputs
+///
+/// The [Span] allocates two [Framebuffer]s large enough to cover all the [Output](crate::Output)s,
+/// laid out as requested, to draw into in turns while the other one is on screen. Each
+/// [Output](crate::Output) then scans out its own area of the [Framebuffer] through its primary
+/// [Plane](crate::Plane) source coordinates, so no copy or scaling is involved.
+///
+/// All the [Output](crate::Output)s are updated in a single commit, so they always display the same
+/// frame.
+#[derive(Debug)]
+pub struct Span {
+    surface: Surface,
+    size: Size,
+    areas: Vec<Rect>,
+}
+
+impl Span {
+    /// Creates a new [Span] on a [Device]
+    ///
+    /// `layout` holds the [Connector]s to span the surface across, along with the position of their
+    /// top-left corner in the surface. Each [Connector] uses its preferred [Mode](crate::Mode). The
+    /// [Output](crate::Output)s will only be enabled on the first call to [`Span::flip`].
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if there's no [Crtc](crate::Crtc) or primary [Plane](crate::Plane)
+    /// supporting [`Format::XRGB8888`](crate::Format::XRGB8888) available for a [Connector], if a
+    /// [Connector] doesn't have a preferred [Mode](crate::Mode), if the [Output](crate::Output)s
+    /// overlap or refresh at different rates, or if the [Framebuffer] allocation fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Point, Span};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let connectors = device.connected_connectors();
+    ///
+    /// let span = Span::new(
+    ///     &device,
+    ///     &[
+    ///         (connectors[0].clone(), Point::new(0, 0)),
+    ///         (connectors[1].clone(), Point::new(1920, 0)),
+    ///     ],
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn new(device: &Device, layout: &[(Arc<Connector>, Point)]) -> Result<Self> {
+        let mut targets: Vec<Target> = Vec::with_capacity(layout.len());
+        let mut areas = Vec::with_capacity(layout.len());
+
+        for (connector, position) in layout {
+            let used_crtcs: Vec<u32> = targets.iter().filter_map(Target::crtc_id).collect();
+
+            let output = device.output_from_connector_excluding(connector, &used_crtcs)?;
+            let target = Target::new(output, connector)?;
+
+            let mode = target.mode();
+            let area = Rect::from_parts(*position, Size::new(mode.width(), mode.height()));
+
+            areas.push((area, mode.refresh()));
+            targets.push(target);
+        }
+
+        let size = check_layout(&areas)?;
+
+        Ok(Self {
+            surface: Surface::new(device, targets, size)?,
+            size,
+            areas: areas.into_iter().map(|(area, _)| area).collect(),
+        })
+    }
+
+    /// Returns the [Span] width, in pixels
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.size.width()
+    }
+
+    /// Returns the [Span] height, in pixels
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.size.height()
+    }
+
+    /// Draws into the [Framebuffer] that will be displayed on the next [`Span::flip`]
+    ///
+    /// The [Framebuffer] covers the whole [Span], each [Output](crate::Output) scanning out its own
+    /// area of it. Its [`XRGB8888`](crate::Format::XRGB8888) pixels are left untouched since they
+    /// were flipped, two calls ago.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Point, Span};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let connectors = device.connected_connectors();
+    ///
+    /// let mut span = Span::new(&device, &[(connectors[0].clone(), Point::new(0, 0))]).unwrap();
+    ///
+    /// span.draw(|buffer| buffer.data().fill(0xff));
+    /// ```
+    pub fn draw<F>(&mut self, draw: F)
+    where
+        F: FnOnce(&mut Framebuffer),
+    {
+        self.surface.draw(draw);
+    }
+
+    /// Displays the [Framebuffer] drawn into with [`Span::draw`] on the [Output](crate::Output)s
+    ///
+    /// The first call will enable the [Output](crate::Output)s. This function will block until the
+    /// new frame is on screen.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the commit fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Point, Span};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let connectors = device.connected_connectors();
+    ///
+    /// let mut span = Span::new(&device, &[(connectors[0].clone(), Point::new(0, 0))]).unwrap();
+    ///
+    /// for frame in 0..=255 {
+    ///     span.draw(|buffer| buffer.data().fill(frame));
+    ///     span.flip().unwrap();
+    /// }
+    /// ```
+    pub fn flip(&mut self) -> Result<()> {
+        let areas = &self.areas;
+
+        self.surface.flip(|idx, _, update| {
+            let area = areas[idx];
+
+            update
+                .set_source_rect(area.into())
+                .set_display_rect(Rect::from_parts(Point::default(), area.size()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_layout;
+    use crate::{Rect, Size};
+
+    #[test]
+    fn test_check_layout() {
+        let left = Rect::new(0, 0, 1920, 1080);
+        let right = Rect::new(1920, 0, 1920, 1080);
+        let below = Rect::new(0, 1080, 1920, 1080);
+
+        assert_eq!(
+            check_layout(&[(left, 60), (right, 60), (below, 60)]).unwrap(),
+            Size::new(3840, 2160)
+        );
+
+        assert!(check_layout(&[(left, 60), (Rect::new(1000, 0, 1920, 1080), 60)]).is_err());
+        assert!(check_layout(&[(left, 60), (right, 50)]).is_err());
+        assert!(check_layout(&[(Rect::new(65000, 0, 1920, 1080), 60)]).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+/// Presentation statistics of an [Output](crate::Output)
+///
+/// The statistics are gathered from the blocking commits once they return, and from the commits
+/// sending a flip event once it's been received, for example with
+/// [`Device::wait_for_flip`](crate::Device::wait_for_flip). A frame is considered skipped if it
+/// has been presented later than the vblank following its submission.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FrameStats {
+    presented: u64,
+    late: u64,
+    skipped: u64,
+}
+
+impl FrameStats {
+    /// Returns the number of frames presented
+    #[must_use]
+    pub const fn presented(&self) -> u64 {
+        self.presented
+    }
+
+    /// Returns the number of frames presented later than the vblank following their submission
+    #[must_use]
+    pub const fn late(&self) -> u64 {
+        self.late
+    }
+
+    /// Returns the number of vblanks that went by without the expected frame being presented
+    #[must_use]
+    pub const fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Accounts for a frame submitted after the vblank `submitted`, and presented at the vblank
+    /// `presented`
+    ///
+    /// Returns the number of vblanks skipped.
+    pub(crate) fn record(&mut self, submitted: Option<u64>, presented: u64) -> u64 {
+        self.presented += 1;
+
+        // NOTE: The flip events only hold the lower 32 bits of the sequence number, so we need
+        // to compare them on 32 bits as well.
+        #[allow(clippy::cast_possible_truncation)]
+        let skipped = submitted.map_or(0, |submitted| {
+            u64::from((presented as u32).wrapping_sub(submitted as u32)).saturating_sub(1)
+        });
+
+        if skipped > 0 {
+            self.late += 1;
+            self.skipped += skipped;
+        }
+
+        skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrameStats;
+
+    #[test]
+    fn test_record() {
+        let mut stats = FrameStats::default();
+
+        assert_eq!(stats.record(Some(10), 11), 0);
+        assert_eq!(stats.record(Some(11), 14), 2);
+        assert_eq!(stats.record(None, 20), 0);
+        assert_eq!(stats.record(Some(0xffff_ffff), 0x1_0000_0001), 1);
+
+        assert_eq!(stats.presented(), 4);
+        assert_eq!(stats.late(), 2);
+        assert_eq!(stats.skipped(), 3);
+    }
+}
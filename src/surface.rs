@@ -0,0 +1,179 @@
+use std::{convert::TryFrom, sync::Arc};
+
+use crate::{
+    BufferType, Connector, ConnectorUpdate, Device, DeviceUpdate, Error, Format, Framebuffer, Mode,
+    Output, Plane, PlaneType, PlaneUpdate, Result, Size, Update,
+};
+
+pub const FORMAT: Format = Format::XRGB8888;
+
+/// An [Output] a [Surface] is displayed on
+#[derive(Debug)]
+pub struct Target {
+    output: Option<Output>,
+    connector: Arc<Connector>,
+    plane: Arc<Plane>,
+    mode: Mode,
+}
+
+impl Target {
+    /// Creates a [Target] driving `connector` through `output`
+    ///
+    /// The [Connector] preferred [Mode] is used, along with the first primary [Plane] supporting
+    /// [`Format::XRGB8888`].
+    pub fn new(output: Output, connector: &Arc<Connector>) -> Result<Self> {
+        let mode = connector.preferred_mode()?;
+
+        let plane = output
+            .planes_of(PlaneType::Primary)
+            .into_iter()
+            .find(|plane| plane.formats().any(|fmt| fmt == FORMAT))
+            .ok_or(Error::Unsupported("No primary plane supports XRGB8888"))?;
+
+        Ok(Self {
+            output: Some(output),
+            connector: Arc::clone(connector),
+            plane,
+            mode,
+        })
+    }
+
+    pub const fn connector(&self) -> &Arc<Connector> {
+        &self.connector
+    }
+
+    pub const fn mode(&self) -> &Mode {
+        &self.mode
+    }
+
+    pub fn crtc_id(&self) -> Option<u32> {
+        self.output.as_ref().map(Output::crtc_id)
+    }
+}
+
+/// A Double-Buffered Surface Displayed on One or More [Output]s
+///
+/// The [Surface] holds two [Framebuffer]s to draw into in turns, while the other one is on
+/// screen. It's what [Display](crate::Display), [Mirror](crate::Mirror) and
+/// [Span](crate::Span) are built upon, and they only differ in their [Output]s and how the
+/// [Framebuffer] is laid out on each of them.
+#[derive(Debug)]
+pub struct Surface {
+    targets: Vec<Target>,
+    buffers: [Framebuffer; 2],
+    back: usize,
+    enabled: bool,
+}
+
+impl Surface {
+    /// Creates a new [Surface] of `size` displayed on `targets`
+    ///
+    /// The [Output]s will only be enabled on the first call to [`Surface::flip`].
+    pub fn new(device: &Device, targets: Vec<Target>, size: Size) -> Result<Self> {
+        if targets.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        let allocate = || {
+            device
+                .allocate_buffer_with_format(BufferType::Dumb, size.width(), size.height(), FORMAT)?
+                .into_framebuffer(FORMAT)
+        };
+        let buffers = [allocate()?, allocate()?];
+
+        Ok(Self {
+            targets,
+            buffers,
+            back: 0,
+            enabled: false,
+        })
+    }
+
+    pub fn targets(&self) -> &[Target] {
+        &self.targets
+    }
+
+    pub fn draw<F>(&mut self, draw: F)
+    where
+        F: FnOnce(&mut Framebuffer),
+    {
+        draw(&mut self.buffers[self.back]);
+    }
+
+    /// Displays the [Framebuffer] drawn into with [`Surface::draw`] on every [Target]
+    ///
+    /// `place` is called with the index of each [Target] to set where the [Framebuffer] is
+    /// scanned out from, and where it's displayed.
+    pub fn flip<F>(&mut self, place: F) -> Result<()>
+    where
+        F: Fn(usize, &Target, PlaneUpdate) -> PlaneUpdate,
+    {
+        // NOTE: The Outputs are only taken once we know they're all there, so that we can't
+        // lose the ones taken before a missing one.
+        if self.targets.iter().any(|target| target.output.is_none()) {
+            return Err(Error::Empty);
+        }
+
+        let framebuffer = &self.buffers[self.back];
+        let enabled = self.enabled;
+
+        let updates: Vec<Update> = self
+            .targets
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(idx, target)| {
+                let output = target.output.take()?;
+                let plane_update = PlaneUpdate::new(&target.plane).set_framebuffer(framebuffer);
+                let mut update = output
+                    .start_update()
+                    .add_plane(place(idx, target, plane_update));
+
+                if !enabled {
+                    update = update
+                        .set_mode(target.mode.clone())
+                        .add_connector(ConnectorUpdate::new(&target.connector));
+                }
+
+                Some(update)
+            })
+            .collect();
+
+        // NOTE: A single Output goes through Update::commit, which also works on legacy
+        // devices. Otherwise, all the CRTCs are updated in a single atomic commit, so that they
+        // all switch to the new frame at once.
+        let (outputs, ret) = match <[Update; 1]>::try_from(updates) {
+            Ok([update]) => match update.commit() {
+                Ok(output) => (vec![output], Ok(())),
+                Err(err) => {
+                    let (error, update) = err.into_parts();
+                    (vec![update.into_output()], Err(error))
+                }
+            },
+            Err(updates) => {
+                let update = updates
+                    .into_iter()
+                    .fold(DeviceUpdate::new(), DeviceUpdate::add_update);
+
+                match update.commit() {
+                    Ok(outputs) => (outputs, Ok(())),
+                    Err(err) => {
+                        let (error, update) = err.into_parts();
+                        (update.into_outputs(), Err(error))
+                    }
+                }
+            }
+        };
+
+        // NOTE: We need to get the Outputs back whether the commit succeeded or not.
+        for (target, output) in self.targets.iter_mut().zip(outputs) {
+            target.output = Some(output);
+        }
+
+        ret?;
+
+        self.enabled = true;
+        self.back ^= 1;
+
+        Ok(())
+    }
+}
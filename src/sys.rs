@@ -0,0 +1,47 @@
+//! Low-level KMS Interface
+//!
+//! This module exposes the thin wrappers around the KMS ioctls, and the kernel uapi structures
+//! they use, that the rest of the crate is built upon. They are meant to reach the features the
+//! high-level API doesn't cover yet, and can be used on the [Device](crate::Device) file
+//! descriptor.
+//!
+//! The structures follow the kernel headers, so their documentation is the one of the kernel
+//! uapi.
+//!
+//! # Stability
+//!
+//! This module isn't covered by the semver guarantees of the rest of the crate: its content
+//! follows the needs of the high-level API, and can change in any release.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use nucleid::{sys, Device};
+//!
+//! let device = Device::new("/dev/dri/card0").unwrap();
+//!
+//! let mut crtcs = Vec::new();
+//! sys::drm_mode_get_resources(&device, Some(&mut crtcs), None, None).unwrap();
+//!
+//! for id in crtcs {
+//!     let crtc = sys::drm_mode_get_crtc(&device, id).unwrap();
+//!     println!("CRTC {} Gamma Size {}", id, crtc.gamma_size);
+//! }
+//! ```
+
+pub use crate::raw::{
+    drm_clip_rect, drm_color_ctm, drm_color_lut, drm_crtc_get_sequence, drm_crtc_queue_sequence,
+    drm_mode_add_framebuffer, drm_mode_atomic_commit, drm_mode_card_res,
+    drm_mode_create_property_blob, drm_mode_crtc, drm_mode_destroy_property_blob,
+    drm_mode_dirty_framebuffer, drm_mode_fb_cmd2, drm_mode_get_connector,
+    drm_mode_get_connector_current, drm_mode_get_crtc, drm_mode_get_encoder,
+    drm_mode_get_framebuffer2, drm_mode_get_plane, drm_mode_get_planes, drm_mode_get_properties,
+    drm_mode_get_property, drm_mode_get_property_blob, drm_mode_get_resources, drm_mode_modeinfo,
+    drm_mode_rect, drm_poll_events, drm_prime_fd_to_handle, drm_prime_handle_to_fd,
+    drm_read_events, drm_set_client_capability, DRM_EVENT_CRTC_SEQUENCE, DRM_EVENT_FLIP_COMPLETE,
+    DRM_EVENT_VBLANK, DRM_MODE_ATOMIC_ALLOW_MODESET, DRM_MODE_ATOMIC_NONBLOCK,
+    DRM_MODE_ATOMIC_TEST_ONLY, DRM_MODE_FB_MODIFIERS, DRM_MODE_FLAG_3D_MASK, DRM_MODE_FLAG_DBLSCAN,
+    DRM_MODE_FLAG_INTERLACE, DRM_MODE_FLAG_NHSYNC, DRM_MODE_FLAG_NVSYNC, DRM_MODE_FLAG_PHSYNC,
+    DRM_MODE_FLAG_PIC_AR_MASK, DRM_MODE_FLAG_PVSYNC, DRM_MODE_PAGE_FLIP_EVENT, DRM_MODE_PROP_BLOB,
+    DRM_MODE_TYPE_DRIVER, DRM_MODE_TYPE_PREFERRED, DRM_MODE_TYPE_USERDEF,
+};
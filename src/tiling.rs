@@ -0,0 +1,302 @@
+use crate::{Error, Result, Size};
+
+const fn fourcc_mod_code(vendor: u64, value: u64) -> u64 {
+    (vendor << 56) | value
+}
+
+const DRM_FORMAT_MOD_VENDOR_INTEL: u64 = 0x01;
+const DRM_FORMAT_MOD_VENDOR_VIVANTE: u64 = 0x06;
+const DRM_FORMAT_MOD_VENDOR_ALLWINNER: u64 = 0x09;
+
+const I915_FORMAT_MOD_X_TILED: u64 = fourcc_mod_code(DRM_FORMAT_MOD_VENDOR_INTEL, 1);
+const I915_FORMAT_MOD_Y_TILED: u64 = fourcc_mod_code(DRM_FORMAT_MOD_VENDOR_INTEL, 2);
+const DRM_FORMAT_MOD_VIVANTE_TILED: u64 = fourcc_mod_code(DRM_FORMAT_MOD_VENDOR_VIVANTE, 1);
+const DRM_FORMAT_MOD_ALLWINNER_TILED: u64 = fourcc_mod_code(DRM_FORMAT_MOD_VENDOR_ALLWINNER, 1);
+
+/// A Tiled Memory Layout
+///
+/// Tiled buffers store their pixels in small rectangular tiles rather than line by line, which
+/// is friendlier to the GPU and the display engine caches but makes them unreadable as is. The
+/// helpers here convert them from and to the linear layout on the CPU, to inspect or generate
+/// their content.
+///
+/// Only the layouts made of uncompressed, fixed size, tiles are supported.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tiling {
+    /// Intel X-tiling: 512 bytes by 8 lines tiles, laid out linearly
+    IntelX,
+
+    /// Intel Y-tiling: 128 bytes by 32 lines tiles, made of 16 bytes wide columns
+    IntelY,
+
+    /// Vivante tiling: 4 by 4 pixels tiles, laid out linearly
+    Vivante,
+
+    /// Allwinner tiling: 32 bytes by 32 lines tiles, laid out linearly
+    Allwinner,
+}
+
+impl Tiling {
+    /// Returns the [Tiling] described by a format modifier, if supported
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, PlaneType, Tiling};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let output = device.first_connected_output().unwrap();
+    ///
+    /// let plane = output
+    ///     .planes_of(PlaneType::Primary)
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap();
+    ///
+    /// let fb = plane.adopt_framebuffer().unwrap().unwrap();
+    /// let tiling = Tiling::from_modifier(fb.modifier());
+    /// ```
+    #[must_use]
+    pub const fn from_modifier(modifier: u64) -> Option<Self> {
+        match modifier {
+            I915_FORMAT_MOD_X_TILED => Some(Self::IntelX),
+            I915_FORMAT_MOD_Y_TILED => Some(Self::IntelY),
+            DRM_FORMAT_MOD_VIVANTE_TILED => Some(Self::Vivante),
+            DRM_FORMAT_MOD_ALLWINNER_TILED => Some(Self::Allwinner),
+            _ => None,
+        }
+    }
+
+    /// Returns the format modifier describing the [Tiling]
+    #[must_use]
+    pub const fn modifier(self) -> u64 {
+        match self {
+            Self::IntelX => I915_FORMAT_MOD_X_TILED,
+            Self::IntelY => I915_FORMAT_MOD_Y_TILED,
+            Self::Vivante => DRM_FORMAT_MOD_VIVANTE_TILED,
+            Self::Allwinner => DRM_FORMAT_MOD_ALLWINNER_TILED,
+        }
+    }
+
+    /// Returns the tile width in bytes, its height in lines, and the number of contiguous
+    /// bytes in each of its lines
+    const fn geometry(self, cpp: usize) -> (usize, usize, usize) {
+        match self {
+            Self::IntelX => (512, 8, 512),
+            Self::IntelY => (128, 32, 16),
+            Self::Vivante => (4 * cpp, 4, 4 * cpp),
+            Self::Allwinner => (32, 32, 32),
+        }
+    }
+
+    /// Returns the offset in the tiled buffer of the byte `x` of the line `y`
+    const fn offset(self, cpp: usize, pitch: usize, x: usize, y: usize) -> usize {
+        let (width, height, span) = self.geometry(cpp);
+
+        // NOTE: The pitch of a tiled buffer is the one of a line of tiles divided by the tile
+        // height, so a line of tiles is pitch * height bytes long.
+        let tile = (y / height) * pitch * height + (x / width) * width * height;
+        let column = (x % width) / span;
+
+        tile + column * span * height + (y % height) * span + x % span
+    }
+
+    const fn check(
+        self,
+        tiled: (usize, usize),
+        linear: (usize, usize),
+        size: Size,
+        bpp: usize,
+    ) -> Result<(usize, usize)> {
+        let (tiled_len, tiled_pitch) = tiled;
+        let (linear_len, linear_pitch) = linear;
+
+        if bpp == 0 || !bpp.is_multiple_of(8) {
+            return Err(Error::PixelLayout);
+        }
+
+        let cpp = bpp / 8;
+        let (width, height, _) = self.geometry(cpp);
+        let row = size.width() * cpp;
+        let lines = size.height().next_multiple_of(height);
+
+        if !tiled_pitch.is_multiple_of(width)
+            || tiled_pitch < row
+            || tiled_len < tiled_pitch * lines
+            || linear_pitch < row
+            || linear_len < linear_pitch * size.height()
+        {
+            return Err(Error::PixelLayout);
+        }
+
+        Ok((cpp, row))
+    }
+
+    /// Copies the `size` pixels of a tiled buffer into a linear one
+    ///
+    /// `bpp` is the number of bits per pixel, and the pitches are in bytes. The padding of the
+    /// linear buffer lines is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::PixelLayout`] if the pixels aren't a whole number of bytes, if the
+    /// tiled pitch isn't a multiple of the tile width, or if either buffer is too small.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Size, Tiling};
+    ///
+    /// # fn read_capture() -> Vec<u8> { unimplemented!() }
+    /// let tiled = read_capture();
+    /// let mut linear = vec![0; 1920 * 1080 * 4];
+    ///
+    /// Tiling::IntelX
+    ///     .detile(&tiled, 7680, &mut linear, 7680, Size::new(1920, 1080), 32)
+    ///     .unwrap();
+    /// ```
+    pub fn detile(
+        self,
+        src: &[u8],
+        src_pitch: usize,
+        dst: &mut [u8],
+        dst_pitch: usize,
+        size: Size,
+        bpp: usize,
+    ) -> Result<()> {
+        let (cpp, row) = self.check((src.len(), src_pitch), (dst.len(), dst_pitch), size, bpp)?;
+        let (_, _, span) = self.geometry(cpp);
+
+        for y in 0..size.height() {
+            for x in (0..row).step_by(span) {
+                let len = span.min(row - x);
+                let offset = self.offset(cpp, src_pitch, x, y);
+                let start = y * dst_pitch + x;
+
+                dst[start..start + len].copy_from_slice(&src[offset..offset + len]);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copies the `size` pixels of a linear buffer into a tiled one
+    ///
+    /// `bpp` is the number of bits per pixel, and the pitches are in bytes. The tiled buffer
+    /// bytes that don't hold any pixel are left untouched.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::PixelLayout`] if the pixels aren't a whole number of bytes, if the
+    /// tiled pitch isn't a multiple of the tile width, or if either buffer is too small.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Size, Tiling};
+    ///
+    /// let linear = vec![0xff; 640 * 480 * 4];
+    /// let mut tiled = vec![0; 640 * 480 * 4];
+    ///
+    /// Tiling::IntelY
+    ///     .retile(&linear, 2560, &mut tiled, 2560, Size::new(640, 480), 32)
+    ///     .unwrap();
+    /// ```
+    pub fn retile(
+        self,
+        src: &[u8],
+        src_pitch: usize,
+        dst: &mut [u8],
+        dst_pitch: usize,
+        size: Size,
+        bpp: usize,
+    ) -> Result<()> {
+        let (cpp, row) = self.check((dst.len(), dst_pitch), (src.len(), src_pitch), size, bpp)?;
+        let (_, _, span) = self.geometry(cpp);
+
+        for y in 0..size.height() {
+            for x in (0..row).step_by(span) {
+                let len = span.min(row - x);
+                let offset = self.offset(cpp, dst_pitch, x, y);
+                let start = y * src_pitch + x;
+
+                dst[offset..offset + len].copy_from_slice(&src[start..start + len]);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::Tiling;
+    use crate::Size;
+
+    #[test]
+    fn test_offset() {
+        assert_eq!(Tiling::IntelX.offset(4, 1024, 0, 1), 512);
+        assert_eq!(Tiling::IntelX.offset(4, 1024, 512, 0), 4096);
+        assert_eq!(Tiling::IntelX.offset(4, 1024, 0, 8), 8192);
+
+        assert_eq!(Tiling::IntelY.offset(4, 256, 0, 1), 16);
+        assert_eq!(Tiling::IntelY.offset(4, 256, 16, 0), 512);
+        assert_eq!(Tiling::IntelY.offset(4, 256, 128, 0), 4096);
+
+        assert_eq!(Tiling::Vivante.offset(4, 64, 4, 0), 4);
+        assert_eq!(Tiling::Vivante.offset(4, 64, 0, 1), 16);
+        assert_eq!(Tiling::Vivante.offset(4, 64, 16, 0), 64);
+        assert_eq!(Tiling::Vivante.offset(4, 64, 0, 4), 256);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let size = Size::new(50, 40);
+        let linear: Vec<u8> = (0..200 * 40)
+            .map(|idx| u8::try_from(idx % 251).unwrap())
+            .collect();
+
+        for tiling in [
+            Tiling::IntelX,
+            Tiling::IntelY,
+            Tiling::Vivante,
+            Tiling::Allwinner,
+        ] {
+            assert_eq!(Tiling::from_modifier(tiling.modifier()), Some(tiling));
+
+            let mut tiled = vec![0; 512 * 64];
+            tiling
+                .retile(&linear, 200, &mut tiled, 512, size, 32)
+                .unwrap();
+
+            let mut result = vec![0; 200 * 40];
+            tiling
+                .detile(&tiled, 512, &mut result, 200, size, 32)
+                .unwrap();
+            assert_eq!(result, linear);
+        }
+
+        assert!(Tiling::IntelX
+            .detile(
+                &[0; 512 * 8],
+                500,
+                &mut [0; 200 * 8],
+                200,
+                Size::new(50, 8),
+                32
+            )
+            .is_err());
+        assert!(Tiling::IntelX
+            .detile(
+                &[0; 512 * 4],
+                512,
+                &mut [0; 200 * 8],
+                200,
+                Size::new(50, 8),
+                32
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,255 @@
+use std::sync::Arc;
+
+use crate::{object::Object, Connector, Device, Error, Output, Plane, PlaneType, Result};
+
+// NOTE: The object IDs of an encoder, a CRTC and a primary plane able to drive a connector.
+type Pipeline = [u32; 3];
+
+fn assigned(solution: &[Option<usize>]) -> usize {
+    solution
+        .iter()
+        .filter(|pipeline| pipeline.is_some())
+        .count()
+}
+
+fn search(
+    candidates: &[Vec<Pipeline>],
+    current: &mut Vec<Option<usize>>,
+    best: &mut Vec<Option<usize>>,
+) {
+    let idx = current.len();
+    let best_count = assigned(best);
+
+    // NOTE: Only a solution driving strictly more connectors can replace the best one, so that
+    // the first connectors win the ties.
+    if assigned(current) + (candidates.len() - idx) <= best_count {
+        return;
+    }
+
+    if idx == candidates.len() {
+        best.clone_from(current);
+        return;
+    }
+
+    for (pipeline_idx, pipeline) in candidates[idx].iter().enumerate() {
+        let conflicts = current
+            .iter()
+            .enumerate()
+            .filter_map(|(other, choice)| choice.map(|choice| &candidates[other][choice]))
+            .any(|used| used.iter().zip(pipeline).any(|(a, b)| a == b));
+        if conflicts {
+            continue;
+        }
+
+        current.push(Some(pipeline_idx));
+        search(candidates, current, best);
+        current.pop();
+
+        if assigned(best) == candidates.len() {
+            return;
+        }
+    }
+
+    current.push(None);
+    search(candidates, current, best);
+    current.pop();
+}
+
+/// Picks a pipeline for as many connectors as possible
+///
+/// `candidates` holds the pipelines each connector can use. No encoder, CRTC or plane is used
+/// twice, and among the solutions driving the most connectors, the first connectors in the list
+/// and their first pipelines are preferred.
+pub fn solve(candidates: &[Vec<Pipeline>]) -> Vec<Option<usize>> {
+    let mut best = vec![None; candidates.len()];
+
+    search(
+        candidates,
+        &mut Vec::with_capacity(candidates.len()),
+        &mut best,
+    );
+    best
+}
+
+/// An Assignment of [Crtc](crate::Crtc)s and Primary [Plane]s to [Connector]s
+///
+/// Each [Connector] can only be fed by some [Encoder](crate::Encoder)s, which in turn can only
+/// be fed by some [Crtc](crate::Crtc)s, and each primary [Plane] can only be used by some
+/// [Crtc](crate::Crtc)s. The [Topology] finds an [Output] and a primary [Plane] for as many
+/// [Connector]s as possible, so that they can all be enabled at the same time.
+///
+/// Each [Output] gets its own [Crtc](crate::Crtc) and [Encoder](crate::Encoder), so the
+/// [Output]s never need to be clones of each other.
+#[derive(Debug)]
+pub struct Topology {
+    outputs: Vec<(Output, Arc<Plane>)>,
+    unassigned: Vec<Arc<Connector>>,
+}
+
+impl Topology {
+    /// Creates a [Topology] for all the connected [Connector]s of a [Device]
+    ///
+    /// The [Connector]s are considered in the order returned by
+    /// [`Device::connected_connectors`].
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails, or if no
+    /// [Connector] can be driven.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{Device, Topology};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    /// let topology = Topology::new(&device).unwrap();
+    ///
+    /// for (output, plane) in topology.into_outputs() {
+    ///     let mode = output.connector().preferred_mode().unwrap();
+    /// }
+    /// ```
+    pub fn new(device: &Device) -> Result<Self> {
+        Self::from_connectors(device, &device.connected_connectors())
+    }
+
+    /// Creates a [Topology] for a list of [Connector]s
+    ///
+    /// If not all the [Connector]s can be driven at the same time, the first ones in the list
+    /// are preferred.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed, if the ioctl fails, or if no
+    /// [Connector] can be driven.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorType, Device, Topology};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let hdmi: Vec<_> = device.connectors()
+    ///     .filter(|con| con.connector_type() == ConnectorType::HDMIA)
+    ///     .collect();
+    ///
+    /// let topology = Topology::from_connectors(&device, &hdmi).unwrap();
+    /// for connector in topology.unassigned() {
+    ///     println!("{} can't be driven", connector.name());
+    /// }
+    /// ```
+    pub fn from_connectors(device: &Device, connectors: &[Arc<Connector>]) -> Result<Self> {
+        let primaries: Vec<_> = device
+            .planes()
+            .filter(|plane| plane.plane_type() == PlaneType::Primary)
+            .collect();
+
+        let mut candidates = Vec::with_capacity(connectors.len());
+        for connector in connectors {
+            let mut pipelines = Vec::new();
+
+            for encoder in connector.encoders()? {
+                for crtc in encoder.crtcs()? {
+                    let mask = 1 << crtc.index();
+
+                    for plane in primaries
+                        .iter()
+                        .filter(|plane| (plane.possible_crtcs() & mask) != 0)
+                    {
+                        pipelines.push([encoder.id(), crtc.object_id(), plane.object_id()]);
+                    }
+                }
+            }
+
+            candidates.push(pipelines);
+        }
+
+        let mut outputs = Vec::new();
+        let mut unassigned = Vec::new();
+        for ((connector, pipelines), choice) in
+            connectors.iter().zip(&candidates).zip(solve(&candidates))
+        {
+            let Some([encoder_id, crtc_id, plane_id]) = choice.map(|idx| pipelines[idx]) else {
+                unassigned.push(Arc::clone(connector));
+                continue;
+            };
+
+            let encoder = device
+                .encoders()
+                .find(|encoder| encoder.id() == encoder_id)
+                .ok_or(Error::Empty)?;
+            let crtc = device
+                .crtcs()
+                .find(|crtc| crtc.object_id() == crtc_id)
+                .ok_or(Error::Empty)?;
+            let plane = primaries
+                .iter()
+                .find(|plane| plane.object_id() == plane_id)
+                .ok_or(Error::Empty)?;
+
+            outputs.push((
+                Output::new(device, &crtc, &encoder, connector),
+                Arc::clone(plane),
+            ));
+        }
+
+        if outputs.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        Ok(Self {
+            outputs,
+            unassigned,
+        })
+    }
+
+    /// Returns an iterator over the [Output]s, along with their primary [Plane]
+    pub fn outputs(&self) -> impl Iterator<Item = (&Output, &Arc<Plane>)> {
+        self.outputs.iter().map(|(output, plane)| (output, plane))
+    }
+
+    /// Returns the [Connector]s that couldn't be driven along with the others
+    #[must_use]
+    pub fn unassigned(&self) -> &[Arc<Connector>] {
+        &self.unassigned
+    }
+
+    /// Returns the [Output]s, along with their primary [Plane]
+    #[must_use]
+    pub fn into_outputs(self) -> Vec<(Output, Arc<Plane>)> {
+        self.outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::solve;
+
+    #[test]
+    fn test_solve() {
+        // NOTE: The first connector could use either CRTC, but the second one can only use the
+        // first one.
+        let candidates = vec![vec![[1, 10, 20], [1, 11, 21]], vec![[2, 10, 20]]];
+        assert_eq!(solve(&candidates), vec![Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn test_solve_shared_encoder() {
+        // NOTE: The first two connectors share their only encoder, so only one of them can be
+        // driven.
+        let candidates = vec![
+            vec![[1, 10, 20], [1, 11, 21]],
+            vec![[1, 10, 20], [1, 11, 21]],
+            vec![[2, 10, 20], [2, 11, 21]],
+        ];
+        assert_eq!(solve(&candidates), vec![Some(0), None, Some(1)]);
+    }
+
+    #[test]
+    fn test_solve_shared_plane() {
+        let candidates = vec![vec![[1, 10, 20]], vec![[2, 11, 20]], vec![]];
+        assert_eq!(solve(&candidates), vec![Some(0), None, None]);
+        assert!(solve(&[]).is_empty());
+    }
+}
@@ -0,0 +1,274 @@
+use std::time::Instant;
+
+#[cfg(feature = "tracing")]
+use std::{collections::BTreeMap, convert::TryInto, fmt::Write};
+
+#[cfg(feature = "tracing")]
+use crate::{
+    raw::{
+        DRM_MODE_ATOMIC_ALLOW_MODESET, DRM_MODE_ATOMIC_NONBLOCK, DRM_MODE_ATOMIC_TEST_ONLY,
+        DRM_MODE_PAGE_FLIP_EVENT,
+    },
+    Device, Error, RecordedCommit, RecordedProperty, RecordedValue,
+};
+use crate::{Result, Vblank};
+
+#[cfg(feature = "tracing")]
+const FLAGS: [(u32, &str); 4] = [
+    (DRM_MODE_PAGE_FLIP_EVENT, "PAGE_FLIP_EVENT"),
+    (DRM_MODE_ATOMIC_TEST_ONLY, "TEST_ONLY"),
+    (DRM_MODE_ATOMIC_NONBLOCK, "NONBLOCK"),
+    (DRM_MODE_ATOMIC_ALLOW_MODESET, "ALLOW_MODESET"),
+];
+
+/// Follows a commit through its stages
+///
+/// Each commit gets a sequence number, unique to its [Device](crate::Device). With the `tracing`
+/// feature, the commit and each of its stages are wrapped in spans holding that number, and with
+/// the `metrics` feature the commits, their failures and their latency are accounted for.
+#[derive(Debug)]
+pub struct CommitTrace {
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    sequence: u64,
+    start: Instant,
+
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+}
+
+impl CommitTrace {
+    pub fn new(sequence: u64, flags: u32) -> Self {
+        #[cfg(not(feature = "tracing"))]
+        let _ = flags;
+
+        Self {
+            sequence,
+            start: Instant::now(),
+
+            #[cfg(feature = "tracing")]
+            _span: tracing::debug_span!("commit", sequence, flags).entered(),
+        }
+    }
+
+    /// Runs one of the commit stages
+    #[cfg_attr(not(feature = "tracing"), allow(clippy::unused_self))]
+    pub fn stage<T, F>(&self, name: &'static str, stage: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("stage", sequence = self.sequence, name).entered();
+
+        #[cfg(not(feature = "tracing"))]
+        let _ = name;
+
+        stage()
+    }
+
+    /// Accounts for the outcome of the commit
+    pub fn finish<T>(&self, ret: &Result<T>) {
+        let latency = self.start.elapsed();
+
+        #[cfg(feature = "tracing")]
+        match ret {
+            Ok(_) => tracing::debug!(sequence = self.sequence, ?latency, "commit succeeded"),
+            Err(err) => tracing::debug!(sequence = self.sequence, %err, "commit failed"),
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            metrics::counter!("nucleid_commits_total").increment(1);
+            if ret.is_err() {
+                metrics::counter!("nucleid_commit_failures_total").increment(1);
+            }
+            metrics::histogram!("nucleid_commit_latency_seconds").record(latency.as_secs_f64());
+        }
+
+        #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+        let _ = (ret, latency);
+    }
+
+    /// Logs the whole state of a failed commit
+    ///
+    /// This needs to be called before the blobs are destroyed, since their content is part of
+    /// the state.
+    #[cfg(feature = "tracing")]
+    pub fn dump(&self, device: &Device, flags: u32, properties: &[(u32, u32, u64)], err: &Error) {
+        if !tracing::enabled!(tracing::Level::DEBUG) {
+            return;
+        }
+
+        match RecordedCommit::from_raw(device, flags, properties) {
+            Ok(commit) => tracing::debug!(
+                sequence = self.sequence,
+                %err,
+                "commit failed with state:\n{}",
+                describe_commit(&commit)
+            ),
+            Err(dump_err) => tracing::debug!(
+                sequence = self.sequence,
+                %err,
+                %dump_err,
+                "commit failed, and its state couldn't be retrieved"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn describe_flags(flags: u32) -> String {
+    let mut names: Vec<String> = FLAGS
+        .iter()
+        .filter(|(flag, _)| flags & flag != 0)
+        .map(|(_, name)| (*name).to_string())
+        .collect();
+
+    let unknown = FLAGS.iter().fold(flags, |flags, (flag, _)| flags & !flag);
+    if unknown != 0 {
+        names.push(format!("{unknown:#x}"));
+    }
+
+    format!("{flags:#x} ({})", names.join(" | "))
+}
+
+#[cfg(feature = "tracing")]
+fn describe_fourcc(fourcc: u32) -> String {
+    fourcc
+        .to_le_bytes()
+        .iter()
+        .map(|byte| char::from(*byte))
+        .collect()
+}
+
+#[cfg(feature = "tracing")]
+fn describe_value(property: &RecordedProperty) -> String {
+    match property.value() {
+        RecordedValue::Value(value) => value.to_string(),
+        RecordedValue::Framebuffer {
+            id,
+            width,
+            height,
+            format,
+        } => format!(
+            "framebuffer {id} ({width}x{height} {})",
+            describe_fourcc(*format)
+        ),
+        RecordedValue::Blob(data) => {
+            // NOTE: The mode blob holds a drm_mode_modeinfo structure, with the active width
+            // at offset 4, the active height at offset 14, and the refresh rate at offset 24.
+            let mode = (property.name() == "MODE_ID")
+                .then(|| {
+                    let hdisplay = u16::from_ne_bytes(data.get(4..6)?.try_into().ok()?);
+                    let vdisplay = u16::from_ne_bytes(data.get(14..16)?.try_into().ok()?);
+                    let vrefresh = u32::from_ne_bytes(data.get(24..28)?.try_into().ok()?);
+
+                    Some(format!("{hdisplay}x{vdisplay}@{vrefresh}, "))
+                })
+                .flatten()
+                .unwrap_or_default();
+
+            let mut bytes = String::with_capacity(data.len() * 2);
+            for byte in data {
+                let _res = write!(bytes, "{byte:02x}");
+            }
+
+            format!("blob ({mode}{} bytes) {bytes}", data.len())
+        }
+    }
+}
+
+/// Formats a commit state, grouping the properties by object
+#[cfg(feature = "tracing")]
+fn describe_commit(commit: &RecordedCommit) -> String {
+    let mut objects: BTreeMap<u32, Vec<&RecordedProperty>> = BTreeMap::new();
+    for property in commit.properties() {
+        objects
+            .entry(property.object_id())
+            .or_default()
+            .push(property);
+    }
+
+    let mut dump = format!("flags: {}\n", describe_flags(commit.flags()));
+    for (object_id, properties) in objects {
+        let _res = writeln!(dump, "object {object_id}:");
+
+        for property in properties {
+            let _res = writeln!(
+                dump,
+                "    {} = {}",
+                property.name(),
+                describe_value(property)
+            );
+        }
+    }
+
+    dump
+}
+
+/// Reports that the commit `sequence` has been presented
+#[cfg_attr(not(feature = "tracing"), allow(clippy::missing_const_for_fn))]
+pub fn presented(sequence: u64, vblank: &Vblank) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(sequence, vblank = vblank.sequence(), "commit presented");
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = (sequence, vblank);
+}
+
+/// Reports a frame presented `skipped` vblanks later than expected on the CRTC `crtc_id`
+#[cfg_attr(not(feature = "tracing"), allow(clippy::missing_const_for_fn))]
+pub fn skipped(crtc_id: u32, skipped: u64) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(crtc_id, skipped, "frame presented late");
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = (crtc_id, skipped);
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::describe_commit;
+    use crate::{
+        raw::{DRM_MODE_ATOMIC_ALLOW_MODESET, DRM_MODE_ATOMIC_TEST_ONLY},
+        Format, RecordedCommit, RecordedProperty, RecordedValue,
+    };
+
+    #[test]
+    fn test_describe_commit() {
+        let mut mode = vec![0; 68];
+        mode[4..6].copy_from_slice(&1920_u16.to_ne_bytes());
+        mode[14..16].copy_from_slice(&1080_u16.to_ne_bytes());
+        mode[24..28].copy_from_slice(&60_u32.to_ne_bytes());
+
+        let commit = RecordedCommit::new(
+            DRM_MODE_ATOMIC_ALLOW_MODESET | DRM_MODE_ATOMIC_TEST_ONLY | 0x8000,
+            vec![
+                RecordedProperty::new(
+                    42,
+                    "FB_ID",
+                    RecordedValue::Framebuffer {
+                        id: 50,
+                        width: 640,
+                        height: 480,
+                        format: Format::XRGB8888 as u32,
+                    },
+                ),
+                RecordedProperty::new(31, "MODE_ID", RecordedValue::Blob(mode)),
+                RecordedProperty::new(42, "CRTC_ID", RecordedValue::Value(31)),
+            ],
+        );
+
+        let dump = describe_commit(&commit);
+        let lines: Vec<&str> = dump.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "flags: 0x8500 (TEST_ONLY | ALLOW_MODESET | 0x8000)"
+        );
+        assert_eq!(lines[1], "object 31:");
+        assert!(lines[2].starts_with("    MODE_ID = blob (1920x1080@60, 68 bytes) 0000"));
+        assert_eq!(lines[3], "object 42:");
+        assert_eq!(lines[4], "    FB_ID = framebuffer 50 (640x480 XR24)");
+        assert_eq!(lines[5], "    CRTC_ID = 31");
+    }
+}
@@ -0,0 +1,159 @@
+use std::{convert::TryFrom, time::Duration};
+
+use crate::Mode;
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// A Vertical Blanking Interval
+///
+/// Identifies a vblank of an [Output](crate::Output) by its sequence number, along with the time
+/// at which it occured. The [Mode] timings can then be used to predict when the following ones
+/// will occur, and thus when a frame will be presented.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Vblank {
+    sequence: u64,
+    time: Duration,
+}
+
+impl Vblank {
+    pub(crate) const fn new(sequence: u64, time: Duration) -> Self {
+        Self { sequence, time }
+    }
+
+    /// Returns the vblank sequence number
+    #[must_use]
+    pub const fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Returns the time at which the vblank occured, on the `CLOCK_MONOTONIC` clock
+    #[must_use]
+    pub const fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Returns the time at which the vblank with the `sequence` number will occur
+    ///
+    /// A frame committed for that `sequence` will be presented at that time.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    /// let mode = output.current_mode().unwrap().unwrap();
+    ///
+    /// let vblank = output.vblank().unwrap();
+    /// let time = vblank.presentation_time(&mode, vblank.sequence() + 2);
+    /// ```
+    #[must_use]
+    pub fn presentation_time(&self, mode: &Mode, sequence: u64) -> Duration {
+        let frame = u128::from(nanos(mode.frame_duration()));
+
+        if sequence >= self.sequence {
+            let delta = frame * u128::from(sequence - self.sequence);
+
+            self.time.saturating_add(Duration::from_nanos(
+                u64::try_from(delta).unwrap_or(u64::MAX),
+            ))
+        } else {
+            let delta = frame * u128::from(self.sequence - sequence);
+
+            self.time.saturating_sub(Duration::from_nanos(
+                u64::try_from(delta).unwrap_or(u64::MAX),
+            ))
+        }
+    }
+
+    /// Returns the sequence number of the first vblank occuring at or after `time`
+    ///
+    /// This is the vblank a frame needs to be committed for to be presented at `time`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use nucleid::{ConnectorStatus, Device};
+    ///
+    /// let device = Device::new("/dev/dri/card0").unwrap();
+    ///
+    /// let connector = device.connectors()
+    ///     .into_iter()
+    ///     .find(|con| con.status().unwrap() == ConnectorStatus::Connected)
+    ///     .unwrap();
+    ///
+    /// let output = device.output_from_current_state(&connector).unwrap();
+    /// let mode = output.current_mode().unwrap().unwrap();
+    ///
+    /// let vblank = output.vblank().unwrap();
+    /// let sequence = vblank.sequence_at(&mode, vblank.time() + Duration::from_millis(100));
+    /// ```
+    #[must_use]
+    pub fn sequence_at(&self, mode: &Mode, time: Duration) -> u64 {
+        let frame = nanos(mode.frame_duration());
+        let delta = time.saturating_sub(self.time);
+        if frame == 0 || delta.is_zero() {
+            return self.sequence;
+        }
+
+        self.sequence.saturating_add(nanos(delta).div_ceil(frame))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Vblank;
+    use crate::{raw::drm_mode_modeinfo, Mode};
+
+    fn mode_1080p60() -> Mode {
+        Mode::new(drm_mode_modeinfo {
+            clock: 148_500,
+            hdisplay: 1920,
+            htotal: 2200,
+            vdisplay: 1080,
+            vtotal: 1125,
+            vrefresh: 60,
+            ..drm_mode_modeinfo::default()
+        })
+    }
+
+    #[test]
+    fn test_presentation_time() {
+        let mode = mode_1080p60();
+        let vblank = Vblank::new(100, Duration::from_secs(10));
+
+        assert_eq!(mode.frame_duration(), Duration::from_nanos(16_666_666));
+        assert_eq!(
+            vblank.presentation_time(&mode, 103),
+            Duration::from_nanos(10_049_999_998)
+        );
+        assert_eq!(
+            vblank.presentation_time(&mode, 99),
+            Duration::from_nanos(9_983_333_334)
+        );
+
+        assert_eq!(vblank.sequence_at(&mode, Duration::from_secs(9)), 100);
+        assert_eq!(vblank.sequence_at(&mode, Duration::from_secs(10)), 100);
+        assert_eq!(
+            vblank.sequence_at(&mode, Duration::from_nanos(10_016_666_666)),
+            101
+        );
+        assert_eq!(
+            vblank.sequence_at(&mode, Duration::from_nanos(10_016_666_667)),
+            102
+        );
+    }
+}
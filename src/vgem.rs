@@ -0,0 +1,90 @@
+use std::sync::{Arc, Weak};
+
+use crate::{
+    device::Inner,
+    raw::{drm_vgem_attach_fence, drm_vgem_signal_fence},
+    Buffer, Device, Error, Result,
+};
+
+/// A vgem fence
+///
+/// vgem is a virtual DRM driver without any display or rendering capability, but that allows to
+/// allocate [Buffer]s and to attach fences to them. This makes it possible to exercise the
+/// buffer sharing and synchronization code paths without any actual hardware.
+///
+/// A fence is attached to a [Buffer] with [`VgemFence::attach`], and will block any access to the
+/// [Buffer] through its implicit fences until [`VgemFence::signal`] is called. If it isn't, the
+/// kernel will signal it automatically after a timeout.
+#[derive(Debug)]
+pub struct VgemFence {
+    dev: Weak<Inner>,
+    id: u32,
+}
+
+impl VgemFence {
+    /// Attaches a new fence to a [Buffer] allocated on a vgem [Device]
+    ///
+    /// If `write` is set, the fence will be an exclusive, write, fence. Otherwise, it will be a
+    /// shared, read, fence.
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails, which will be
+    /// the case if the [Buffer] hasn't been allocated on a vgem [Device].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, VgemFence};
+    ///
+    /// let device = Device::new("/dev/dri/card1")
+    ///     .unwrap();
+    ///
+    /// let buffer = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    ///
+    /// let fence = VgemFence::attach(&buffer, true)
+    ///     .unwrap();
+    ///
+    /// fence.signal()
+    ///     .unwrap();
+    /// ```
+    pub fn attach(buffer: &Buffer, write: bool) -> Result<Self> {
+        let device = buffer.device()?;
+        let id = drm_vgem_attach_fence(&device, buffer.handle(), write)?;
+
+        Ok(Self {
+            dev: Arc::downgrade(&device.inner),
+            id,
+        })
+    }
+
+    /// Signals the fence
+    ///
+    /// # Errors
+    ///
+    /// Will return [Error] if the [Device] can't be accessed or if the ioctl fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nucleid::{BufferType, Device, VgemFence};
+    ///
+    /// let device = Device::new("/dev/dri/card1")
+    ///     .unwrap();
+    ///
+    /// let buffer = device.allocate_buffer(BufferType::Dumb, 640, 480, 32)
+    ///     .unwrap();
+    ///
+    /// let fence = VgemFence::attach(&buffer, false)
+    ///     .unwrap();
+    ///
+    /// fence.signal()
+    ///     .unwrap();
+    /// ```
+    pub fn signal(self) -> Result<()> {
+        let device: Device = self.dev.upgrade().ok_or(Error::DeviceGone)?.into();
+
+        drm_vgem_signal_fence(&device, self.id)
+    }
+}
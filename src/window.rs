@@ -0,0 +1,35 @@
+use std::os::unix::io::AsRawFd;
+
+use raw_window_handle::{
+    DisplayHandle, DrmDisplayHandle, DrmWindowHandle, HandleError, HasDisplayHandle,
+    HasWindowHandle, RawDisplayHandle, RawWindowHandle, WindowHandle,
+};
+
+use crate::{object::Object, Device, Output, PlaneType};
+
+impl HasDisplayHandle for Device {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        let raw = RawDisplayHandle::Drm(DrmDisplayHandle::new(self.as_raw_fd()));
+
+        // NOTE: The file descriptor is only closed when the last Device referencing it is
+        // dropped, so it stays valid as long as the Device is borrowed. An Output only holds a
+        // weak reference to its Device, so it can't provide a display handle itself.
+        Ok(unsafe { DisplayHandle::borrow_raw(raw) })
+    }
+}
+
+impl HasWindowHandle for Output {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        let _device = self.device().map_err(|_| HandleError::Unavailable)?;
+
+        let plane = self
+            .planes_of(PlaneType::Primary)
+            .into_iter()
+            .next()
+            .ok_or(HandleError::Unavailable)?;
+
+        let raw = RawWindowHandle::Drm(DrmWindowHandle::new(plane.object_id()));
+
+        Ok(unsafe { WindowHandle::borrow_raw(raw) })
+    }
+}